@@ -0,0 +1,40 @@
+//! Detects optional compiler capabilities so that newer APIs can be used
+//! without raising the minimum supported Rust version of the crate.
+//!
+//! See the `compat` module for the corresponding shims.
+
+use std::env;
+use std::process::Command;
+
+/// Capabilities enabled by a compiler version, as `(cfg name, minor version)`.
+const CAPABILITIES: &[(&str, u32)] = &[
+    ("unwind_context_core_error", 81),
+    ("unwind_context_panic_hook_info", 81),
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    for (cfg, _) in CAPABILITIES {
+        println!("cargo:rustc-check-cfg=cfg({cfg})");
+    }
+
+    let Some(minor) = rustc_minor_version() else {
+        return;
+    };
+    for (cfg, since) in CAPABILITIES {
+        if minor >= *since {
+            println!("cargo:rustc-cfg={cfg}");
+        }
+    }
+}
+
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = String::from_utf8(output.stdout).ok()?;
+    let mut parts = version.strip_prefix("rustc ")?.split('.');
+    if parts.next()? != "1" {
+        return None;
+    }
+    parts.next()?.parse().ok()
+}
@@ -13,11 +13,68 @@ comma_separated() {
     echo "$*"
 }
 
-all_features=(
-    "default"
+check_features() {
+    local toolchain="$1"
+    local features="$2"
+    (
+        set -x
+        cargo "+$toolchain" clippy \
+            --no-default-features --features "${features}" \
+            -- -D warnings \
+            || $NO_EXIT_ON_FAIL
+        cargo "+$toolchain" clippy --all-targets \
+            --no-default-features --features "${features}" \
+            -- -D warnings \
+            || $NO_EXIT_ON_FAIL
+        cargo "+$toolchain" build \
+            --no-default-features --features "${features}" \
+            || $NO_EXIT_ON_FAIL
+        cargo "+$toolchain" test --all-targets \
+            --no-default-features --features "${features}" \
+            || $NO_EXIT_ON_FAIL
+        # documentation examples require the `std` feature
+        cargo "+$toolchain" test --doc \
+            --no-default-features --features "std,${features}" \
+            || $NO_EXIT_ON_FAIL
+        cargo "+$toolchain" build --release \
+            --no-default-features --features "${features}" \
+            || $NO_EXIT_ON_FAIL
+        cargo "+$toolchain" test --release --all-targets \
+            --no-default-features --features "${features}" \
+            || $NO_EXIT_ON_FAIL
+    )
+}
+
+# features checked in all combinations
+core_features=(
     "std"
+    "alloc"
     "detect-color-support"
     "custom-default-colors"
+    "timestamps"
+)
+
+# features checked one at a time
+single_features=(
+    "default"
+    "windows-console"
+    "test-macro"
+    "anyhow"
+    "eyre"
+    "color-eyre"
+    "serde"
+    "wasm"
+    "android-log"
+    "syslog"
+    "win-debug-output"
+    "semihosting"
+    "rtt"
+    "async"
+    "tokio"
+    "rayon"
+    "off"
+    "force-debug-context"
+    "disable-debug-context"
 )
 
 toolchains=(
@@ -38,10 +95,10 @@ toolchains=(
 for toolchain in "${toolchains[@]}"; do
     export CARGO_TARGET_DIR="target/check-$toolchain"
 
-    num_features=${#all_features[@]}
+    num_features=${#core_features[@]}
     num_combinations=$(echo "2^$num_features" | bc)
 
-    # iterate over all 2^num_features features combinations if required
+    # iterate over all 2^num_features core features combinations
     # `j1` is used as a bitmask of the enabled features
     for ((j1 = 0; j1 < num_combinations; j1++)); do
         features_set=()
@@ -49,35 +106,15 @@ for toolchain in "${toolchains[@]}"; do
             mask=$(echo "2^$j2" | bc) # the mask of `j2`-th feature
 
             if (( j1 & mask )); then
-                features_set+=(${all_features[$j2]})
+                features_set+=(${core_features[$j2]})
             fi
         done
 
-        features=$(comma_separated "${features_set[@]}")
+        check_features "$toolchain" "$(comma_separated "${features_set[@]}")"
+    done
 
-        (
-            set -x
-            cargo "+$toolchain" clippy \
-                --no-default-features --features "${features}" \
-                -- -D warnings \
-                || $NO_EXIT_ON_FAIL
-            cargo "+$toolchain" clippy --all-targets \
-                --no-default-features --features "${features}" \
-                -- -D warnings \
-                || $NO_EXIT_ON_FAIL
-            cargo "+$toolchain" build \
-                --no-default-features --features "${features}" \
-                || $NO_EXIT_ON_FAIL
-            cargo "+$toolchain" test --all-targets \
-                --no-default-features --features "${features}" \
-                || $NO_EXIT_ON_FAIL
-            cargo "+$toolchain" build --release \
-                --no-default-features --features "${features}" \
-                || $NO_EXIT_ON_FAIL
-            cargo "+$toolchain" test --release --all-targets \
-                --no-default-features --features "${features}" \
-                || $NO_EXIT_ON_FAIL
-        )
+    for feature in "${single_features[@]}"; do
+        check_features "$toolchain" "$feature"
     done
 
     (
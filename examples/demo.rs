@@ -1,4 +1,5 @@
 #![allow(missing_docs, unused_crate_dependencies)]
+#![cfg_attr(feature = "off", allow(clippy::no_effect_underscore_binding))]
 
 use unwind_context::unwind_context;
 
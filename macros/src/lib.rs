@@ -0,0 +1,106 @@
+//! Procedural macros for the [`unwind-context`] crate.
+//!
+//! This crate is not intended to be used directly. Enable the `test-macro`
+//! feature of the [`unwind-context`] crate and use the macros re-exported by
+//! it instead.
+//!
+//! [`unwind-context`]: https://crates.io/crates/unwind-context
+
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+// Marks a function as a test and adds an unwind context to it. It is documented
+// at its re-export in the `unwind-context` crate.
+#[doc(hidden)]
+#[proc_macro_attribute]
+pub fn test(attr: TokenStream, item: TokenStream) -> TokenStream {
+    if let Some(token) = attr.into_iter().next() {
+        return compile_error(
+            "the `unwind_context::test` attribute does not accept arguments",
+            token.span(),
+        );
+    }
+
+    let mut tokens: Vec<TokenTree> = item.into_iter().collect();
+    let Some(name) = fn_name(&tokens) else {
+        return compile_error(
+            "the `unwind_context::test` attribute can only be applied to functions",
+            Span::call_site(),
+        );
+    };
+    let Some(TokenTree::Group(body)) = tokens.pop() else {
+        return compile_error(
+            "the `unwind_context::test` attribute requires a function body",
+            Span::call_site(),
+        );
+    };
+    if body.delimiter() != Delimiter::Brace {
+        return compile_error(
+            "the `unwind_context::test` attribute requires a function body",
+            body.span(),
+        );
+    }
+
+    let prelude: TokenStream = format!(
+        concat!(
+            "::unwind_context::enable_colors_if_supported();",
+            "::unwind_context::set_eprint_output_enabled(true);",
+            "::unwind_context::install_panic_location_hook();",
+            "let _unwind_context_test_ctx = ::unwind_context::unwind_context!(fn {}());",
+        ),
+        Literal::string(&name)
+    )
+    .parse()
+    .expect("the generated test prelude must be a valid token stream");
+
+    let mut stream: TokenStream = "#[::core::prelude::v1::test]"
+        .parse()
+        .expect("the test attribute must be a valid token stream");
+    stream.extend(tokens);
+    let mut body_stream = prelude;
+    body_stream.extend(body.stream());
+    let mut new_body = Group::new(Delimiter::Brace, body_stream);
+    new_body.set_span(body.span());
+    stream.extend([TokenTree::Group(new_body)]);
+    stream
+}
+
+/// Returns the name of the function declared by the given tokens.
+fn fn_name(tokens: &[TokenTree]) -> Option<String> {
+    let mut tokens = tokens.iter();
+    while let Some(token) = tokens.next() {
+        if let TokenTree::Ident(ident) = token {
+            if ident.to_string() == "fn" {
+                return match tokens.next() {
+                    Some(TokenTree::Ident(name)) => Some(name.to_string()),
+                    _ => None,
+                };
+            }
+        }
+    }
+    None
+}
+
+/// Returns a token stream invoking `compile_error!` with the given message.
+fn compile_error(message: &str, span: Span) -> TokenStream {
+    let tokens = [
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("core", span)),
+        TokenTree::Punct(Punct::new(':', Spacing::Joint)),
+        TokenTree::Punct(Punct::new(':', Spacing::Alone)),
+        TokenTree::Ident(Ident::new("compile_error", span)),
+        TokenTree::Punct(Punct::new('!', Spacing::Alone)),
+        TokenTree::Group(Group::new(
+            Delimiter::Parenthesis,
+            TokenStream::from(TokenTree::Literal(Literal::string(message))),
+        )),
+        TokenTree::Punct(Punct::new(';', Spacing::Alone)),
+    ];
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
@@ -0,0 +1,151 @@
+#[cfg(any(target_os = "android", test))]
+use std::ffi::CString;
+use std::io::{Result as IoResult, Write as IoWrite};
+#[cfg(any(target_os = "android", test))]
+use std::vec::Vec;
+
+/// The default tag used by [`AndroidLogWriter`].
+pub const DEFAULT_ANDROID_LOG_TAG: &str = "unwind-context";
+
+/// A [`std::io::Write`] writer that writes to the Android system log with
+/// `__android_log_write`.
+///
+/// On Android the standard error stream is discarded, so the context frames
+/// written with the default writer are lost, for example in NDK or JNI
+/// libraries. This writer writes an error priority log message with the given
+/// tag once per write with the trailing newline removed. Context scope guards
+/// write every frame with a single write only if it fits into the 1024-byte
+/// formatting buffer of [`IoSink`], so longer frames are split into several log
+/// messages. On other targets it writes to the standard error stream with
+/// [`StderrWriter`] instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, AndroidLogWriter};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx =
+///         unwind_context_with_io!((fn(foo, bar)), writer = AndroidLogWriter::new("my-app"));
+///     // ...
+/// }
+/// ```
+///
+/// [`IoSink`]: crate::IoSink
+/// [`StderrWriter`]: crate::StderrWriter
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct AndroidLogWriter {
+    tag: &'static str,
+}
+
+impl AndroidLogWriter {
+    /// Creates a new `AndroidLogWriter` writing log messages with the given
+    /// tag.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::AndroidLogWriter::new("my-app");
+    /// assert_eq!(writer.tag(), "my-app");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(tag: &'static str) -> Self {
+        Self { tag }
+    }
+
+    /// Returns the tag of log messages written by the writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{AndroidLogWriter, DEFAULT_ANDROID_LOG_TAG};
+    ///
+    /// assert_eq!(AndroidLogWriter::default().tag(), DEFAULT_ANDROID_LOG_TAG);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn tag(&self) -> &'static str {
+        self.tag
+    }
+}
+
+impl Default for AndroidLogWriter {
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_ANDROID_LOG_TAG)
+    }
+}
+
+impl IoWrite for AndroidLogWriter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    #[cfg(target_os = "android")]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        use core::ffi::{c_char, c_int};
+
+        const ANDROID_LOG_ERROR: c_int = 6;
+
+        #[link(name = "log")]
+        extern "C" {
+            fn __android_log_write(prio: c_int, tag: *const c_char, text: *const c_char) -> c_int;
+        }
+
+        let tag = to_c_string(self.tag.as_bytes());
+        let text = to_c_string(buf);
+        // SAFETY: Both pointers point to valid nul-terminated strings that
+        // outlive the call.
+        let _ = unsafe { __android_log_write(ANDROID_LOG_ERROR, tag.as_ptr(), text.as_ptr()) };
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "android"))]
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        crate::StderrWriter.write_all(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+/// Converts the written bytes to a nul-terminated log message without the
+/// trailing newline and the interior nul bytes.
+#[cfg(any(target_os = "android", test))]
+fn to_c_string(buf: &[u8]) -> CString {
+    let buf = buf.strip_suffix(b"\n").unwrap_or(buf);
+    let bytes: Vec<u8> = buf.iter().copied().filter(|&byte| byte != 0).collect();
+    CString::new(bytes).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as IoWrite;
+
+    use crate::android_log_writer::to_c_string;
+    use crate::{AndroidLogWriter, DEFAULT_ANDROID_LOG_TAG};
+
+    #[test]
+    fn test_to_c_string() {
+        assert_eq!(
+            to_c_string(b"fn func(foo: 1)\n").as_bytes(),
+            b"fn func(foo: 1)"
+        );
+        assert_eq!(to_c_string(b"a\0b\n\n").as_bytes(), b"ab\n");
+        assert_eq!(to_c_string(b"").as_bytes(), b"");
+    }
+
+    #[test]
+    fn test_android_log_writer() {
+        assert_eq!(AndroidLogWriter::default().tag(), DEFAULT_ANDROID_LOG_TAG);
+        assert_eq!(AndroidLogWriter::new("tag").tag(), "tag");
+        assert_eq!(AndroidLogWriter::new("tag").write(b"").unwrap(), 0);
+        assert!(AndroidLogWriter::new("tag").flush().is_ok());
+    }
+}
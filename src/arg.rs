@@ -1,6 +1,11 @@
 use core::fmt::{Debug, Formatter, Result as FmtResult, Write as FmtWrite};
+#[cfg(feature = "std")]
+use core::panic::AssertUnwindSafe;
 
-use crate::{AnsiColorScheme, DebugAnsiColored};
+use crate::{
+    AnsiColorScheme, ColoredValue, ColoredValueFormatter, ColoredValueStyle, ContextVisitor,
+    DebugAnsiColored,
+};
 
 /// A structure representing an argument name and its value.
 ///
@@ -26,7 +31,7 @@ impl<T> UnwindContextArg<T> {
     /// let arg = unwind_context::UnwindContextArg::new(Some("foo"), 123);
     /// ```
     #[inline]
-    pub fn new(name: Option<&'static str>, value: T) -> Self {
+    pub const fn new(name: Option<&'static str>, value: T) -> Self {
         Self { name, value }
     }
 }
@@ -40,7 +45,7 @@ where
         if let Some(name) = &self.name {
             write!(f, "{name}: ")?;
         }
-        write!(f, "{:?}", self.value)?;
+        write_value(f, &self.value, Formatter::write_str)?;
         Ok(())
     }
 }
@@ -55,18 +60,209 @@ where
         f: &mut Formatter<'_>,
         color_scheme: &'static AnsiColorScheme,
     ) -> FmtResult {
-        if let Some(name) = &self.name {
-            write!(f, "{name}: ")?;
-        }
+        write_colored_name(f, self.name, color_scheme)?;
         let mut writer = ColoredWriter {
             writer: f,
             mode: ColoredWriterMode::Default,
             color_scheme,
         };
-        write!(writer, "{:?}", self.value)?;
+        write_value(&mut writer, &self.value, |writer, placeholder| {
+            writer.reset()?;
+            writer.writer.write_str(placeholder)
+        })?;
         writer.reset()?;
         Ok(())
     }
+
+    #[inline]
+    fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        visitor.visit_arg(self.name, &self.value);
+    }
+}
+
+/// A structure representing an argument name and its value colorized
+/// according to the value structure with [`ColoredValue`].
+///
+/// Unlike [`UnwindContextArg`], its value is not re-lexed after being
+/// formatted with [`core::fmt::Debug`] when colorized.
+///
+/// This type is not intended to be used directly. Consider using macros like
+/// [`build_unwind_context_data`] or [`unwind_context`] instead.
+///
+/// [`build_unwind_context_data`]: crate::build_unwind_context_data
+/// [`unwind_context`]: crate::unwind_context
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct UnwindContextColoredArg<T> {
+    /// Optional argument name.
+    pub name: Option<&'static str>,
+    /// Argument value.
+    pub value: T,
+}
+
+impl<T> UnwindContextColoredArg<T> {
+    /// Create a new `UnwindContextColoredArg` with the provided name and
+    /// value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let arg = unwind_context::UnwindContextColoredArg::new(Some("foo"), 123);
+    /// ```
+    #[inline]
+    pub const fn new(name: Option<&'static str>, value: T) -> Self {
+        Self { name, value }
+    }
+}
+
+impl<T> Debug for UnwindContextColoredArg<T>
+where
+    T: ColoredValue,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(&UnwindContextArg::new(self.name, &self.value), f)
+    }
+}
+
+impl<T> DebugAnsiColored for UnwindContextColoredArg<T>
+where
+    T: ColoredValue,
+{
+    #[inline]
+    fn fmt_colored(
+        &self,
+        f: &mut Formatter<'_>,
+        color_scheme: &'static AnsiColorScheme,
+    ) -> FmtResult {
+        write_colored_name(f, self.name, color_scheme)?;
+        write_value_with(
+            f,
+            |writer| {
+                let mut formatter = ColoredValueFormatter::new(writer, color_scheme);
+                self.value.fmt_colored_value(&mut formatter)?;
+                formatter.finish()
+            },
+            |writer, placeholder| write!(writer, "{}{placeholder}", color_scheme.default),
+        )
+    }
+
+    #[inline]
+    fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        visitor.visit_arg(self.name, &self.value);
+    }
+}
+
+/// Writes an optional argument name followed by a colon with the `arg_name`
+/// and `punct` styles of the color scheme.
+#[inline]
+fn write_colored_name(
+    f: &mut Formatter<'_>,
+    name: Option<&str>,
+    color_scheme: &AnsiColorScheme,
+) -> FmtResult {
+    if let Some(name) = name {
+        if color_scheme.arg_name == color_scheme.default {
+            f.write_str(name)?;
+        } else {
+            write!(f, "{}{name}{}", color_scheme.arg_name, color_scheme.default)?;
+        }
+        write_punct(f, ":", color_scheme)?;
+        f.write_str(" ")?;
+    }
+    Ok(())
+}
+
+/// Writes a punctuation character like `:` or `,` with the `punct` style of
+/// the color scheme.
+///
+/// If the `punct` style is equal to the `default` one, the punctuation is
+/// written without any additional ANSI escape sequences.
+#[inline]
+pub(crate) fn write_punct(
+    f: &mut Formatter<'_>,
+    punct: &str,
+    color_scheme: &AnsiColorScheme,
+) -> FmtResult {
+    if color_scheme.punct == color_scheme.default {
+        f.write_str(punct)
+    } else {
+        write!(f, "{}{punct}{}", color_scheme.punct, color_scheme.default)
+    }
+}
+
+/// Writes the argument value formatted with [`core::fmt::Debug`].
+///
+/// If the `Debug` implementation of the value returns an error that was not
+/// caused by the writer itself, for example because of a poisoned lock, a
+/// `<fmt error>` placeholder is written instead, so that the rest of the frame
+/// is still printed.
+///
+/// With the `std` feature, a panic raised by the `Debug` implementation of the
+/// value is caught and a `<Debug panicked>` placeholder is written instead.
+/// Otherwise a panic raised while a context scope guard prints its context
+/// during unwinding would abort the process and hide the original panic
+/// context.
+#[inline]
+pub(crate) fn write_value<W, T>(
+    writer: &mut W,
+    value: &T,
+    write_placeholder: impl FnOnce(&mut W, &str) -> FmtResult,
+) -> FmtResult
+where
+    W: FmtWrite,
+    T: Debug,
+{
+    write_value_with(
+        writer,
+        |writer| write!(writer, "{value:?}"),
+        write_placeholder,
+    )
+}
+
+/// Writes the argument value with the given formatting function.
+///
+/// The errors and panics of the formatting function are handled the same way
+/// as in [`write_value`].
+#[inline]
+fn write_value_with<W>(
+    writer: &mut W,
+    fmt: impl FnOnce(&mut ErrorTrackingWriter<'_, W>) -> FmtResult,
+    write_placeholder: impl FnOnce(&mut W, &str) -> FmtResult,
+) -> FmtResult
+where
+    W: FmtWrite,
+{
+    let mut tracking_writer = ErrorTrackingWriter {
+        writer: &mut *writer,
+        is_failed: false,
+    };
+    #[cfg(feature = "std")]
+    let result = std::panic::catch_unwind(AssertUnwindSafe(|| fmt(&mut tracking_writer)));
+    #[cfg(not(feature = "std"))]
+    let result: Result<FmtResult, core::convert::Infallible> = Ok(fmt(&mut tracking_writer));
+    let is_failed = tracking_writer.is_failed;
+    match result {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(err)) if is_failed => Err(err),
+        Ok(Err(_)) => write_placeholder(writer, "<fmt error>"),
+        Err(_) if is_failed => Err(core::fmt::Error),
+        Err(_) => write_placeholder(writer, "<Debug panicked>"),
+    }
+}
+
+/// A writer adapter that remembers whether the underlying writer failed.
+struct ErrorTrackingWriter<'a, W> {
+    writer: &'a mut W,
+    is_failed: bool,
+}
+
+impl<W: FmtWrite> FmtWrite for ErrorTrackingWriter<'_, W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        let result = self.writer.write_str(s);
+        self.is_failed |= result.is_err();
+        result
+    }
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
@@ -83,41 +279,31 @@ enum ColoredWriterMode {
     Item,
     Boolean,
     Number,
+    NumberZero,
+    NumberRadix,
+    NumberExponent,
+    NumberSuffix,
     DoubleQuoted,
     DoubleQuotedEscapeChar,
     DoubleQuotedEscaped,
+    DoubleQuotedUnicodeEscape,
+    // The number of remaining hex digits of the `\x` escape.
+    DoubleQuotedHexEscape(u8),
     SingleQuoted,
     SingleQuotedEscapeChar,
     SingleQuotedEscaped,
+    SingleQuotedUnicodeEscape,
+    // The number of remaining hex digits of the `\x` escape.
+    SingleQuotedHexEscape(u8),
+    // The number of `#` characters of the raw string delimiter.
+    RawQuotedStart(u8),
+    RawQuoted(u8),
+    // The number of `#` characters of the raw string delimiter and the number
+    // of `#` characters after the closing quote.
+    RawQuotedEnd(u8, u8),
     QuotedEnd,
     Brace,
-}
-
-#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
-enum ColoredWriterModeStyle {
-    Default,
-    Ident,
-    Item,
-    Boolean,
-    Number,
-    Quoted,
-    Escaped,
-    Brace,
-}
-
-impl ColoredWriterModeStyle {
-    fn ansi_style(self, color_scheme: &AnsiColorScheme) -> &'static str {
-        match self {
-            Self::Default => color_scheme.default,
-            Self::Ident => color_scheme.ident,
-            Self::Item => color_scheme.item,
-            Self::Boolean => color_scheme.boolean,
-            Self::Number => color_scheme.number,
-            Self::Quoted => color_scheme.quoted,
-            Self::Escaped => color_scheme.escaped,
-            Self::Brace => color_scheme.value_braces,
-        }
-    }
+    Punct,
 }
 
 impl<W> ColoredWriter<W>
@@ -125,30 +311,63 @@ where
     W: FmtWrite,
 {
     fn reset(&mut self) -> FmtResult {
-        if self.mode.style() != ColoredWriterModeStyle::Default {
+        if self.mode.style() != ColoredValueStyle::Default {
             self.writer.write_str(self.color_scheme.default)?;
             self.mode = ColoredWriterMode::Default;
         }
         Ok(())
     }
+
+    fn punct_mode(&self) -> ColoredWriterMode {
+        if self.color_scheme.punct == self.color_scheme.default {
+            ColoredWriterMode::Default
+        } else {
+            ColoredWriterMode::Punct
+        }
+    }
+
+    // Returns the mode following a number literal ended by the given
+    // character.
+    fn number_end_mode(&self, ch: char) -> ColoredWriterMode {
+        match ch {
+            '(' | ')' | '[' | ']' | '{' | '}' => ColoredWriterMode::Brace,
+            '"' => ColoredWriterMode::DoubleQuoted,
+            '\'' => ColoredWriterMode::SingleQuoted,
+            ',' | ':' => self.punct_mode(),
+            ch if ch.is_alphanumeric() || ch == '_' => ColoredWriterMode::NumberSuffix,
+            _ => ColoredWriterMode::Default,
+        }
+    }
 }
 
 impl ColoredWriterMode {
-    fn style(self) -> ColoredWriterModeStyle {
+    fn style(self) -> ColoredValueStyle {
         match self {
-            Self::Default => ColoredWriterModeStyle::Default,
-            Self::Ident => ColoredWriterModeStyle::Ident,
-            Self::Item => ColoredWriterModeStyle::Item,
-            Self::Boolean => ColoredWriterModeStyle::Boolean,
-            Self::Number => ColoredWriterModeStyle::Number,
-            Self::DoubleQuoted | Self::SingleQuoted | Self::QuotedEnd => {
-                ColoredWriterModeStyle::Quoted
-            }
+            Self::Default => ColoredValueStyle::Default,
+            Self::Ident => ColoredValueStyle::Ident,
+            Self::Item => ColoredValueStyle::Item,
+            Self::Boolean => ColoredValueStyle::Boolean,
+            Self::Number
+            | Self::NumberZero
+            | Self::NumberRadix
+            | Self::NumberExponent
+            | Self::NumberSuffix => ColoredValueStyle::Number,
+            Self::DoubleQuoted
+            | Self::SingleQuoted
+            | Self::RawQuotedStart(_)
+            | Self::RawQuoted(_)
+            | Self::RawQuotedEnd(..)
+            | Self::QuotedEnd => ColoredValueStyle::Quoted,
             Self::DoubleQuotedEscapeChar
             | Self::DoubleQuotedEscaped
+            | Self::DoubleQuotedUnicodeEscape
+            | Self::DoubleQuotedHexEscape(_)
             | Self::SingleQuotedEscapeChar
-            | Self::SingleQuotedEscaped => ColoredWriterModeStyle::Escaped,
-            Self::Brace => ColoredWriterModeStyle::Brace,
+            | Self::SingleQuotedEscaped
+            | Self::SingleQuotedUnicodeEscape
+            | Self::SingleQuotedHexEscape(_) => ColoredValueStyle::Escaped,
+            Self::Brace => ColoredValueStyle::Brace,
+            Self::Punct => ColoredValueStyle::Punct,
         }
     }
 }
@@ -166,12 +385,18 @@ where
             self.mode = match self.mode {
                 ColoredWriterMode::Default
                 | ColoredWriterMode::QuotedEnd
-                | ColoredWriterMode::Brace => match ch {
-                    '0'..='9' | '+' | '-' | '.' => ColoredWriterMode::Number,
+                | ColoredWriterMode::Brace
+                | ColoredWriterMode::Punct => match ch {
+                    '0' => ColoredWriterMode::NumberZero,
+                    '1'..='9' | '+' | '-' | '.' => ColoredWriterMode::Number,
                     '(' | ')' | '[' | ']' | '{' | '}' => ColoredWriterMode::Brace,
+                    ',' | ':' => self.punct_mode(),
                     '_' => ColoredWriterMode::Ident,
                     '"' => ColoredWriterMode::DoubleQuoted,
                     '\'' => ColoredWriterMode::SingleQuoted,
+                    'r' if match_raw_quoted_start(s, offset) => {
+                        ColoredWriterMode::RawQuotedStart(0)
+                    }
                     'A'..='Z' => ColoredWriterMode::Item,
                     _ => {
                         if ch.is_alphanumeric() {
@@ -188,6 +413,7 @@ where
                 },
                 ColoredWriterMode::Ident | ColoredWriterMode::Item => match ch {
                     '(' | ')' | '[' | ']' | '{' | '}' => ColoredWriterMode::Brace,
+                    ',' | ':' => self.punct_mode(),
                     '#' | '_' => self.mode,
                     '"' => ColoredWriterMode::DoubleQuoted,
                     '\'' => ColoredWriterMode::SingleQuoted,
@@ -202,6 +428,7 @@ where
                 ColoredWriterMode::Boolean => match ch {
                     '0'..='9' | '+' | '-' | '.' => ColoredWriterMode::Number,
                     '(' | ')' | '[' | ']' | '{' | '}' => ColoredWriterMode::Brace,
+                    ',' | ':' => self.punct_mode(),
                     '#' | '_' => ColoredWriterMode::Ident,
                     '"' => ColoredWriterMode::DoubleQuoted,
                     '\'' => ColoredWriterMode::SingleQuoted,
@@ -213,35 +440,96 @@ where
                         }
                     }
                 },
-                ColoredWriterMode::Number => match ch {
+                ColoredWriterMode::NumberZero if matches!(ch, 'x' | 'o' | 'b') => {
+                    ColoredWriterMode::NumberRadix
+                }
+                ColoredWriterMode::Number | ColoredWriterMode::NumberZero => match ch {
                     '0'..='9' | '+' | '-' | '.' | '_' => ColoredWriterMode::Number,
-                    '(' | ')' | '[' | ']' | '{' | '}' => ColoredWriterMode::Brace,
-                    '"' => ColoredWriterMode::DoubleQuoted,
-                    '\'' => ColoredWriterMode::SingleQuoted,
-                    ch => {
-                        if ch.is_alphanumeric() {
-                            ColoredWriterMode::Ident
-                        } else {
-                            ColoredWriterMode::Default
-                        }
+                    'e' | 'E' => ColoredWriterMode::NumberExponent,
+                    ch => self.number_end_mode(ch),
+                },
+                ColoredWriterMode::NumberRadix => match ch {
+                    '0'..='9' | 'a'..='f' | 'A'..='F' | '_' => ColoredWriterMode::NumberRadix,
+                    ch => self.number_end_mode(ch),
+                },
+                ColoredWriterMode::NumberExponent => match ch {
+                    '0'..='9' | '+' | '-' => ColoredWriterMode::Number,
+                    ch => self.number_end_mode(ch),
+                },
+                ColoredWriterMode::NumberSuffix => match ch {
+                    ch if ch.is_alphanumeric() || ch == '_' => ColoredWriterMode::NumberSuffix,
+                    ch => self.number_end_mode(ch),
+                },
+                ColoredWriterMode::DoubleQuotedHexEscape(digits) if ch.is_ascii_hexdigit() => {
+                    if digits > 1 {
+                        ColoredWriterMode::DoubleQuotedHexEscape(digits.saturating_sub(1))
+                    } else {
+                        ColoredWriterMode::DoubleQuotedEscaped
                     }
+                }
+                ColoredWriterMode::DoubleQuoted
+                | ColoredWriterMode::DoubleQuotedEscaped
+                | ColoredWriterMode::DoubleQuotedHexEscape(_) => match ch {
+                    '"' => ColoredWriterMode::QuotedEnd,
+                    '\\' => ColoredWriterMode::DoubleQuotedEscapeChar,
+                    _ => ColoredWriterMode::DoubleQuoted,
+                },
+                ColoredWriterMode::DoubleQuotedEscapeChar => match ch {
+                    'u' => ColoredWriterMode::DoubleQuotedUnicodeEscape,
+                    'x' => ColoredWriterMode::DoubleQuotedHexEscape(2),
+                    _ => ColoredWriterMode::DoubleQuotedEscaped,
+                },
+                ColoredWriterMode::DoubleQuotedUnicodeEscape => match ch {
+                    '}' => ColoredWriterMode::DoubleQuotedEscaped,
+                    '"' => ColoredWriterMode::QuotedEnd,
+                    '\\' => ColoredWriterMode::DoubleQuotedEscapeChar,
+                    _ => ColoredWriterMode::DoubleQuotedUnicodeEscape,
                 },
-                ColoredWriterMode::DoubleQuoted | ColoredWriterMode::DoubleQuotedEscaped => {
-                    match ch {
-                        '"' => ColoredWriterMode::QuotedEnd,
-                        '\\' => ColoredWriterMode::DoubleQuotedEscapeChar,
-                        _ => ColoredWriterMode::DoubleQuoted,
+                ColoredWriterMode::SingleQuotedHexEscape(digits) if ch.is_ascii_hexdigit() => {
+                    if digits > 1 {
+                        ColoredWriterMode::SingleQuotedHexEscape(digits.saturating_sub(1))
+                    } else {
+                        ColoredWriterMode::SingleQuotedEscaped
                     }
                 }
-                ColoredWriterMode::DoubleQuotedEscapeChar => ColoredWriterMode::DoubleQuotedEscaped,
-                ColoredWriterMode::SingleQuoted | ColoredWriterMode::SingleQuotedEscaped => {
-                    match ch {
-                        '\'' => ColoredWriterMode::QuotedEnd,
-                        '\\' => ColoredWriterMode::SingleQuotedEscapeChar,
-                        _ => ColoredWriterMode::SingleQuoted,
+                ColoredWriterMode::SingleQuoted
+                | ColoredWriterMode::SingleQuotedEscaped
+                | ColoredWriterMode::SingleQuotedHexEscape(_) => match ch {
+                    '\'' => ColoredWriterMode::QuotedEnd,
+                    '\\' => ColoredWriterMode::SingleQuotedEscapeChar,
+                    _ => ColoredWriterMode::SingleQuoted,
+                },
+                ColoredWriterMode::SingleQuotedEscapeChar => match ch {
+                    'u' => ColoredWriterMode::SingleQuotedUnicodeEscape,
+                    'x' => ColoredWriterMode::SingleQuotedHexEscape(2),
+                    _ => ColoredWriterMode::SingleQuotedEscaped,
+                },
+                ColoredWriterMode::SingleQuotedUnicodeEscape => match ch {
+                    '}' => ColoredWriterMode::SingleQuotedEscaped,
+                    '\'' => ColoredWriterMode::QuotedEnd,
+                    '\\' => ColoredWriterMode::SingleQuotedEscapeChar,
+                    _ => ColoredWriterMode::SingleQuotedUnicodeEscape,
+                },
+                ColoredWriterMode::RawQuotedStart(hashes) => match ch {
+                    '#' => ColoredWriterMode::RawQuotedStart(hashes.saturating_add(1)),
+                    _ => ColoredWriterMode::RawQuoted(hashes),
+                },
+                ColoredWriterMode::RawQuoted(hashes)
+                | ColoredWriterMode::RawQuotedEnd(hashes, _)
+                    if ch == '"' =>
+                {
+                    if hashes == 0 {
+                        ColoredWriterMode::QuotedEnd
+                    } else {
+                        ColoredWriterMode::RawQuotedEnd(hashes, 0)
                     }
                 }
-                ColoredWriterMode::SingleQuotedEscapeChar => ColoredWriterMode::SingleQuotedEscaped,
+                ColoredWriterMode::RawQuoted(hashes) => ColoredWriterMode::RawQuoted(hashes),
+                ColoredWriterMode::RawQuotedEnd(hashes, closing) => match ch {
+                    '#' if closing.saturating_add(1) == hashes => ColoredWriterMode::QuotedEnd,
+                    '#' => ColoredWriterMode::RawQuotedEnd(hashes, closing.saturating_add(1)),
+                    _ => ColoredWriterMode::RawQuoted(hashes),
+                },
             };
             let style = self.mode.style();
             if prev_style != style {
@@ -253,6 +541,13 @@ where
     }
 }
 
+fn match_raw_quoted_start(s: &str, offset: usize) -> bool {
+    s.as_bytes()
+        .get(offset.saturating_add(1)..)
+        .and_then(|rest| rest.iter().find(|&&ch| ch != b'#'))
+        == Some(&b'"')
+}
+
 fn match_true_ident(s: &str, offset: usize) -> bool {
     s.as_bytes().get(offset..offset.saturating_add(4)) == Some(b"true")
         && s.as_bytes()
@@ -275,7 +570,7 @@ mod tests {
     use crate::arg::{match_false_ident, match_true_ident};
     use crate::test_common::{arg, colored_arg, TEST_COLOR_SCHEME};
     use crate::test_util::{debug_fmt, TransparentDebug};
-    use crate::{AnsiColored, UnwindContextArg};
+    use crate::{AnsiColorScheme, AnsiColored, UnwindContextArg};
 
     #[derive(Clone, Debug)]
     struct Wrapper<T> {
@@ -373,12 +668,12 @@ mod tests {
         );
         assert_eq!(
             debug_fmt(&mut buffer, &colored_arg(Some("foo"), 123)),
-            Ok("foo: {NUM}123{DEF}")
+            Ok("{ARG}foo{DEF}: {NUM}123{DEF}")
         );
         assert_eq!(
             debug_fmt(&mut buffer, &colored_arg(Some("foo"), "bar\n-\"-'-\"bar")),
             Ok(concat!(
-                "foo: ",
+                "{ARG}foo{DEF}: ",
                 "{QUOT}\"bar",
                 "{ESC}\\n",
                 "{QUOT}-",
@@ -391,7 +686,7 @@ mod tests {
         );
         assert_eq!(
             debug_fmt(&mut buffer, &colored_arg(Some("foo"), 'a')),
-            Ok("foo: {QUOT}'a'{DEF}")
+            Ok("{ARG}foo{DEF}: {QUOT}'a'{DEF}")
         );
         assert_eq!(
             debug_fmt(
@@ -406,7 +701,7 @@ mod tests {
                 )
             ),
             Ok(concat!(
-                "foo: ",
+                "{ARG}foo{DEF}: ",
                 "{ITEM}Wrapper",
                 "{DEF} {BRACE}{",
                 "{DEF} ",
@@ -420,6 +715,55 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_arg_punct_colored_fmt() {
+        static PUNCT_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+            punct: "{PUNCT}",
+            ..TEST_COLOR_SCHEME
+        };
+
+        let mut buffer = [0; 256];
+        assert_eq!(
+            debug_fmt(
+                &mut buffer,
+                &AnsiColored::new(
+                    UnwindContextArg::new(Some("foo"), (1, "a", true)),
+                    &PUNCT_COLOR_SCHEME
+                )
+            ),
+            Ok(concat!(
+                "{ARG}foo{DEF}{PUNCT}:{DEF} ",
+                "{BRACE}({NUM}1{PUNCT},{DEF} ",
+                "{QUOT}\"a\"{PUNCT},{DEF} ",
+                "{BOOL}true{BRACE}){DEF}"
+            ))
+        );
+        assert_eq!(
+            debug_fmt(
+                &mut buffer,
+                &AnsiColored::new(
+                    UnwindContextArg::new(
+                        None,
+                        Wrapper {
+                            _first: 1,
+                            _second: 2,
+                            _phantom: PhantomData,
+                        }
+                    ),
+                    &PUNCT_COLOR_SCHEME
+                )
+            ),
+            Ok(concat!(
+                "{ITEM}Wrapper{DEF} {BRACE}{{DEF} ",
+                "{IDENT}_first{PUNCT}:{DEF} {NUM}1{PUNCT},{DEF} ",
+                "{IDENT}_second{PUNCT}:{DEF} {NUM}2{PUNCT},{DEF} ",
+                "{IDENT}_phantom{PUNCT}:{DEF} ",
+                "{ITEM}PhantomData{DEF}<{IDENT}u32{DEF}> ",
+                "{BRACE}}{DEF}"
+            ))
+        );
+    }
+
     #[test]
     fn test_complex_colored_fmt() {
         use fmt_str_as_arg as f;
@@ -458,11 +802,28 @@ mod tests {
         assert_eq!(f(buf, "2[]"), Ok("{NUM}2{BRACE}[]{DEF}"));
         assert_eq!(f(buf, "3\"\""), Ok("{NUM}3{QUOT}\"\"{DEF}"));
         assert_eq!(f(buf, "4\'\'"), Ok("{NUM}4{QUOT}''{DEF}"));
-        assert_eq!(f(buf, "5a"), Ok("{NUM}5{IDENT}a{DEF}"));
+        assert_eq!(f(buf, "5a"), Ok("{NUM}5a{DEF}"));
         assert_eq!(f(buf, "6^7"), Ok("{NUM}6{DEF}^{NUM}7{DEF}"));
+        assert_eq!(f(buf, "10usize"), Ok("{NUM}10usize{DEF}"));
+        assert_eq!(f(buf, "0xff_u8"), Ok("{NUM}0xff_u8{DEF}"));
+        assert_eq!(f(buf, "[0b1010]"), Ok("{BRACE}[{NUM}0b1010{BRACE}]{DEF}"));
+        assert_eq!(f(buf, "0o17&"), Ok("{NUM}0o17{DEF}&"));
+        assert_eq!(f(buf, "1e-9"), Ok("{NUM}1e-9{DEF}"));
+        assert_eq!(f(buf, "1.5E+10f64"), Ok("{NUM}1.5E+10f64{DEF}"));
+        assert_eq!(f(buf, "0.0"), Ok("{NUM}0.0{DEF}"));
 
         assert_eq!(f(buf, "\"\\\"\""), Ok("{QUOT}\"{ESC}\\\"{QUOT}\"{DEF}"));
         assert_eq!(f(buf, "'\\''"), Ok("{QUOT}'{ESC}\\'{QUOT}'{DEF}"));
+        assert_eq!(
+            f(buf, "\"\\u{1f600}a\\x7fb\""),
+            Ok("{QUOT}\"{ESC}\\u{1f600}{QUOT}a{ESC}\\x7f{QUOT}b\"{DEF}")
+        );
+        assert_eq!(f(buf, "'\\u{301}'"), Ok("{QUOT}'{ESC}\\u{301}{QUOT}'{DEF}"));
+        assert_eq!(f(buf, "'\\x0a'"), Ok("{QUOT}'{ESC}\\x0a{QUOT}'{DEF}"));
+        assert_eq!(f(buf, "\"\\xz\""), Ok("{QUOT}\"{ESC}\\x{QUOT}z\"{DEF}"));
+        assert_eq!(f(buf, "r\"\\\""), Ok("{QUOT}r\"\\\"{DEF}"));
+        assert_eq!(f(buf, "r#\"a\"b\"#1"), Ok("{QUOT}r#\"a\"b\"#{NUM}1{DEF}"));
+        assert_eq!(f(buf, "r##\"a\"#b\"##"), Ok("{QUOT}r##\"a\"#b\"##{DEF}"));
         assert_eq!(f(buf, ""), Ok(""));
     }
 
@@ -481,10 +842,84 @@ mod tests {
     fn test_arg_failed_colored_fmt() {
         let arg = colored_arg(Some("foo"), TransparentDebug("[1, 2, 3]"));
 
-        let mut buffer = [0; 64];
+        let mut buffer = [0; 128];
         let len = debug_fmt(&mut buffer, &arg).unwrap().len();
         for len in 0..len {
             assert_eq!(debug_fmt(&mut buffer[0..len], &arg), Err(FmtError));
         }
     }
+
+    #[test]
+    fn test_fmt_error_placeholder() {
+        struct FailingDebug;
+
+        impl Debug for FailingDebug {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.write_str("partial")?;
+                Err(FmtError)
+            }
+        }
+
+        let mut buffer = [0; 64];
+        assert_eq!(
+            debug_fmt(&mut buffer, &arg(Some("foo"), FailingDebug)),
+            Ok("foo: partial<fmt error>")
+        );
+        assert_eq!(
+            debug_fmt(
+                &mut buffer,
+                &AnsiColored::new(arg(None, FailingDebug), &TEST_COLOR_SCHEME)
+            ),
+            Ok("{IDENT}partial{DEF}<fmt error>")
+        );
+
+        // Errors of the writer itself are not replaced.
+        let mut buffer = [0; 4];
+        assert_eq!(
+            debug_fmt(&mut buffer, &arg(Some("foo"), 12_345)),
+            Err(FmtError)
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_debug_panic_placeholder() {
+        use core::fmt::Formatter;
+        use core::panic::AssertUnwindSafe;
+        use std::panic::catch_unwind;
+        use std::string::String;
+
+        use crate::{unwind_context_with_fmt, StdPanicDetector};
+
+        struct PanickingDebug;
+
+        impl Debug for PanickingDebug {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                f.write_str("partial")?;
+                panic!("Debug panic");
+            }
+        }
+
+        let mut buffer = [0; 64];
+        assert_eq!(
+            debug_fmt(&mut buffer, &arg(Some("foo"), PanickingDebug)),
+            Ok("foo: partial<Debug panicked>")
+        );
+
+        let mut output = String::new();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let value = 123;
+            let _ctx = unwind_context_with_fmt!(
+                (fn func(PanickingDebug, value)),
+                writer = &mut output,
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            panic!("Error");
+        }));
+        assert!(result.is_err());
+        assert!(output
+            .starts_with("fn func(PanickingDebug: partial<Debug panicked>, value: 123)\n    at "));
+    }
 }
@@ -1,6 +1,7 @@
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 
-use crate::{AnsiColorScheme, AnsiColored, DebugAnsiColored, UnwindContextArg};
+use crate::arg::write_punct;
+use crate::{AnsiColorScheme, AnsiColored, ContextVisitor, DebugAnsiColored};
 
 /// A structure representing function argument names and their values.
 ///
@@ -39,7 +40,7 @@ impl<Params> UnwindContextArgs<Params> {
     /// ));
     /// ```
     #[inline]
-    pub fn new(args: Params) -> Self {
+    pub const fn new(args: Params) -> Self {
         Self(args)
     }
 }
@@ -96,9 +97,14 @@ where
         DebugAnsiColored::fmt_colored(&UnwindContextArgs(&self.0), f, color_scheme)?;
         Ok(())
     }
+
+    #[inline]
+    fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        DebugAnsiColored::visit_context(&UnwindContextArgs(&self.0), visitor);
+    }
 }
 
-impl<First> Debug for UnwindContextArgs<&(UnwindContextArg<First>, ())>
+impl<First> Debug for UnwindContextArgs<&(First, ())>
 where
     First: Debug,
 {
@@ -109,9 +115,9 @@ where
     }
 }
 
-impl<First> DebugAnsiColored for UnwindContextArgs<&(UnwindContextArg<First>, ())>
+impl<First> DebugAnsiColored for UnwindContextArgs<&(First, ())>
 where
-    First: Debug,
+    First: DebugAnsiColored,
 {
     #[inline]
     fn fmt_colored(
@@ -122,10 +128,14 @@ where
         DebugAnsiColored::fmt_colored(&self.0 .0, f, color_scheme)?;
         Ok(())
     }
+
+    #[inline]
+    fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        DebugAnsiColored::visit_context(&self.0 .0, visitor);
+    }
 }
 
-impl<'a, First, Second, Rest> Debug
-    for UnwindContextArgs<&'a (UnwindContextArg<First>, (Second, Rest))>
+impl<'a, First, Second, Rest> Debug for UnwindContextArgs<&'a (First, (Second, Rest))>
 where
     First: Debug,
     UnwindContextArgs<&'a (Second, Rest)>: Debug,
@@ -137,10 +147,9 @@ where
     }
 }
 
-impl<'a, First, Second, Rest> DebugAnsiColored
-    for UnwindContextArgs<&'a (UnwindContextArg<First>, (Second, Rest))>
+impl<'a, First, Second, Rest> DebugAnsiColored for UnwindContextArgs<&'a (First, (Second, Rest))>
 where
-    First: Debug,
+    First: DebugAnsiColored,
     UnwindContextArgs<&'a (Second, Rest)>: DebugAnsiColored,
 {
     #[inline]
@@ -149,22 +158,30 @@ where
         f: &mut Formatter<'_>,
         color_scheme: &'static AnsiColorScheme,
     ) -> FmtResult {
+        DebugAnsiColored::fmt_colored(&self.0 .0, f, color_scheme)?;
+        write_punct(f, ",", color_scheme)?;
         write!(
             f,
-            "{:?}, {:?}",
-            AnsiColored::new(&self.0 .0, color_scheme),
+            " {:?}",
             AnsiColored::new(UnwindContextArgs(&self.0 .1), color_scheme)
         )?;
         Ok(())
     }
+
+    #[inline]
+    fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        DebugAnsiColored::visit_context(&self.0 .0, visitor);
+        DebugAnsiColored::visit_context(&UnwindContextArgs(&self.0 .1), visitor);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use core::fmt::Error as FmtError;
 
-    use crate::test_common::{arg, args, colored_args};
+    use crate::test_common::{arg, args, colored_args, TEST_COLOR_SCHEME};
     use crate::test_util::debug_fmt;
+    use crate::{AnsiColorScheme, AnsiColored};
 
     #[test]
     fn test_args_fmt() {
@@ -215,14 +232,14 @@ mod tests {
 
     #[test]
     fn test_args_colored_fmt() {
-        let mut buffer = [0; 64];
+        let mut buffer = [0; 128];
 
         assert_eq!(debug_fmt(&mut buffer, &colored_args(())), Ok(""));
         assert_eq!(debug_fmt(&mut buffer, &colored_args(&())), Ok(""));
 
         assert_eq!(
             debug_fmt(&mut buffer, &colored_args((arg(Some("foo"), 1), ()))),
-            Ok("foo: {NUM}1{DEF}")
+            Ok("{ARG}foo{DEF}: {NUM}1{DEF}")
         );
 
         assert_eq!(
@@ -233,7 +250,27 @@ mod tests {
                     (arg(Some("bar"), 2), (arg(None, 3), ()))
                 ))
             ),
-            Ok("foo: {NUM}1{DEF}, bar: {NUM}2{DEF}, {NUM}3{DEF}")
+            Ok("{ARG}foo{DEF}: {NUM}1{DEF}, {ARG}bar{DEF}: {NUM}2{DEF}, {NUM}3{DEF}")
+        );
+    }
+
+    #[test]
+    fn test_args_punct_colored_fmt() {
+        static PUNCT_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+            punct: "{PUNCT}",
+            ..TEST_COLOR_SCHEME
+        };
+
+        let mut buffer = [0; 128];
+        assert_eq!(
+            debug_fmt(
+                &mut buffer,
+                &AnsiColored::new(
+                    args(&(arg(Some("foo"), 1), (arg(None, 2), ()))),
+                    &PUNCT_COLOR_SCHEME
+                )
+            ),
+            Ok("{ARG}foo{DEF}{PUNCT}:{DEF} {NUM}1{DEF}{PUNCT},{DEF} {NUM}2{DEF}")
         );
     }
 
@@ -0,0 +1,233 @@
+/// Asserts that a boolean expression is `true` and adds an unwind context to
+/// the assertion failure.
+///
+/// It works like [`assert!`], but optionally accepts a context enclosed in
+/// parentheses right after the asserted expression. The context uses the same
+/// syntax as the [`unwind_context`] macro. If the assertion fails, the context
+/// is printed as the innermost frame, followed by the frames of the context
+/// scope guards that are currently alive, so that table-driven tests
+/// immediately show which input case failed.
+///
+/// The context is created only if the assertion fails, so its arguments are
+/// not evaluated otherwise.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::assert_uc;
+///
+/// fn is_valid(value: u32) -> bool {
+///     value < 100
+/// }
+///
+/// for (index, value) in [1, 10, 50].into_iter().enumerate() {
+///     assert_uc!(is_valid(value));
+///     assert_uc!(is_valid(value), (index, value));
+///     assert_uc!(is_valid(value), (fn is_valid(value)), "{value} is not valid");
+/// }
+/// ```
+///
+/// [`unwind_context`]: crate::unwind_context
+#[macro_export]
+macro_rules! assert_uc {
+    ( $cond:expr, ( $( $context:tt )* ) $(,)? ) => {
+        if !$cond {
+            let _ctx = $crate::unwind_context!( $($context)* );
+            ::core::panic!(::core::concat!("assertion failed: ", ::core::stringify!($cond)));
+        }
+    };
+    ( $cond:expr, ( $( $context:tt )* ), $( $arg:tt )+ ) => {
+        if !$cond {
+            let _ctx = $crate::unwind_context!( $($context)* );
+            ::core::panic!($($arg)+);
+        }
+    };
+    ( $cond:expr $(,)? ) => {
+        ::core::assert!($cond)
+    };
+    ( $cond:expr, $( $arg:tt )+ ) => {
+        ::core::assert!($cond, $($arg)+)
+    };
+}
+
+/// Asserts that two expressions are equal to each other using [`PartialEq`]
+/// and adds an unwind context to the assertion failure.
+///
+/// It works like [`assert_eq!`], but optionally accepts a context enclosed in
+/// parentheses right after the compared expressions. See [`assert_uc`] for
+/// more details.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::assert_eq_uc;
+///
+/// let cases = [(1, 2), (10, 20), (50, 100)];
+/// for (index, (value, expected)) in cases.into_iter().enumerate() {
+///     assert_eq_uc!(value * 2, expected);
+///     assert_eq_uc!(value * 2, expected, (index, value));
+///     assert_eq_uc!(value * 2, expected, (index, value), "unexpected result");
+/// }
+/// ```
+///
+/// [`assert_uc`]: crate::assert_uc
+#[macro_export]
+macro_rules! assert_eq_uc {
+    ( $left:expr, $right:expr, ( $( $context:tt )* ) $(,)? ) => {
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if !(*left_val == *right_val) {
+                    let _ctx = $crate::unwind_context!( $($context)* );
+                    ::core::assert_eq!(*left_val, *right_val);
+                }
+            }
+        }
+    };
+    ( $left:expr, $right:expr, ( $( $context:tt )* ), $( $arg:tt )+ ) => {
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if !(*left_val == *right_val) {
+                    let _ctx = $crate::unwind_context!( $($context)* );
+                    ::core::assert_eq!(*left_val, *right_val, $($arg)+);
+                }
+            }
+        }
+    };
+    ( $left:expr, $right:expr $(,)? ) => {
+        ::core::assert_eq!($left, $right)
+    };
+    ( $left:expr, $right:expr, $( $arg:tt )+ ) => {
+        ::core::assert_eq!($left, $right, $($arg)+)
+    };
+}
+
+/// Asserts that two expressions are not equal to each other using
+/// [`PartialEq`] and adds an unwind context to the assertion failure.
+///
+/// It works like [`assert_ne!`], but optionally accepts a context enclosed in
+/// parentheses right after the compared expressions. See [`assert_uc`] for
+/// more details.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::assert_ne_uc;
+///
+/// for (index, value) in [1, 10, 50].into_iter().enumerate() {
+///     assert_ne_uc!(value, 0);
+///     assert_ne_uc!(value, 0, (index, value));
+///     assert_ne_uc!(value, 0, (index, value), "unexpected zero");
+/// }
+/// ```
+///
+/// [`assert_uc`]: crate::assert_uc
+#[macro_export]
+macro_rules! assert_ne_uc {
+    ( $left:expr, $right:expr, ( $( $context:tt )* ) $(,)? ) => {
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if *left_val == *right_val {
+                    let _ctx = $crate::unwind_context!( $($context)* );
+                    ::core::assert_ne!(*left_val, *right_val);
+                }
+            }
+        }
+    };
+    ( $left:expr, $right:expr, ( $( $context:tt )* ), $( $arg:tt )+ ) => {
+        match (&$left, &$right) {
+            (left_val, right_val) => {
+                if *left_val == *right_val {
+                    let _ctx = $crate::unwind_context!( $($context)* );
+                    ::core::assert_ne!(*left_val, *right_val, $($arg)+);
+                }
+            }
+        }
+    };
+    ( $left:expr, $right:expr $(,)? ) => {
+        ::core::assert_ne!($left, $right)
+    };
+    ( $left:expr, $right:expr, $( $arg:tt )+ ) => {
+        ::core::assert_ne!($left, $right, $($arg)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use core::panic::AssertUnwindSafe;
+    use std::panic::catch_unwind;
+    use std::string::String;
+
+    use crate::{capture, unwind_context};
+
+    fn panic_message(f: impl FnOnce()) -> String {
+        let payload = catch_unwind(AssertUnwindSafe(f)).expect_err("expected a panic");
+        if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else if let Some(message) = payload.downcast_ref::<&str>() {
+            (*message).into()
+        } else {
+            String::new()
+        }
+    }
+
+    #[test]
+    fn test_assert_uc() {
+        let capture = capture();
+        let value = 123;
+
+        assert_uc!(value > 100);
+        assert_uc!(value > 100, (value));
+        assert_uc!(value > 100, (value), "message");
+        assert!(capture.is_empty());
+
+        assert_eq!(
+            panic_message(|| assert_uc!(value < 100)),
+            "assertion failed: value < 100"
+        );
+        assert!(capture.is_empty());
+
+        assert_eq!(
+            panic_message(|| {
+                let _ctx = unwind_context!(fn outer());
+                assert_uc!(value < 100, (value, "case"));
+            }),
+            "assertion failed: value < 100"
+        );
+        let frames = capture.take_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].data, "value: 123, \"case\"");
+        assert_eq!(frames[1].func_name, Some("outer"));
+
+        assert_eq!(
+            panic_message(|| assert_uc!(value < 100, (fn check(value)), "bad {}", value)),
+            "bad 123"
+        );
+        assert_eq!(capture.take_frames()[0].data, "fn check(value: 123)");
+    }
+
+    #[test]
+    fn test_assert_eq_uc() {
+        let capture = capture();
+        let (first, second) = (1, 2);
+
+        assert_eq_uc!(first, 1);
+        assert_eq_uc!(first, 1, (first));
+        assert_eq_uc!(first, 1, (first), "message");
+        assert_ne_uc!(first, second);
+        assert_ne_uc!(first, second, (first, second));
+        assert_ne_uc!(first, second, (first, second), "message");
+        assert!(capture.is_empty());
+
+        let message = panic_message(|| assert_eq_uc!(first, second, (first, second)));
+        assert!(message.starts_with("assertion `left == right` failed"));
+        assert_eq!(capture.take_frames()[0].data, "first: 1, second: 2");
+
+        let message = panic_message(|| assert_eq_uc!(first, second, (first), "bad {}", first));
+        assert!(message.starts_with("assertion `left == right` failed: bad 1"));
+        assert_eq!(capture.take_frames()[0].data, "first: 1");
+
+        let message = panic_message(|| assert_ne_uc!(first, 1, (first)));
+        assert!(message.starts_with("assertion `left != right` failed"));
+        assert_eq!(capture.take_frames()[0].data, "first: 1");
+    }
+}
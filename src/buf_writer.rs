@@ -0,0 +1,290 @@
+use core::fmt::{Error as FmtError, Result as FmtResult, Write as FmtWrite};
+
+/// A [`core::fmt::Write`] writer that writes into a fixed-size byte buffer.
+///
+/// It does not allocate, so it can be used as a writer for the
+/// [`unwind_context_with_fmt`] macro or with the [`format_frame_into`]
+/// function in `no_std` environments.
+///
+/// If the written string does not fit into the remaining buffer space, its
+/// longest prefix ending at a character boundary is written, the writer is
+/// marked as truncated, and an error is returned. All subsequent writes are
+/// rejected, so the written part of the buffer is always a valid UTF-8 prefix
+/// of the formatted output. The writer never panics on overflow.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::fmt::Write;
+///
+/// use unwind_context::FixedBufWriter;
+///
+/// let mut buffer = [0; 8];
+/// let mut writer = FixedBufWriter::new(&mut buffer);
+/// assert!(write!(writer, "{}", 1234).is_ok());
+/// assert!(write!(writer, "{}", 567_890).is_err());
+/// assert!(write!(writer, "!").is_err());
+/// assert!(writer.is_truncated());
+/// assert_eq!(writer.as_str(), "12345678");
+/// ```
+///
+/// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+/// [`format_frame_into`]: crate::format_frame_into
+#[derive(Debug)]
+pub struct FixedBufWriter<'a> {
+    buffer: &'a mut [u8],
+    used: usize,
+    truncated: bool,
+}
+
+impl<'a> FixedBufWriter<'a> {
+    /// Creates a new `FixedBufWriter` writing into the given buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut buffer = [0; 64];
+    /// let writer = unwind_context::FixedBufWriter::new(&mut buffer);
+    /// assert_eq!(writer.capacity(), 64);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            used: 0,
+            truncated: false,
+        }
+    }
+
+    /// Returns the number of bytes written into the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0; 64];
+    /// let mut writer = unwind_context::FixedBufWriter::new(&mut buffer);
+    /// writer.write_str("abc").unwrap();
+    /// assert_eq!(writer.len(), 3);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.used
+    }
+
+    /// Returns `true` if nothing was written into the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut buffer = [0; 64];
+    /// assert!(unwind_context::FixedBufWriter::new(&mut buffer).is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.used == 0
+    }
+
+    /// Returns the size of the underlying buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut buffer = [0; 64];
+    /// assert_eq!(
+    ///     unwind_context::FixedBufWriter::new(&mut buffer).capacity(),
+    ///     64
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns `true` if a written string did not fit into the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0; 2];
+    /// let mut writer = unwind_context::FixedBufWriter::new(&mut buffer);
+    /// assert!(writer.write_str("abc").is_err());
+    /// assert!(writer.is_truncated());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns the string written into the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0; 64];
+    /// let mut writer = unwind_context::FixedBufWriter::new(&mut buffer);
+    /// write!(writer, "foo: {}", 123).unwrap();
+    /// assert_eq!(writer.as_str(), "foo: 123");
+    /// ```
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        written_str(self.buffer, self.used)
+    }
+
+    /// Consumes the writer and returns the string written into the buffer
+    /// with the lifetime of the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0; 64];
+    /// let mut writer = unwind_context::FixedBufWriter::new(&mut buffer);
+    /// write!(writer, "foo: {}", 123).unwrap();
+    /// let output: &str = writer.into_str();
+    /// assert_eq!(output, "foo: 123");
+    /// ```
+    #[must_use]
+    pub fn into_str(self) -> &'a str {
+        written_str(self.buffer, self.used)
+    }
+
+    /// Clears the written string and the truncation flag, so the buffer can
+    /// be reused.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0; 2];
+    /// let mut writer = unwind_context::FixedBufWriter::new(&mut buffer);
+    /// assert!(writer.write_str("abc").is_err());
+    /// writer.clear();
+    /// assert!(!writer.is_truncated());
+    /// assert!(writer.write_str("de").is_ok());
+    /// assert_eq!(writer.as_str(), "de");
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.used = 0;
+        self.truncated = false;
+    }
+}
+
+/// Converts the written part of the buffer to a string.
+///
+/// The writer only writes whole characters, so the conversion never fails.
+fn written_str(buffer: &[u8], used: usize) -> &str {
+    let bytes = buffer.get(..used).unwrap_or_default();
+    core::str::from_utf8(bytes).unwrap_or_default()
+}
+
+impl FmtWrite for FixedBufWriter<'_> {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        if self.truncated {
+            return Err(FmtError);
+        }
+        let from = self.used;
+        let available = self.buffer.len().saturating_sub(from);
+        let len = if s.len() <= available {
+            s.len()
+        } else {
+            self.truncated = true;
+            (0..=available)
+                .rev()
+                .find(|&len| s.is_char_boundary(len))
+                .unwrap_or(0)
+        };
+        let until = from.checked_add(len).ok_or(FmtError)?;
+        self.buffer
+            .get_mut(from..until)
+            .ok_or(FmtError)?
+            .copy_from_slice(&s.as_bytes()[..len]);
+        self.used = until;
+        if self.truncated {
+            Err(FmtError)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::{Error as FmtError, Write as FmtWrite};
+
+    use crate::FixedBufWriter;
+
+    #[test]
+    fn test_no_panic_on_buffer_overflow() {
+        let mut buffer = [0; 10];
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        assert_eq!(writer.write_str("0123456789"), Ok(()));
+        assert!(!writer.is_truncated());
+
+        let mut buffer = [0; 9];
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        assert_eq!(writer.write_str("0123456789"), Err(FmtError));
+        assert!(writer.is_truncated());
+
+        let mut writer = FixedBufWriter::new(&mut []);
+
+        // Emulate an inconsistent state with a very high value of buffer used.
+        writer.used = usize::MAX - 9;
+
+        assert_eq!(writer.write_str("0123456789"), Err(FmtError));
+    }
+
+    #[test]
+    fn test_truncation_at_char_boundary() {
+        let mut buffer = [0; 9];
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        assert_eq!(writer.write_str("0123456789"), Err(FmtError));
+        assert_eq!(writer.len(), 9);
+        assert_eq!(writer.into_str(), "012345678");
+
+        let mut buffer = [0; 4];
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        assert_eq!(writer.write_str("ab"), Ok(()));
+        assert_eq!(writer.write_str("цd"), Err(FmtError));
+        assert_eq!(writer.into_str(), "abц");
+
+        let mut buffer = [0; 3];
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        assert_eq!(writer.write_str("abц"), Err(FmtError));
+        assert!(writer.is_truncated());
+        assert_eq!(writer.into_str(), "ab");
+    }
+
+    #[test]
+    fn test_writes_after_truncation() {
+        let mut buffer = [0; 3];
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        assert_eq!(writer.write_str("abц"), Err(FmtError));
+        assert_eq!(writer.as_str(), "ab");
+
+        // The remaining space is not used after truncation.
+        assert_eq!(writer.write_str("c"), Err(FmtError));
+        assert_eq!(writer.as_str(), "ab");
+        assert_eq!(writer.len(), 2);
+        assert_eq!(writer.capacity(), 3);
+
+        writer.clear();
+        assert!(writer.is_empty());
+        assert!(!writer.is_truncated());
+        assert_eq!(writer.write_str("cd"), Ok(()));
+        assert_eq!(writer.into_str(), "cd");
+    }
+}
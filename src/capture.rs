@@ -0,0 +1,518 @@
+use core::any::Any;
+use core::cell::RefCell;
+use core::fmt::{Debug, Write as FmtWrite};
+use core::panic::{Location, UnwindSafe};
+use std::boxed::Box;
+use std::panic::catch_unwind;
+use std::rc::Rc;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::arg::write_value;
+use crate::frame::FrameFmt;
+use crate::{ContextVisitor, DebugAnsiColored, FrameInfo, FrameOptions};
+
+/// A structure representing an argument recorded by a [`FrameCapture`].
+///
+/// # Examples
+///
+/// ```rust
+/// let arg = unwind_context::CapturedArg {
+///     name: Some("foo"),
+///     value: "123".to_owned(),
+/// };
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct CapturedArg {
+    /// Optional argument name.
+    pub name: Option<&'static str>,
+    /// Argument value formatted with [`core::fmt::Debug`].
+    pub value: String,
+}
+
+/// A structure representing an unwind context frame recorded by a
+/// [`FrameCapture`] instead of being printed.
+///
+/// It is created with the [`capture`] function. See its documentation for
+/// more details.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct CapturedFrame {
+    /// Function name, if the context data is a function context.
+    pub func_name: Option<&'static str>,
+    /// Function arguments or scope variables.
+    pub args: Vec<CapturedArg>,
+    /// The whole context data formatted with [`core::fmt::Debug`] without
+    /// colorization.
+    pub data: String,
+    /// The whole frame rendered the same way the context scope guard would
+    /// print it, including the colorization if it is enabled for the guard.
+    pub rendered: String,
+    /// Code location where the context scope guard was created.
+    pub location: &'static Location<'static>,
+    /// Frame index within a single unwind, if frames are numbered.
+    pub index: Option<usize>,
+    /// The number of context scope guards enclosing the context scope guard
+    /// of this frame.
+    pub depth: usize,
+    /// The name of the thread the frame was recorded on, if it has one.
+    pub thread_name: Option<String>,
+}
+
+impl CapturedFrame {
+    /// Returns the formatted value of the first argument with the given name.
+    ///
+    /// # Examples
+    #[cfg_attr(not(feature = "off"), doc = "```rust")]
+    #[cfg_attr(feature = "off", doc = "```rust,ignore")]
+    /// use std::panic::catch_unwind;
+    ///
+    /// use unwind_context::unwind_context;
+    ///
+    /// fn func(foo: u32, bar: &str) {
+    ///     let _ctx = unwind_context!(fn(foo, bar));
+    ///     panic!("Error");
+    /// }
+    ///
+    /// let capture = unwind_context::capture();
+    /// let _ = catch_unwind(|| func(123, "abc"));
+    /// let frames = capture.take_frames();
+    /// assert_eq!(frames[0].arg("foo"), Some("123"));
+    /// assert_eq!(frames[0].arg("bar"), Some("\"abc\""));
+    /// assert_eq!(frames[0].arg("baz"), None);
+    #[doc = "```"]
+    #[must_use]
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.args
+            .iter()
+            .find(|arg| arg.name == Some(name))
+            .map(|arg| arg.value.as_str())
+    }
+}
+
+type CapturedFrames = Rc<RefCell<Vec<CapturedFrame>>>;
+
+std::thread_local! {
+    static ACTIVE_CAPTURE: RefCell<Option<CapturedFrames>> = const { RefCell::new(None) };
+}
+
+/// A guard that records unwind context frames of the current thread instead
+/// of printing them while it is alive.
+///
+/// It is created with the [`capture`] function. See its documentation for
+/// more details.
+#[derive(Debug)]
+#[must_use = "\
+    if unused, the `FrameCapture` will immediately drop,
+    consider binding the `FrameCapture` like `let capture = ...`.
+"]
+pub struct FrameCapture {
+    frames: CapturedFrames,
+    previous: Option<CapturedFrames>,
+}
+
+/// Starts recording unwind context frames of the current thread instead of
+/// printing them and returns a [`FrameCapture`] collector handle.
+///
+/// While the returned handle is alive, context scope guards created with any
+/// macro of this crate record their frames into it instead of writing them to
+/// their writers. This allows tests to run a panicking code with
+/// [`std::panic::catch_unwind`] and then assert on the captured function
+/// names, argument values and locations programmatically instead of
+/// inspecting the standard error output.
+///
+/// Captures are thread-local and can be nested. The innermost live capture
+/// receives the frames, and the previous capture is restored when it is
+/// dropped. Nested captures are expected to be dropped in the reverse order of
+/// their creation.
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use std::panic::catch_unwind;
+///
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn func(foo, bar));
+///     inner(foo * 2);
+/// }
+///
+/// fn inner(value: u32) {
+///     let _ctx = unwind_context!(fn inner(value));
+///     panic!("Error");
+/// }
+///
+/// let capture = unwind_context::capture();
+/// let result = catch_unwind(|| func(123, "abc"));
+/// assert!(result.is_err());
+///
+/// let frames = capture.take_frames();
+/// assert_eq!(frames.len(), 2);
+/// assert_eq!(frames[0].func_name, Some("inner"));
+/// assert_eq!(frames[0].arg("value"), Some("246"));
+/// assert_eq!(frames[1].func_name, Some("func"));
+/// assert_eq!(frames[1].data, "fn func(foo: 123, bar: \"abc\")");
+#[doc = "```"]
+#[inline]
+pub fn capture() -> FrameCapture {
+    let frames = CapturedFrames::default();
+    let previous = ACTIVE_CAPTURE.with(|active| active.replace(Some(Rc::clone(&frames))));
+    FrameCapture { frames, previous }
+}
+
+impl FrameCapture {
+    /// Returns the number of frames captured so far.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let capture = unwind_context::capture();
+    /// assert_eq!(capture.len(), 0);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.frames.borrow().len()
+    }
+
+    /// Returns `true` if no frames were captured so far.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let capture = unwind_context::capture();
+    /// assert!(capture.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frames.borrow().is_empty()
+    }
+
+    /// Returns a copy of the frames captured so far in the order they were
+    /// recorded, from the innermost to the outermost one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let capture = unwind_context::capture();
+    /// assert_eq!(capture.frames(), []);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn frames(&self) -> Vec<CapturedFrame> {
+        self.frames.borrow().clone()
+    }
+
+    /// Takes the frames captured so far in the order they were recorded,
+    /// leaving the capture empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let capture = unwind_context::capture();
+    /// assert_eq!(capture.take_frames(), []);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn take_frames(&self) -> Vec<CapturedFrame> {
+        self.frames.borrow_mut().split_off(0)
+    }
+}
+
+impl Drop for FrameCapture {
+    #[inline]
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        ACTIVE_CAPTURE.with(|active| {
+            let _ = active.replace(previous);
+        });
+    }
+}
+
+/// A structure representing a panic caught by [`catch_unwind_with_context`]
+/// together with the unwind context frames recorded during the unwind.
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use unwind_context::{catch_unwind_with_context, unwind_context};
+///
+/// let caught = catch_unwind_with_context(|| {
+///     let value = 123;
+///     let _ctx = unwind_context!(value);
+///     panic!("Error");
+/// })
+/// .unwrap_err();
+/// assert_eq!(caught.message(), Some("Error"));
+/// assert_eq!(caught.frames[0].data, "value: 123");
+#[doc = "```"]
+#[derive(Debug)]
+pub struct CaughtPanic {
+    /// The panic payload as returned by [`std::panic::catch_unwind`].
+    pub payload: Box<dyn Any + Send + 'static>,
+    /// Unwind context frames recorded during the unwind, from the innermost to
+    /// the outermost one.
+    pub frames: Vec<CapturedFrame>,
+}
+
+impl CaughtPanic {
+    /// Returns the panic message if the panic payload is a string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let caught = unwind_context::catch_unwind_with_context(|| panic!("Error {}", 123));
+    /// assert_eq!(caught.unwrap_err().message(), Some("Error 123"));
+    /// ```
+    #[must_use]
+    pub fn message(&self) -> Option<&str> {
+        if let Some(message) = self.payload.downcast_ref::<&'static str>() {
+            Some(message)
+        } else {
+            self.payload.downcast_ref::<String>().map(String::as_str)
+        }
+    }
+}
+
+/// Invokes a closure, capturing the cause of an unwinding panic if one occurs,
+/// and returns the panic payload together with the unwind context frames
+/// recorded during the unwind.
+///
+/// It works like [`std::panic::catch_unwind`], but also records the frames of
+/// the context scope guards dropped during the unwind with a [`FrameCapture`]
+/// instead of printing them. This allows frameworks to attach the context to
+/// their own error reporting instead of relying on the standard error stream.
+///
+/// # Errors
+///
+/// If the closure panics, the panic payload and the recorded frames are
+/// returned as a [`CaughtPanic`].
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use unwind_context::{catch_unwind_with_context, unwind_context};
+///
+/// fn func(foo: u32, bar: &str) -> u32 {
+///     let _ctx = unwind_context!(fn func(foo, bar));
+///     assert!(foo < 100, "foo is too large");
+///     foo
+/// }
+///
+/// assert_eq!(catch_unwind_with_context(|| func(12, "abc")).unwrap(), 12);
+///
+/// let caught = catch_unwind_with_context(|| func(123, "abc")).unwrap_err();
+/// assert_eq!(caught.message(), Some("foo is too large"));
+/// assert_eq!(caught.frames.len(), 1);
+/// assert_eq!(caught.frames[0].data, "fn func(foo: 123, bar: \"abc\")");
+#[doc = "```"]
+pub fn catch_unwind_with_context<F, R>(f: F) -> Result<R, CaughtPanic>
+where
+    F: FnOnce() -> R + UnwindSafe,
+{
+    let capture = capture();
+    catch_unwind(f).map_err(|payload| CaughtPanic {
+        payload,
+        frames: capture.take_frames(),
+    })
+}
+
+/// Returns `true` if a [`FrameCapture`] is active on the current thread.
+pub(crate) fn is_capture_active() -> bool {
+    ACTIVE_CAPTURE
+        .try_with(|active| active.borrow().is_some())
+        .unwrap_or(false)
+}
+
+/// Records the frame into the [`FrameCapture`] active on the current thread.
+///
+/// Returns `false` if there is no active capture and the frame should be
+/// printed.
+pub(crate) fn capture_frame<T: Debug + DebugAnsiColored>(
+    frame: &FrameInfo<'_, T>,
+    options: &FrameOptions,
+) -> bool {
+    let Some(frames) = ACTIVE_CAPTURE
+        .try_with(|active| active.borrow().clone())
+        .ok()
+        .flatten()
+    else {
+        return false;
+    };
+
+    let mut visitor = CaptureVisitor {
+        func_name: None,
+        args: Vec::new(),
+    };
+    frame.data.visit_context(&mut visitor);
+    let captured = CapturedFrame {
+        func_name: visitor.func_name,
+        args: visitor.args,
+        data: format_value(&frame.data),
+        rendered: format_value(&format_args!(
+            "{}",
+            FrameFmt {
+                frame: *frame,
+                options,
+            }
+        )),
+        location: frame.location,
+        index: frame.index,
+        depth: frame.depth,
+        thread_name: std::thread::current().name().map(String::from),
+    };
+    if let Ok(mut frames) = frames.try_borrow_mut() {
+        frames.push(captured);
+    }
+    true
+}
+
+#[derive(Debug)]
+struct CaptureVisitor {
+    func_name: Option<&'static str>,
+    args: Vec<CapturedArg>,
+}
+
+impl ContextVisitor for CaptureVisitor {
+    fn visit_func_name(&mut self, name: &'static str) {
+        self.func_name = Some(name);
+    }
+
+    fn visit_arg(&mut self, name: Option<&'static str>, value: &dyn Debug) {
+        self.args.push(CapturedArg {
+            name,
+            value: format_value(&value),
+        });
+    }
+}
+
+/// Formats the value with [`core::fmt::Debug`] the same way as a context
+/// argument value is printed, so that a panicking `Debug` implementation is
+/// recorded as a `<Debug panicked>` placeholder instead of aborting the process
+/// while a context scope guard is dropped during unwinding.
+fn format_value<T: Debug>(value: &T) -> String {
+    let mut formatted = String::new();
+    let _ = write_value(&mut formatted, value, FmtWrite::write_str);
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use core::panic::AssertUnwindSafe;
+    use std::panic::catch_unwind;
+
+    use crate::test_common::TEST_COLOR_SCHEME;
+    use crate::{capture, unwind_context, unwind_context_with_fmt, StdPanicDetector};
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_capture() {
+        let capture = capture();
+        let result = catch_unwind(|| {
+            let (foo, bar) = (123, "abc");
+            let _ctx = unwind_context!(fn func(foo, ..., bar));
+            let _ctx = unwind_context!(foo, 234);
+            panic!("Error");
+        });
+        assert!(result.is_err());
+
+        let frames = capture.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].func_name, None);
+        assert_eq!(frames[0].args.len(), 2);
+        assert_eq!(frames[0].args[0], arg_value(Some("foo"), "123"));
+        assert_eq!(frames[0].args[1], arg_value(None, "234"));
+        assert_eq!(frames[0].data, "foo: 123, 234");
+        assert!(frames[0]
+            .rendered
+            .starts_with("foo: 123, 234\n    at src/capture.rs:"));
+        assert_eq!(frames[0].depth, 1);
+        assert_eq!(frames[1].func_name, Some("func"));
+        assert_eq!(frames[1].args[0], arg_value(Some("foo"), "123"));
+        assert_eq!(frames[1].args[1], arg_value(None, "..."));
+        assert_eq!(frames[1].args[2], arg_value(Some("bar"), "\"abc\""));
+        assert_eq!(frames[1].data, "fn func(foo: 123, ..., bar: \"abc\")");
+        assert_eq!(frames[1].depth, 0);
+        assert_eq!(frames[1].location.line() + 1, frames[0].location.line());
+        assert_eq!(
+            frames[0].thread_name.as_deref(),
+            std::thread::current().name()
+        );
+
+        assert_eq!(capture.take_frames().len(), 2);
+        assert!(capture.is_empty());
+    }
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_nested_capture() {
+        let mut output = std::string::String::new();
+        let outer = capture();
+        {
+            let inner = capture();
+            let mut ctx = unwind_context_with_fmt!(
+                (fn func()),
+                writer = &mut output,
+                panic_detector = StdPanicDetector,
+                color_scheme = Some(&TEST_COLOR_SCHEME),
+            );
+            ctx.print();
+            drop(ctx);
+            assert_eq!(inner.len(), 1);
+        }
+        let _ = catch_unwind(AssertUnwindSafe(|| {
+            let _ctx = unwind_context!(fn func());
+            panic!("Error");
+        }));
+        assert_eq!(outer.len(), 1);
+        drop(outer);
+        assert!(!super::is_capture_active());
+        assert_eq!(output, "");
+    }
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_capture_debug_panic_placeholder() {
+        use core::fmt::{Debug, Formatter};
+
+        struct PanickingDebug;
+
+        impl Debug for PanickingDebug {
+            fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+                f.write_str("partial")?;
+                panic!("Debug panic");
+            }
+        }
+
+        let capture = capture();
+        let result = catch_unwind(|| {
+            let value = 123;
+            let _ctx = unwind_context!(fn func(PanickingDebug, value));
+            panic!("Error");
+        });
+        assert!(result.is_err());
+
+        let frames = capture.frames();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(
+            frames[0].args[0],
+            arg_value(Some("PanickingDebug"), "partial<Debug panicked>")
+        );
+        assert_eq!(frames[0].args[1], arg_value(Some("value"), "123"));
+        assert_eq!(
+            frames[0].data,
+            "fn func(PanickingDebug: partial<Debug panicked>, value: 123)"
+        );
+        assert!(frames[0]
+            .rendered
+            .starts_with("fn func(PanickingDebug: partial<Debug panicked>, value: 123)\n"));
+    }
+
+    fn arg_value(name: Option<&'static str>, value: &str) -> super::CapturedArg {
+        super::CapturedArg {
+            name,
+            value: value.into(),
+        }
+    }
+}
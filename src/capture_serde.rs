@@ -0,0 +1,139 @@
+use core::panic::Location;
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::{CapturedArg, CapturedFrame};
+
+/// Serializes a [`CapturedArg`] as a structure with `name` and `value`
+/// fields.
+///
+/// # Examples
+///
+/// ```rust
+/// let arg = unwind_context::CapturedArg {
+///     name: Some("foo"),
+///     value: "123".to_owned(),
+/// };
+/// assert_eq!(
+///     serde_json::to_string(&arg).unwrap(),
+///     r#"{"name":"foo","value":"123"}"#
+/// );
+/// ```
+impl Serialize for CapturedArg {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("CapturedArg", 2)?;
+        state.serialize_field("name", &self.name)?;
+        state.serialize_field("value", &self.value)?;
+        state.end()
+    }
+}
+
+/// Serializes a [`CapturedFrame`] as a structure with the frame fields and
+/// the location split into `file`, `line`, and `column` fields.
+///
+/// It allows applications to ship captured unwind context frames to
+/// structured logging pipelines and crash reporters.
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use std::panic::catch_unwind;
+///
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32) {
+///     let _ctx = unwind_context!(fn func(foo));
+///     panic!("Error");
+/// }
+///
+/// let capture = unwind_context::capture();
+/// let _ = catch_unwind(|| func(123));
+/// let frames = capture.take_frames();
+/// let json = serde_json::to_value(&frames[0]).unwrap();
+/// assert_eq!(json["func_name"], "func");
+/// assert_eq!(json["args"][0]["name"], "foo");
+/// assert_eq!(json["args"][0]["value"], "123");
+/// assert!(json["location"]["line"].is_u64());
+#[doc = "```"]
+impl Serialize for CapturedFrame {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("CapturedFrame", 8)?;
+        state.serialize_field("func_name", &self.func_name)?;
+        state.serialize_field("args", &self.args)?;
+        state.serialize_field("data", &self.data)?;
+        state.serialize_field("rendered", &self.rendered)?;
+        state.serialize_field("location", &SerializeLocation(self.location))?;
+        state.serialize_field("index", &self.index)?;
+        state.serialize_field("depth", &self.depth)?;
+        state.serialize_field("thread_name", &self.thread_name)?;
+        state.end()
+    }
+}
+
+/// A helper serializing a [`Location`] as a structure with `file`, `line`,
+/// and `column` fields.
+struct SerializeLocation(&'static Location<'static>);
+
+impl Serialize for SerializeLocation {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Location", 3)?;
+        state.serialize_field("file", self.0.file())?;
+        state.serialize_field("line", &self.0.line())?;
+        state.serialize_field("column", &self.0.column())?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::catch_unwind;
+
+    use serde_json::json;
+
+    use crate::{capture, unwind_context};
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_serialize_captured_frame() {
+        let capture = capture();
+        let result = catch_unwind(|| {
+            let (foo, bar) = (123, "abc");
+            let _ctx = unwind_context!(fn func(foo, bar));
+            panic!("Error");
+        });
+        assert!(result.is_err());
+
+        let frames = capture.take_frames();
+        let location = frames[0].location;
+        let value = serde_json::to_value(&frames[0]).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "func_name": "func",
+                "args": [
+                    { "name": "foo", "value": "123" },
+                    { "name": "bar", "value": "\"abc\"" },
+                ],
+                "data": "fn func(foo: 123, bar: \"abc\")",
+                "rendered": frames[0].rendered,
+                "location": {
+                    "file": location.file(),
+                    "line": location.line(),
+                    "column": location.column(),
+                },
+                "index": null,
+                "depth": 0,
+                "thread_name": std::thread::current().name(),
+            })
+        );
+    }
+}
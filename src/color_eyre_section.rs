@@ -0,0 +1,192 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::string::String;
+use std::vec::Vec;
+
+use color_eyre::Section;
+
+use crate::FrameCapture;
+
+/// A structure representing the unwind context frames rendered as a
+/// dedicated "Unwind context" section of a [`color_eyre`] report.
+///
+/// The frames are rendered the same way the context scope guards print them,
+/// including the colorization if it is enabled, so the section looks like the
+/// regular unwind context output.
+///
+/// It is usually attached to a report with the [`UnwindContextSection`]
+/// extension trait.
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use std::panic::catch_unwind;
+///
+/// use unwind_context::{unwind_context, UnwindContextFrames};
+///
+/// fn func(foo: u32) {
+///     let _ctx = unwind_context!(fn func(foo));
+///     panic!("Error");
+/// }
+///
+/// let capture = unwind_context::capture();
+/// let _ = catch_unwind(|| func(123));
+/// let section = UnwindContextFrames::from_capture(&capture);
+/// let expected = "Unwind context:\n    fn func(foo: 123)\n        at ";
+/// assert!(section.to_string().starts_with(expected));
+#[doc = "```"]
+#[doc = ""]
+/// [`color_eyre`]: https://docs.rs/color-eyre
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct UnwindContextFrames {
+    frames: Vec<String>,
+}
+
+impl UnwindContextFrames {
+    /// Takes the frames recorded by the given capture and creates a new
+    /// `UnwindContextFrames` section.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let capture = unwind_context::capture();
+    /// let section = unwind_context::UnwindContextFrames::from_capture(&capture);
+    /// assert!(section.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn from_capture(capture: &FrameCapture) -> Self {
+        Self {
+            frames: capture
+                .take_frames()
+                .into_iter()
+                .map(|frame| frame.rendered)
+                .collect(),
+        }
+    }
+
+    /// Returns `true` if the section contains no frames.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let section = unwind_context::UnwindContextFrames::default();
+    /// assert!(section.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl Display for UnwindContextFrames {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+        f.write_str("Unwind context:")?;
+        for frame in &self.frames {
+            for line in frame.lines() {
+                write!(f, "\n    {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An extension trait for [`color_eyre::Section`] types that adds the unwind
+/// context frames recorded by a [`FrameCapture`] as a dedicated
+/// "Unwind context" section of the report.
+///
+/// The panic report of the `color-eyre` panic hook is printed before the
+/// unwinding starts, so the frames are not known yet at that moment. Instead,
+/// catch the panic, for example with [`catch_unwind_with_context`] or
+/// [`std::panic::catch_unwind`] while a [`FrameCapture`] is alive, convert it
+/// into a report and attach the captured frames with this trait.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::panic::catch_unwind;
+///
+/// use unwind_context::{unwind_context, UnwindContextSection};
+///
+/// fn func(foo: u32) -> u32 {
+///     let _ctx = unwind_context!(fn func(foo));
+///     assert!(foo < 100, "foo is too large");
+///     foo
+/// }
+///
+/// fn handle(foo: u32) -> color_eyre::Result<u32> {
+///     let capture = unwind_context::capture();
+///     catch_unwind(|| func(foo))
+///         .map_err(|_| color_eyre::eyre::eyre!("handler panicked"))
+///         .unwind_context_section(&capture)
+/// }
+///
+/// assert_eq!(handle(12).unwrap(), 12);
+/// assert!(handle(123).is_err());
+/// ```
+///
+/// [`color_eyre::Section`]: https://docs.rs/color-eyre/latest/color_eyre/trait.Section.html
+/// [`catch_unwind_with_context`]: crate::catch_unwind_with_context
+pub trait UnwindContextSection: Section {
+    /// Takes the frames recorded by the given capture and adds them as
+    /// a dedicated "Unwind context" section of the report.
+    ///
+    /// If no frames were captured, the section is empty.
+    fn unwind_context_section(self, capture: &FrameCapture) -> Self::Return;
+}
+
+impl<T: Section> UnwindContextSection for T {
+    #[inline]
+    fn unwind_context_section(self, capture: &FrameCapture) -> Self::Return {
+        self.section(UnwindContextFrames::from_capture(capture))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::format;
+    use std::panic::catch_unwind;
+    use std::string::ToString;
+
+    use crate::{capture, unwind_context, UnwindContextFrames, UnwindContextSection};
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_unwind_context_section() {
+        let capture = capture();
+        assert_eq!(UnwindContextFrames::from_capture(&capture).to_string(), "");
+
+        let _ = catch_unwind(|| {
+            let _ctx = unwind_context!(fn outer());
+            let _ctx = unwind_context!(fn inner());
+            panic!("Error");
+        });
+        let section = UnwindContextFrames::from_capture(&capture).to_string();
+        let mut lines = section.lines();
+        assert_eq!(lines.next(), Some("Unwind context:"));
+        assert_eq!(lines.next(), Some("    fn inner()"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("        at src/color_eyre_section.rs:"));
+        assert_eq!(lines.next(), Some("    fn outer()"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("        at src/color_eyre_section.rs:"));
+        assert_eq!(lines.next(), None);
+
+        let _ = catch_unwind(|| {
+            let _ctx = unwind_context!(fn func());
+            panic!("Error");
+        });
+        let result: color_eyre::Result<()> =
+            Err(color_eyre::eyre::eyre!("Error")).unwind_context_section(&capture);
+        let error = result.unwrap_err();
+        assert_eq!(format!("{error}"), "Error");
+        assert!(capture.is_empty());
+    }
+}
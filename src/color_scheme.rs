@@ -15,9 +15,14 @@
 pub static DEFAULT_DEFAULT_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
     default: "\u{1b}[0m",
     location: "\u{1b}[94m",
+    location_file: "\u{1b}[94m",
+    location_line: "\u{1b}[94m",
+    location_column: "\u{1b}[94m",
     fn_keyword: "\u{1b}[33m",
     func_name: "\u{1b}[93m",
     func_braces: "\u{1b}[0m",
+    arg_name: "\u{1b}[0m",
+    punct: "\u{1b}[0m",
     value_braces: "\u{1b}[0m",
     ident: "\u{1b}[0;33m",
     item: "\u{1b}[0;33m",
@@ -27,6 +32,332 @@ pub static DEFAULT_DEFAULT_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
     escaped: "\u{1b}[0;95m",
 };
 
+/// The default ANSI color scheme for terminals supporting the 256-color
+/// palette.
+///
+/// It is used instead of [`DEFAULT_DEFAULT_COLOR_SCHEME`] if colorization is
+/// enabled, no custom color scheme is set, and the color depth is set to
+/// [`ColorDepth::Ansi256`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{ColorDepth, ANSI_256_COLOR_SCHEME};
+///
+/// assert_eq!(ColorDepth::Ansi256.color_scheme(), &ANSI_256_COLOR_SCHEME);
+/// ```
+pub static ANSI_256_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+    default: "\u{1b}[0m",
+    location: crate::ansi_color_256!(75),
+    location_file: crate::ansi_color_256!(75),
+    location_line: crate::ansi_color_256!(75),
+    location_column: crate::ansi_color_256!(75),
+    fn_keyword: crate::ansi_color_256!(172),
+    func_name: crate::ansi_color_256!(221),
+    func_braces: "\u{1b}[0m",
+    arg_name: "\u{1b}[0m",
+    punct: "\u{1b}[0m",
+    value_braces: "\u{1b}[0m",
+    ident: crate::ansi_color_256!(179),
+    item: crate::ansi_color_256!(179),
+    boolean: "\u{1b}[1;38;5;221m",
+    number: crate::ansi_color_256!(80),
+    quoted: crate::ansi_color_256!(114),
+    escaped: crate::ansi_color_256!(176),
+};
+
+/// The default ANSI color scheme for terminals supporting 24-bit truecolor.
+///
+/// It is used instead of [`DEFAULT_DEFAULT_COLOR_SCHEME`] if colorization is
+/// enabled, no custom color scheme is set, and the color depth is set to
+/// [`ColorDepth::TrueColor`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{ColorDepth, TRUECOLOR_COLOR_SCHEME};
+///
+/// assert_eq!(
+///     ColorDepth::TrueColor.color_scheme(),
+///     &TRUECOLOR_COLOR_SCHEME
+/// );
+/// ```
+pub static TRUECOLOR_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+    default: "\u{1b}[0m",
+    location: crate::ansi_color_rgb!(97, 175, 239),
+    location_file: crate::ansi_color_rgb!(97, 175, 239),
+    location_line: crate::ansi_color_rgb!(97, 175, 239),
+    location_column: crate::ansi_color_rgb!(97, 175, 239),
+    fn_keyword: crate::ansi_color_rgb!(209, 154, 102),
+    func_name: crate::ansi_color_rgb!(229, 192, 123),
+    func_braces: "\u{1b}[0m",
+    arg_name: "\u{1b}[0m",
+    punct: "\u{1b}[0m",
+    value_braces: "\u{1b}[0m",
+    ident: crate::ansi_color_rgb!(224, 108, 117),
+    item: crate::ansi_color_rgb!(229, 192, 123),
+    boolean: "\u{1b}[1;38;2;209;154;102m",
+    number: crate::ansi_color_rgb!(86, 182, 194),
+    quoted: crate::ansi_color_rgb!(152, 195, 121),
+    escaped: crate::ansi_color_rgb!(198, 120, 221),
+};
+
+/// The default ANSI color scheme for terminals with a light background.
+///
+/// It uses the basic 16-color palette avoiding yellow and other bright colors
+/// which are hard to read on a white background. It is used instead of the
+/// color depth specific default color scheme if colorization is enabled, no
+/// custom color scheme is set, and the terminal background is set to
+/// [`TerminalBackground::Light`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{TerminalBackground, LIGHT_BACKGROUND_COLOR_SCHEME};
+///
+/// unwind_context::set_terminal_background(TerminalBackground::Light);
+/// # #[cfg(not(feature = "custom-default-colors"))]
+/// assert_eq!(
+///     unwind_context::get_default_color_scheme(),
+///     &LIGHT_BACKGROUND_COLOR_SCHEME
+/// );
+/// # unwind_context::set_terminal_background(TerminalBackground::Dark);
+/// ```
+pub static LIGHT_BACKGROUND_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+    default: "\u{1b}[0m",
+    location: "\u{1b}[0;34m",
+    location_file: "\u{1b}[0;34m",
+    location_line: "\u{1b}[0;34m",
+    location_column: "\u{1b}[0;34m",
+    fn_keyword: "\u{1b}[0;35m",
+    func_name: "\u{1b}[0;1;34m",
+    func_braces: "\u{1b}[0m",
+    arg_name: "\u{1b}[0m",
+    punct: "\u{1b}[0m",
+    value_braces: "\u{1b}[0m",
+    ident: "\u{1b}[0;31m",
+    item: "\u{1b}[0;35m",
+    boolean: "\u{1b}[0;1;31m",
+    number: "\u{1b}[0;36m",
+    quoted: "\u{1b}[0;32m",
+    escaped: "\u{1b}[0;35m",
+};
+
+/// The terminal background brightness, which is used to choose the default
+/// ANSI color scheme.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::TerminalBackground;
+///
+/// assert_eq!(TerminalBackground::default(), TerminalBackground::Dark);
+/// assert_eq!(
+///     TerminalBackground::from_colorfgbg("0;15"),
+///     Some(TerminalBackground::Light)
+/// );
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum TerminalBackground {
+    /// A dark terminal background.
+    #[default]
+    Dark,
+    /// A light terminal background.
+    Light,
+}
+
+impl TerminalBackground {
+    /// Parses the terminal background from a `COLORFGBG` environment variable
+    /// value like `15;0` or `0;default;15` set by some terminals, like `rxvt`
+    /// or `Konsole`.
+    ///
+    /// The last component is the background color index of the basic 16-color
+    /// palette. Returns `None` if the value can not be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::TerminalBackground;
+    ///
+    /// assert_eq!(
+    ///     TerminalBackground::from_colorfgbg("15;0"),
+    ///     Some(TerminalBackground::Dark)
+    /// );
+    /// assert_eq!(
+    ///     TerminalBackground::from_colorfgbg("0;default;15"),
+    ///     Some(TerminalBackground::Light)
+    /// );
+    /// assert_eq!(TerminalBackground::from_colorfgbg("default;default"), None);
+    /// ```
+    #[must_use]
+    pub fn from_colorfgbg(value: &str) -> Option<Self> {
+        let background = value.rsplit(';').next()?.trim().parse::<u8>().ok()?;
+        match background {
+            0..=6 | 8 => Some(Self::Dark),
+            7 | 9..=15 => Some(Self::Light),
+            _ => None,
+        }
+    }
+}
+
+/// The number of colors supported by the terminal, which is used to choose the
+/// default ANSI color scheme.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{ColorDepth, DEFAULT_DEFAULT_COLOR_SCHEME};
+///
+/// assert_eq!(ColorDepth::default(), ColorDepth::Basic);
+/// assert_eq!(
+///     ColorDepth::Basic.color_scheme(),
+///     &DEFAULT_DEFAULT_COLOR_SCHEME
+/// );
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum ColorDepth {
+    /// The basic 16-color palette.
+    #[default]
+    Basic,
+    /// The 256-color palette.
+    Ansi256,
+    /// The 24-bit truecolor palette.
+    TrueColor,
+}
+
+impl ColorDepth {
+    /// Returns the default ANSI color scheme for the color depth.
+    #[inline]
+    #[must_use]
+    pub const fn color_scheme(self) -> &'static AnsiColorScheme {
+        match self {
+            Self::Basic => &DEFAULT_DEFAULT_COLOR_SCHEME,
+            Self::Ansi256 => &ANSI_256_COLOR_SCHEME,
+            Self::TrueColor => &TRUECOLOR_COLOR_SCHEME,
+        }
+    }
+
+    pub(crate) const fn to_u8(self) -> u8 {
+        match self {
+            Self::Basic => 0,
+            Self::Ansi256 => 1,
+            Self::TrueColor => 2,
+        }
+    }
+
+    pub(crate) const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::Ansi256,
+            2 => Self::TrueColor,
+            _ => Self::Basic,
+        }
+    }
+}
+
+/// Creates an ANSI escape sequence literal resetting the text style and
+/// setting the foreground color from the 256-color palette.
+///
+/// It can be used to build custom [`AnsiColorScheme`] values in `const` and
+/// `static` items.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::ansi_color_256;
+///
+/// assert_eq!(ansi_color_256!(208), "\u{1b}[0;38;5;208m");
+/// ```
+#[macro_export]
+macro_rules! ansi_color_256 {
+    ($color:literal) => {
+        concat!("\u{1b}[0;38;5;", $color, "m")
+    };
+}
+
+/// Creates an ANSI escape sequence literal resetting the text style and
+/// setting the 24-bit truecolor foreground color.
+///
+/// It can be used to build custom [`AnsiColorScheme`] values in `const` and
+/// `static` items.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::ansi_color_rgb;
+///
+/// assert_eq!(ansi_color_rgb!(255, 128, 0), "\u{1b}[0;38;2;255;128;0m");
+/// ```
+#[macro_export]
+macro_rules! ansi_color_rgb {
+    ($red:literal, $green:literal, $blue:literal) => {
+        concat!("\u{1b}[0;38;2;", $red, ";", $green, ";", $blue, "m")
+    };
+}
+
+/// Creates an ANSI escape sequence literal resetting the text style and
+/// setting the given text attributes and foreground color.
+///
+/// The supported attributes are `bold`, `dim`, `italic`, `underline`,
+/// `reverse`, and `strikethrough`. The foreground color can be set with
+/// `fg = 208` for a 256-color palette color or `fg = (255, 128, 0)` for a
+/// 24-bit truecolor color.
+///
+/// It can be used to build custom [`AnsiColorScheme`] values in `const` and
+/// `static` items, for example with bold function names and dimmed locations.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{ansi_style, AnsiColorScheme, DEFAULT_DEFAULT_COLOR_SCHEME};
+///
+/// static COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+///     location: ansi_style!(dim),
+///     func_name: ansi_style!(bold, fg = 221),
+///     quoted: ansi_style!(italic, fg = (152, 195, 121)),
+///     ..DEFAULT_DEFAULT_COLOR_SCHEME
+/// };
+///
+/// assert_eq!(COLOR_SCHEME.location, "\u{1b}[0;2m");
+/// assert_eq!(COLOR_SCHEME.func_name, "\u{1b}[0;1;38;5;221m");
+/// assert_eq!(COLOR_SCHEME.quoted, "\u{1b}[0;3;38;2;152;195;121m");
+/// assert_eq!(ansi_style!(), "\u{1b}[0m");
+/// ```
+#[macro_export]
+macro_rules! ansi_style {
+    ( $( $attr:ident $( = $value:tt )? ),* $(,)? ) => {
+        concat!("\u{1b}[0", $( $crate::ansi_style_code!($attr $( = $value )?), )* "m")
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! ansi_style_code {
+    (bold) => {
+        ";1"
+    };
+    (dim) => {
+        ";2"
+    };
+    (italic) => {
+        ";3"
+    };
+    (underline) => {
+        ";4"
+    };
+    (reverse) => {
+        ";7"
+    };
+    (strikethrough) => {
+        ";9"
+    };
+    (fg = ($red:literal, $green:literal, $blue:literal)) => {
+        concat!(";38;2;", $red, ";", $green, ";", $blue)
+    };
+    (fg = $color:literal) => {
+        concat!(";38;5;", $color)
+    };
+}
+
 #[doc(hidden)]
 #[deprecated(since = "0.2.0", note = "renamed to `DEFAULT_DEFAULT_COLOR_SCHEME`.")]
 pub use DEFAULT_DEFAULT_COLOR_SCHEME as DEFAULT_ANSI_COLOR_SCHEME;
@@ -34,15 +365,24 @@ pub use DEFAULT_DEFAULT_COLOR_SCHEME as DEFAULT_ANSI_COLOR_SCHEME;
 /// A structure representing an ANSI color scheme used by [`DebugAnsiColored`]
 /// formatter.
 ///
+/// Each field holds an arbitrary ANSI escape sequence, so besides foreground
+/// colors it can also set text attributes like bold or dimmed text. The
+/// [`ansi_style`] macro can be used to build such sequences.
+///
 /// # Examples
 #[cfg_attr(feature = "custom-default-colors", doc = "```rust")]
 #[cfg_attr(not(feature = "custom-default-colors"), doc = "```rust,compile_fail")]
 /// unwind_context::set_default_color_scheme(&unwind_context::AnsiColorScheme {
 ///     default: "\u{1b}[0m",
 ///     location: "\u{1b}[31m",
+///     location_file: "\u{1b}[31m",
+///     location_line: "\u{1b}[1;31m",
+///     location_column: "\u{1b}[31m",
 ///     fn_keyword: "\u{1b}[32m",
 ///     func_name: "\u{1b}[33m",
 ///     func_braces: "\u{1b}[34m",
+///     arg_name: "\u{1b}[95m",
+///     punct: "\u{1b}[90m",
 ///     value_braces: "\u{1b}[35m",
 ///     ident: "\u{1b}[36m",
 ///     item: "\u{1b}[37m",
@@ -54,18 +394,50 @@ pub use DEFAULT_DEFAULT_COLOR_SCHEME as DEFAULT_ANSI_COLOR_SCHEME;
 #[doc = "```"]
 #[doc = ""]
 /// [`DebugAnsiColored`]: crate::DebugAnsiColored
+/// [`ansi_style`]: crate::ansi_style
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
 pub struct AnsiColorScheme {
     /// The ANSI escape sequence used for default text styling.
     pub default: &'static str,
     /// The ANSI escape sequence used before code location.
     pub location: &'static str,
+    /// The ANSI escape sequence used before the file path of a code location.
+    ///
+    /// It is followed by the `location` one. If it is equal to the `location`
+    /// one, the file path is written without any additional ANSI escape
+    /// sequences.
+    pub location_file: &'static str,
+    /// The ANSI escape sequence used before the line number of a code
+    /// location.
+    ///
+    /// It is followed by the `location` one. If it is equal to the `location`
+    /// one, the line number is written without any additional ANSI escape
+    /// sequences.
+    pub location_line: &'static str,
+    /// The ANSI escape sequence used before the column number of a code
+    /// location.
+    ///
+    /// It is followed by the `location` one. If it is equal to the `location`
+    /// one, the column number is written without any additional ANSI escape
+    /// sequences.
+    pub location_column: &'static str,
     /// The ANSI escape sequence used before `fn` keyword.
     pub fn_keyword: &'static str,
     /// The ANSI escape sequence used before function name.
     pub func_name: &'static str,
     /// The ANSI escape sequence used before function braces.
     pub func_braces: &'static str,
+    /// The ANSI escape sequence used before argument names.
+    ///
+    /// If it is equal to the `default` one, argument names are written without
+    /// any additional ANSI escape sequences.
+    pub arg_name: &'static str,
+    /// The ANSI escape sequence used before punctuation characters like commas
+    /// and colons separating arguments, fields and items.
+    ///
+    /// If it is equal to the `default` one, punctuation characters are written
+    /// without any additional ANSI escape sequences.
+    pub punct: &'static str,
     /// The ANSI escape sequence used before any value braces.
     pub value_braces: &'static str,
     /// The ANSI escape sequence used before identifiers.
@@ -0,0 +1,133 @@
+#[cfg(feature = "std")]
+use std::fs::File;
+#[cfg(feature = "std")]
+use std::io::{IsTerminal, Stderr, StderrLock, Stdout, StdoutLock};
+
+use crate::AnsiColorScheme;
+#[cfg(feature = "std")]
+use crate::{get_default_color_scheme, get_default_writer, DefaultWriter, IoSink, StderrWriter};
+
+/// A choice of the color scheme of a context scope guard resolved with the
+/// guard sink when the guard is created.
+///
+/// It is implemented for `Option<&'static AnsiColorScheme>`, which is used as
+/// is, and for [`AutoColorScheme`], which chooses the color scheme depending
+/// on whether the guard writer is a terminal.
+pub trait ColorSchemeChoice<S> {
+    /// Returns the color scheme used by a context scope guard with the given
+    /// sink.
+    fn resolve(self, sink: &S) -> Option<&'static AnsiColorScheme>;
+}
+
+impl<S> ColorSchemeChoice<S> for Option<&'static AnsiColorScheme> {
+    #[inline]
+    fn resolve(self, _sink: &S) -> Option<&'static AnsiColorScheme> {
+        self
+    }
+}
+
+/// A color scheme choice that uses the default color scheme if the context
+/// scope guard writer is a terminal and disables colorization otherwise,
+/// regardless of the global colorization setting.
+///
+/// It is usually used as the `color_scheme = Auto` parameter of the
+/// [`unwind_context_with_io`] macro, which is supported for writers
+/// implementing [`TerminalWriter`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context_with_io;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!(
+///         (fn(foo, bar)),
+///         writer = std::io::stdout(),
+///         color_scheme = Auto,
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct AutoColorScheme;
+
+#[cfg(feature = "std")]
+impl<W: TerminalWriter> ColorSchemeChoice<IoSink<W>> for AutoColorScheme {
+    #[inline]
+    fn resolve(self, sink: &IoSink<W>) -> Option<&'static AnsiColorScheme> {
+        sink.0.writes_to_terminal().then(get_default_color_scheme)
+    }
+}
+
+/// A writer that can tell whether it writes to a terminal.
+///
+/// It is used by the [`AutoColorScheme`] color scheme choice. It is similar to
+/// [`std::io::IsTerminal`], which can not be implemented outside of the
+/// standard library.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::TerminalWriter;
+///
+/// if std::io::stderr().writes_to_terminal() {
+///     eprintln!("stderr is a terminal");
+/// }
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub trait TerminalWriter {
+    /// Returns `true` if the writer writes to a terminal.
+    fn writes_to_terminal(&self) -> bool;
+}
+
+#[cfg(feature = "std")]
+macro_rules! impl_terminal_writer_for_is_terminal {
+    ( $( $ty:ty ),* $(,)? ) => {
+        $(
+            impl TerminalWriter for $ty {
+                #[inline]
+                fn writes_to_terminal(&self) -> bool {
+                    IsTerminal::is_terminal(self)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "std")]
+impl_terminal_writer_for_is_terminal!(Stdout, Stderr, StdoutLock<'_>, StderrLock<'_>, File);
+
+#[cfg(feature = "std")]
+impl TerminalWriter for StderrWriter {
+    #[inline]
+    fn writes_to_terminal(&self) -> bool {
+        std::io::stderr().is_terminal()
+    }
+}
+
+#[cfg(feature = "std")]
+impl TerminalWriter for DefaultWriter {
+    #[inline]
+    fn writes_to_terminal(&self) -> bool {
+        get_default_writer().is_none() && StderrWriter.writes_to_terminal()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: TerminalWriter + ?Sized> TerminalWriter for &W {
+    #[inline]
+    fn writes_to_terminal(&self) -> bool {
+        (**self).writes_to_terminal()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: TerminalWriter + ?Sized> TerminalWriter for &mut W {
+    #[inline]
+    fn writes_to_terminal(&self) -> bool {
+        (**self).writes_to_terminal()
+    }
+}
@@ -1,6 +1,6 @@
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 
-use crate::AnsiColorScheme;
+use crate::{AnsiColorScheme, ContextVisitor};
 
 /// An utility alternative [`core::fmt::Debug`] trait which can used for colored
 /// context formatting.
@@ -12,16 +12,19 @@ use crate::AnsiColorScheme;
 /// # Examples
 ///
 /// ```rust
-/// use unwind_context::{are_colors_enabled, unwind_context, DebugAnsiColored};
+/// use core::fmt::{Debug, Write};
 ///
-/// fn fmt_example(writer: core::fmt::Write, value: impl Debug + DebugAnsiColored) {
+/// use unwind_context::{are_colors_enabled, AnsiColored, DebugAnsiColored};
+///
+/// fn fmt_example(writer: &mut impl Write, value: impl Debug + DebugAnsiColored) {
 ///     if are_colors_enabled() {
 ///         let _ = writeln!(
+///             writer,
 ///             "{:?}",
 ///             AnsiColored::new(value, &unwind_context::DEFAULT_DEFAULT_COLOR_SCHEME)
 ///         );
 ///     } else {
-///         let _ = writeln!("{value:?}");
+///         let _ = writeln!(writer, "{value:?}");
 ///     }
 /// }
 /// ```
@@ -40,6 +43,16 @@ pub trait DebugAnsiColored {
         f: &mut Formatter<'_>,
         color_scheme: &'static AnsiColorScheme,
     ) -> FmtResult;
+
+    /// Visits the function name and the argument names and values of the
+    /// value with a given [`ContextVisitor`].
+    ///
+    /// It is used to record context frames in a structured form. The default
+    /// implementation visits nothing.
+    #[inline]
+    fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        let _ = visitor;
+    }
 }
 
 /// An utility wrapper type is used to forward value [`core::fmt::Debug`]
@@ -77,7 +90,7 @@ impl<T> AnsiColored<T> {
     /// [`build_unwind_context_data`]: crate::build_unwind_context_data
     /// [`unwind_context`]: crate::unwind_context
     #[inline]
-    pub fn new(value: T, color_scheme: &'static AnsiColorScheme) -> Self {
+    pub const fn new(value: T, color_scheme: &'static AnsiColorScheme) -> Self {
         Self {
             value,
             color_scheme,
@@ -107,4 +120,9 @@ where
     ) -> FmtResult {
         DebugAnsiColored::fmt_colored(&**self, f, color_scheme)
     }
+
+    #[inline]
+    fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        DebugAnsiColored::visit_context(&**self, visitor);
+    }
 }
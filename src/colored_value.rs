@@ -0,0 +1,777 @@
+use core::fmt::{Arguments, Debug, Result as FmtResult, Write as FmtWrite};
+
+use crate::AnsiColorScheme;
+
+/// A trait for values that can be colorized according to their structure.
+///
+/// By default argument values are formatted with [`core::fmt::Debug`] and the
+/// resulting text is colorized with a simple heuristic lexer. Values
+/// implementing this trait are colorized directly by their structure instead,
+/// which is more accurate and avoids re-lexing the formatted text. The output
+/// without colors is still produced by [`core::fmt::Debug`], so both outputs
+/// should contain the same text.
+///
+/// Macros like [`build_unwind_context_data`] or [`unwind_context`] use this
+/// trait for argument values whose types are known to implement it, and fall
+/// back to the heuristic lexer otherwise, for example for values of generic
+/// types.
+///
+/// It is implemented for primitive types, strings, tuples, arrays, slices,
+/// [`Option`], [`Result`], and `alloc` and `std` collections of such values.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::fmt::{Debug, Formatter, Result as FmtResult};
+///
+/// use unwind_context::{ColoredValue, ColoredValueFormatter, ColoredValueStyle};
+///
+/// struct Meters(f64);
+///
+/// impl Debug for Meters {
+///     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+///         write!(f, "{:?}m", self.0)
+///     }
+/// }
+///
+/// impl ColoredValue for Meters {
+///     fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+///         f.write_styled_fmt(ColoredValueStyle::Number, format_args!("{:?}m", self.0))
+///     }
+/// }
+/// ```
+///
+/// [`build_unwind_context_data`]: crate::build_unwind_context_data
+/// [`unwind_context`]: crate::unwind_context
+pub trait ColoredValue: Debug {
+    /// Formats the value with colorization using a given
+    /// [`ColoredValueFormatter`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the value formatting fails.
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult;
+}
+
+/// A style of a colorized value fragment corresponding to an
+/// [`AnsiColorScheme`] field.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum ColoredValueStyle {
+    /// Default style, corresponds to the `default` field.
+    Default,
+    /// Identifier style, corresponds to the `ident` field.
+    Ident,
+    /// Item style, corresponds to the `item` field.
+    Item,
+    /// Boolean style, corresponds to the `boolean` field.
+    Boolean,
+    /// Number style, corresponds to the `number` field.
+    Number,
+    /// Quoted string style, corresponds to the `quoted` field.
+    Quoted,
+    /// Escaped character style, corresponds to the `escaped` field.
+    Escaped,
+    /// Brace style, corresponds to the `value_braces` field.
+    Brace,
+    /// Punctuation style, corresponds to the `punct` field.
+    Punct,
+}
+
+impl ColoredValueStyle {
+    /// Returns the ANSI escape sequence of the style in a given color scheme.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{ColoredValueStyle, DEFAULT_DEFAULT_COLOR_SCHEME};
+    ///
+    /// assert_eq!(
+    ///     ColoredValueStyle::Number.ansi_style(&DEFAULT_DEFAULT_COLOR_SCHEME),
+    ///     DEFAULT_DEFAULT_COLOR_SCHEME.number
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn ansi_style(self, color_scheme: &AnsiColorScheme) -> &'static str {
+        match self {
+            Self::Default => color_scheme.default,
+            Self::Ident => color_scheme.ident,
+            Self::Item => color_scheme.item,
+            Self::Boolean => color_scheme.boolean,
+            Self::Number => color_scheme.number,
+            Self::Quoted => color_scheme.quoted,
+            Self::Escaped => color_scheme.escaped,
+            Self::Brace => color_scheme.value_braces,
+            Self::Punct => color_scheme.punct,
+        }
+    }
+}
+
+/// A formatter used by [`ColoredValue`] implementations to write colorized
+/// value fragments.
+///
+/// ANSI escape sequences are only written when the style changes, and the
+/// punctuation is written with the default style if the `punct` style is equal
+/// to the `default` one, so the output is the same as the heuristic lexer
+/// produces for the same text.
+pub struct ColoredValueFormatter<'a> {
+    writer: &'a mut dyn FmtWrite,
+    style: ColoredValueStyle,
+    color_scheme: &'static AnsiColorScheme,
+}
+
+impl<'a> ColoredValueFormatter<'a> {
+    /// Creates a new `ColoredValueFormatter` writing to the given writer with
+    /// the given color scheme.
+    ///
+    /// The writer is expected to be in the default style.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{ColoredValue, ColoredValueFormatter, DEFAULT_DEFAULT_COLOR_SCHEME};
+    ///
+    /// let mut output = String::new();
+    /// let mut f = ColoredValueFormatter::new(&mut output, &DEFAULT_DEFAULT_COLOR_SCHEME);
+    /// (1, "a").fmt_colored_value(&mut f).unwrap();
+    /// f.finish().unwrap();
+    /// ```
+    #[inline]
+    pub fn new(writer: &'a mut dyn FmtWrite, color_scheme: &'static AnsiColorScheme) -> Self {
+        Self {
+            writer,
+            style: ColoredValueStyle::Default,
+            color_scheme,
+        }
+    }
+
+    /// Returns the color scheme used by the formatter.
+    #[inline]
+    #[must_use]
+    pub fn color_scheme(&self) -> &'static AnsiColorScheme {
+        self.color_scheme
+    }
+
+    /// Writes a string slice with the given style.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying writer fails.
+    #[inline]
+    pub fn write_styled(&mut self, style: ColoredValueStyle, s: &str) -> FmtResult {
+        self.set_style(style)?;
+        self.writer.write_str(s)
+    }
+
+    /// Writes formatted arguments with the given style.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the arguments formatting or the
+    /// underlying writer fails.
+    #[inline]
+    pub fn write_styled_fmt(&mut self, style: ColoredValueStyle, args: Arguments<'_>) -> FmtResult {
+        self.set_style(style)?;
+        self.writer.write_fmt(args)
+    }
+
+    /// Switches the writer back to the default style.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying writer fails.
+    #[inline]
+    pub fn finish(&mut self) -> FmtResult {
+        self.set_style(ColoredValueStyle::Default)
+    }
+
+    fn set_style(&mut self, style: ColoredValueStyle) -> FmtResult {
+        let style = if style == ColoredValueStyle::Punct
+            && self.color_scheme.punct == self.color_scheme.default
+        {
+            ColoredValueStyle::Default
+        } else {
+            style
+        };
+        if self.style != style {
+            self.writer.write_str(style.ansi_style(self.color_scheme))?;
+            self.style = style;
+        }
+        Ok(())
+    }
+
+    fn write_separator(&mut self) -> FmtResult {
+        self.write_styled(ColoredValueStyle::Punct, ",")?;
+        self.write_styled(ColoredValueStyle::Default, " ")
+    }
+
+    fn write_seq<'v, T, I>(&mut self, open: &str, items: I, close: &str) -> FmtResult
+    where
+        T: 'v + ColoredValue + ?Sized,
+        I: IntoIterator<Item = &'v T>,
+    {
+        self.write_styled(ColoredValueStyle::Brace, open)?;
+        for (index, item) in items.into_iter().enumerate() {
+            if index > 0 {
+                self.write_separator()?;
+            }
+            item.fmt_colored_value(self)?;
+        }
+        self.write_styled(ColoredValueStyle::Brace, close)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn write_map<'v, K, V, I>(&mut self, entries: I) -> FmtResult
+    where
+        K: 'v + ColoredValue,
+        V: 'v + ColoredValue,
+        I: IntoIterator<Item = (&'v K, &'v V)>,
+    {
+        self.write_styled(ColoredValueStyle::Brace, "{")?;
+        for (index, (key, value)) in entries.into_iter().enumerate() {
+            if index > 0 {
+                self.write_separator()?;
+            }
+            key.fmt_colored_value(self)?;
+            self.write_styled(ColoredValueStyle::Punct, ":")?;
+            self.write_styled(ColoredValueStyle::Default, " ")?;
+            value.fmt_colored_value(self)?;
+        }
+        self.write_styled(ColoredValueStyle::Brace, "}")
+    }
+
+    fn write_quoted(&mut self, quote: char, s: &str) -> FmtResult {
+        let mut buf = [0; 4];
+        let quote_str = quote.encode_utf8(&mut buf);
+        self.write_styled(ColoredValueStyle::Quoted, quote_str)?;
+        let mut start = 0;
+        for (offset, ch) in s.char_indices() {
+            // Only the quote character used as a delimiter is escaped.
+            let is_escaped = match ch {
+                '"' | '\'' => ch == quote,
+                ch => ch.escape_debug().len() > 1,
+            };
+            if is_escaped {
+                self.write_plain_quoted(s.get(start..offset))?;
+                self.write_styled_fmt(
+                    ColoredValueStyle::Escaped,
+                    format_args!("{}", ch.escape_debug()),
+                )?;
+                start = offset.saturating_add(ch.len_utf8());
+            }
+        }
+        self.write_plain_quoted(s.get(start..))?;
+        self.write_styled(ColoredValueStyle::Quoted, quote_str)
+    }
+
+    fn write_plain_quoted(&mut self, s: Option<&str>) -> FmtResult {
+        match s {
+            Some(s) if !s.is_empty() => self.write_styled(ColoredValueStyle::Quoted, s),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Debug for ColoredValueFormatter<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> FmtResult {
+        f.debug_struct("ColoredValueFormatter")
+            .field("style", &self.style)
+            .field("color_scheme", &self.color_scheme)
+            .finish_non_exhaustive()
+    }
+}
+
+macro_rules! impl_colored_value_with_debug {
+    ( $style:ident: $( $ty:ty ),* $(,)? ) => {
+        $(
+            impl ColoredValue for $ty {
+                #[inline]
+                fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+                    f.write_styled_fmt(ColoredValueStyle::$style, format_args!("{self:?}"))
+                }
+            }
+        )*
+    };
+}
+
+impl_colored_value_with_debug!(
+    Number: i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64,
+);
+impl_colored_value_with_debug!(Boolean: bool);
+
+impl ColoredValue for char {
+    #[inline]
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+        let mut buf = [0; 4];
+        f.write_quoted('\'', self.encode_utf8(&mut buf))
+    }
+}
+
+impl ColoredValue for str {
+    #[inline]
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+        f.write_quoted('"', self)
+    }
+}
+
+impl ColoredValue for () {
+    #[inline]
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+        f.write_styled(ColoredValueStyle::Brace, "()")
+    }
+}
+
+impl<T> ColoredValue for &T
+where
+    T: ColoredValue + ?Sized,
+{
+    #[inline]
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+        ColoredValue::fmt_colored_value(&**self, f)
+    }
+}
+
+impl<T> ColoredValue for &mut T
+where
+    T: ColoredValue + ?Sized,
+{
+    #[inline]
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+        ColoredValue::fmt_colored_value(&**self, f)
+    }
+}
+
+impl<T> ColoredValue for [T]
+where
+    T: ColoredValue,
+{
+    #[inline]
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+        f.write_seq("[", self, "]")
+    }
+}
+
+impl<T, const N: usize> ColoredValue for [T; N]
+where
+    T: ColoredValue,
+{
+    #[inline]
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+        f.write_seq("[", self, "]")
+    }
+}
+
+impl<T> ColoredValue for Option<T>
+where
+    T: ColoredValue,
+{
+    #[inline]
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+        match self {
+            Some(value) => {
+                f.write_styled(ColoredValueStyle::Item, "Some")?;
+                f.write_seq("(", [value], ")")
+            }
+            None => f.write_styled(ColoredValueStyle::Item, "None"),
+        }
+    }
+}
+
+impl<T, E> ColoredValue for Result<T, E>
+where
+    T: ColoredValue,
+    E: ColoredValue,
+{
+    #[inline]
+    fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+        match self {
+            Ok(value) => {
+                f.write_styled(ColoredValueStyle::Item, "Ok")?;
+                f.write_seq("(", [value], ")")
+            }
+            Err(err) => {
+                f.write_styled(ColoredValueStyle::Item, "Err")?;
+                f.write_seq("(", [err], ")")
+            }
+        }
+    }
+}
+
+macro_rules! impl_colored_value_for_tuple {
+    ( $first:ident $( $rest:ident )* ) => {
+        impl<$first, $( $rest ),*> ColoredValue for ($first, $( $rest, )*)
+        where
+            $first: ColoredValue,
+            $( $rest: ColoredValue, )*
+        {
+            #[inline]
+            #[allow(non_snake_case)]
+            fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+                let ($first, $( $rest, )*) = self;
+                f.write_styled(ColoredValueStyle::Brace, "(")?;
+                $first.fmt_colored_value(f)?;
+                $(
+                    f.write_separator()?;
+                    $rest.fmt_colored_value(f)?;
+                )*
+                if impl_colored_value_for_tuple!(@is_single $( $rest )*) {
+                    f.write_styled(ColoredValueStyle::Punct, ",")?;
+                }
+                f.write_styled(ColoredValueStyle::Brace, ")")
+            }
+        }
+        impl_colored_value_for_tuple!( $( $rest )* );
+    };
+    ( @is_single ) => {
+        true
+    };
+    ( @is_single $( $rest:ident )+ ) => {
+        false
+    };
+    () => {};
+}
+
+impl_colored_value_for_tuple!(A B C D E F G H I J K L);
+
+#[cfg(feature = "alloc")]
+mod alloc_impls {
+    use alloc::boxed::Box;
+    use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use core::fmt::Result as FmtResult;
+
+    use crate::{ColoredValue, ColoredValueFormatter};
+
+    impl ColoredValue for String {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            f.write_quoted('"', self)
+        }
+    }
+
+    impl<T> ColoredValue for Box<T>
+    where
+        T: ColoredValue + ?Sized,
+    {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            ColoredValue::fmt_colored_value(&**self, f)
+        }
+    }
+
+    impl<T> ColoredValue for Rc<T>
+    where
+        T: ColoredValue + ?Sized,
+    {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            ColoredValue::fmt_colored_value(&**self, f)
+        }
+    }
+
+    impl<T> ColoredValue for Arc<T>
+    where
+        T: ColoredValue + ?Sized,
+    {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            ColoredValue::fmt_colored_value(&**self, f)
+        }
+    }
+
+    impl<T> ColoredValue for Vec<T>
+    where
+        T: ColoredValue,
+    {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            f.write_seq("[", self, "]")
+        }
+    }
+
+    impl<T> ColoredValue for VecDeque<T>
+    where
+        T: ColoredValue,
+    {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            f.write_seq("[", self, "]")
+        }
+    }
+
+    impl<T> ColoredValue for BTreeSet<T>
+    where
+        T: ColoredValue,
+    {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            f.write_seq("{", self, "}")
+        }
+    }
+
+    impl<K, V> ColoredValue for BTreeMap<K, V>
+    where
+        K: ColoredValue,
+        V: ColoredValue,
+    {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            f.write_map(self)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+mod std_impls {
+    use core::fmt::Result as FmtResult;
+    use std::collections::{HashMap, HashSet};
+
+    use crate::{ColoredValue, ColoredValueFormatter};
+
+    impl<T, S> ColoredValue for HashSet<T, S>
+    where
+        T: ColoredValue,
+    {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            f.write_seq("{", self, "}")
+        }
+    }
+
+    impl<K, V, S> ColoredValue for HashMap<K, V, S>
+    where
+        K: ColoredValue,
+        V: ColoredValue,
+    {
+        #[inline]
+        fn fmt_colored_value(&self, f: &mut ColoredValueFormatter<'_>) -> FmtResult {
+            f.write_map(self)
+        }
+    }
+}
+
+/// An utility type used by macros to check whether an argument value
+/// implements [`ColoredValue`].
+///
+/// This type is not intended to be used directly.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct ColoredValueProbe<'a, T>(pub &'a T);
+
+/// An utility type used by macros to build arguments colorized with
+/// [`ColoredValue`].
+///
+/// This type is not intended to be used directly.
+#[doc(hidden)]
+#[derive(Copy, Clone, Debug)]
+pub struct ColoredValueArgBuilder;
+
+/// An utility type used by macros to build arguments colorized with the
+/// heuristic lexer.
+///
+/// This type is not intended to be used directly.
+#[doc(hidden)]
+#[derive(Copy, Clone, Debug)]
+pub struct DebugValueArgBuilder;
+
+/// An utility trait used by macros to select [`ColoredValueArgBuilder`] for
+/// values implementing [`ColoredValue`].
+///
+/// This trait is not intended to be used directly.
+#[doc(hidden)]
+pub trait ColoredValueArgSelector {
+    #[inline]
+    fn arg_builder(&self) -> ColoredValueArgBuilder {
+        ColoredValueArgBuilder
+    }
+}
+
+impl<T> ColoredValueArgSelector for ColoredValueProbe<'_, T> where T: ColoredValue {}
+
+/// An utility trait used by macros to select [`DebugValueArgBuilder`] for
+/// values not implementing [`ColoredValue`].
+///
+/// This trait is not intended to be used directly.
+#[doc(hidden)]
+pub trait DebugValueArgSelector {
+    #[inline]
+    fn arg_builder(&self) -> DebugValueArgBuilder {
+        DebugValueArgBuilder
+    }
+}
+
+impl<T> DebugValueArgSelector for &ColoredValueProbe<'_, T> {}
+
+impl ColoredValueArgBuilder {
+    #[inline]
+    pub const fn build<T>(
+        self,
+        name: Option<&'static str>,
+        value: T,
+    ) -> crate::UnwindContextColoredArg<T> {
+        crate::UnwindContextColoredArg::new(name, value)
+    }
+}
+
+impl DebugValueArgBuilder {
+    #[inline]
+    pub const fn build<T>(
+        self,
+        name: Option<&'static str>,
+        value: T,
+    ) -> crate::UnwindContextArg<T> {
+        crate::UnwindContextArg::new(name, value)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::fmt::Debug;
+    use std::boxed::Box;
+    use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+    use std::format;
+    use std::string::String;
+    use std::vec::Vec;
+
+    use crate::test_common::{colored_arg, TEST_COLOR_SCHEME};
+    use crate::test_util::debug_fmt;
+    use crate::{
+        build_unwind_context_data, AnsiColorScheme, AnsiColored, ColoredValue,
+        UnwindContextColoredArg,
+    };
+
+    static EMPTY_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+        default: "",
+        location: "",
+        location_file: "",
+        location_line: "",
+        location_column: "",
+        fn_keyword: "",
+        func_name: "",
+        func_braces: "",
+        arg_name: "",
+        punct: "",
+        value_braces: "",
+        ident: "",
+        item: "",
+        boolean: "",
+        number: "",
+        quoted: "",
+        escaped: "",
+    };
+
+    fn structured<T: ColoredValue>(value: T, color_scheme: &'static AnsiColorScheme) -> String {
+        format!(
+            "{:?}",
+            AnsiColored::new(UnwindContextColoredArg::new(None, value), color_scheme)
+        )
+    }
+
+    #[track_caller]
+    fn check_same_as_lexer<T: ColoredValue + Clone>(value: T) {
+        assert_eq!(
+            structured(value.clone(), &EMPTY_COLOR_SCHEME),
+            format!("{value:?}")
+        );
+        assert_eq!(
+            structured(value.clone(), &TEST_COLOR_SCHEME),
+            format!("{:?}", colored_arg(None, value))
+        );
+    }
+
+    #[test]
+    fn test_colored_value_same_as_lexer() {
+        check_same_as_lexer(123_u8);
+        check_same_as_lexer(-45_i64);
+        check_same_as_lexer(1.5_f32);
+        check_same_as_lexer(-0.0_f64);
+        check_same_as_lexer(1e-9_f64);
+        check_same_as_lexer(true);
+        check_same_as_lexer('a');
+        check_same_as_lexer('\'');
+        check_same_as_lexer('"');
+        check_same_as_lexer("");
+        check_same_as_lexer("bar\n-\"-'-\"bar");
+        check_same_as_lexer("\u{1f600}\u{7f}\u{301}a\u{301}\\\\");
+        check_same_as_lexer(String::from("foo"));
+        check_same_as_lexer(());
+        check_same_as_lexer((1,));
+        check_same_as_lexer((1, "a", true, 'b', (), (2, 3)));
+        check_same_as_lexer([1, 2, 3]);
+        check_same_as_lexer(&[1, 2, 3][..]);
+        check_same_as_lexer(Vec::<u32>::new());
+        check_same_as_lexer(Some(Vec::from([Some(1), None])));
+        check_same_as_lexer(Ok::<_, &str>(1));
+        check_same_as_lexer(Err::<u32, _>("error"));
+        check_same_as_lexer(Box::new(VecDeque::from([1, 2])));
+        check_same_as_lexer(BTreeSet::from([1, 2]));
+        check_same_as_lexer(BTreeMap::from([(1, "a"), (2, "b")]));
+        check_same_as_lexer(HashMap::from([(1, [true])]));
+    }
+
+    #[test]
+    fn test_colored_value_fmt() {
+        static PUNCT_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+            punct: "{PUNCT}",
+            ..TEST_COLOR_SCHEME
+        };
+
+        // Values the heuristic lexer colorizes as identifiers or items.
+        assert_eq!(
+            structured((f64::NAN, f32::INFINITY), &TEST_COLOR_SCHEME),
+            "{BRACE}({NUM}NaN{DEF}, {NUM}inf{BRACE}){DEF}"
+        );
+        assert_eq!(
+            structured("r#\"a\"#", &TEST_COLOR_SCHEME),
+            "{QUOT}\"r#{ESC}\\\"{QUOT}a{ESC}\\\"{QUOT}#\"{DEF}"
+        );
+        assert_eq!(
+            structured(BTreeMap::from([(1, 'a')]), &PUNCT_COLOR_SCHEME),
+            "{BRACE}{{NUM}1{PUNCT}:{DEF} {QUOT}'a'{BRACE}}{DEF}"
+        );
+        assert_eq!(
+            structured((1,), &PUNCT_COLOR_SCHEME),
+            "{BRACE}({NUM}1{PUNCT},{BRACE}){DEF}"
+        );
+    }
+
+    #[test]
+    fn test_colored_value_arg_selection() {
+        fn generic_context<T: Debug>(value: T) -> impl Debug + crate::DebugAnsiColored {
+            build_unwind_context_data!(fn func(value))
+        }
+
+        let mut buffer = [0; 128];
+        let value = f64::NAN;
+
+        let context = build_unwind_context_data!(fn func(value, 1.0, "a"));
+        assert_eq!(
+            debug_fmt(&mut buffer, &context),
+            Ok("fn func(value: NaN, 1.0, \"a\")")
+        );
+        assert_eq!(
+            debug_fmt(&mut buffer, &AnsiColored::new(context, &TEST_COLOR_SCHEME)),
+            Ok(concat!(
+                "{FN}fn {FN_NAME}func{FN_BRACE}({DEF}",
+                "{ARG}value{DEF}: {NUM}NaN{DEF}, ",
+                "{NUM}1.0{DEF}, ",
+                "{QUOT}\"a\"{DEF}",
+                "{FN_BRACE}){DEF}"
+            ))
+        );
+
+        // Values of generic types fall back to the heuristic lexer.
+        let context = generic_context(value);
+        assert_eq!(
+            debug_fmt(&mut buffer, &AnsiColored::new(context, &TEST_COLOR_SCHEME)),
+            Ok(concat!(
+                "{FN}fn {FN_NAME}func{FN_BRACE}({DEF}",
+                "{ARG}value{DEF}: {ITEM}NaN{DEF}",
+                "{FN_BRACE}){DEF}"
+            ))
+        );
+    }
+}
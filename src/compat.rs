@@ -0,0 +1,114 @@
+// Shims for compiler capabilities that are newer than the minimum supported
+// Rust version. Capabilities are detected by the build script which sets the
+// corresponding `unwind_context_*` configuration options.
+
+/// Expands the given items only if `core::error::Error` is available.
+macro_rules! if_core_error {
+    ( $( $item:item )* ) => {
+        $( #[cfg(unwind_context_core_error)] $item )*
+    };
+}
+
+/// Expands the given items only if `core::error::Error` is not available.
+macro_rules! if_not_core_error {
+    ( $( $item:item )* ) => {
+        $( #[cfg(not(unwind_context_core_error))] $item )*
+    };
+}
+
+pub(crate) use if_core_error;
+pub(crate) use if_not_core_error;
+
+if_core_error! {
+    /// The `Error` trait, available in `core` since Rust 1.81.
+    pub(crate) use core::error::Error;
+}
+
+if_not_core_error! {
+    /// The `Error` trait, available only in `std` before Rust 1.81.
+    #[cfg(feature = "std")]
+    pub(crate) use std::error::Error;
+}
+
+/// The panic hook information type, named `PanicHookInfo` since Rust 1.81.
+#[cfg(all(feature = "std", unwind_context_panic_hook_info))]
+#[allow(unused_imports)] // Used by feature-gated integrations.
+pub(crate) use std::panic::PanicHookInfo;
+/// The panic hook information type, named `PanicInfo` before Rust 1.81.
+#[cfg(all(feature = "std", not(unwind_context_panic_hook_info)))]
+#[allow(unused_imports)] // Used by feature-gated integrations.
+pub(crate) use std::panic::PanicInfo as PanicHookInfo;
+
+/// The type of a boxed panic hook.
+#[cfg(feature = "std")]
+#[allow(dead_code)] // Used by feature-gated integrations.
+#[allow(clippy::incompatible_msrv)] // `PanicHookInfo` is used only if available.
+pub(crate) type PanicHook = std::boxed::Box<dyn Fn(&PanicHookInfo<'_>) + Sync + Send + 'static>;
+
+/// The lock serializing panic hook replacements made by this crate.
+#[cfg(feature = "std")]
+#[allow(dead_code)] // Used by feature-gated integrations.
+static PANIC_HOOK_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Replaces the panic hook with a new one that may call the previous hook.
+///
+/// This is a fallback for the unstable `std::panic::update_hook` function.
+/// Replacements made by this function are serialized, so concurrently
+/// installed hooks of this crate are never lost. But since the previous hook is
+/// taken and the new one is set in two steps, a hook set concurrently with
+/// `std::panic::set_hook` by other code may still be lost.
+#[cfg(feature = "std")]
+#[allow(dead_code)] // Used by feature-gated integrations.
+#[allow(clippy::incompatible_msrv)] // `PanicHookInfo` is used only if available.
+pub(crate) fn update_panic_hook<F>(hook_fn: F)
+where
+    F: Fn(&PanicHook, &PanicHookInfo<'_>) + Sync + Send + 'static,
+{
+    let _lock = PANIC_HOOK_LOCK
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    let prev = std::panic::take_hook();
+    std::panic::set_hook(std::boxed::Box::new(move |info| hook_fn(&prev, info)));
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use std::panic::catch_unwind;
+    use std::sync::Arc;
+    use std::vec::Vec;
+
+    use super::update_panic_hook;
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_concurrent_update_panic_hook() {
+        const NUM_HOOKS: usize = 16;
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let handles: Vec<_> = (0..NUM_HOOKS)
+            .map(|_| {
+                let calls = Arc::clone(&calls);
+                std::thread::spawn(move || {
+                    update_panic_hook(move |prev, info| {
+                        if std::thread::current().name() == Some("hook-test") {
+                            let _ = calls.fetch_add(1, AtomicOrdering::Relaxed);
+                        }
+                        prev(info);
+                    });
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().is_ok());
+        }
+
+        let result = std::thread::Builder::new()
+            .name("hook-test".into())
+            .spawn(|| catch_unwind(|| panic!("Error")).is_err())
+            .unwrap()
+            .join();
+        assert!(matches!(result, Ok(true)));
+        assert_eq!(calls.load(AtomicOrdering::Relaxed), NUM_HOOKS);
+    }
+}
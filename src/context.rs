@@ -1,7 +1,7 @@
 /// Creates [`UnwindContextWithIo`] with a default writer, panic detector, color
 /// scheme , and given function or scope context.
 ///
-/// It uses [`std::io::stderr`] writer, [`StdPanicDetector`] panic detector, and
+/// It uses [`DefaultWriter`] writer, [`StdPanicDetector`] panic detector, and
 /// a color scheme determined by the [`get_default_color_scheme_if_enabled`]
 /// function. If you want to customize a writer, a panic detector, or a color
 /// scheme, use [`unwind_context_with_io`] or [`unwind_context_with_fmt`].
@@ -88,6 +88,7 @@
 /// [`unwind_context_with_io`]: crate::unwind_context_with_io
 /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
 /// [`UnwindContextWithIo`]: crate::UnwindContextWithIo
+/// [`DefaultWriter`]: crate::DefaultWriter
 /// [`StdPanicDetector`]: crate::StdPanicDetector
 /// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
 /// [`set_colors_enabled`]: crate::set_colors_enabled
@@ -102,17 +103,59 @@ macro_rules! unwind_context {
     ( $( $context:tt )* ) => {
         $crate::unwind_context_with_io!(
             ( $($context)* ),
-            writer = ::std::io::stderr(),
+            writer = $crate::DefaultWriter,
             panic_detector = $crate::StdPanicDetector,
-            color_scheme = $crate::get_default_color_scheme_if_enabled(),
         )
     };
 }
 
+/// Runs the given block inside an unwind context scope guard created with the
+/// given function or scope context.
+///
+/// The context is passed in parentheses like in [`unwind_context_with_io`],
+/// and supports all forms of the [`unwind_context`] macro. The macro expands
+/// to a new scope with a guard binding followed by the block, so the guard can
+/// not be dropped immediately by mistake, like with `unwind_context!(...);`
+/// statement without a binding. The macro evaluates to the value of the block.
+///
+/// It uses [`DefaultWriter`] writer, [`StdPanicDetector`] panic detector, and
+/// a color scheme determined by the [`get_default_color_scheme_if_enabled`]
+/// function, like the [`unwind_context`] macro.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context_scope;
+///
+/// fn func(a: u32, b: &str) -> usize {
+///     let len = unwind_context_scope!((fn(a, b)), {
+///         // ...
+///         b.len()
+///     });
+///     unwind_context_scope!((a, len), {
+///         // ...
+///     });
+///     len
+/// }
+/// ```
+///
+/// [`unwind_context`]: crate::unwind_context
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+/// [`DefaultWriter`]: crate::DefaultWriter
+/// [`StdPanicDetector`]: crate::StdPanicDetector
+/// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
+#[macro_export]
+macro_rules! unwind_context_scope {
+    ( ( $( $context:tt )* ), $body:block $(,)? ) => {{
+        let _ctx = $crate::unwind_context!( $($context)* );
+        $body
+    }};
+}
+
 /// Creates [`UnwindContextWithIo`] with a default writer, panic detector, color
 /// scheme , and given function or scope context in debug builds only.
 ///
-/// It uses [`std::io::stderr`] writer, [`StdPanicDetector`] panic detector, and
+/// It uses [`DefaultWriter`] writer, [`StdPanicDetector`] panic detector, and
 /// a color scheme determined by the [`get_default_color_scheme_if_enabled`]
 /// function. If you want to customize a writer, a panic detector, or a color
 /// scheme, use [`unwind_context_with_io`] or [`unwind_context_with_fmt`].
@@ -140,7 +183,10 @@ macro_rules! unwind_context {
 ///
 /// An optimized build will generate `()` unless `-C debug-assertions` is passed
 /// to the compiler. This makes this macro no-op with the default release
-/// profile.
+/// profile. The `force-debug-context` feature makes it always create the
+/// context scope guard, and the `disable-debug-context` feature makes it always
+/// generate `()`. If both features are enabled, `force-debug-context` takes
+/// precedence.
 ///
 /// There are three forms of this macro:
 /// - Create [`UnwindContextFunc`] with an automatically determined function
@@ -206,6 +252,7 @@ macro_rules! unwind_context {
 /// [`unwind_context_with_io`]: crate::unwind_context_with_io
 /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
 /// [`UnwindContextWithIo`]: crate::UnwindContextWithIo
+/// [`DefaultWriter`]: crate::DefaultWriter
 /// [`StdPanicDetector`]: crate::StdPanicDetector
 /// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
 /// [`set_colors_enabled`]: crate::set_colors_enabled
@@ -221,14 +268,20 @@ macro_rules! debug_unwind_context {
 }
 
 #[doc(hidden)]
-#[cfg(debug_assertions)]
+#[cfg(any(
+    feature = "force-debug-context",
+    all(debug_assertions, not(feature = "disable-debug-context"))
+))]
 #[macro_export]
 macro_rules! debug_unwind_context_impl {
     ( $( $context:tt )* ) => { $crate::unwind_context!( $($context)* ) };
 }
 
 #[doc(hidden)]
-#[cfg(not(debug_assertions))]
+#[cfg(not(any(
+    feature = "force-debug-context",
+    all(debug_assertions, not(feature = "disable-debug-context"))
+)))]
 #[macro_export]
 macro_rules! debug_unwind_context_impl {
     ($($context:tt)*) => {
@@ -265,4 +318,32 @@ mod tests {
         // Only positive cases checked to avoid capturing `stderr`.
         // Negative cases checked separately with `unwind_context_with_io`.
     }
+
+    #[test]
+    fn test_unwind_context_scope() {
+        use core::panic::AssertUnwindSafe;
+        use std::panic::catch_unwind;
+
+        let capture = crate::capture();
+        let foo = 2;
+        let result = unwind_context_scope!((fn func(foo)), { foo * 3 });
+        assert_eq!(result, 6);
+        assert!(capture.take_frames().is_empty());
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            unwind_context_scope!((fn func(foo)), {
+                unwind_context_scope!((foo, "inner"), {
+                    assert_ne!(foo, 2, "scope panic");
+                });
+            });
+        }));
+        assert!(result.is_err());
+
+        let frames = capture.take_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].func_name, None);
+        assert_eq!(frames[0].arg("foo"), Some("2"));
+        assert_eq!(frames[1].func_name, Some("func"));
+        assert_eq!(frames[1].arg("foo"), Some("2"));
+    }
 }
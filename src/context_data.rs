@@ -67,10 +67,65 @@
 /// }
 /// ```
 ///
+/// Any of these forms can be prefixed with `eager:` to format all non-literal
+/// arguments immediately into [`DebugSnapshot`] values, so that the state of
+/// the arguments at the moment of the context creation is printed even if they
+/// are mutated later, for example through interior mutability.
+///
+/// ```rust
+/// use core::cell::Cell;
+///
+/// use unwind_context::build_unwind_context_data;
+///
+/// fn func(a: &Cell<u32>, b: &str) {
+///     let _data = build_unwind_context_data!(eager: fn(a, b));
+///     let _data = build_unwind_context_data!(eager: fn func(a, ..., "step 1"));
+///     let _data = build_unwind_context_data!(eager: a, b);
+///     // ...
+/// }
+/// ```
+///
+/// With the `alloc` feature, any of these forms, including the `eager:` ones,
+/// can also be prefixed with `dyn:` to box the created wrapper into a
+/// type-erased [`UnwindContextDyn`], so that context scope guards with
+/// different arguments share the same type and generated code.
+#[cfg_attr(feature = "alloc", doc = "```rust")]
+#[cfg_attr(not(feature = "alloc"), doc = "```rust,compile_fail")]
+/// use unwind_context::build_unwind_context_data;
+///
+/// fn func(a: u32, b: &str) {
+///     let _data = build_unwind_context_data!(dyn: fn(a, b));
+///     let _data = build_unwind_context_data!(dyn: eager: fn func(a, ...));
+///     let _data = build_unwind_context_data!(dyn: a, b);
+///     // ...
+/// }
+#[doc = "```"]
+#[doc = ""]
 /// [`UnwindContextFunc`]: crate::UnwindContextFunc
 /// [`UnwindContextArgs`]: crate::UnwindContextArgs
+/// [`DebugSnapshot`]: crate::DebugSnapshot
+/// [`UnwindContextDyn`]: crate::UnwindContextDyn
 #[macro_export]
 macro_rules! build_unwind_context_data {
+    ( dyn: $( $rest:tt )* ) => {
+        $crate::UnwindContextDyn::new( $crate::build_unwind_context_data!( $($rest)* ) )
+    };
+    ( eager: fn $name:ident ( $( $args:tt )* ) ) => {
+        $crate::build_unwind_context_data_impl!(
+            @eager_fn ::core::stringify!($name), $($args)*
+        )
+    };
+    ( eager: fn $name:literal ( $( $args:tt )* ) ) => {
+        $crate::build_unwind_context_data_impl!( @eager_fn $name, $($args)* )
+    };
+    ( eager: fn ( $( $args:tt )* ) ) => {
+        $crate::build_unwind_context_data_impl!( @eager_fn $crate::func_name!(), $($args)* )
+    };
+    ( eager: $( $vars:tt )* ) => {
+        $crate::UnwindContextArgs::new(
+            $crate::build_unwind_context_data_impl!( @eager_args $($vars)* )
+        )
+    };
     ( fn $name:ident ( $( $args:tt )* ) ) => {
         $crate::build_unwind_context_data_impl!( @fn ::core::stringify!($name), $($args)* )
     };
@@ -104,19 +159,60 @@ macro_rules! build_unwind_context_data_impl {
     };
     ( @args $value:literal $(, $( $args:tt )* )? ) => {
         (
-            $crate::UnwindContextArg::new( None, $value ),
+            $crate::build_unwind_context_data_impl!( @arg None, $value ),
             $crate::build_unwind_context_data_impl!( @args $( $($args)* )? ),
         )
     };
     ( @args $arg:expr $(, $( $args:tt )* )? ) => {
         (
-            $crate::UnwindContextArg::new( Some(::core::stringify!($arg)), $arg ),
+            $crate::build_unwind_context_data_impl!( @arg Some(::core::stringify!($arg)), $arg ),
             $crate::build_unwind_context_data_impl!( @args $( $($args)* )? ),
         )
     };
     ( @args ) => {
         ()
     };
+    ( @arg $name:expr, $value:expr ) => {
+        match $value {
+            value => {
+                // Autoref-based selection of the structured colorization for
+                // values implementing `ColoredValue`.
+                #[allow(unused_imports)]
+                use $crate::{ColoredValueArgSelector as _, DebugValueArgSelector as _};
+                (&$crate::ColoredValueProbe(&value)).arg_builder().build($name, value)
+            }
+        }
+    };
+    ( @eager_fn $name:expr, $( $args:tt )* ) => {
+        $crate::UnwindContextFunc::new(
+            $name,
+            $crate::build_unwind_context_data_impl!( @eager_args $($args)* )
+        )
+    };
+    ( @eager_args ... $(, $( $args:tt )* )? ) => {
+        (
+            $crate::UnwindContextArg::new( None, $crate::NonExhaustiveMarker ),
+            $crate::build_unwind_context_data_impl!( @eager_args $( $($args)* )? ),
+        )
+    };
+    ( @eager_args $value:literal $(, $( $args:tt )* )? ) => {
+        (
+            $crate::UnwindContextArg::new( None, $value ),
+            $crate::build_unwind_context_data_impl!( @eager_args $( $($args)* )? ),
+        )
+    };
+    ( @eager_args $arg:expr $(, $( $args:tt )* )? ) => {
+        (
+            $crate::UnwindContextArg::new(
+                Some(::core::stringify!($arg)),
+                <$crate::DebugSnapshot>::new(&$arg),
+            ),
+            $crate::build_unwind_context_data_impl!( @eager_args $( $($args)* )? ),
+        )
+    };
+    ( @eager_args ) => {
+        ()
+    };
 }
 
 #[cfg(test)]
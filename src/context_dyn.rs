@@ -0,0 +1,152 @@
+use alloc::boxed::Box;
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+use crate::{AnsiColorScheme, ContextVisitor, DebugAnsiColored};
+
+/// A type-erased unwind context data storing boxed [`UnwindContextFunc`] or
+/// [`UnwindContextArgs`] behind a vtable.
+///
+/// Each distinct set of context argument types instantiates its own context
+/// scope guard and formatting code. In heavily instrumented crates it may
+/// noticeably increase compile times and binary sizes. Context scope guards
+/// with erased context data share a single instantiation per writer and panic
+/// detector type, at the cost of a heap allocation when the guard is created
+/// and a virtual call when the context is printed.
+///
+/// It is usually created with the `dyn:` prefix of the
+/// [`build_unwind_context_data`] macro and macros like [`unwind_context`].
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use unwind_context::{unwind_context, UnwindContextDyn, UnwindContextWithIo};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx: UnwindContextWithIo<_, UnwindContextDyn<'_>, _> =
+///         unwind_context!(dyn: fn(foo, bar));
+///     // ...
+/// }
+#[doc = "```"]
+#[doc = ""]
+/// [`UnwindContextFunc`]: crate::UnwindContextFunc
+/// [`UnwindContextArgs`]: crate::UnwindContextArgs
+/// [`build_unwind_context_data`]: crate::build_unwind_context_data
+/// [`unwind_context`]: crate::unwind_context
+pub struct UnwindContextDyn<'a> {
+    data: Box<dyn ErasedContextData + 'a>,
+}
+
+impl<'a> UnwindContextDyn<'a> {
+    /// Create a new `UnwindContextDyn` with the provided context data.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{UnwindContextArg, UnwindContextDyn, UnwindContextFunc};
+    ///
+    /// let data = UnwindContextDyn::new(UnwindContextFunc::new(
+    ///     "name",
+    ///     (UnwindContextArg::new(Some("first"), 123), ()),
+    /// ));
+    /// assert_eq!(format!("{data:?}"), "fn name(first: 123)");
+    /// ```
+    #[inline]
+    pub fn new<T>(data: T) -> Self
+    where
+        T: 'a + Debug + DebugAnsiColored,
+    {
+        Self {
+            data: Box::new(data),
+        }
+    }
+}
+
+impl Debug for UnwindContextDyn<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.data.fmt_erased(f)
+    }
+}
+
+impl DebugAnsiColored for UnwindContextDyn<'_> {
+    #[inline]
+    fn fmt_colored(
+        &self,
+        f: &mut Formatter<'_>,
+        color_scheme: &'static AnsiColorScheme,
+    ) -> FmtResult {
+        self.data.fmt_colored(f, color_scheme)
+    }
+
+    #[inline]
+    fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        self.data.visit_context(visitor);
+    }
+}
+
+/// An object-safe combination of [`Debug`] and [`DebugAnsiColored`] traits.
+trait ErasedContextData: DebugAnsiColored {
+    fn fmt_erased(&self, f: &mut Formatter<'_>) -> FmtResult;
+}
+
+impl<T: Debug + DebugAnsiColored> ErasedContextData for T {
+    #[inline]
+    fn fmt_erased(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(self, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use alloc::string::String;
+    use core::fmt::Debug;
+
+    use crate::test_common::TEST_COLOR_SCHEME;
+    use crate::{
+        build_unwind_context_data, AnsiColored, ContextVisitor, DebugAnsiColored, UnwindContextDyn,
+    };
+
+    #[derive(Default)]
+    struct NamesVisitor(String);
+
+    impl ContextVisitor for NamesVisitor {
+        fn visit_func_name(&mut self, name: &'static str) {
+            self.0.push_str(name);
+        }
+
+        fn visit_arg(&mut self, name: Option<&'static str>, _value: &dyn Debug) {
+            self.0.push(' ');
+            self.0.push_str(name.unwrap_or("_"));
+        }
+    }
+
+    #[test]
+    fn test_unwind_context_dyn() {
+        let foo = 123;
+        let bar = "value";
+
+        let data: UnwindContextDyn<'_> = build_unwind_context_data!(dyn: fn func(foo, 234, bar));
+        assert_eq!(
+            format!("{data:?}"),
+            "fn func(foo: 123, 234, bar: \"value\")"
+        );
+
+        let data = build_unwind_context_data!(dyn: foo, ..., bar);
+        assert_eq!(format!("{data:?}"), "foo: 123, ..., bar: \"value\"");
+
+        let data = build_unwind_context_data!(dyn: eager: fn func(foo));
+        assert_eq!(format!("{data:?}"), "fn func(foo: 123)");
+
+        let colored = build_unwind_context_data!(fn func(foo, 234, bar));
+        let erased = UnwindContextDyn::new(colored.clone());
+        assert_eq!(
+            format!("{:?}", AnsiColored::new(erased, &TEST_COLOR_SCHEME)),
+            format!("{:?}", AnsiColored::new(colored, &TEST_COLOR_SCHEME))
+        );
+
+        let mut visitor = NamesVisitor::default();
+        build_unwind_context_data!(dyn: fn func(foo, 234, bar)).visit_context(&mut visitor);
+        assert_eq!(visitor.0, "func foo _ bar");
+    }
+}
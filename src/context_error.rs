@@ -0,0 +1,264 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use std::borrow::ToOwned;
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+use crate::{CapturedFrame, CaughtPanic};
+
+/// An error type owning the panic message together with the formatted unwind
+/// context frames of a caught panic.
+///
+/// It is intended to be used by applications that convert panics into error
+/// values, for example servers that respond with an internal server error, so
+/// that the context can be embedded into structured error logs instead of
+/// being printed to the standard error stream.
+///
+/// It is usually created from a [`CaughtPanic`] returned by the
+/// [`catch_unwind_with_context`] function.
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use unwind_context::{
+///     catch_unwind_with_context, unwind_context, ContextError,
+/// };
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn func(foo, bar));
+///     panic!("Error");
+/// }
+///
+/// let caught = catch_unwind_with_context(|| func(123, "abc")).unwrap_err();
+/// let error = ContextError::from(caught);
+/// assert_eq!(error.message(), Some("Error"));
+/// assert_eq!(error.frames().len(), 1);
+/// let expected = "fn func(foo: 123, bar: \"abc\") at ";
+/// assert!(error.frames()[0].starts_with(expected));
+///
+/// let error: Box<dyn std::error::Error> = Box::new(error);
+/// let expected = "Error\n    fn func(foo: 123, bar: \"abc\") at ";
+/// assert!(error.to_string().starts_with(expected));
+#[doc = "```"]
+#[doc = ""]
+/// [`catch_unwind_with_context`]: crate::catch_unwind_with_context
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ContextError {
+    message: Option<String>,
+    frames: Vec<String>,
+}
+
+impl ContextError {
+    /// Create a new `ContextError` with the provided panic message and
+    /// formatted frames ordered from the innermost to the outermost one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let error = unwind_context::ContextError::new(
+    ///     Some("Error".to_owned()),
+    ///     vec!["fn func(foo: 123) at src/lib.rs:1:1".to_owned()],
+    /// );
+    /// assert_eq!(
+    ///     error.to_string(),
+    ///     "Error\n    fn func(foo: 123) at src/lib.rs:1:1"
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(message: Option<String>, frames: Vec<String>) -> Self {
+        Self { message, frames }
+    }
+
+    /// Returns the panic message if the panic payload was a string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let error = unwind_context::ContextError::new(None, Vec::new());
+    /// assert_eq!(error.message(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Returns the formatted frames ordered from the innermost to the
+    /// outermost one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let error = unwind_context::ContextError::new(None, Vec::new());
+    /// assert!(error.frames().is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn frames(&self) -> &[String] {
+        &self.frames
+    }
+}
+
+impl From<CaughtPanic> for ContextError {
+    #[inline]
+    fn from(caught: CaughtPanic) -> Self {
+        Self {
+            message: caught.message().map(ToOwned::to_owned),
+            frames: caught.frames.iter().map(format_frame).collect(),
+        }
+    }
+}
+
+impl Display for ContextError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(
+            self.message
+                .as_deref()
+                .unwrap_or("panic with a non-string payload"),
+        )?;
+        for frame in &self.frames {
+            write!(f, "\n    {frame}")?;
+        }
+        Ok(())
+    }
+}
+
+impl crate::compat::Error for ContextError {}
+
+/// A structure owning formatted unwind context frames that can be attached as
+/// a context message to other error types.
+///
+/// It is used by the error report integrations to attach frames recorded by
+/// a [`FrameCapture`] to error reports.
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use std::panic::catch_unwind;
+///
+/// use unwind_context::{unwind_context, ContextFrames};
+///
+/// fn func(foo: u32) {
+///     let _ctx = unwind_context!(fn func(foo));
+///     panic!("Error");
+/// }
+///
+/// let capture = unwind_context::capture();
+/// let _ = catch_unwind(|| func(123));
+/// let frames = ContextFrames::from(capture.take_frames());
+/// let expected = "unwind context:\n    fn func(foo: 123) at ";
+/// assert!(frames.to_string().starts_with(expected));
+#[doc = "```"]
+#[doc = ""]
+/// [`FrameCapture`]: crate::FrameCapture
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct ContextFrames {
+    frames: Vec<String>,
+}
+
+impl ContextFrames {
+    /// Create a new `ContextFrames` with the provided formatted frames ordered
+    /// from the innermost to the outermost one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let frames = unwind_context::ContextFrames::new(vec!["fn func()".to_owned()]);
+    /// assert_eq!(frames.to_string(), "unwind context:\n    fn func()");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(frames: Vec<String>) -> Self {
+        Self { frames }
+    }
+
+    /// Returns the formatted frames ordered from the innermost to the
+    /// outermost one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let frames = unwind_context::ContextFrames::default();
+    /// assert!(frames.frames().is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn frames(&self) -> &[String] {
+        &self.frames
+    }
+}
+
+impl From<Vec<CapturedFrame>> for ContextFrames {
+    #[inline]
+    fn from(frames: Vec<CapturedFrame>) -> Self {
+        Self {
+            frames: frames.iter().map(format_frame).collect(),
+        }
+    }
+}
+
+impl Display for ContextFrames {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str("unwind context:")?;
+        for frame in &self.frames {
+            write!(f, "\n    {frame}")?;
+        }
+        Ok(())
+    }
+}
+
+fn format_frame(frame: &CapturedFrame) -> String {
+    format!(
+        "{} at {}:{}:{}",
+        frame.data,
+        frame.location.file(),
+        frame.location.line(),
+        frame.location.column()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+    use std::vec;
+
+    use crate::{catch_unwind_with_context, unwind_context, ContextError};
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_context_error() {
+        let error = ContextError::from(
+            catch_unwind_with_context(|| {
+                let value = 123;
+                let _ctx = unwind_context!(fn func(value));
+                let _ctx = unwind_context!(value, "abc");
+                panic!("Error {value}");
+            })
+            .unwrap_err(),
+        );
+        assert_eq!(error.message(), Some("Error 123"));
+        assert_eq!(error.frames().len(), 2);
+        assert!(error.frames()[0].starts_with("value: 123, \"abc\" at src/context_error.rs:"));
+        assert!(error.frames()[1].starts_with("fn func(value: 123) at src/context_error.rs:"));
+
+        let message = error.to_string();
+        let mut lines = message.lines();
+        assert_eq!(lines.next(), Some("Error 123"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("    value: 123, \"abc\" at "));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("    fn func(value: 123) at "));
+        assert_eq!(lines.next(), None);
+
+        let error = ContextError::from(
+            catch_unwind_with_context(|| std::panic::panic_any(123)).unwrap_err(),
+        );
+        assert_eq!(error, ContextError::new(None, vec![]));
+        assert_eq!(error.to_string(), "panic with a non-string payload");
+    }
+}
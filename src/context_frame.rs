@@ -0,0 +1,254 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use core::panic::Location;
+use core::time::Duration;
+
+use crate::{AnsiColorScheme, ContextVisitor, DebugAnsiColored, FrameInfo};
+
+/// A structure representing an unwind context frame passed to a
+/// [`FrameSink`] callback or a [`ContextLayout`].
+///
+/// Unlike [`FrameInfo`] it does not depend on the context data type, so it
+/// can be consumed by a plain function. The context data is not formatted in
+/// advance, it can be inspected with [`ContextFrame::func_name`],
+/// [`ContextFrame::visit_context`], or formatted with [`ContextFrame::data`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::ContextFrame;
+///
+/// fn sink(frame: &ContextFrame<'_>) {
+///     eprintln!(
+///         "{:?} at {}:{}",
+///         frame.data(),
+///         frame.location().file(),
+///         frame.location().line()
+///     );
+/// }
+/// ```
+///
+/// [`FrameSink`]: crate::FrameSink
+/// [`ContextLayout`]: crate::ContextLayout
+#[derive(Copy, Clone)]
+pub struct ContextFrame<'a> {
+    data: &'a dyn ContextFrameData,
+    location: &'static Location<'static>,
+    elapsed: Option<Duration>,
+    index: Option<usize>,
+    depth: usize,
+}
+
+impl<'a> ContextFrame<'a> {
+    /// Create a new `ContextFrame` from a [`FrameInfo`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, ContextFrame, FrameInfo};
+    ///
+    /// let foo = 123;
+    /// let data = build_unwind_context_data!(fn func(foo));
+    /// let frame = ContextFrame::new(&FrameInfo::new(&data, core::panic::Location::caller()));
+    /// assert_eq!(frame.func_name(), Some("func"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new<T: Debug + DebugAnsiColored>(frame: &FrameInfo<'a, T>) -> Self {
+        Self {
+            data: frame.data,
+            location: frame.location,
+            elapsed: frame.elapsed,
+            index: frame.index,
+            depth: frame.depth,
+        }
+    }
+
+    /// Returns the context data formatted with [`core::fmt::Debug`] without
+    /// colorization.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, ContextFrame, FrameInfo};
+    ///
+    /// let (foo, bar) = (123, "abc");
+    /// let data = build_unwind_context_data!(fn func(foo, bar));
+    /// let frame = ContextFrame::new(&FrameInfo::new(&data, core::panic::Location::caller()));
+    /// assert_eq!(format!("{:?}", frame.data()), "fn func(foo: 123, bar: \"abc\")");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn data(&self) -> &'a dyn Debug {
+        self.data.as_debug()
+    }
+
+    /// Formats the context data with a given color scheme.
+    pub(crate) fn fmt_data_colored(
+        &self,
+        f: &mut Formatter<'_>,
+        color_scheme: &'static AnsiColorScheme,
+    ) -> FmtResult {
+        self.data.fmt_colored(f, color_scheme)
+    }
+
+    /// Returns the function name, if the context data is a function context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, ContextFrame, FrameInfo};
+    ///
+    /// let foo = 123;
+    /// let data = build_unwind_context_data!(foo);
+    /// let frame = ContextFrame::new(&FrameInfo::new(&data, core::panic::Location::caller()));
+    /// assert_eq!(frame.func_name(), None);
+    /// ```
+    #[must_use]
+    pub fn func_name(&self) -> Option<&'static str> {
+        struct FuncNameVisitor(Option<&'static str>);
+
+        impl ContextVisitor for FuncNameVisitor {
+            fn visit_func_name(&mut self, name: &'static str) {
+                self.0 = Some(name);
+            }
+
+            fn visit_arg(&mut self, _: Option<&'static str>, _: &dyn Debug) {}
+        }
+
+        let mut visitor = FuncNameVisitor(None);
+        self.visit_context(&mut visitor);
+        visitor.0
+    }
+
+    /// Visits the function name and the argument names and values of the
+    /// context data with a given [`ContextVisitor`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Debug;
+    ///
+    /// use unwind_context::{build_unwind_context_data, ContextFrame, ContextVisitor, FrameInfo};
+    ///
+    /// struct ArgNames(Vec<Option<&'static str>>);
+    ///
+    /// impl ContextVisitor for ArgNames {
+    ///     fn visit_func_name(&mut self, _: &'static str) {}
+    ///
+    ///     fn visit_arg(&mut self, name: Option<&'static str>, _: &dyn Debug) {
+    ///         self.0.push(name);
+    ///     }
+    /// }
+    ///
+    /// let foo = 123;
+    /// let data = build_unwind_context_data!(fn func(foo, 234));
+    /// let frame = ContextFrame::new(&FrameInfo::new(&data, core::panic::Location::caller()));
+    /// let mut names = ArgNames(Vec::new());
+    /// frame.visit_context(&mut names);
+    /// assert_eq!(names.0, [Some("foo"), None]);
+    /// ```
+    #[inline]
+    pub fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        self.data.visit_context(visitor);
+    }
+
+    /// Returns the code location where the context scope guard was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, ContextFrame, FrameInfo};
+    ///
+    /// let data = build_unwind_context_data!(fn func());
+    /// let location = core::panic::Location::caller();
+    /// let frame = ContextFrame::new(&FrameInfo::new(&data, location));
+    /// assert_eq!(frame.location(), location);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+
+    /// Returns the time elapsed since the context scope guard was created, if
+    /// it was measured.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, ContextFrame, FrameInfo};
+    ///
+    /// let data = build_unwind_context_data!(fn func());
+    /// let frame = ContextFrame::new(&FrameInfo::new(&data, core::panic::Location::caller()));
+    /// assert_eq!(frame.elapsed(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+
+    /// Returns the frame index within a single unwind, if frames are
+    /// numbered.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, ContextFrame, FrameInfo};
+    ///
+    /// let data = build_unwind_context_data!(fn func());
+    /// let frame = ContextFrame::new(
+    ///     &FrameInfo::new(&data, core::panic::Location::caller()).with_index(Some(1)),
+    /// );
+    /// assert_eq!(frame.index(), Some(1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    /// Returns the number of context scope guards enclosing the context scope
+    /// guard of this frame.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, ContextFrame, FrameInfo};
+    ///
+    /// let data = build_unwind_context_data!(fn func());
+    /// let frame =
+    ///     ContextFrame::new(&FrameInfo::new(&data, core::panic::Location::caller()).with_depth(2));
+    /// assert_eq!(frame.depth(), 2);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+impl Debug for ContextFrame<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("ContextFrame")
+            .field("data", self.data.as_debug())
+            .field("location", &self.location)
+            .field("elapsed", &self.elapsed)
+            .field("index", &self.index)
+            .field("depth", &self.depth)
+            .finish()
+    }
+}
+
+/// An object-safe combination of [`core::fmt::Debug`] and
+/// [`DebugAnsiColored`] used to erase the context data type.
+trait ContextFrameData: DebugAnsiColored {
+    fn as_debug(&self) -> &dyn Debug;
+}
+
+impl<T: Debug + DebugAnsiColored> ContextFrameData for T {
+    #[inline]
+    fn as_debug(&self) -> &dyn Debug {
+        self
+    }
+}
@@ -0,0 +1,383 @@
+use core::cmp::Ordering;
+use core::fmt::{Debug, Formatter, Result as FmtResult, Write as FmtWrite};
+use core::hash::{Hash, Hasher};
+#[cfg(feature = "std")]
+use std::sync::{PoisonError, RwLock};
+
+use crate::location_template::{HyperlinkFmt, LocationTemplateFmt};
+use crate::{AnsiColorScheme, ContextFrame, FrameOptions, LocationStyle, DEFAULT_LOCATION_FORMAT};
+
+#[cfg(feature = "std")]
+static CONTEXT_LAYOUT: RwLock<Option<&'static dyn ContextLayout>> = RwLock::new(None);
+
+/// A layout of the unwind context frames written by context scope guards.
+///
+/// The [`DefaultLayout`] writes the frame data and its location on separate
+/// lines. Custom layouts can write single-line frames, logfmt-style frames, or
+/// any other format, reusing the parts of the default layout provided by the
+/// [`LayoutFrame`] methods.
+///
+/// A layout can be set for a specific context scope guard with the
+/// `layout = ...` macro parameter, or for all context scope guards with
+/// [`set_context_layout`].
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use core::fmt::{Result, Write};
+///
+/// use unwind_context::{
+///     unwind_context_with_fmt, ContextLayout, LayoutFrame, StdPanicDetector,
+/// };
+///
+/// struct SingleLineLayout;
+///
+/// impl ContextLayout for SingleLineLayout {
+///     fn write_frame(
+///         &self,
+///         f: &mut dyn Write,
+///         frame: &LayoutFrame<'_>,
+///     ) -> Result {
+///         frame.write_data(f)?;
+///         f.write_str(" @ ")?;
+///         frame.write_location(f)?;
+///         f.write_str("\n")
+///     }
+/// }
+///
+/// let mut output = String::new();
+/// let foo = 123;
+/// let mut ctx = unwind_context_with_fmt!(
+///     (fn func(foo)),
+///     writer = &mut output,
+///     panic_detector = StdPanicDetector,
+///     color_scheme = None,
+///     layout = &SingleLineLayout,
+/// );
+/// ctx.print();
+/// drop(ctx);
+/// assert!(output.starts_with("fn func(foo: 123) @ "));
+/// assert_eq!(output.lines().count(), 1);
+#[doc = "```"]
+#[doc = ""]
+/// [`set_context_layout`]: crate::set_context_layout
+pub trait ContextLayout: Sync {
+    /// Writes an unwind context frame.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the frame formatting fails.
+    fn write_frame(&self, f: &mut dyn FmtWrite, frame: &LayoutFrame<'_>) -> FmtResult;
+}
+
+/// The default layout of the unwind context frames writing the frame data and
+/// its location on separate lines, like:
+///
+/// ```text
+/// fn func(foo: 123, bar: "abc")
+///     at src/main.rs:4:16
+/// ```
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{DefaultLayout, FrameOptions};
+///
+/// let options = FrameOptions::new().with_layout(Some(&DefaultLayout));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct DefaultLayout;
+
+impl ContextLayout for DefaultLayout {
+    fn write_frame(&self, f: &mut dyn FmtWrite, frame: &LayoutFrame<'_>) -> FmtResult {
+        frame.write_prefix(f)?;
+        frame.write_data(f)?;
+        if frame.options().location_style() != LocationStyle::Hidden {
+            f.write_str("\n    at ")?;
+            frame.write_location(f)?;
+        }
+        frame.write_details(f)?;
+        f.write_str("\n")
+    }
+}
+
+/// A structure representing an unwind context frame passed to a
+/// [`ContextLayout`] together with the options used to print it.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{
+///     build_unwind_context_data, ContextFrame, FrameInfo, FrameOptions, LayoutFrame,
+/// };
+///
+/// let foo = 123;
+/// let data = build_unwind_context_data!(fn func(foo));
+/// let options = FrameOptions::new();
+/// let frame = LayoutFrame::new(
+///     ContextFrame::new(&FrameInfo::new(&data, core::panic::Location::caller())),
+///     &options,
+/// );
+///
+/// let mut output = String::new();
+/// frame.write_data(&mut output).unwrap();
+/// assert_eq!(output, "fn func(foo: 123)");
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct LayoutFrame<'a> {
+    frame: ContextFrame<'a>,
+    options: &'a FrameOptions,
+}
+
+impl<'a> LayoutFrame<'a> {
+    /// Create a new `LayoutFrame` with the provided frame and options.
+    #[inline]
+    #[must_use]
+    pub fn new(frame: ContextFrame<'a>, options: &'a FrameOptions) -> Self {
+        Self { frame, options }
+    }
+
+    /// Returns the unwind context frame.
+    #[inline]
+    #[must_use]
+    pub fn frame(&self) -> &ContextFrame<'a> {
+        &self.frame
+    }
+
+    /// Returns the options used to print the frame.
+    #[inline]
+    #[must_use]
+    pub fn options(&self) -> &'a FrameOptions {
+        self.options
+    }
+
+    /// Writes the frame number, if frames are numbered, and the timestamp, if
+    /// a clock source is set with the `timestamps` feature, each followed by a
+    /// space.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the formatting fails.
+    pub fn write_prefix(&self, f: &mut dyn FmtWrite) -> FmtResult {
+        if let Some(index) = self.frame.index() {
+            write!(f, "#{index} ")?;
+        }
+        #[cfg(feature = "timestamps")]
+        if let Some(clock_source) = crate::get_clock_source() {
+            if let Some(color_scheme) = self.options.color_scheme() {
+                write!(
+                    f,
+                    "{}[{}]{} ",
+                    color_scheme.location,
+                    clock_source.now(),
+                    color_scheme.default
+                )?;
+            } else {
+                write!(f, "[{}] ", clock_source.now())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the context data, colorized if a color scheme is set.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the context data formatting
+    /// fails.
+    pub fn write_data(&self, f: &mut dyn FmtWrite) -> FmtResult {
+        if let Some(color_scheme) = self.options.color_scheme() {
+            write!(
+                f,
+                "{:?}",
+                ColoredDataFmt {
+                    frame: &self.frame,
+                    color_scheme,
+                }
+            )
+        } else {
+            write!(f, "{:?}", self.frame.data())
+        }
+    }
+
+    /// Writes the frame location according to the location style, format,
+    /// path, and hyperlink options, colorized if a color scheme is set.
+    ///
+    /// Nothing is written if the location style is [`LocationStyle::Hidden`].
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the formatting fails.
+    pub fn write_location(&self, f: &mut dyn FmtWrite) -> FmtResult {
+        let location = self.frame.location();
+        let default_format = match self.options.location_style() {
+            LocationStyle::Full => DEFAULT_LOCATION_FORMAT,
+            LocationStyle::WithoutColumn => "{file}:{line}",
+            LocationStyle::Hidden => return Ok(()),
+        };
+        let text = LocationTemplateFmt {
+            template: self.options.location_format().unwrap_or(default_format),
+            file: self.options.location_path().shorten(location.file()),
+            line: location.line(),
+            column: location.column(),
+            color_scheme: self.options.color_scheme(),
+        };
+        let Some(color_scheme) = self.options.color_scheme() else {
+            return write!(f, "{text}");
+        };
+        f.write_str(color_scheme.location)?;
+        if let Some(template) = self.options.location_hyperlink() {
+            let url = LocationTemplateFmt {
+                template,
+                file: location.file(),
+                line: location.line(),
+                column: location.column(),
+                color_scheme: None,
+            };
+            write!(f, "{}", HyperlinkFmt { url, text })?;
+        } else {
+            write!(f, "{text}")?;
+        }
+        f.write_str(color_scheme.default)
+    }
+
+    /// Writes the optional frame details usually written after the location,
+    /// like the time spent in the context scope, the thread name and ID, and
+    /// the process ID, each preceded by a space.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the formatting fails.
+    pub fn write_details(&self, f: &mut dyn FmtWrite) -> FmtResult {
+        if let Some(elapsed) = self.frame.elapsed() {
+            write!(f, " after {:.2}s in scope", elapsed.as_secs_f64())?;
+        }
+        #[cfg(feature = "std")]
+        if self.options.should_print_thread_info() {
+            let thread = std::thread::current();
+            write!(
+                f,
+                " in thread '{}' ({:?})",
+                thread.name().unwrap_or("<unnamed>"),
+                thread.id()
+            )?;
+        }
+        #[cfg(feature = "std")]
+        if self.options.should_print_process_id() {
+            write!(f, " in process {}", std::process::id())?;
+        }
+        Ok(())
+    }
+}
+
+/// An utility wrapper type which is used to format the frame context data
+/// with a given color scheme.
+struct ColoredDataFmt<'a, 'b> {
+    frame: &'a ContextFrame<'b>,
+    color_scheme: &'static AnsiColorScheme,
+}
+
+impl Debug for ColoredDataFmt<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.frame.fmt_data_colored(f, self.color_scheme)
+    }
+}
+
+/// A reference to a [`ContextLayout`] compared and hashed by address, so that
+/// it can be stored in [`FrameOptions`].
+#[derive(Copy, Clone)]
+pub(crate) struct LayoutRef(pub &'static dyn ContextLayout);
+
+impl LayoutRef {
+    fn addr(self) -> *const () {
+        let ptr: *const dyn ContextLayout = self.0;
+        ptr.cast::<()>()
+    }
+}
+
+impl PartialEq for LayoutRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl Eq for LayoutRef {}
+
+impl PartialOrd for LayoutRef {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LayoutRef {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.addr().cmp(&other.addr())
+    }
+}
+
+impl Hash for LayoutRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr().hash(state);
+    }
+}
+
+impl Debug for LayoutRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_tuple("LayoutRef").field(&self.addr()).finish()
+    }
+}
+
+/// Sets the layout of the unwind context frames written by all context scope
+/// guards, or restores the [`DefaultLayout`] if `None` is given.
+///
+/// It can also be set for a specific context scope guard with the
+/// `layout = ...` macro parameter. See [`ContextLayout`] for more details.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::fmt::{Result, Write};
+///
+/// use unwind_context::{ContextLayout, LayoutFrame};
+///
+/// struct LogfmtLayout;
+///
+/// impl ContextLayout for LogfmtLayout {
+///     fn write_frame(&self, f: &mut dyn Write, frame: &LayoutFrame<'_>) -> Result {
+///         f.write_str("context=\"")?;
+///         frame.write_data(f)?;
+///         f.write_str("\" location=")?;
+///         frame.write_location(f)?;
+///         f.write_str("\n")
+///     }
+/// }
+///
+/// unwind_context::set_context_layout(Some(&LogfmtLayout));
+/// # unwind_context::set_context_layout(None);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_context_layout(layout: Option<&'static dyn ContextLayout>) {
+    *CONTEXT_LAYOUT
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = layout;
+}
+
+/// Returns the layout of the unwind context frames written by all context
+/// scope guards, if set.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(unwind_context::get_context_layout().is_none());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn get_context_layout() -> Option<&'static dyn ContextLayout> {
+    *CONTEXT_LAYOUT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,182 @@
+use core::fmt::{Arguments as FmtArguments, Write as FmtWrite};
+#[cfg(feature = "std")]
+use core::fmt::{Error as FmtError, Result as FmtResult};
+#[cfg(feature = "std")]
+use std::io::Write as IoWrite;
+
+/// A destination of the unwind context output written by
+/// [`UnwindContextWithSink`] context scope guards.
+///
+/// It is implemented by [`FmtSink`] for [`core::fmt::Write`] writers and by
+/// [`IoSink`] for [`std::io::Write`] writers, so every output feature of the
+/// context scope guards is implemented once for both kinds of writers.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::fmt::Arguments;
+///
+/// use unwind_context::ContextSink;
+///
+/// struct LineCounter(usize);
+///
+/// impl ContextSink for LineCounter {
+///     fn write_context(&mut self, args: Arguments<'_>) {
+///         self.0 += args.to_string().matches('\n').count();
+///     }
+/// }
+/// ```
+///
+/// [`UnwindContextWithSink`]: crate::UnwindContextWithSink
+/// [`IoSink`]: crate::IoSink
+pub trait ContextSink {
+    /// Writes a formatted part of the unwind context output, like a frame or
+    /// a panic location header.
+    ///
+    /// Errors are ignored, because there is no way to report them during
+    /// unwinding.
+    fn write_context(&mut self, args: FmtArguments<'_>);
+}
+
+/// A [`ContextSink`] writing the unwind context output to a
+/// [`core::fmt::Write`] writer.
+///
+/// It is used by [`UnwindContextWithFmt`] context scope guards.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{ContextSink, FmtSink};
+///
+/// let mut sink = FmtSink(String::new());
+/// sink.write_context(format_args!("value: {}\n", 123));
+/// assert_eq!(sink.0, "value: 123\n");
+/// ```
+///
+/// [`UnwindContextWithFmt`]: crate::UnwindContextWithFmt
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct FmtSink<W>(
+    /// The wrapped [`core::fmt::Write`] writer.
+    pub W,
+);
+
+/// A [`ContextSink`] writing the unwind context output to a
+/// [`std::io::Write`] writer.
+///
+/// Every part of the output is formatted into a stack buffer first and
+/// written with a single `write_all` call followed by a `flush` call, so that
+/// frames printed by different threads do not interleave mid-line. Parts
+/// longer than the buffer are written in several chunks.
+///
+/// It is used by [`UnwindContextWithIo`] context scope guards.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{ContextSink, IoSink};
+///
+/// let mut sink = IoSink(Vec::new());
+/// sink.write_context(format_args!("value: {}\n", 123));
+/// assert_eq!(sink.0, b"value: 123\n");
+/// ```
+///
+/// [`UnwindContextWithIo`]: crate::UnwindContextWithIo
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct IoSink<W>(
+    /// The wrapped [`std::io::Write`] writer.
+    pub W,
+);
+
+impl<W> From<W> for FmtSink<W> {
+    #[inline]
+    fn from(writer: W) -> Self {
+        Self(writer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W> From<W> for IoSink<W> {
+    #[inline]
+    fn from(writer: W) -> Self {
+        Self(writer)
+    }
+}
+
+impl<W: FmtWrite> ContextSink for FmtSink<W> {
+    #[inline]
+    fn write_context(&mut self, args: FmtArguments<'_>) {
+        let _ = self.0.write_fmt(args);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: IoWrite> ContextSink for IoSink<W> {
+    #[inline]
+    fn write_context(&mut self, args: FmtArguments<'_>) {
+        write_atomically(&mut self.0, args);
+        let _ = self.0.flush();
+    }
+}
+
+/// The size of the stack buffer used to format an unwind context frame before
+/// writing it.
+#[cfg(feature = "std")]
+const FRAME_BUFFER_SIZE: usize = 1024;
+
+/// Formats the arguments into a stack buffer first and writes them with a
+/// single `write_all` call, so that output of different threads does not
+/// interleave mid-line. If the formatted arguments do not fit into the buffer,
+/// the buffer is written each time it is full and formatting continues, so the
+/// arguments are always formatted only once.
+#[cfg(feature = "std")]
+fn write_atomically<W: IoWrite>(writer: &mut W, args: FmtArguments<'_>) {
+    let mut chunked_writer = ChunkedWriter {
+        writer,
+        buffer: [0; FRAME_BUFFER_SIZE],
+        used: 0,
+    };
+    if chunked_writer.write_fmt(args).is_ok() {
+        let _ = chunked_writer.write_buffer();
+    }
+}
+
+/// A [`core::fmt::Write`] adapter collecting the formatted output in a stack
+/// buffer and writing the buffer to a [`std::io::Write`] writer when it is
+/// full.
+#[cfg(feature = "std")]
+struct ChunkedWriter<'a, W> {
+    writer: &'a mut W,
+    buffer: [u8; FRAME_BUFFER_SIZE],
+    used: usize,
+}
+
+#[cfg(feature = "std")]
+impl<W: IoWrite> ChunkedWriter<'_, W> {
+    fn write_buffer(&mut self) -> FmtResult {
+        let buffer = self.buffer.get(..self.used).unwrap_or_default();
+        self.used = 0;
+        self.writer.write_all(buffer).map_err(|_| FmtError)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: IoWrite> FmtWrite for ChunkedWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        let available = self.buffer.len().saturating_sub(self.used);
+        if s.len() > available {
+            self.write_buffer()?;
+            if s.len() > self.buffer.len() {
+                return self.writer.write_all(s.as_bytes()).map_err(|_| FmtError);
+            }
+        }
+        let until = self.used.checked_add(s.len()).ok_or(FmtError)?;
+        self.buffer
+            .get_mut(self.used..until)
+            .ok_or(FmtError)?
+            .copy_from_slice(s.as_bytes());
+        self.used = until;
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use core::fmt::Debug;
+
+/// A visitor of the function name and the argument names and values of
+/// context data.
+///
+/// It is used with [`DebugAnsiColored::visit_context`] to inspect context data
+/// in a structured form instead of formatting it as a whole.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::fmt::Debug;
+///
+/// use unwind_context::{build_unwind_context_data, ContextVisitor, DebugAnsiColored};
+///
+/// struct ArgCounter(usize);
+///
+/// impl ContextVisitor for ArgCounter {
+///     fn visit_func_name(&mut self, _: &'static str) {}
+///
+///     fn visit_arg(&mut self, _: Option<&'static str>, _: &dyn Debug) {
+///         self.0 += 1;
+///     }
+/// }
+///
+/// let (foo, bar) = (123, "abc");
+/// let data = build_unwind_context_data!(fn func(foo, bar));
+/// let mut counter = ArgCounter(0);
+/// data.visit_context(&mut counter);
+/// assert_eq!(counter.0, 2);
+/// ```
+///
+/// [`DebugAnsiColored::visit_context`]: crate::DebugAnsiColored::visit_context
+pub trait ContextVisitor {
+    /// Visits the function name.
+    fn visit_func_name(&mut self, name: &'static str);
+
+    /// Visits an argument with an optional name and a value.
+    fn visit_arg(&mut self, name: Option<&'static str>, value: &dyn Debug);
+}
@@ -1,21 +1,19 @@
-use core::fmt::{Debug, Write};
-use core::panic::Location;
-
-use crate::{AnsiColorScheme, AnsiColored, DebugAnsiColored, PanicDetector};
+use crate::{DefaultGuardOptions, FmtSink, UnwindContextWithSink};
 
 /// A structure representing a scoped guard with unwind context with
-/// [`std::io::Write`] writer.
+/// [`core::fmt::Write`] writer.
 ///
 /// If dropped during unwind it will write a message to a given writer
-/// containing given function or scope context. If created with
-/// [`unwind_context`] it will write to [`std::io::Stderr`].
+/// containing given function or scope context.
 ///
 /// When this structure is dropped (falls out of scope) and the current thread
 /// is not unwinding, the unwind context will be forgotten.
 ///
-/// # Examples
+/// It is an alias of [`UnwindContextWithSink`] writing to a [`FmtSink`].
 ///
-/// ```rust
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
 /// use unwind_context::{unwind_context_with_fmt, UnwindContextWithFmt};
 ///
 /// fn func(foo: u32, bar: &str, secret: &str, custom_writer: &mut String) {
@@ -23,92 +21,12 @@ use crate::{AnsiColorScheme, AnsiColored, DebugAnsiColored, PanicDetector};
 ///         (fn(foo, bar, ...)),
 ///         writer = custom_writer,
 ///         panic_detector = unwind_context::StdPanicDetector,
-///         color_scheme = None,
 ///     );
 ///     // ...
 /// }
-/// ```
-///
-/// [`unwind_context`]: crate::unwind_context
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-pub struct UnwindContextWithFmt<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> {
-    data: T,
-    writer: W,
-    panic_detector: P,
-    color_scheme: Option<&'static AnsiColorScheme>,
-    location: &'static Location<'static>,
-}
-
-impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> Drop
-    for UnwindContextWithFmt<W, T, P>
-{
-    #[inline]
-    fn drop(&mut self) {
-        if self.panic_detector.is_panicking() {
-            self.print();
-        }
-    }
-}
-
-impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> UnwindContextWithFmt<W, T, P> {
-    /// Create a new `UnwindContextWithFmt` with the provided
-    /// [`core::fmt::Write`] writer, context scope data, and color scheme.
-    ///
-    /// This function is not intended to be used directly. Consider using macros
-    /// like [`unwind_context_with_fmt`] instead.
-    ///
-    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
-    #[inline]
-    #[must_use = "\
-        if unused, the `UnwindContextWithFmt` will immediately drop,
-        consider binding the `UnwindContextWithFmt` like `let _ctx = ...`.
-    "]
-    #[track_caller]
-    pub fn new(
-        data: T,
-        writer: W,
-        panic_detector: P,
-        color_scheme: Option<&'static AnsiColorScheme>,
-    ) -> Self {
-        Self {
-            data,
-            writer,
-            panic_detector,
-            color_scheme,
-            location: Location::caller(),
-        }
-    }
-
-    /// Print context to a writer specified in the `UnwindContextWithFmt`
-    /// constructor.
-    ///
-    /// This method is called when a panic detected.
-    #[cold]
-    #[inline(never)]
-    pub fn print(&mut self) {
-        if let Some(color_scheme) = self.color_scheme {
-            let _ = writeln!(
-                self.writer,
-                "{:?}\n    at {}{}:{}:{}{}",
-                AnsiColored::new(&self.data, color_scheme),
-                color_scheme.location,
-                self.location.file(),
-                self.location.line(),
-                self.location.column(),
-                color_scheme.default,
-            );
-        } else {
-            let _ = writeln!(
-                self.writer,
-                "{:?}\n    at {}:{}:{}",
-                self.data,
-                self.location.file(),
-                self.location.line(),
-                self.location.column(),
-            );
-        }
-    }
-}
+#[doc = "```"]
+pub type UnwindContextWithFmt<W, T, P, O = DefaultGuardOptions> =
+    UnwindContextWithSink<FmtSink<W>, T, P, O>;
 
 /// Creates [`UnwindContextWithFmt`] with a given [`core::fmt::Write`] writer,
 /// panic detector, color scheme, and a given function or scope context.
@@ -116,6 +34,64 @@ impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> UnwindContextWithF
 /// If not specified it uses [`get_default_color_scheme_if_enabled`] as a
 /// default color scheme.
 ///
+/// If `writer_fn = || ...` is specified instead of `writer`, the writer is
+/// created with the given function only when the context is printed, so the
+/// context scope guard stores only the function. See [`WriterFn`] for more
+/// details.
+///
+/// If `thread_info = true` is specified, the current thread name and ID are
+/// printed after the context location regardless of the global
+/// [`set_thread_info_enabled`] setting. This parameter requires the `std`
+/// feature.
+///
+/// If `process_id = true` is specified, the current process ID is printed
+/// after the context location regardless of the global
+/// [`set_process_id_enabled`] setting. This parameter requires the `std`
+/// feature.
+///
+/// If `elapsed_time = true` is specified, the time spent in the context scope
+/// is printed after the context location regardless of the global
+/// [`set_elapsed_time_enabled`] setting. This parameter requires the `std`
+/// feature.
+///
+/// If `backtrace = true` is specified, a [`std::backtrace::Backtrace`] is
+/// captured when the context is printed and printed after the context
+/// location. This parameter requires the `std` feature.
+///
+/// If `location = ...` is specified, the given [`core::panic::Location`] is
+/// printed instead of the location where the context scope guard is created.
+/// It allows helper functions creating context scope guards on behalf of their
+/// callers to report the caller location with a `#[track_caller]` attribute
+/// and [`core::panic::Location::caller`].
+///
+/// If `location_style = ...` is specified, the code location is printed with
+/// the given [`LocationStyle`] regardless of the global
+/// [`set_location_style`] setting.
+///
+/// If `layout = ...` is specified, the context is written with the given
+/// [`ContextLayout`] regardless of the global [`set_context_layout`] setting.
+///
+/// If `verbose = true` is specified, the context is also printed prefixed with
+/// `enter` when the context scope guard is created regardless of the global
+/// [`set_verbose_enabled`] setting.
+///
+/// If `print_on_drop = true` is specified, the context is printed every time
+/// the context scope guard is dropped, prefixed with `ok` on a normal scope
+/// exit and with `panic` on unwind, regardless of the global
+/// [`set_print_on_drop_enabled`] setting.
+///
+/// If `rate_limit = ...` is specified, frames of the context scope guard
+/// location are printed at most as often as allowed by the given [`RateLimit`]
+/// regardless of the global [`set_rate_limit`] setting. The limit is stored in
+/// a static variable, so it should be a constant expression.
+///
+/// If `sample_rate = ...` is specified, only the first and then every `n`-th
+/// frame of the context scope guard location is printed regardless of the
+/// global [`set_sample_rate`] setting.
+///
+/// If `once = true` is specified, the frame of the macro call site is printed
+/// at most once per process run, which is useful for known-noisy locations.
+///
 /// The returned unwind context scope guard value should be kept alive as long
 /// as unwind context is needed. If unused, the [`UnwindContextWithFmt`] will
 /// immediately drop.
@@ -166,24 +142,138 @@ impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> UnwindContextWithF
 ///
 /// [`build_unwind_context_data`]: crate::build_unwind_context_data
 /// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
+/// [`WriterFn`]: crate::WriterFn
+/// [`set_thread_info_enabled`]: crate::set_thread_info_enabled
+/// [`set_process_id_enabled`]: crate::set_process_id_enabled
+/// [`set_elapsed_time_enabled`]: crate::set_elapsed_time_enabled
+/// [`LocationStyle`]: crate::LocationStyle
+/// [`set_location_style`]: crate::set_location_style
+/// [`ContextLayout`]: crate::ContextLayout
+/// [`set_context_layout`]: crate::set_context_layout
+/// [`set_verbose_enabled`]: crate::set_verbose_enabled
+/// [`set_print_on_drop_enabled`]: crate::set_print_on_drop_enabled
+/// [`RateLimit`]: crate::RateLimit
+/// [`set_rate_limit`]: crate::set_rate_limit
+/// [`set_sample_rate`]: crate::set_sample_rate
 #[macro_export]
 macro_rules! unwind_context_with_fmt {
+    (
+        ( $( $context:tt )* )
+        , writer_fn = $writer_fn:expr
+        , $( $rest:tt )*
+    ) => {
+        $crate::unwind_context_with_fmt!(
+            ( $($context)* ),
+            writer = $crate::WriterFn($writer_fn),
+            $($rest)*
+        )
+    };
     (
         ( $( $context:tt )* )
         , writer = $writer:expr
         , panic_detector = $panic_detector:expr
         $(, color_scheme = $color_scheme:expr )?
+        $(, thread_info = $thread_info:expr )?
+        $(, process_id = $process_id:expr )?
+        $(, elapsed_time = $elapsed_time:expr )?
+        $(, backtrace = $backtrace:expr )?
+        $(, location = $location:expr )?
+        $(, location_style = $location_style:expr )?
+        $(, layout = $layout:expr )?
+        $(, verbose = $verbose:expr )?
+        $(, print_on_drop = $print_on_drop:expr )?
+        $(, rate_limit = $rate_limit:expr )?
+        $(, sample_rate = $sample_rate:expr )?
+        $(, once = $once:expr )?
         $(,)?
     ) => {
-        $crate::UnwindContextWithFmt::new(
-            $crate::build_unwind_context_data!( $($context)* ),
-            $writer,
-            $panic_detector,
-            $crate::expr_or_default_expr!(
-                $( $color_scheme )?,
-                $crate::get_default_color_scheme_if_enabled()
-            ),
-        )
+        $crate::unless_off! { $crate::if_any_tokens!(
+            [
+                $( $color_scheme )? $( $thread_info )? $( $process_id )?
+                $( $elapsed_time )? $( $backtrace )? $( $location_style )?
+                $( $layout )? $( $verbose )? $( $print_on_drop )?
+                $( $rate_limit )? $( $sample_rate )? $( $once )?
+            ],
+            { $crate::unwind_context_with_fmt!(
+                @custom ( $($context)* )
+                , writer = $writer
+                , panic_detector = $panic_detector
+                $(, color_scheme = $color_scheme )?
+                $(, thread_info = $thread_info )?
+                $(, process_id = $process_id )?
+                $(, elapsed_time = $elapsed_time )?
+                $(, backtrace = $backtrace )?
+                $(, location = $location )?
+                $(, location_style = $location_style )?
+                $(, layout = $layout )?
+                $(, verbose = $verbose )?
+                $(, print_on_drop = $print_on_drop )?
+                $(, rate_limit = $rate_limit )?
+                $(, sample_rate = $sample_rate )?
+                $(, once = $once )?
+            ) },
+            {
+                $crate::UnwindContextWithFmt::new_default(
+                    $crate::build_unwind_context_data!( $($context)* ),
+                    $writer,
+                    $panic_detector,
+                )
+                $( .with_location($location) )?
+                .trace_entry()
+            }
+        ) }
+    };
+    (
+        @custom ( $( $context:tt )* )
+        , writer = $writer:expr
+        , panic_detector = $panic_detector:expr
+        $(, color_scheme = $color_scheme:expr )?
+        $(, thread_info = $thread_info:expr )?
+        $(, process_id = $process_id:expr )?
+        $(, elapsed_time = $elapsed_time:expr )?
+        $(, backtrace = $backtrace:expr )?
+        $(, location = $location:expr )?
+        $(, location_style = $location_style:expr )?
+        $(, layout = $layout:expr )?
+        $(, verbose = $verbose:expr )?
+        $(, print_on_drop = $print_on_drop:expr )?
+        $(, rate_limit = $rate_limit:expr )?
+        $(, sample_rate = $sample_rate:expr )?
+        $(, once = $once:expr )?
+    ) => {
+        {
+            $crate::UnwindContextWithFmt::new(
+                $crate::build_unwind_context_data!( $($context)* ),
+                $writer,
+                $panic_detector,
+                $crate::expr_or_default_expr!(
+                    $( $color_scheme )?,
+                    $crate::get_default_color_scheme_if_enabled()
+                ),
+            )
+            $( .with_thread_info($thread_info) )?
+            $( .with_process_id($process_id) )?
+            $( .with_elapsed_time($elapsed_time) )?
+            $( .with_backtrace($backtrace) )?
+            $( .with_location($location) )?
+            $( .with_location_style($location_style) )?
+            $( .with_layout($layout) )?
+            $( .with_verbose($verbose) )?
+            $( .with_print_on_drop($print_on_drop) )?
+            $( .with_rate_limit({
+                static RATE_LIMIT: $crate::RateLimit = $rate_limit;
+                &RATE_LIMIT
+            }) )?
+            $( .with_sample_rate($sample_rate) )?
+            $( .with_once(if $once {
+                static PRINTED: ::core::sync::atomic::AtomicBool =
+                    ::core::sync::atomic::AtomicBool::new(false);
+                Some(&PRINTED)
+            } else {
+                None
+            }) )?
+            .trace_entry()
+        }
     };
 }
 
@@ -205,7 +295,10 @@ macro_rules! unwind_context_with_fmt {
 ///
 /// An optimized build will generate `()` unless `-C debug-assertions` is passed
 /// to the compiler. This makes this macro no-op with the default release
-/// profile.
+/// profile. The `force-debug-context` feature makes it always create the
+/// context scope guard, and the `disable-debug-context` feature makes it always
+/// generate `()`. If both features are enabled, `force-debug-context` takes
+/// precedence.
 ///
 /// For more information about macro arguments, see [`unwind_context_with_fmt`].
 /// For more information about context argument, see
@@ -256,14 +349,20 @@ macro_rules! debug_unwind_context_with_fmt {
 }
 
 #[doc(hidden)]
-#[cfg(debug_assertions)]
+#[cfg(any(
+    feature = "force-debug-context",
+    all(debug_assertions, not(feature = "disable-debug-context"))
+))]
 #[macro_export]
 macro_rules! debug_unwind_context_with_fmt_impl {
     ( $( $tokens:tt )* ) => { $crate::unwind_context_with_fmt!( $($tokens)* ) };
 }
 
 #[doc(hidden)]
-#[cfg(not(debug_assertions))]
+#[cfg(not(any(
+    feature = "force-debug-context",
+    all(debug_assertions, not(feature = "disable-debug-context"))
+)))]
 #[macro_export]
 macro_rules! debug_unwind_context_with_fmt_impl {
     ($($tokens:tt)*) => {
@@ -392,7 +491,7 @@ mod tests {
     }
 
     #[cfg(feature = "std")]
-    #[allow(clippy::unwrap_used)]
+    #[allow(clippy::unwrap_used, clippy::no_effect_underscore_binding)]
     fn func_with_debug_unwind_context<W: FmtWrite, P: PanicDetector>(
         foo: usize,
         bar: &str,
@@ -534,8 +633,8 @@ mod tests {
         let output = &mut writer1.into_str();
         output
             .expect_str(
-                "{FN}fn {FN_NAME}func1{FN_BRACE}({DEF}foo: {NUM}1000{DEF}, bar: \
-                 {QUOT}\"ab\"{DEF}{FN_BRACE}){DEF}\n",
+                "{FN}fn {FN_NAME}func1{FN_BRACE}({DEF}{ARG}foo{DEF}: {NUM}1000{DEF}, \
+                 {ARG}bar{DEF}: {QUOT}\"ab\"{DEF}{FN_BRACE}){DEF}\n",
             )
             .unwrap();
         check_location_part(
@@ -551,8 +650,8 @@ mod tests {
         let output = &mut writer2.into_str();
         output
             .expect_str(
-                "{FN}fn {FN_NAME}func2{FN_BRACE}({DEF}foo: {NUM}2000{DEF}, bar: \
-                 {QUOT}\"b\"{DEF}{FN_BRACE}){DEF}\n",
+                "{FN}fn {FN_NAME}func2{FN_BRACE}({DEF}{ARG}foo{DEF}: {NUM}2000{DEF}, \
+                 {ARG}bar{DEF}: {QUOT}\"b\"{DEF}{FN_BRACE}){DEF}\n",
             )
             .unwrap();
         check_location_part(
@@ -568,8 +667,8 @@ mod tests {
         let output = &mut writer3.into_str();
         output
             .expect_str(
-                "{FN}fn {FN_NAME}func3{FN_BRACE}({DEF}foo: {NUM}6000{DEF}, bar: \
-                 {QUOT}\"\"{DEF}{FN_BRACE}){DEF}\n",
+                "{FN}fn {FN_NAME}func3{FN_BRACE}({DEF}{ARG}foo{DEF}: {NUM}6000{DEF}, \
+                 {ARG}bar{DEF}: {QUOT}\"\"{DEF}{FN_BRACE}){DEF}\n",
             )
             .unwrap();
         check_location_part(
@@ -710,7 +809,10 @@ mod tests {
         let output = collect_string_from_recv(&recv);
         let output = &mut output.as_str();
 
-        #[cfg(debug_assertions)]
+        #[cfg(any(
+            feature = "force-debug-context",
+            all(debug_assertions, not(feature = "disable-debug-context"))
+        ))]
         {
             output
                 .expect_str("fn func_with_debug_unwind_context(foo: 2, bar: \"abc\")\n")
@@ -719,4 +821,202 @@ mod tests {
         }
         assert_eq!(*output, "");
     }
+
+    #[cfg(feature = "std")]
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_unwind_context_with_fmt_with_thread_info() {
+        let mut buffer = [0; 256];
+        let value = 123;
+
+        let result = std::thread::Builder::new()
+            .name("worker-3".into())
+            .spawn(move || {
+                let mut writer = FixedBufWriter::new(&mut buffer);
+                let mut ctx = unwind_context_with_fmt!(
+                    (value),
+                    writer = &mut writer,
+                    panic_detector = StdPanicDetector,
+                    color_scheme = None,
+                    thread_info = true,
+                );
+                ctx.print();
+                drop(ctx);
+
+                let thread_id = std::format!("{:?}", std::thread::current().id());
+                let output = &mut writer.into_str();
+                output.expect_str("value: 123\n    at ").unwrap();
+                let _location = output.read_until(" in thread ").unwrap();
+                output.expect_str("'worker-3' (").unwrap();
+                output.expect_str(&thread_id).unwrap();
+                assert_eq!(*output, ")\n");
+            })
+            .unwrap()
+            .join();
+        assert!(result.is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_unwind_context_with_fmt_with_writer_fn() {
+        struct LenWriter<'a>(&'a core::cell::Cell<usize>);
+
+        impl FmtWrite for LenWriter<'_> {
+            fn write_str(&mut self, s: &str) -> FmtResult {
+                self.0.set(self.0.get().saturating_add(s.len()));
+                Ok(())
+            }
+        }
+
+        let len = core::cell::Cell::new(0);
+        let value = 123;
+        let mut ctx = unwind_context_with_fmt!(
+            (value),
+            writer_fn = || LenWriter(&len),
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+        );
+        assert_eq!(len.get(), 0);
+        ctx.print();
+        drop(ctx);
+        assert!(len.get() > "value: 123\n    at ".len());
+    }
+
+    #[cfg(feature = "std")]
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_unwind_context_with_fmt_with_elapsed_time() {
+        let mut buffer = [0; 256];
+        let value = 123;
+
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        let mut ctx = unwind_context_with_fmt!(
+            (value),
+            writer = &mut writer,
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+            elapsed_time = true,
+        );
+        ctx.print();
+        drop(ctx);
+
+        let output = &mut writer.into_str();
+        output.expect_str("value: 123\n    at ").unwrap();
+        let _location = output.read_until(" after ").unwrap();
+        let _seconds = output.read_until("s in scope").unwrap();
+        assert_eq!(*output, "\n");
+    }
+
+    #[cfg(feature = "std")]
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_unwind_context_with_fmt_with_process_id() {
+        let mut buffer = [0; 256];
+        let value = 123;
+
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        let mut ctx = unwind_context_with_fmt!(
+            (value),
+            writer = &mut writer,
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+            process_id = true,
+        );
+        ctx.print();
+        drop(ctx);
+
+        let process_id = std::format!("{}", std::process::id());
+        let output = &mut writer.into_str();
+        output.expect_str("value: 123\n    at ").unwrap();
+        let _location = output.read_until(" in process ").unwrap();
+        output.expect_str(&process_id).unwrap();
+        assert_eq!(*output, "\n");
+    }
+
+    #[cfg(feature = "std")]
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_unwind_context_with_fmt_with_backtrace() {
+        let mut output = String::new();
+        let value = 123;
+
+        let mut ctx = unwind_context_with_fmt!(
+            (value),
+            writer = &mut output,
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+            backtrace = true,
+        );
+        ctx.print();
+        drop(ctx);
+
+        let output = &mut output.as_str();
+        output.expect_str("value: 123\n    at ").unwrap();
+        let _location = output.read_until("\nstack backtrace:\n").unwrap();
+        assert!(output.ends_with('\n'));
+    }
+
+    #[cfg(feature = "std")]
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_unwind_context_with_fmt_with_location() {
+        use core::panic::Location;
+
+        #[track_caller]
+        fn caller_location() -> &'static Location<'static> {
+            Location::caller()
+        }
+
+        let mut output = String::new();
+        let value = 123;
+        let location = caller_location();
+
+        let mut ctx = unwind_context_with_fmt!(
+            (value),
+            writer = &mut output,
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+            location = location,
+        );
+        ctx.print();
+        drop(ctx);
+
+        let output = &mut output.as_str();
+        output.expect_str("value: 123\n    at ").unwrap();
+        output
+            .expect_str(&std::format!(
+                "{}:{}:{}",
+                location.file(),
+                location.line(),
+                location.column()
+            ))
+            .unwrap();
+        assert_eq!(*output, "\n");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_unwind_context_with_fmt_accessors() {
+        use core::panic::AssertUnwindSafe;
+
+        fn func(foo: usize, output: &mut String) -> usize {
+            let ctx = unwind_context_with_fmt!(
+                (fn func(foo)),
+                writer = &mut *output,
+                panic_detector = StdPanicDetector,
+                color_scheme = Some(&TEST_COLOR_SCHEME),
+            );
+            assert_eq!(std::format!("{:?}", ctx.data()), "fn func(foo: 1)");
+            assert_eq!(ctx.location().file(), file!());
+            assert_eq!(ctx.color_scheme(), Some(&TEST_COLOR_SCHEME));
+            let data = ctx.into_inner();
+            assert_eq!(std::format!("{data:?}"), "fn func(foo: 1)");
+            foo.checked_sub(2).unwrap()
+        }
+
+        let mut output = String::new();
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| func(1, &mut output)));
+        assert!(result.is_err());
+        assert_eq!(output, "");
+    }
 }
@@ -1,122 +1,105 @@
-use core::fmt::Debug;
-use core::panic::Location;
-use std::io::Write;
-
-use crate::{AnsiColorScheme, AnsiColored, DebugAnsiColored, PanicDetector};
+use crate::{DefaultGuardOptions, IoSink, UnwindContextWithSink};
 
 /// A structure representing a scoped guard with unwind context with
-/// [`core::fmt::Write`] writer.
+/// [`std::io::Write`] writer.
 ///
 /// If dropped during unwind it will write a message to a given writer
-/// containing given function or scope context.
+/// containing given function or scope context. If created with
+/// [`unwind_context`] it will write to [`DefaultWriter`].
 ///
 /// When this structure is dropped (falls out of scope) and the current thread
 /// is not unwinding, the unwind context will be forgotten.
 ///
-/// # Examples
+/// It is an alias of [`UnwindContextWithSink`] writing to an [`IoSink`].
 ///
-/// ```rust
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
 /// use unwind_context::{unwind_context, UnwindContextWithIo};
 ///
 /// fn func(foo: u32, bar: &str, secret: &str) {
-///     let _ctx: UnwindContextWithIo<_, _, _> = unwind_context!(fn(foo, bar, ...));
+///     let _ctx: UnwindContextWithIo<_, _, _> =
+///         unwind_context!(fn(foo, bar, ...));
 ///     // ...
 /// }
-/// ```
-///
+#[doc = "```"]
+#[doc = ""]
 /// [`unwind_context`]: crate::unwind_context
-#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
-pub struct UnwindContextWithIo<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> {
-    data: T,
-    writer: W,
-    panic_detector: P,
-    color_scheme: Option<&'static AnsiColorScheme>,
-    location: &'static Location<'static>,
-}
-
-impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> Drop
-    for UnwindContextWithIo<W, T, P>
-{
-    #[inline]
-    fn drop(&mut self) {
-        if self.panic_detector.is_panicking() {
-            self.print();
-        }
-    }
-}
-
-impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> UnwindContextWithIo<W, T, P> {
-    /// Create a new `UnwindContextWithFmt` with the provided
-    /// [`core::fmt::Write`] writer, context scope data, and color scheme.
-    ///
-    /// This function is not intended to be used directly. Consider using macros
-    /// like [`unwind_context`] or [`unwind_context_with_io`] instead.
-    ///
-    /// [`unwind_context`]: crate::unwind_context
-    /// [`unwind_context_with_io`]: crate::unwind_context_with_io
-    #[inline]
-    #[must_use = "\
-        if unused, the `UnwindContextWithIo` will immediately drop,
-        consider binding the `UnwindContextWithIo` like `let _ctx = ...`.
-    "]
-    #[track_caller]
-    pub fn new(
-        data: T,
-        writer: W,
-        panic_detector: P,
-        color_scheme: Option<&'static AnsiColorScheme>,
-    ) -> Self {
-        Self {
-            data,
-            writer,
-            panic_detector,
-            color_scheme,
-            location: Location::caller(),
-        }
-    }
-
-    /// Print context to a writer specified in the `UnwindContextWithIo`
-    /// constructor.
-    ///
-    /// This method is called when a panic detected.
-    #[cold]
-    #[inline(never)]
-    pub fn print(&mut self) {
-        if let Some(color_scheme) = self.color_scheme {
-            let _ = writeln!(
-                self.writer,
-                "{:?}\n    at {}{}:{}:{}{}",
-                AnsiColored::new(&self.data, color_scheme),
-                color_scheme.location,
-                self.location.file(),
-                self.location.line(),
-                self.location.column(),
-                color_scheme.default,
-            );
-        } else {
-            let _ = writeln!(
-                self.writer,
-                "{:?}\n    at {}:{}:{}",
-                self.data,
-                self.location.file(),
-                self.location.line(),
-                self.location.column(),
-            );
-        }
-        let _ = self.writer.flush();
-    }
-}
+/// [`DefaultWriter`]: crate::DefaultWriter
+pub type UnwindContextWithIo<W, T, P, O = DefaultGuardOptions> =
+    UnwindContextWithSink<IoSink<W>, T, P, O>;
 
 /// Creates [`UnwindContextWithIo`] with a given [`std::io::Write`] writer,
 /// panic detector, color scheme, and a given function or scope context.
 ///
-/// If not specified it uses [`std::io::stderr`] as a default writer,
+/// If not specified it uses [`DefaultWriter`] as a default writer,
 /// [`StdPanicDetector`] as a default panic detector and
 /// [`get_default_color_scheme_if_enabled`] as a default color scheme. When
 /// using default values for all optional parameters, consider the
 /// use of [`unwind_context`] macro instead. See
 /// [equivalent macros](#equivalent-macros) section below.
 ///
+/// If `writer_fn = || ...` is specified instead of `writer`, the writer is
+/// created with the given function only when the context is printed, so the
+/// context scope guard stores only the function. See [`WriterFn`] for more
+/// details.
+///
+/// If `color_scheme = Auto` is specified, the default color scheme is used only
+/// if the writer writes to a terminal, which is checked once when the context
+/// scope guard is created. It requires the writer to implement
+/// [`TerminalWriter`]. See [`AutoColorScheme`] for more details.
+///
+/// If `thread_info = true` is specified, the current thread name and ID are
+/// printed after the context location regardless of the global
+/// [`set_thread_info_enabled`] setting.
+///
+/// If `process_id = true` is specified, the current process ID is printed
+/// after the context location regardless of the global
+/// [`set_process_id_enabled`] setting.
+///
+/// If `elapsed_time = true` is specified, the time spent in the context scope
+/// is printed after the context location regardless of the global
+/// [`set_elapsed_time_enabled`] setting.
+///
+/// If `backtrace = true` is specified, a [`std::backtrace::Backtrace`] is
+/// captured when the context is printed and printed after the context
+/// location. It helps to correlate the context with the real call stack when
+/// not every function is instrumented with unwind context.
+///
+/// If `location = ...` is specified, the given [`core::panic::Location`] is
+/// printed instead of the location where the context scope guard is created.
+/// It allows helper functions creating context scope guards on behalf of their
+/// callers to report the caller location with a `#[track_caller]` attribute
+/// and [`core::panic::Location::caller`].
+///
+/// If `location_style = ...` is specified, the code location is printed with
+/// the given [`LocationStyle`] regardless of the global
+/// [`set_location_style`] setting.
+///
+/// If `layout = ...` is specified, the context is written with the given
+/// [`ContextLayout`] regardless of the global [`set_context_layout`] setting.
+///
+/// If `verbose = true` is specified, the context is also printed prefixed with
+/// `enter` when the context scope guard is created regardless of the global
+/// [`set_verbose_enabled`] setting.
+///
+/// If `print_on_drop = true` is specified, the context is printed every time
+/// the context scope guard is dropped, prefixed with `ok` on a normal scope
+/// exit and with `panic` on unwind, regardless of the global
+/// [`set_print_on_drop_enabled`] setting.
+///
+/// If `rate_limit = ...` is specified, frames of the context scope guard
+/// location are printed at most as often as allowed by the given [`RateLimit`]
+/// regardless of the global [`set_rate_limit`] setting. The limit is stored in
+/// a static variable, so it should be a constant expression.
+///
+/// If `sample_rate = ...` is specified, only the first and then every `n`-th
+/// frame of the context scope guard location is printed regardless of the
+/// global [`set_sample_rate`] setting.
+///
+/// If `once = true` is specified, the frame of the macro call site is printed
+/// at most once per process run, which is useful for known-noisy locations.
+///
 /// The returned unwind context scope guard value should be kept alive as long
 /// as unwind context is needed. If unused, the [`UnwindContextWithIo`] will
 /// immediately drop.
@@ -150,6 +133,15 @@ impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> UnwindContextWithI
 /// ```
 ///
 /// ```rust
+/// use unwind_context::unwind_context_with_io;
+///
+/// fn example4(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!((fn(foo, bar)), thread_info = true, elapsed_time = true);
+///     // ...
+/// }
+/// ```
+///
+/// ```rust
 /// use unwind_context::{unwind_context_with_io, AnsiColorScheme};
 ///
 /// fn example3<W: std::io::Write, P: unwind_context::PanicDetector>(
@@ -169,6 +161,23 @@ impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> UnwindContextWithI
 /// }
 /// ```
 ///
+/// ```rust
+/// use core::panic::Location;
+///
+/// use unwind_context::unwind_context_with_io;
+///
+/// #[track_caller]
+/// fn step_context(step: u32) -> impl Sized {
+///     unwind_context_with_io!((step), location = Location::caller())
+/// }
+///
+/// fn example5() {
+///     // The location of this line is printed with the context.
+///     let _ctx = step_context(1);
+///     // ...
+/// }
+/// ```
+///
 /// # Equivalent macros
 /// ```rust
 /// use unwind_context::{unwind_context, unwind_context_with_io};
@@ -178,7 +187,7 @@ impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> UnwindContextWithI
 ///     let _ctx = unwind_context_with_io!((fn(foo, bar)));
 ///     let _ctx = unwind_context_with_io!(
 ///         (fn(foo, bar)),
-///         writer = ::std::io::stderr(),
+///         writer = unwind_context::DefaultWriter,
 ///         panic_detector = unwind_context::StdPanicDetector,
 ///         color_scheme = unwind_context::get_default_color_scheme_if_enabled(),
 ///     );
@@ -186,33 +195,153 @@ impl<W: Write, T: Debug + DebugAnsiColored, P: PanicDetector> UnwindContextWithI
 /// ```
 ///
 /// [`unwind_context`]: crate::unwind_context
+/// [`DefaultWriter`]: crate::DefaultWriter
 /// [`StdPanicDetector`]: crate::StdPanicDetector
 /// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
 /// [`build_unwind_context_data`]: crate::build_unwind_context_data
+/// [`WriterFn`]: crate::WriterFn
+/// [`TerminalWriter`]: crate::TerminalWriter
+/// [`AutoColorScheme`]: crate::AutoColorScheme
+/// [`set_thread_info_enabled`]: crate::set_thread_info_enabled
+/// [`set_process_id_enabled`]: crate::set_process_id_enabled
+/// [`set_elapsed_time_enabled`]: crate::set_elapsed_time_enabled
+/// [`LocationStyle`]: crate::LocationStyle
+/// [`set_location_style`]: crate::set_location_style
+/// [`ContextLayout`]: crate::ContextLayout
+/// [`set_context_layout`]: crate::set_context_layout
+/// [`set_verbose_enabled`]: crate::set_verbose_enabled
+/// [`set_print_on_drop_enabled`]: crate::set_print_on_drop_enabled
+/// [`RateLimit`]: crate::RateLimit
+/// [`set_rate_limit`]: crate::set_rate_limit
+/// [`set_sample_rate`]: crate::set_sample_rate
 #[macro_export]
 macro_rules! unwind_context_with_io {
     (
         ( $( $context:tt )* )
         $(, writer = $writer:expr )?
+        $(, writer_fn = $writer_fn:expr )?
         $(, panic_detector = $panic_detector:expr )?
         $(, color_scheme = $color_scheme:expr )?
+        $(, thread_info = $thread_info:expr )?
+        $(, process_id = $process_id:expr )?
+        $(, elapsed_time = $elapsed_time:expr )?
+        $(, backtrace = $backtrace:expr )?
+        $(, location = $location:expr )?
+        $(, location_style = $location_style:expr )?
+        $(, layout = $layout:expr )?
+        $(, verbose = $verbose:expr )?
+        $(, print_on_drop = $print_on_drop:expr )?
+        $(, rate_limit = $rate_limit:expr )?
+        $(, sample_rate = $sample_rate:expr )?
+        $(, once = $once:expr )?
         $(,)?
     ) => {
-        $crate::UnwindContextWithIo::new(
-            $crate::build_unwind_context_data!( $($context)* ),
-            $crate::expr_or_default_expr!(
-                $( $writer )?,
-                ::std::io::stderr()
-            ),
-            $crate::expr_or_default_expr!(
-                $( $panic_detector )?,
-                $crate::StdPanicDetector
-            ),
-            $crate::expr_or_default_expr!(
-                $( $color_scheme )?,
+        $crate::unless_off! { $crate::if_any_tokens!(
+            [
+                $( $color_scheme )? $( $thread_info )? $( $process_id )?
+                $( $elapsed_time )? $( $backtrace )? $( $location_style )?
+                $( $layout )? $( $verbose )? $( $print_on_drop )?
+                $( $rate_limit )? $( $sample_rate )? $( $once )?
+            ],
+            { $crate::unwind_context_with_io!(
+                @custom ( $($context)* )
+                $(, writer = $writer )?
+                $(, writer_fn = $writer_fn )?
+                $(, panic_detector = $panic_detector )?
+                $(, color_scheme = $color_scheme )?
+                $(, thread_info = $thread_info )?
+                $(, process_id = $process_id )?
+                $(, elapsed_time = $elapsed_time )?
+                $(, backtrace = $backtrace )?
+                $(, location = $location )?
+                $(, location_style = $location_style )?
+                $(, layout = $layout )?
+                $(, verbose = $verbose )?
+                $(, print_on_drop = $print_on_drop )?
+                $(, rate_limit = $rate_limit )?
+                $(, sample_rate = $sample_rate )?
+                $(, once = $once )?
+            ) },
+            {
+                $crate::UnwindContextWithIo::new_default(
+                    $crate::build_unwind_context_data!( $($context)* ),
+                    $crate::expr_or_default_expr!(
+                        $( $writer )? $( $crate::WriterFn($writer_fn) )?,
+                        $crate::DefaultWriter
+                    ),
+                    $crate::expr_or_default_expr!(
+                        $( $panic_detector )?,
+                        $crate::StdPanicDetector
+                    ),
+                )
+                $( .with_location($location) )?
+                .trace_entry()
+            }
+        ) }
+    };
+    (
+        @custom ( $( $context:tt )* )
+        $(, writer = $writer:expr )?
+        $(, writer_fn = $writer_fn:expr )?
+        $(, panic_detector = $panic_detector:expr )?
+        $(, color_scheme = $color_scheme:expr )?
+        $(, thread_info = $thread_info:expr )?
+        $(, process_id = $process_id:expr )?
+        $(, elapsed_time = $elapsed_time:expr )?
+        $(, backtrace = $backtrace:expr )?
+        $(, location = $location:expr )?
+        $(, location_style = $location_style:expr )?
+        $(, layout = $layout:expr )?
+        $(, verbose = $verbose:expr )?
+        $(, print_on_drop = $print_on_drop:expr )?
+        $(, rate_limit = $rate_limit:expr )?
+        $(, sample_rate = $sample_rate:expr )?
+        $(, once = $once:expr )?
+    ) => {
+        {
+            $crate::UnwindContextWithIo::new(
+                $crate::build_unwind_context_data!( $($context)* ),
+                $crate::expr_or_default_expr!(
+                    $( $writer )? $( $crate::WriterFn($writer_fn) )?,
+                    $crate::DefaultWriter
+                ),
+                $crate::expr_or_default_expr!(
+                    $( $panic_detector )?,
+                    $crate::StdPanicDetector
+                ),
+                None,
+            )
+            .with_color_scheme($crate::expr_or_default_expr!(
+                $({
+                    #[allow(unused_imports)]
+                    use $crate::AutoColorScheme as Auto;
+                    $color_scheme
+                })?,
                 $crate::get_default_color_scheme_if_enabled()
-            ),
-        )
+            ))
+            $( .with_thread_info($thread_info) )?
+            $( .with_process_id($process_id) )?
+            $( .with_elapsed_time($elapsed_time) )?
+            $( .with_backtrace($backtrace) )?
+            $( .with_location($location) )?
+            $( .with_location_style($location_style) )?
+            $( .with_layout($layout) )?
+            $( .with_verbose($verbose) )?
+            $( .with_print_on_drop($print_on_drop) )?
+            $( .with_rate_limit({
+                static RATE_LIMIT: $crate::RateLimit = $rate_limit;
+                &RATE_LIMIT
+            }) )?
+            $( .with_sample_rate($sample_rate) )?
+            $( .with_once(if $once {
+                static PRINTED: ::core::sync::atomic::AtomicBool =
+                    ::core::sync::atomic::AtomicBool::new(false);
+                Some(&PRINTED)
+            } else {
+                None
+            }) )?
+            .trace_entry()
+        }
     };
 }
 
@@ -220,7 +349,7 @@ macro_rules! unwind_context_with_io {
 /// panic detector, color scheme, and a given function or scope context in debug
 /// builds only.
 ///
-/// If not specified it uses [`std::io::stderr`] as a default writer,
+/// If not specified it uses [`DefaultWriter`] as a default writer,
 /// [`StdPanicDetector`] as a default panic detector and
 /// [`get_default_color_scheme_if_enabled`] as a default color scheme. When
 /// using default values for all optional parameters, consider the
@@ -238,7 +367,10 @@ macro_rules! unwind_context_with_io {
 ///
 /// An optimized build will generate `()` unless `-C debug-assertions` is passed
 /// to the compiler. This makes this macro no-op with the default release
-/// profile.
+/// profile. The `force-debug-context` feature makes it always create the
+/// context scope guard, and the `disable-debug-context` feature makes it always
+/// generate `()`. If both features are enabled, `force-debug-context` takes
+/// precedence.
 ///
 /// For more information about macro arguments, see [`unwind_context_with_io`].
 /// For more information about context argument, see
@@ -293,7 +425,7 @@ macro_rules! unwind_context_with_io {
 ///     debug_unwind_context_with_io!((fn(foo, bar)));
 ///     debug_unwind_context_with_io!(
 ///         (fn(foo, bar)),
-///         writer = ::std::io::stderr(),
+///         writer = unwind_context::DefaultWriter,
 ///         panic_detector = unwind_context::StdPanicDetector,
 ///         color_scheme = unwind_context::get_default_color_scheme_if_enabled(),
 ///     );
@@ -302,6 +434,7 @@ macro_rules! unwind_context_with_io {
 ///
 /// [`unwind_context_with_io`]: crate::unwind_context_with_io
 /// [`debug_unwind_context`]: crate::debug_unwind_context
+/// [`DefaultWriter`]: crate::DefaultWriter
 /// [`StdPanicDetector`]: crate::StdPanicDetector
 /// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
 /// [`build_unwind_context_data`]: crate::build_unwind_context_data
@@ -311,14 +444,20 @@ macro_rules! debug_unwind_context_with_io {
 }
 
 #[doc(hidden)]
-#[cfg(debug_assertions)]
+#[cfg(any(
+    feature = "force-debug-context",
+    all(debug_assertions, not(feature = "disable-debug-context"))
+))]
 #[macro_export]
 macro_rules! debug_unwind_context_with_io_impl {
     ( $( $tokens:tt )* ) => { $crate::unwind_context_with_io!( $($tokens)* ) };
 }
 
 #[doc(hidden)]
-#[cfg(not(debug_assertions))]
+#[cfg(not(any(
+    feature = "force-debug-context",
+    all(debug_assertions, not(feature = "disable-debug-context"))
+)))]
 #[macro_export]
 macro_rules! debug_unwind_context_with_io_impl {
     ($($tokens:tt)*) => {
@@ -328,14 +467,17 @@ macro_rules! debug_unwind_context_with_io_impl {
 
 #[cfg(test)]
 mod tests {
+    use core::fmt::{Debug, Formatter, Result as FmtResult};
+    use core::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
     use std::borrow::ToOwned;
     use std::io::{Result as IoResult, Write as IoWrite};
     use std::string::String;
     use std::sync::mpsc;
+    use std::vec::Vec;
 
-    use crate::test_common::{check_location_part, TEST_COLOR_SCHEME};
+    use crate::test_common::{check_location_part, SERIAL_TEST, TEST_COLOR_SCHEME};
     use crate::test_util::{collect_string_from_recv, PatternMatcher};
-    use crate::AnsiColorScheme;
+    use crate::{AnsiColorScheme, TerminalWriter};
 
     #[derive(Clone)]
     pub struct Writer(mpsc::Sender<String>);
@@ -400,7 +542,7 @@ mod tests {
         foo.checked_sub(bar.len()).unwrap()
     }
 
-    #[allow(clippy::unwrap_used)]
+    #[allow(clippy::unwrap_used, clippy::no_effect_underscore_binding)]
     fn func_with_debug_unwind_context<W: IoWrite>(
         foo: usize,
         bar: &str,
@@ -487,6 +629,311 @@ mod tests {
         assert_eq!(*output, "");
     }
 
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_unwind_context_with_io_writes_frames_atomically() {
+        let (sender, recv) = mpsc::channel();
+        let mut writer = Writer(sender);
+        let result = std::panic::catch_unwind(move || func1(1000, "a", &mut writer, None));
+        assert!(result.is_err());
+        let chunks: Vec<_> = recv.try_iter().collect();
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks[0].starts_with("fn func2(foo: 2000, bar: \"\")\n    at "));
+        assert!(chunks[1].starts_with("fn func1(foo: 1000, bar: \"a\")\n    at "));
+        assert!(chunks.iter().all(|chunk| chunk.ends_with('\n')));
+
+        let (sender, recv) = mpsc::channel();
+        let mut writer = Writer(sender);
+        let long = "a".repeat(2000);
+        let result = std::panic::catch_unwind(move || {
+            let _ctx =
+                unwind_context_with_io!((long), writer = writer.clone(), color_scheme = None);
+            func1(1000, "", &mut writer, None)
+        });
+        assert!(result.is_err());
+        let output = collect_string_from_recv(&recv);
+        let output = output.split_once("\n    at ").unwrap().1;
+        let output = output.split_once('\n').unwrap().1;
+        assert!(output.starts_with("long: \"aaaa"));
+        assert!(output.ends_with('\n'));
+        assert!(output.len() > 2000);
+    }
+
+    struct CountedDebug<'a>(&'a AtomicUsize);
+
+    impl Debug for CountedDebug<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+            let _ = self.0.fetch_add(1, AtomicOrdering::Relaxed);
+            f.write_str(&"a".repeat(2000))
+        }
+    }
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_unwind_context_with_io_formats_long_frames_once() {
+        let (sender, recv) = mpsc::channel();
+        let writer = Writer(sender);
+        let count = AtomicUsize::new(0);
+        let result = std::panic::catch_unwind(|| {
+            let counted = CountedDebug(&count);
+            let _ctx = unwind_context_with_io!((counted), writer = writer, color_scheme = None);
+            panic!("Error");
+        });
+        assert!(result.is_err());
+        assert_eq!(count.load(AtomicOrdering::Relaxed), 1);
+        let output = collect_string_from_recv(&recv);
+        assert!(output.starts_with("counted: aaaa"));
+        assert!(output.len() > 2000);
+    }
+
+    #[test]
+    fn test_unwind_context_default_guard_size() {
+        use core::mem::{size_of, size_of_val};
+        use core::panic::Location;
+
+        use crate::{DefaultGuardOptions, DefaultWriter, StdPanicDetector, StderrWriter};
+
+        assert_eq!(size_of::<DefaultWriter>(), 0);
+        assert_eq!(size_of::<StderrWriter>(), 0);
+        assert_eq!(size_of::<StdPanicDetector>(), 0);
+        assert_eq!(size_of::<DefaultGuardOptions>(), 0);
+
+        let value = 123_u32;
+        let data = crate::build_unwind_context_data!(value);
+        let ctx = crate::unwind_context!(value);
+        assert_eq!(
+            size_of_val(&ctx),
+            size_of_val(&data) + size_of::<&Location<'_>>()
+        );
+
+        let ctx = crate::unwind_context!(fn func(value));
+        let data = crate::build_unwind_context_data!(fn func(value));
+        assert_eq!(
+            size_of_val(&ctx),
+            size_of_val(&data) + size_of::<&Location<'_>>()
+        );
+    }
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_unwind_context_with_io_with_writer_fn() {
+        let (sender, recv) = mpsc::channel();
+        let writer = Writer(sender);
+        let result = std::panic::catch_unwind(move || {
+            let foo = 123;
+            let _ctx = unwind_context_with_io!(
+                (fn func(foo)),
+                writer_fn = || writer.clone(),
+                color_scheme = None,
+            );
+            panic!("Error");
+        });
+        assert!(result.is_err());
+        let output = collect_string_from_recv(&recv);
+        assert!(output.starts_with("fn func(foo: 123)\n    at src/context_with_io.rs:"));
+    }
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_unwind_context_with_io_with_verbose() {
+        let (sender, recv) = mpsc::channel();
+        let writer = Writer(sender);
+        let result = std::panic::catch_unwind(move || {
+            let foo = 123;
+            let _outer = unwind_context_with_io!(
+                (fn outer(foo)),
+                writer = writer.clone(),
+                color_scheme = None,
+                verbose = true,
+            );
+            let _inner = unwind_context_with_io!(
+                (fn inner()),
+                writer = writer.clone(),
+                color_scheme = None,
+                verbose = false,
+            );
+            panic!("Error");
+        });
+        assert!(result.is_err());
+        let output = collect_string_from_recv(&recv);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "enter fn outer(foo: 123)");
+        assert_eq!(lines[2], "fn inner()");
+        assert_eq!(lines[4], "fn outer(foo: 123)");
+        assert_eq!(lines[1], lines[5]);
+    }
+
+    #[test]
+    fn test_unwind_context_with_io_with_print_on_drop() {
+        fn func<W: Clone + IoWrite>(foo: u32, writer: &W) {
+            let _ctx = unwind_context_with_io!(
+                (fn func(foo)),
+                writer = writer.clone(),
+                color_scheme = None,
+                print_on_drop = true,
+            );
+            assert_ne!(foo, 0, "Error");
+        }
+
+        let (sender, recv) = mpsc::channel();
+        let writer = Writer(sender);
+        func(1, &writer);
+        let result = std::panic::catch_unwind(move || func(0, &writer));
+        assert!(result.is_err());
+        let output = collect_string_from_recv(&recv);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "ok fn func(foo: 1)");
+        assert_eq!(lines[2], "panic fn func(foo: 0)");
+        assert_eq!(lines[1], lines[3]);
+    }
+
+    #[test]
+    fn test_unwind_context_with_io_with_rate_limit() {
+        fn func<W: Clone + IoWrite>(foo: u32, writer: &W) {
+            let _ctx = unwind_context_with_io!(
+                (fn func(foo)),
+                writer = writer.clone(),
+                color_scheme = None,
+                rate_limit = crate::RateLimit::per_occurrences(2),
+            );
+            assert_ne!(foo, 0, "Error");
+        }
+
+        let (sender, recv) = mpsc::channel();
+        let writer = Writer(sender);
+        let result = std::panic::catch_unwind(move || {
+            for _ in 0..4 {
+                let result = std::panic::catch_unwind(|| func(0, &writer));
+                assert!(result.is_err());
+            }
+        });
+        assert!(result.is_ok());
+        let output = collect_string_from_recv(&recv);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[0], "fn func(foo: 0)");
+        assert_eq!(lines[2], "fn func(foo: 0)");
+        assert_eq!(lines[1], lines[3]);
+        assert_eq!(lines[4], "... frame suppressed 1 time by rate limit");
+
+        // Skipped frames are not captured either.
+        let capture = crate::capture();
+        for _ in 0..4 {
+            let result = std::panic::catch_unwind(|| func(0, &std::io::sink()));
+            assert!(result.is_err());
+        }
+        assert_eq!(capture.len(), 2);
+    }
+
+    #[test]
+    fn test_unwind_context_with_io_with_sample_rate() {
+        fn func<W: Clone + IoWrite>(foo: u32, writer: &W) {
+            let _ctx = unwind_context_with_io!(
+                (fn func(foo)),
+                writer = writer.clone(),
+                color_scheme = None,
+                sample_rate = 3,
+            );
+            assert_ne!(foo, 0, "Error");
+        }
+
+        let (sender, recv) = mpsc::channel();
+        let writer = Writer(sender);
+        for _ in 0..4 {
+            let result = std::panic::catch_unwind(|| func(0, &writer));
+            assert!(result.is_err());
+        }
+        drop(writer);
+        let output = collect_string_from_recv(&recv);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "fn func(foo: 0)");
+        assert_eq!(lines[2], "fn func(foo: 0)");
+        assert_eq!(lines[1], lines[3]);
+
+        // Skipped frames are not captured either.
+        let capture = crate::capture();
+        for _ in 0..4 {
+            let result = std::panic::catch_unwind(|| func(0, &std::io::sink()));
+            assert!(result.is_err());
+        }
+        assert_eq!(capture.len(), 1);
+    }
+
+    #[test]
+    fn test_unwind_context_with_io_with_once() {
+        fn func<W: Clone + IoWrite>(foo: u32, writer: &W) {
+            let _ctx = unwind_context_with_io!(
+                (fn func(foo)),
+                writer = writer.clone(),
+                color_scheme = None,
+                once = true,
+            );
+            assert_ne!(foo, 0, "Error");
+        }
+
+        let (sender, recv) = mpsc::channel();
+        let writer = Writer(sender);
+        for _ in 0..3 {
+            let result = std::panic::catch_unwind(|| func(0, &writer));
+            assert!(result.is_err());
+        }
+        drop(writer);
+        let output = collect_string_from_recv(&recv);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "fn func(foo: 0)");
+
+        // Skipped frames are not captured either.
+        let capture = crate::capture();
+        let result = std::panic::catch_unwind(|| func(0, &std::io::sink()));
+        assert!(result.is_err());
+        assert!(capture.is_empty());
+    }
+
+    #[test]
+    fn test_unwind_context_with_io_with_auto_color_scheme() {
+        struct TerminalBuffer(bool, Vec<u8>);
+
+        impl IoWrite for TerminalBuffer {
+            fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+                self.1.write(buf)
+            }
+
+            fn flush(&mut self) -> IoResult<()> {
+                Ok(())
+            }
+        }
+
+        impl TerminalWriter for TerminalBuffer {
+            fn writes_to_terminal(&self) -> bool {
+                self.0
+            }
+        }
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        let foo = 123;
+        for is_terminal in [false, true] {
+            let mut writer = TerminalBuffer(is_terminal, Vec::new());
+            let mut ctx = unwind_context_with_io!(
+                (fn func(foo)),
+                writer = &mut writer,
+                color_scheme = Auto,
+            );
+            ctx.print();
+            drop(ctx);
+            let output = String::from_utf8(writer.1).unwrap();
+            if is_terminal {
+                assert!(output.starts_with("\u{1b}[33mfn \u{1b}[93mfunc"));
+            } else {
+                assert!(output.starts_with("fn func(foo: 123)\n    at "));
+            }
+        }
+    }
+
     #[allow(clippy::unwrap_used)]
     #[test]
     fn test_unwind_context_with_io_with_unwind_with_colored_fmt() {
@@ -500,8 +947,8 @@ mod tests {
         let output = &mut output.as_str();
         output
             .expect_str(
-                "{FN}fn {FN_NAME}func2{FN_BRACE}({DEF}foo: {NUM}2000{DEF}, bar: \
-                 {QUOT}\"\"{DEF}{FN_BRACE}){DEF}\n",
+                "{FN}fn {FN_NAME}func2{FN_BRACE}({DEF}{ARG}foo{DEF}: {NUM}2000{DEF}, \
+                 {ARG}bar{DEF}: {QUOT}\"\"{DEF}{FN_BRACE}){DEF}\n",
             )
             .unwrap();
         check_location_part(
@@ -514,8 +961,8 @@ mod tests {
         );
         output
             .expect_str(
-                "{FN}fn {FN_NAME}func1{FN_BRACE}({DEF}foo: {NUM}1000{DEF}, bar: \
-                 {QUOT}\"a\"{DEF}{FN_BRACE}){DEF}\n",
+                "{FN}fn {FN_NAME}func1{FN_BRACE}({DEF}{ARG}foo{DEF}: {NUM}1000{DEF}, \
+                 {ARG}bar{DEF}: {QUOT}\"a\"{DEF}{FN_BRACE}){DEF}\n",
             )
             .unwrap();
         check_location_part(
@@ -554,7 +1001,10 @@ mod tests {
         let output = collect_string_from_recv(&recv);
         let output = &mut output.as_str();
 
-        #[cfg(debug_assertions)]
+        #[cfg(any(
+            feature = "force-debug-context",
+            all(debug_assertions, not(feature = "disable-debug-context"))
+        ))]
         {
             output
                 .expect_str("fn func_with_debug_unwind_context(foo: 2, bar: \"abc\")\n")
@@ -0,0 +1,822 @@
+use core::fmt::Debug;
+use core::panic::Location;
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicBool;
+
+#[cfg(feature = "std")]
+use crate::capture::{capture_frame, is_capture_active};
+use crate::frame::{FrameFmt, RepeatedFramesFmt, SuppressedFramesFmt};
+#[cfg(feature = "std")]
+use crate::frame::{RateLimitedFramesFmt, UnwindSummaryFmt};
+use crate::frame_options::resolve_frame_options;
+#[cfg(feature = "std")]
+use crate::frame_sink::send_to_frame_sink;
+#[cfg(feature = "std")]
+use crate::once_flag::OnceFlag;
+#[cfg(feature = "std")]
+use crate::output_budget::charge_output_budget;
+#[cfg(feature = "std")]
+use crate::panic_location::{take_panic_location, PanicLocationFmt};
+#[cfg(feature = "std")]
+use crate::rate_limit::check_rate_limit;
+#[cfg(feature = "std")]
+use crate::sampling::is_sampled;
+#[cfg(feature = "std")]
+use crate::stats::{record_frame_printed, record_frame_suppressed, record_unwind};
+use crate::thread_contexts::should_print_context;
+#[cfg(feature = "std")]
+use crate::unwind_banner::{ThreadBannerFmt, UnwindBannerFmt};
+use crate::unwind_state::{
+    current_scope, end_unwind, enter_frame, enter_scope, exit_scope, scope_depth, GuardScope,
+    UnwindEnd,
+};
+#[cfg(feature = "std")]
+use crate::unwind_state::{lock_stderr_for_unwind, take_stderr_lock};
+#[cfg(feature = "std")]
+use crate::{
+    get_unwind_footer, get_unwind_header, is_stderr_lock_enabled, is_thread_banner_enabled,
+    is_unwind_summary_enabled, RateLimit, UnwindBannerFn,
+};
+use crate::{
+    AnsiColorScheme, ColorSchemeChoice, ContextLayout, ContextSink, CustomGuardOptions,
+    DebugAnsiColored, DefaultGuardOptions, FrameInfo, GuardOptions, LocationStyle, PanicDetector,
+};
+
+/// A structure representing a scoped guard with unwind context with a
+/// [`ContextSink`] output.
+///
+/// If dropped during unwind it will write a message to a given sink
+/// containing given function or scope context.
+///
+/// When this structure is dropped (falls out of scope) and the current thread
+/// is not unwinding, the unwind context will be forgotten.
+///
+/// It is usually used through the [`UnwindContextWithIo`] and
+/// [`UnwindContextWithFmt`] type aliases created by macros like
+/// [`unwind_context`] or [`unwind_context_with_fmt`].
+///
+/// The guard options type is [`DefaultGuardOptions`] if no per-guard options
+/// are given, so the guard stores only the context data, the code location,
+/// the sink and the panic detector, and [`CustomGuardOptions`] otherwise.
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use unwind_context::{
+///     unwind_context_with_fmt, FmtSink, UnwindContextWithSink,
+/// };
+///
+/// fn func(foo: u32, bar: &str, secret: &str, custom_writer: &mut String) {
+///     let _ctx: UnwindContextWithSink<FmtSink<_>, _, _> =
+///         unwind_context_with_fmt!(
+///             (fn(foo, bar, ...)),
+///             writer = custom_writer,
+///             panic_detector = unwind_context::StdPanicDetector,
+///         );
+///     // ...
+/// }
+#[doc = "```"]
+#[doc = ""]
+/// [`UnwindContextWithIo`]: crate::UnwindContextWithIo
+/// [`UnwindContextWithFmt`]: crate::UnwindContextWithFmt
+/// [`unwind_context`]: crate::unwind_context
+/// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct UnwindContextWithSink<
+    S: ContextSink,
+    T: Debug + DebugAnsiColored,
+    P: PanicDetector,
+    O: GuardOptions = DefaultGuardOptions,
+> {
+    /// The context data and location, which are taken only by
+    /// [`Self::into_inner`].
+    context: Option<GuardContext<T>>,
+    sink: S,
+    panic_detector: P,
+    options: O,
+}
+
+/// The context data of a context scope guard with its code location.
+///
+/// The location reference lets `Option<GuardContext<T>>` take no more space
+/// than the data and the location themselves.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+struct GuardContext<T> {
+    data: T,
+    location: &'static Location<'static>,
+}
+
+impl<S: ContextSink, T: Debug + DebugAnsiColored, P: PanicDetector, O: GuardOptions> Drop
+    for UnwindContextWithSink<S, T, P, O>
+{
+    #[inline]
+    fn drop(&mut self) {
+        // The panic detector is asked first, since ending the scope of the
+        // outermost guard may end the unwind and reset the panic state.
+        let is_panicking = self.panic_detector.is_panicking();
+        let scope = exit_scope();
+        // The guard consumed by `into_inner` only ends its scope.
+        let should_print = self.context.is_some() && should_print_context();
+        if is_panicking && should_print {
+            self.print_on_unwind(scope);
+        } else if !is_panicking || scope.is_outermost {
+            let _ = end_unwind();
+        }
+        if !is_panicking && should_print && self.options.should_print_on_drop() {
+            self.print_trace("ok", scope_depth());
+        }
+    }
+}
+
+impl<S, T, P, O> Clone for UnwindContextWithSink<S, T, P, O>
+where
+    S: ContextSink + Clone,
+    T: Debug + DebugAnsiColored + Clone,
+    P: PanicDetector + Clone,
+    O: GuardOptions,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        enter_scope();
+        Self {
+            context: self.context.clone(),
+            sink: self.sink.clone(),
+            panic_detector: self.panic_detector.clone(),
+            options: self.options,
+        }
+    }
+}
+
+impl<S: ContextSink, T: Debug + DebugAnsiColored, P: PanicDetector> UnwindContextWithSink<S, T, P> {
+    /// Create a new `UnwindContextWithSink` with the provided sink or a writer
+    /// convertible into it, and context scope data, which resolves all options
+    /// from the global settings when the context is printed.
+    ///
+    /// The color scheme is determined by the
+    /// [`get_default_color_scheme_if_enabled`] function when the context is
+    /// printed.
+    ///
+    /// This function is not intended to be used directly. Consider using macros
+    /// like [`unwind_context`] or [`unwind_context_with_fmt`] instead.
+    ///
+    /// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
+    /// [`unwind_context`]: crate::unwind_context
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[inline]
+    #[must_use = "\
+        if unused, the `UnwindContextWithSink` will immediately drop,
+        consider binding the `UnwindContextWithSink` like `let _ctx = ...`.
+    "]
+    #[track_caller]
+    pub fn new_default(data: T, sink: impl Into<S>, panic_detector: P) -> Self {
+        enter_scope();
+        Self {
+            context: Some(GuardContext {
+                data,
+                location: Location::caller(),
+            }),
+            sink: sink.into(),
+            panic_detector,
+            options: DefaultGuardOptions,
+        }
+    }
+}
+
+impl<S: ContextSink, T: Debug + DebugAnsiColored, P: PanicDetector>
+    UnwindContextWithSink<S, T, P, CustomGuardOptions>
+{
+    /// Create a new `UnwindContextWithSink` with the provided sink or a writer
+    /// convertible into it, context scope data, and color scheme.
+    ///
+    /// This function is not intended to be used directly. Consider using macros
+    /// like [`unwind_context`] or [`unwind_context_with_fmt`] instead.
+    ///
+    /// [`unwind_context`]: crate::unwind_context
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[inline]
+    #[must_use = "\
+        if unused, the `UnwindContextWithSink` will immediately drop,
+        consider binding the `UnwindContextWithSink` like `let _ctx = ...`.
+    "]
+    #[track_caller]
+    pub fn new(
+        data: T,
+        sink: impl Into<S>,
+        panic_detector: P,
+        color_scheme: Option<&'static AnsiColorScheme>,
+    ) -> Self {
+        enter_scope();
+        Self {
+            context: Some(GuardContext {
+                data,
+                location: Location::caller(),
+            }),
+            sink: sink.into(),
+            panic_detector,
+            options: CustomGuardOptions::new(color_scheme),
+        }
+    }
+
+    /// Sets the color scheme resolved with the guard sink, like
+    /// `Option<&'static AnsiColorScheme>` or [`AutoColorScheme`].
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `color_scheme = ...` parameter of macros like
+    /// [`unwind_context_with_io`] instead.
+    ///
+    /// [`AutoColorScheme`]: crate::AutoColorScheme
+    /// [`unwind_context_with_io`]: crate::unwind_context_with_io
+    #[inline]
+    #[must_use]
+    pub fn with_color_scheme(mut self, color_scheme: impl ColorSchemeChoice<S>) -> Self {
+        self.options.frame = self
+            .options
+            .frame
+            .with_color_scheme(color_scheme.resolve(&self.sink));
+        self
+    }
+
+    /// Enables or disables printing of the current thread name and ID after
+    /// the context location regardless of the global
+    /// [`set_thread_info_enabled`] setting.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `thread_info = true` parameter of macros like
+    /// [`unwind_context_with_fmt`] instead.
+    ///
+    /// [`set_thread_info_enabled`]: crate::set_thread_info_enabled
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn with_thread_info(mut self, enabled: bool) -> Self {
+        self.options.frame = self.options.frame.with_thread_info(enabled);
+        self
+    }
+
+    /// Enables or disables printing of the current process ID after the
+    /// context location regardless of the global [`set_process_id_enabled`]
+    /// setting.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `process_id = true` parameter of macros like
+    /// [`unwind_context_with_fmt`] instead.
+    ///
+    /// [`set_process_id_enabled`]: crate::set_process_id_enabled
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn with_process_id(mut self, enabled: bool) -> Self {
+        self.options.frame = self.options.frame.with_process_id(enabled);
+        self
+    }
+
+    /// Enables or disables measuring of the time spent in the context scope
+    /// regardless of the global [`set_elapsed_time_enabled`] setting.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `elapsed_time = true` parameter of macros like
+    /// [`unwind_context_with_fmt`] instead.
+    ///
+    /// [`set_elapsed_time_enabled`]: crate::set_elapsed_time_enabled
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn with_elapsed_time(mut self, enabled: bool) -> Self {
+        self.options.elapsed_time = Some(enabled);
+        self.options.entered_at = enabled.then(|| {
+            self.options
+                .entered_at
+                .unwrap_or_else(std::time::Instant::now)
+        });
+        self
+    }
+
+    /// Enables or disables printing of a [`std::backtrace::Backtrace`]
+    /// captured when the context is printed after the context location.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `backtrace = true` parameter of macros like [`unwind_context_with_fmt`]
+    /// instead.
+    ///
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn with_backtrace(mut self, enabled: bool) -> Self {
+        self.options.frame = self.options.frame.with_backtrace(enabled);
+        self
+    }
+
+    /// Sets the style of the printed code location regardless of the global
+    /// [`set_location_style`] setting.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `location_style = ...` parameter of macros like
+    /// [`unwind_context_with_fmt`] instead.
+    ///
+    /// [`set_location_style`]: crate::set_location_style
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[inline]
+    #[must_use]
+    pub fn with_location_style(mut self, style: LocationStyle) -> Self {
+        self.options.frame = self.options.frame.with_location_style(style);
+        self
+    }
+
+    /// Sets the layout used to write the context regardless of the global
+    /// [`set_context_layout`] setting.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `layout = ...` parameter of macros like [`unwind_context_with_fmt`]
+    /// instead.
+    ///
+    /// [`set_context_layout`]: crate::set_context_layout
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[inline]
+    #[must_use]
+    pub fn with_layout(mut self, layout: &'static dyn ContextLayout) -> Self {
+        self.options.frame = self.options.frame.with_layout(Some(layout));
+        self
+    }
+
+    /// Enables or disables printing of the frame prefixed with `enter` when
+    /// the guard is created regardless of the global [`set_verbose_enabled`]
+    /// setting.
+    ///
+    /// The frame is printed by the [`trace_entry`] method after all other
+    /// options are set.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `verbose = true` parameter of macros like [`unwind_context_with_fmt`]
+    /// instead.
+    ///
+    /// [`set_verbose_enabled`]: crate::set_verbose_enabled
+    /// [`trace_entry`]: Self::trace_entry
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[inline]
+    #[must_use]
+    pub fn with_verbose(mut self, enabled: bool) -> Self {
+        self.options.frame = self.options.frame.with_verbose(enabled);
+        self
+    }
+
+    /// Enables or disables printing of the frame on every guard drop, tagged
+    /// with `ok` or `panic`, regardless of the global
+    /// [`set_print_on_drop_enabled`] setting.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `print_on_drop = true` parameter of macros like
+    /// [`unwind_context_with_fmt`] instead.
+    ///
+    /// [`set_print_on_drop_enabled`]: crate::set_print_on_drop_enabled
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[inline]
+    #[must_use]
+    pub fn with_print_on_drop(mut self, enabled: bool) -> Self {
+        self.options.frame = self.options.frame.with_print_on_drop(enabled);
+        self
+    }
+
+    /// Sets the rate limit of printed frames of this guard location
+    /// regardless of the global [`set_rate_limit`] setting.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `rate_limit = ...` parameter of macros like [`unwind_context_with_fmt`]
+    /// instead.
+    ///
+    /// [`set_rate_limit`]: crate::set_rate_limit
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn with_rate_limit(mut self, limit: &'static RateLimit) -> Self {
+        self.options.rate_limit = Some(limit);
+        self
+    }
+
+    /// Sets the sample rate of printed frames of this guard location
+    /// regardless of the global [`set_sample_rate`] setting.
+    ///
+    /// Zero is treated as one, which means every frame is printed.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `sample_rate = ...` parameter of macros like [`unwind_context_with_fmt`]
+    /// instead.
+    ///
+    /// [`set_sample_rate`]: crate::set_sample_rate
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn with_sample_rate(mut self, rate: usize) -> Self {
+        self.options.sample_rate = core::num::NonZeroUsize::new(rate.max(1));
+        self
+    }
+
+    /// Sets the flag marking whether a frame of this guard call site has
+    /// already been printed, so the frame is printed at most once per process
+    /// run, or removes it if `None` is given.
+    ///
+    /// Frames skipped because of the flag are not numbered, not recorded with
+    /// a [`FrameCapture`], and not passed to a [`FrameSink`].
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `once = true` parameter of macros like [`unwind_context_with_fmt`]
+    /// instead, which creates a separate flag for every call site.
+    ///
+    /// [`FrameCapture`]: crate::FrameCapture
+    /// [`FrameSink`]: crate::FrameSink
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn with_once(mut self, printed: Option<&'static AtomicBool>) -> Self {
+        self.options.printed_once = printed.map(OnceFlag);
+        self
+    }
+}
+
+impl<S: ContextSink, T: Debug + DebugAnsiColored, P: PanicDetector, O: GuardOptions>
+    UnwindContextWithSink<S, T, P, O>
+{
+    /// Overrides the code location printed with the context, which defaults to
+    /// the location where the context scope guard is created.
+    ///
+    /// It allows helper functions creating context scope guards on behalf of
+    /// their callers to report the caller location, for example one returned
+    /// by [`Location::caller`] in a `#[track_caller]` function.
+    ///
+    /// This function is not intended to be used directly. Consider using the
+    /// `location = ...` parameter of macros like [`unwind_context_with_fmt`]
+    /// instead.
+    ///
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[inline]
+    #[must_use]
+    pub fn with_location(mut self, location: &'static Location<'static>) -> Self {
+        self.context_mut().location = location;
+        self
+    }
+
+    /// Prints the frame prefixed with `enter` if the verbose trace mode is
+    /// enabled for this guard or globally with [`set_verbose_enabled`].
+    ///
+    /// This function is not intended to be used directly. Macros like
+    /// [`unwind_context_with_fmt`] call it after all other options are set.
+    ///
+    /// [`set_verbose_enabled`]: crate::set_verbose_enabled
+    /// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+    #[inline]
+    #[must_use]
+    pub fn trace_entry(mut self) -> Self {
+        if self.options.is_verbose() && should_print_context() {
+            self.print_trace("enter", scope_depth().saturating_sub(1));
+        }
+        self
+    }
+
+    /// Returns a reference to the context data.
+    ///
+    /// # Examples
+    #[cfg_attr(not(feature = "off"), doc = "```rust")]
+    #[cfg_attr(feature = "off", doc = "```rust,ignore")]
+    /// use unwind_context::unwind_context_with_fmt;
+    ///
+    /// let mut output = String::new();
+    /// let value = 123;
+    /// let ctx = unwind_context_with_fmt!(
+    ///     (value),
+    ///     writer = &mut output,
+    ///     panic_detector = unwind_context::StdPanicDetector,
+    ///     color_scheme = None,
+    /// );
+    /// assert_eq!(format!("{:?}", ctx.data()), "value: 123");
+    #[doc = "```"]
+    #[inline]
+    #[must_use]
+    pub fn data(&self) -> &T {
+        &self.context_ref().data
+    }
+
+    /// Returns the code location of the guard, which is printed with its
+    /// context.
+    ///
+    /// # Examples
+    #[cfg_attr(not(feature = "off"), doc = "```rust")]
+    #[cfg_attr(feature = "off", doc = "```rust,ignore")]
+    /// use unwind_context::unwind_context_with_fmt;
+    ///
+    /// let mut output = String::new();
+    /// let ctx = unwind_context_with_fmt!(
+    ///     (fn()),
+    ///     writer = &mut output,
+    ///     panic_detector = unwind_context::StdPanicDetector,
+    ///     color_scheme = None,
+    /// );
+    /// assert_eq!(ctx.location().file(), file!());
+    #[doc = "```"]
+    #[inline]
+    #[must_use]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.context_ref().location
+    }
+
+    /// Returns the color scheme used to print the context, if colorization is
+    /// enabled for the guard.
+    ///
+    /// # Examples
+    #[cfg_attr(not(feature = "off"), doc = "```rust")]
+    #[cfg_attr(feature = "off", doc = "```rust,ignore")]
+    /// use unwind_context::{
+    ///     unwind_context_with_fmt, DEFAULT_DEFAULT_COLOR_SCHEME,
+    /// };
+    ///
+    /// let mut output = String::new();
+    /// let ctx = unwind_context_with_fmt!(
+    ///     (fn()),
+    ///     writer = &mut output,
+    ///     panic_detector = unwind_context::StdPanicDetector,
+    ///     color_scheme = Some(&DEFAULT_DEFAULT_COLOR_SCHEME),
+    /// );
+    /// assert_eq!(ctx.color_scheme(), Some(&DEFAULT_DEFAULT_COLOR_SCHEME));
+    #[doc = "```"]
+    #[inline]
+    #[must_use]
+    pub fn color_scheme(&self) -> Option<&'static AnsiColorScheme> {
+        self.options.frame_options().color_scheme()
+    }
+
+    /// Consumes the guard without printing its context and returns the
+    /// context data.
+    ///
+    /// # Examples
+    #[cfg_attr(not(feature = "off"), doc = "```rust")]
+    #[cfg_attr(feature = "off", doc = "```rust,ignore")]
+    /// use unwind_context::unwind_context_with_fmt;
+    ///
+    /// let mut output = String::new();
+    /// let value = 123;
+    /// let ctx = unwind_context_with_fmt!(
+    ///     (value),
+    ///     writer = &mut output,
+    ///     panic_detector = unwind_context::StdPanicDetector,
+    ///     color_scheme = None,
+    /// );
+    /// let data = ctx.into_inner();
+    /// assert_eq!(format!("{data:?}"), "value: 123");
+    /// assert_eq!(output, "");
+    #[doc = "```"]
+    #[inline]
+    #[must_use]
+    pub fn into_inner(mut self) -> T {
+        match self.context.take() {
+            Some(context) => context.data,
+            None => unreachable!("the context data is taken only when the guard is consumed"),
+        }
+    }
+
+    /// Returns a reference to the context of a guard that is not consumed
+    /// yet.
+    fn context_ref(&self) -> &GuardContext<T> {
+        match &self.context {
+            Some(context) => context,
+            None => unreachable!("the context data is taken only when the guard is consumed"),
+        }
+    }
+
+    /// Returns a mutable reference to the context of a guard that is not
+    /// consumed yet.
+    fn context_mut(&mut self) -> &mut GuardContext<T> {
+        match &mut self.context {
+            Some(context) => context,
+            None => unreachable!("the context data is taken only when the guard is consumed"),
+        }
+    }
+
+    /// Returns a mutable reference to the context data, used by adapters
+    /// updating the context of a long-lived guard.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn data_mut(&mut self) -> &mut T {
+        &mut self.context_mut().data
+    }
+
+    /// Print context to a sink specified in the `UnwindContextWithSink`
+    /// constructor.
+    ///
+    /// This method is called when a panic detected.
+    #[cold]
+    #[inline(never)]
+    pub fn print(&mut self) {
+        self.print_frame(current_scope());
+    }
+
+    #[cold]
+    #[inline(never)]
+    #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+    fn print_frame(&mut self, scope: GuardScope) {
+        let location = self.context_ref().location;
+        #[cfg(feature = "std")]
+        if !is_sampled(self.options.sample_rate(), location) {
+            record_frame_suppressed();
+            return;
+        }
+        #[cfg(feature = "std")]
+        if self
+            .options
+            .printed_once()
+            .is_some_and(|printed| OnceFlag(printed).mark_printed())
+        {
+            record_frame_suppressed();
+            return;
+        }
+        #[cfg(feature = "std")]
+        let Some(rate_limited) = check_rate_limit(self.options.rate_limit(), location) else {
+            record_frame_suppressed();
+            return;
+        };
+        let slot = enter_frame(&self.context_ref().data, location);
+        #[cfg(feature = "std")]
+        if slot.is_first {
+            record_unwind();
+            self.print_unwind_banner(get_unwind_header());
+            self.print_thread_banner();
+            self.print_panic_location();
+        }
+        self.print_repeated_frames(slot.repeated_before);
+        if slot.is_hidden {
+            #[cfg(feature = "std")]
+            record_frame_suppressed();
+            return;
+        }
+        let Some(context) = &self.context else {
+            return;
+        };
+        let frame = FrameInfo::new(&context.data, location)
+            .with_index(slot.index)
+            .with_depth(slot.depth);
+        #[cfg(feature = "std")]
+        let frame = frame.with_elapsed(
+            self.options
+                .entered_at(scope.entered_at)
+                .map(|entered_at| entered_at.elapsed()),
+        );
+        let options = resolve_frame_options(self.options.frame_options());
+        #[cfg(feature = "std")]
+        let should_write = send_to_frame_sink(&frame);
+        #[cfg(feature = "std")]
+        if capture_frame(&frame, &options) || !should_write {
+            return;
+        }
+        let frame = FrameFmt {
+            frame,
+            options: &options,
+        };
+        #[cfg(feature = "std")]
+        let Some(frame) = charge_output_budget(frame) else {
+            record_frame_suppressed();
+            return;
+        };
+        let tag = if self.options.should_print_on_drop() {
+            "panic "
+        } else {
+            ""
+        };
+        self.sink.write_context(format_args!("{tag}{frame}"));
+        #[cfg(feature = "std")]
+        record_frame_printed();
+        #[cfg(feature = "std")]
+        if rate_limited > 0 {
+            self.sink.write_context(format_args!(
+                "{}",
+                RateLimitedFramesFmt {
+                    count: rate_limited
+                }
+            ));
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn print_on_unwind(&mut self, scope: GuardScope) {
+        // The lock is held until the end of the unwind and is reentrant, so
+        // the sink can still write to the standard error stream.
+        #[cfg(feature = "std")]
+        if is_stderr_lock_enabled() {
+            lock_stderr_for_unwind();
+        }
+        self.print_frame(scope);
+        if scope.is_outermost {
+            // The lock is released after the unwind end is printed.
+            #[cfg(feature = "std")]
+            let _stderr_lock = take_stderr_lock();
+            self.print_unwind_end(end_unwind());
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn print_trace(&mut self, tag: &str, depth: usize) {
+        let Some(context) = &self.context else {
+            return;
+        };
+        let frame = FrameInfo::new(&context.data, context.location).with_depth(depth);
+        let options = resolve_frame_options(self.options.frame_options());
+        #[cfg(feature = "std")]
+        let options = options.with_backtrace(false);
+        let frame = FrameFmt {
+            frame,
+            options: &options,
+        };
+        self.sink.write_context(format_args!("{tag} {frame}"));
+    }
+
+    #[cfg(feature = "std")]
+    #[cold]
+    #[inline(never)]
+    fn print_thread_banner(&mut self) {
+        if is_thread_banner_enabled() && !is_capture_active() {
+            self.sink.write_context(format_args!("{ThreadBannerFmt}"));
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[cold]
+    #[inline(never)]
+    fn print_panic_location(&mut self) {
+        if let Some(location) = take_panic_location() {
+            if !is_capture_active() {
+                let color_scheme = self.options.frame_options().color_scheme();
+                self.sink.write_context(format_args!(
+                    "{}",
+                    PanicLocationFmt {
+                        location: &location,
+                        color_scheme,
+                    }
+                ));
+            }
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn print_repeated_frames(&mut self, count: usize) {
+        if count > 0 && !is_capture_active() {
+            self.sink
+                .write_context(format_args!("{}", RepeatedFramesFmt { count }));
+        }
+    }
+
+    #[cold]
+    #[inline(never)]
+    fn print_unwind_end(&mut self, end: UnwindEnd) {
+        self.print_repeated_frames(end.repeated);
+        if end.suppressed > 0 && !is_capture_active() {
+            let suppressed = SuppressedFramesFmt {
+                count: end.suppressed,
+                is_budget_exceeded: end.is_budget_exceeded,
+            };
+            self.sink.write_context(format_args!("{suppressed}"));
+        }
+        #[cfg(feature = "std")]
+        if end.frames > 0 {
+            if is_unwind_summary_enabled() && !is_capture_active() {
+                let summary = UnwindSummaryFmt {
+                    frames: end.frames,
+                    hidden: end.hidden,
+                };
+                self.sink.write_context(format_args!("{summary}"));
+            }
+            self.print_unwind_banner(get_unwind_footer());
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[cold]
+    #[inline(never)]
+    fn print_unwind_banner(&mut self, banner: Option<UnwindBannerFn>) {
+        if let Some(banner) = banner {
+            if !is_capture_active() {
+                self.sink
+                    .write_context(format_args!("{}", UnwindBannerFmt(banner)));
+            }
+        }
+    }
+}
+
+/// Frames can be captured only if the `std` feature is enabled.
+#[cfg(not(feature = "std"))]
+#[inline]
+fn is_capture_active() -> bool {
+    false
+}
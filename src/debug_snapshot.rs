@@ -0,0 +1,221 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult, Write as FmtWrite};
+
+use crate::FixedBufWriter;
+
+/// A fixed-size snapshot of a value formatted with [`core::fmt::Debug`] when
+/// the snapshot is created.
+///
+/// Context arguments are formatted lazily, when the context is printed. So if
+/// a value is mutated after the context scope guard is created, for example
+/// through interior mutability, its final state is printed. A snapshot
+/// records the state of the value at the moment of the guard creation
+/// instead.
+///
+/// The formatted value is stored in a `N` bytes buffer without allocation. If
+/// it does not fit, it is truncated and printed with a `...` suffix. If the
+/// `Debug` implementation of the value returns an error, a `<fmt error>`
+/// placeholder is stored instead.
+///
+/// Note that formatting happens eagerly, so its cost is paid every time the
+/// snapshot is created, even if the context is never printed.
+///
+/// All arguments of a context can be snapshotted at once with the `eager:`
+/// prefix of the [`build_unwind_context_data`] macro and macros like
+/// [`unwind_context`].
+///
+/// # Examples
+///
+/// ```rust
+/// use core::cell::Cell;
+///
+/// use unwind_context::{unwind_context, DebugSnapshot};
+///
+/// fn func(counter: &Cell<u32>) {
+///     let _ctx = unwind_context!(fn(DebugSnapshot::<16>::new(counter)));
+///     counter.set(counter.get() + 1);
+///     // ...
+/// }
+///
+/// fn eager_func(counter: &Cell<u32>) {
+///     let _ctx = unwind_context!(eager: fn(counter));
+///     counter.set(counter.get() + 1);
+///     // ...
+/// }
+/// ```
+///
+/// [`build_unwind_context_data`]: crate::build_unwind_context_data
+/// [`unwind_context`]: crate::unwind_context
+#[derive(Copy, Clone)]
+pub struct DebugSnapshot<const N: usize = 128> {
+    buffer: [u8; N],
+    len: usize,
+    is_truncated: bool,
+}
+
+impl<const N: usize> DebugSnapshot<N> {
+    /// Creates a new `DebugSnapshot` by formatting the given value with
+    /// [`core::fmt::Debug`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::DebugSnapshot;
+    ///
+    /// let snapshot = DebugSnapshot::<8>::new(&"abc");
+    /// assert_eq!(snapshot.as_str(), "\"abc\"");
+    /// assert!(!snapshot.is_truncated());
+    ///
+    /// let snapshot = DebugSnapshot::<8>::new(&"abcdefgh");
+    /// assert_eq!(snapshot.as_str(), "\"abcdefg");
+    /// assert!(snapshot.is_truncated());
+    /// ```
+    #[must_use]
+    pub fn new<T: Debug + ?Sized>(value: &T) -> Self {
+        let mut buffer = [0; N];
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        let result = write!(writer, "{value:?}");
+        let mut len = writer.len();
+        let is_truncated = writer.is_truncated();
+        if result.is_err() && !is_truncated {
+            let mut writer = FixedBufWriter::new(&mut buffer);
+            let _ = writer.write_str("<fmt error>");
+            len = writer.len();
+        }
+        Self {
+            buffer,
+            len,
+            is_truncated,
+        }
+    }
+
+    /// Returns the recorded formatted value without the truncation suffix.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::DebugSnapshot;
+    ///
+    /// let snapshot: DebugSnapshot = DebugSnapshot::new(&[1, 2, 3]);
+    /// assert_eq!(snapshot.as_str(), "[1, 2, 3]");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.buffer
+            .get(..self.len)
+            .and_then(|bytes| core::str::from_utf8(bytes).ok())
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if the formatted value did not fit into the buffer and
+    /// was truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::DebugSnapshot;
+    ///
+    /// assert!(!DebugSnapshot::<4>::new(&1234).is_truncated());
+    /// assert!(DebugSnapshot::<4>::new(&12345).is_truncated());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.is_truncated
+    }
+}
+
+impl<const N: usize> Debug for DebugSnapshot<N> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(self.as_str())?;
+        if self.is_truncated {
+            f.write_str("...")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use core::fmt::{Debug, Error as FmtError, Formatter, Result as FmtResult};
+
+    use crate::test_common::TEST_COLOR_SCHEME;
+    use crate::test_util::buf_fmt;
+    use crate::{build_unwind_context_data, AnsiColored, DebugSnapshot};
+
+    struct FailingDebug;
+
+    impl Debug for FailingDebug {
+        fn fmt(&self, _: &mut Formatter<'_>) -> FmtResult {
+            Err(FmtError)
+        }
+    }
+
+    #[test]
+    fn test_debug_snapshot() {
+        let mut buffer = [0; 64];
+
+        let snapshot = DebugSnapshot::<16>::new(&[1, 2, 3]);
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{snapshot:?}")),
+            Ok("[1, 2, 3]")
+        );
+
+        let snapshot = DebugSnapshot::<4>::new("abcdef");
+        assert_eq!(snapshot.as_str(), "\"abc");
+        assert!(snapshot.is_truncated());
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{snapshot:?}")),
+            Ok("\"abc...")
+        );
+
+        let snapshot = DebugSnapshot::<16>::new(&FailingDebug);
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{snapshot:?}")),
+            Ok("<fmt error>")
+        );
+
+        let snapshot = DebugSnapshot::<0>::new(&123);
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{snapshot:?}")),
+            Ok("...")
+        );
+    }
+
+    #[test]
+    fn test_eager_context_data() {
+        let mut buffer = [0; 128];
+        let counter = Cell::new(1);
+        let name = "abc";
+
+        let lazy = build_unwind_context_data!(fn func(&counter, name, 123));
+        let eager = build_unwind_context_data!(eager: fn func(&counter, name, 123, ...));
+        let eager_args = build_unwind_context_data!(eager: &counter, name);
+        counter.set(2);
+
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{lazy:?}")),
+            Ok("fn func(&counter: Cell { value: 2 }, name: \"abc\", 123)")
+        );
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{eager:?}")),
+            Ok("fn func(&counter: Cell { value: 1 }, name: \"abc\", 123, ...)")
+        );
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{eager_args:?}")),
+            Ok("&counter: Cell { value: 1 }, name: \"abc\"")
+        );
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{:?}", AnsiColored::new(&eager_args, &TEST_COLOR_SCHEME))
+            ),
+            Ok(concat!(
+                "{ARG}&counter{DEF}: {ITEM}Cell{DEF} {BRACE}{{DEF} {IDENT}value{DEF}: {NUM}1{DEF} ",
+                "{BRACE}}{DEF}, {ARG}name{DEF}: {QUOT}\"abc\"{DEF}"
+            ))
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use core::fmt::{Debug, Display, Formatter, Pointer, Result as FmtResult};
 
 /// An utility wrapper type which is used to forward both [`core::fmt::Debug`]
 /// and [`core::fmt::Display`] value implementations to its
@@ -45,6 +45,61 @@ pub struct WithPrettyDebug<T>(
     pub T,
 );
 
+/// An utility wrapper type which is used to forward both [`core::fmt::Debug`]
+/// and [`core::fmt::Display`] value implementations to the name of the
+/// wrapped value type enclosed in angle brackets, like `<alloc::vec::Vec<u8>>`.
+///
+/// It can be used to record what was passed for huge values or values that do
+/// not implement [`core::fmt::Debug`]. The type name is obtained with
+/// [`core::any::type_name`], so its exact format is not guaranteed.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context, WithTypeName};
+///
+/// struct Connection;
+///
+/// fn func(connection: &Connection) {
+///     let _ctx = unwind_context!(fn(WithTypeName(connection)));
+///     // ...
+/// }
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct WithTypeName<T>(
+    /// The wrapped value whose type name is printed regardless of whether
+    /// formatting is invoked with [`core::fmt::Debug`] or
+    /// [`core::fmt::Display`] formatter.
+    pub T,
+);
+
+/// An utility wrapper type which is used to forward both [`core::fmt::Debug`]
+/// and [`core::fmt::Display`] value implementations to its
+/// [`core::fmt::Pointer`] implementation, printing references, raw pointers
+/// and smart pointers as addresses, like `0x7ffd5f0a1c2c`.
+///
+/// It is useful for FFI debugging, where the pointee either does not
+/// implement [`core::fmt::Debug`] or is unsafe to dereference during
+/// unwinding.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context, WithPtr};
+///
+/// fn func(buffer: *const u8, len: usize) {
+///     let _ctx = unwind_context!(fn(WithPtr(buffer), len));
+///     // ...
+/// }
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct WithPtr<T>(
+    /// The wrapped pointer to be formatted with [`core::fmt::Pointer`]
+    /// regardless of whether formatting is invoked with [`core::fmt::Debug`]
+    /// or [`core::fmt::Display`] formatter.
+    pub T,
+);
+
 impl<T> Display for WithDisplay<T>
 where
     T: Display,
@@ -81,10 +136,40 @@ where
     }
 }
 
+impl<T> Display for WithTypeName<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "<{}>", core::any::type_name::<T>())
+    }
+}
+
+impl<T> Debug for WithTypeName<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "<{}>", core::any::type_name::<T>())
+    }
+}
+
+impl<T> Display for WithPtr<T>
+where
+    T: Pointer,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{:p}", self.0)
+    }
+}
+
+impl<T> Debug for WithPtr<T>
+where
+    T: Pointer,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{:p}", self.0)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::test_util::buf_fmt;
-    use crate::{WithDisplay, WithPrettyDebug};
+    use crate::{WithDisplay, WithPrettyDebug, WithPtr, WithTypeName};
 
     #[derive(Clone, Debug)]
     struct Struct {
@@ -145,4 +230,52 @@ mod tests {
             Ok("Struct {\n    _first: 1,\n    _second: \"foo\\nbar\",\n}")
         );
     }
+
+    struct NonDebug;
+
+    #[test]
+    fn test_debug_with_type_name() {
+        let mut buffer = [0; 64];
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{}", WithTypeName(1_u32))),
+            Ok("<u32>")
+        );
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{:?}", WithTypeName(NonDebug))),
+            Ok("<unwind_context::debug_with::tests::NonDebug>")
+        );
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{:#?}", WithTypeName(&[1_u8; 4]))),
+            Ok("<&[u8; 4]>")
+        );
+    }
+
+    #[test]
+    fn test_debug_with_ptr() {
+        let mut buffer = [0; 64];
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{:?}", WithPtr(core::ptr::null::<u8>()))
+            ),
+            Ok("0x0")
+        );
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{}", WithPtr(core::ptr::null_mut::<u8>()))
+            ),
+            Ok("0x0")
+        );
+
+        let value = NonDebug;
+        let reference = &value;
+        let mut expected = [0; 64];
+        let expected = buf_fmt(&mut expected, format_args!("{reference:p}")).unwrap();
+        assert!(expected.starts_with("0x"));
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{:?}", WithPtr(reference))),
+            Ok(expected)
+        );
+    }
 }
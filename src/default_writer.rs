@@ -0,0 +1,159 @@
+use core::cell::RefCell;
+use std::boxed::Box;
+use std::io::{Result as IoResult, Write as IoWrite};
+use std::sync::{PoisonError, RwLock};
+
+use crate::StderrWriter;
+
+/// A function creating a writer used by [`DefaultWriter`] instead of
+/// [`StderrWriter`].
+pub type DefaultWriterFactory = fn() -> Box<dyn IoWrite + Send>;
+
+static DEFAULT_WRITER_FACTORY: RwLock<Option<DefaultWriterFactory>> = RwLock::new(None);
+
+std::thread_local! {
+    /// The writer created by the default writer factory for the part of the
+    /// output being written, dropped when the part is flushed.
+    static CURRENT_WRITER: RefCell<Option<Box<dyn IoWrite + Send>>> = const { RefCell::new(None) };
+}
+
+/// A [`std::io::Write`] writer that writes to the writer set with
+/// [`set_default_writer`], or to [`StderrWriter`] if none is set.
+///
+/// The writer created by the function set with [`set_default_writer`] is
+/// reused by all writes on the current thread until `flush` is called, which
+/// flushes and drops it.
+///
+/// It is the default writer of the [`unwind_context`] and
+/// [`unwind_context_with_io`] macros.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, DefaultWriter};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!((fn(foo, bar)), writer = DefaultWriter);
+///     // ...
+/// }
+/// ```
+///
+/// [`unwind_context`]: crate::unwind_context
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct DefaultWriter;
+
+impl IoWrite for DefaultWriter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        let Some(factory) = get_default_writer() else {
+            return StderrWriter.write_all(buf);
+        };
+        CURRENT_WRITER
+            .try_with(|current| match current.try_borrow_mut() {
+                Ok(mut current) => current.get_or_insert_with(factory).write_all(buf),
+                // The created writer itself writes through `DefaultWriter`.
+                Err(_) => write_with_new_writer(factory, buf),
+            })
+            .unwrap_or_else(|_| write_with_new_writer(factory, buf))
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        let current = CURRENT_WRITER
+            .try_with(|current| current.try_borrow_mut().ok()?.take())
+            .ok()
+            .flatten();
+        match (current, get_default_writer()) {
+            (Some(mut writer), _) => writer.flush(),
+            (None, Some(_)) => Ok(()),
+            (None, None) => StderrWriter.flush(),
+        }
+    }
+}
+
+/// Writes the buffer with a newly created writer and flushes it.
+fn write_with_new_writer(factory: DefaultWriterFactory, buf: &[u8]) -> IoResult<()> {
+    let mut writer = factory();
+    writer.write_all(buf)?;
+    writer.flush()
+}
+
+/// Sets the function creating the writer used by the [`unwind_context`] and
+/// [`unwind_context_with_io`] macros by default for all threads, or restores
+/// writing to [`StderrWriter`] if `None` is given.
+///
+/// It allows applications to redirect all the default unwind context output to
+/// a file, a logger bridge, or a test sink without changing every call site.
+///
+/// The function is called once for every part of the unwind context output,
+/// like a frame or an unwind banner, even if the part is written in several
+/// chunks, and the created writer is flushed and dropped right after the part
+/// is written. So it should be cheap, like creating a handle to a shared sink,
+/// and it should not discard previously written output, so files should be
+/// opened in append mode rather than with [`std::fs::File::create`].
+///
+/// By default the output is written to [`StderrWriter`].
+///
+/// # Examples
+///
+/// ```rust
+/// use std::fs::OpenOptions;
+/// use std::io::{sink, Write};
+///
+/// use unwind_context::unwind_context;
+///
+/// fn crash_log() -> Box<dyn Write + Send> {
+///     match OpenOptions::new()
+///         .create(true)
+///         .append(true)
+///         .open("crash.log")
+///     {
+///         Ok(file) => Box::new(file),
+///         Err(_) => Box::new(sink()),
+///     }
+/// }
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_default_writer(Some(crash_log));
+/// func(123, "abc");
+/// unwind_context::set_default_writer(None);
+/// ```
+///
+/// [`unwind_context`]: crate::unwind_context
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+#[inline]
+pub fn set_default_writer(factory: Option<DefaultWriterFactory>) {
+    *DEFAULT_WRITER_FACTORY
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = factory;
+}
+
+/// Returns the function creating the writer used by the [`unwind_context`]
+/// and [`unwind_context_with_io`] macros by default, if set.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(unwind_context::get_default_writer().is_none());
+/// ```
+///
+/// [`unwind_context`]: crate::unwind_context
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+#[inline]
+#[must_use]
+pub fn get_default_writer() -> Option<DefaultWriterFactory> {
+    *DEFAULT_WRITER_FACTORY
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,216 @@
+use crate::{
+    enable_colors_from_env, set_colors_enabled, set_contexts_enabled, set_elapsed_time_enabled,
+    set_frame_numbers_enabled, set_max_frames_per_unwind, set_output_budget,
+    set_print_on_drop_enabled, set_process_id_enabled, set_sample_rate, set_thread_banner_enabled,
+    set_thread_info_enabled, set_unwind_summary_enabled, set_verbose_enabled,
+};
+
+/// The name of the environment variable read by [`configure_from_env`].
+pub const UNWIND_CONTEXT_ENV: &str = "UNWIND_CONTEXT";
+
+/// Configures unwind context output according to the `UNWIND_CONTEXT`
+/// environment variable.
+///
+/// It allows end users of a binary to silence or tune context output without
+/// the application exposing its own flag. The variable contains a
+/// comma-separated list of settings applied in order:
+///
+/// - `off` and `on` disable and enable context printing for all threads, see
+///   [`set_contexts_enabled`].
+/// - `color=never`, `color=always`, and `color=auto` disable colors, enable
+///   them, or enable them with [`enable_colors_from_env`].
+/// - `max_frames=N`, `output_budget=N`, and `sample_rate=N` set the
+///   corresponding limits, and `max_frames=off`, `output_budget=off`, and
+///   `sample_rate=off` remove them. Note that `max_frames=0` and
+///   `output_budget=0` suppress all frames.
+/// - `verbose`, `print_on_drop`, `thread_info`, `process_id`, `elapsed_time`,
+///   `frame_numbers`, `thread_banner`, and `summary` enable the corresponding
+///   options, and the same names with `=off` disable them.
+///
+/// Unknown settings and invalid values are ignored, so a misspelled variable
+/// never prevents the application from starting. If the variable is not set,
+/// the configuration is left unchanged.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// // For example `UNWIND_CONTEXT=off` or `UNWIND_CONTEXT=color=never,max_frames=16`.
+/// unwind_context::configure_from_env();
+/// func(123, "abc");
+/// ```
+#[inline]
+pub fn configure_from_env() {
+    if let Some(config) = std::env::var_os(UNWIND_CONTEXT_ENV) {
+        if let Some(config) = config.to_str() {
+            configure_from_str(config);
+        }
+    }
+}
+
+/// A single setting of the `UNWIND_CONTEXT` environment variable.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum Setting {
+    Contexts(bool),
+    /// Colors are enabled or disabled, or detected from the environment if
+    /// `None`.
+    Colors(Option<bool>),
+    MaxFrames(Option<usize>),
+    OutputBudget(Option<usize>),
+    SampleRate(Option<usize>),
+    Verbose(bool),
+    PrintOnDrop(bool),
+    ThreadInfo(bool),
+    ProcessId(bool),
+    ElapsedTime(bool),
+    FrameNumbers(bool),
+    ThreadBanner(bool),
+    Summary(bool),
+}
+
+fn configure_from_str(config: &str) {
+    for setting in config.split(',').filter_map(parse_setting) {
+        apply_setting(setting);
+    }
+}
+
+/// Parses a single setting, returning `None` if it is unknown or its value is
+/// invalid.
+fn parse_setting(setting: &str) -> Option<Setting> {
+    let setting = setting.trim();
+    let (name, value) = match setting.split_once('=') {
+        Some((name, value)) => (name.trim(), Some(value.trim())),
+        None => (setting, None),
+    };
+    match (name, value) {
+        ("off", None) => Some(Setting::Contexts(false)),
+        ("on", None) => Some(Setting::Contexts(true)),
+        ("color", Some("never")) => Some(Setting::Colors(Some(false))),
+        ("color", Some("always")) => Some(Setting::Colors(Some(true))),
+        ("color", Some("auto")) => Some(Setting::Colors(None)),
+        ("max_frames", Some(value)) => parse_limit(value, Setting::MaxFrames),
+        ("output_budget", Some(value)) => parse_limit(value, Setting::OutputBudget),
+        ("sample_rate", Some(value)) => parse_limit(value, Setting::SampleRate),
+        ("verbose", value) => parse_flag(value).map(Setting::Verbose),
+        ("print_on_drop", value) => parse_flag(value).map(Setting::PrintOnDrop),
+        ("thread_info", value) => parse_flag(value).map(Setting::ThreadInfo),
+        ("process_id", value) => parse_flag(value).map(Setting::ProcessId),
+        ("elapsed_time", value) => parse_flag(value).map(Setting::ElapsedTime),
+        ("frame_numbers", value) => parse_flag(value).map(Setting::FrameNumbers),
+        ("thread_banner", value) => parse_flag(value).map(Setting::ThreadBanner),
+        ("summary", value) => parse_flag(value).map(Setting::Summary),
+        _ => None,
+    }
+}
+
+fn apply_setting(setting: Setting) {
+    match setting {
+        Setting::Contexts(enabled) => set_contexts_enabled(enabled),
+        Setting::Colors(Some(enabled)) => set_colors_enabled(enabled),
+        Setting::Colors(None) => enable_colors_from_env(),
+        Setting::MaxFrames(limit) => set_max_frames_per_unwind(limit),
+        Setting::OutputBudget(limit) => set_output_budget(limit),
+        Setting::SampleRate(limit) => set_sample_rate(limit),
+        Setting::Verbose(enabled) => set_verbose_enabled(enabled),
+        Setting::PrintOnDrop(enabled) => set_print_on_drop_enabled(enabled),
+        Setting::ThreadInfo(enabled) => set_thread_info_enabled(enabled),
+        Setting::ProcessId(enabled) => set_process_id_enabled(enabled),
+        Setting::ElapsedTime(enabled) => set_elapsed_time_enabled(enabled),
+        Setting::FrameNumbers(enabled) => set_frame_numbers_enabled(enabled),
+        Setting::ThreadBanner(enabled) => set_thread_banner_enabled(enabled),
+        Setting::Summary(enabled) => set_unwind_summary_enabled(enabled),
+    }
+}
+
+/// Parses a limit value where `off` means no limit into a setting created with
+/// the given function.
+fn parse_limit(value: &str, setting: fn(Option<usize>) -> Setting) -> Option<Setting> {
+    match value {
+        "off" => Some(setting(None)),
+        value => value.parse().ok().map(|limit| setting(Some(limit))),
+    }
+}
+
+/// Parses an optional flag value where a missing value means `true`.
+fn parse_flag(value: Option<&str>) -> Option<bool> {
+    match value {
+        None | Some("on" | "true" | "1") => Some(true),
+        Some("off" | "false" | "0") => Some(false),
+        Some(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::env_config::{parse_flag, parse_limit, parse_setting, Setting};
+
+    #[test]
+    fn test_parse_flag() {
+        assert_eq!(parse_flag(None), Some(true));
+        assert_eq!(parse_flag(Some("on")), Some(true));
+        assert_eq!(parse_flag(Some("0")), Some(false));
+        assert_eq!(parse_flag(Some("maybe")), None);
+    }
+
+    #[test]
+    fn test_parse_limit() {
+        let parse = |value| parse_limit(value, Setting::MaxFrames);
+        assert_eq!(parse("16"), Some(Setting::MaxFrames(Some(16))));
+        assert_eq!(parse("0"), Some(Setting::MaxFrames(Some(0))));
+        assert_eq!(parse("off"), Some(Setting::MaxFrames(None)));
+        assert_eq!(parse("-1"), None);
+        assert_eq!(parse(""), None);
+    }
+
+    #[test]
+    fn test_parse_setting() {
+        assert_eq!(parse_setting("off"), Some(Setting::Contexts(false)));
+        assert_eq!(parse_setting(" on "), Some(Setting::Contexts(true)));
+        assert_eq!(parse_setting("off=1"), None);
+        assert_eq!(
+            parse_setting("color=never"),
+            Some(Setting::Colors(Some(false)))
+        );
+        assert_eq!(
+            parse_setting("color = always"),
+            Some(Setting::Colors(Some(true)))
+        );
+        assert_eq!(parse_setting("color=auto"), Some(Setting::Colors(None)));
+        assert_eq!(parse_setting("color"), None);
+        assert_eq!(parse_setting("color=blue"), None);
+        assert_eq!(
+            parse_setting("max_frames=16"),
+            Some(Setting::MaxFrames(Some(16)))
+        );
+        assert_eq!(
+            parse_setting("max_frames=0"),
+            Some(Setting::MaxFrames(Some(0)))
+        );
+        assert_eq!(
+            parse_setting("max_frames=off"),
+            Some(Setting::MaxFrames(None))
+        );
+        assert_eq!(parse_setting("max_frames"), None);
+        assert_eq!(
+            parse_setting("output_budget=1024"),
+            Some(Setting::OutputBudget(Some(1024)))
+        );
+        assert_eq!(
+            parse_setting("sample_rate=off"),
+            Some(Setting::SampleRate(None))
+        );
+        assert_eq!(parse_setting("sample_rate=many"), None);
+        assert_eq!(parse_setting("verbose"), Some(Setting::Verbose(true)));
+        assert_eq!(parse_setting("summary=off"), Some(Setting::Summary(false)));
+        assert_eq!(parse_setting("thread_info=maybe"), None);
+        assert_eq!(parse_setting("unknown"), None);
+        assert_eq!(parse_setting("unknown=on"), None);
+        assert_eq!(parse_setting(""), None);
+    }
+}
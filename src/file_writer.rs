@@ -0,0 +1,130 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{LineWriter, Result as IoResult, Write as IoWrite};
+use std::path::Path;
+
+/// A [`std::io::Write`] writer that lazily opens a file in append mode on the
+/// first write.
+///
+/// It is created with the [`file_writer`] function. See its documentation for
+/// more details.
+#[derive(Debug)]
+pub struct FileWriter<P: AsRef<Path>> {
+    path: P,
+    file: Option<LineWriter<File>>,
+}
+
+/// Creates a [`std::io::Write`] writer suitable for the `writer =` parameter of
+/// macros like [`unwind_context_with_io`] that writes to a file at the given
+/// path.
+///
+/// The file is not opened until the first write, so creating a context scope
+/// guard with this writer does not touch the file system. On the first write
+/// the parent directories are created if needed, and the file is created or
+/// opened in append mode. Output is line-buffered.
+///
+/// Errors are returned to the writer caller. Since context scope guards ignore
+/// writer errors, a failure to open the file is silently ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context_with_io;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!(
+///         (fn(foo, bar)),
+///         writer = unwind_context::file_writer("target/crash.log"),
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+#[inline]
+pub fn file_writer<P: AsRef<Path>>(path: P) -> FileWriter<P> {
+    FileWriter { path, file: None }
+}
+
+impl<P: AsRef<Path>> FileWriter<P> {
+    /// Returns the path of the file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::file_writer("crash.log");
+    /// assert_eq!(writer.path(), &"crash.log");
+    /// ```
+    #[inline]
+    pub fn path(&self) -> &P {
+        &self.path
+    }
+
+    fn file(&mut self) -> IoResult<&mut LineWriter<File>> {
+        if let Some(ref mut file) = self.file {
+            return Ok(file);
+        }
+        let path = self.path.as_ref();
+        if let Some(parent) = path.parent() {
+            if !parent.as_os_str().is_empty() {
+                fs::create_dir_all(parent)?;
+            }
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(self.file.insert(LineWriter::new(file)))
+    }
+}
+
+impl<P: AsRef<Path>> IoWrite for FileWriter<P> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.file()?.write(buf)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        self.file()?.write_all(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        match self.file {
+            Some(ref mut file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as IoWrite;
+    use std::{format, fs};
+
+    use crate::file_writer;
+
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_file_writer() {
+        let dir = std::env::temp_dir().join(format!(
+            "unwind-context-test-file-writer-{}",
+            std::process::id()
+        ));
+        let path = dir.join("nested").join("crash.log");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut writer = file_writer(&path);
+        assert!(writer.flush().is_ok());
+        assert!(!dir.exists());
+
+        assert!(writer.write_all(b"abc\n").is_ok());
+        assert!(writer.write_all(b"def").is_ok());
+        assert!(writer.flush().is_ok());
+        drop(writer);
+
+        let mut writer = file_writer(&path);
+        assert!(writer.write_all(b"ghi\n").is_ok());
+        drop(writer);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "abc\ndefghi\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
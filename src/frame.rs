@@ -0,0 +1,603 @@
+use core::fmt::{Debug, Display, Formatter, Result as FmtResult, Write as FmtWrite};
+use core::panic::Location;
+use core::time::Duration;
+
+use crate::compat::{if_core_error, if_not_core_error};
+use crate::{
+    ContextFrame, ContextLayout, DebugAnsiColored, DefaultLayout, FixedBufWriter, FrameOptions,
+    LayoutFrame,
+};
+
+/// A structure representing an unwind context frame: the context data and the
+/// code location where the context scope guard was created.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, FrameInfo};
+///
+/// let foo = 123;
+/// let data = build_unwind_context_data!(fn func(foo));
+/// let frame = FrameInfo::new(&data, core::panic::Location::caller());
+/// ```
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct FrameInfo<'a, T> {
+    /// Context data.
+    pub data: &'a T,
+    /// Code location where the context scope guard was created.
+    pub location: &'static Location<'static>,
+    /// Time elapsed since the context scope guard was created, if it was
+    /// measured.
+    pub elapsed: Option<Duration>,
+    /// Frame index within a single unwind, if frames are numbered.
+    pub index: Option<usize>,
+    /// The number of context scope guards enclosing the context scope guard
+    /// of this frame.
+    pub depth: usize,
+}
+
+impl<T> Clone for FrameInfo<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for FrameInfo<'_, T> {}
+
+impl<'a, T> FrameInfo<'a, T> {
+    /// Create a new `FrameInfo` with the provided context data and location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, FrameInfo};
+    ///
+    /// let data = build_unwind_context_data!(fn func());
+    /// let frame = FrameInfo::new(&data, core::panic::Location::caller());
+    /// ```
+    #[inline]
+    pub fn new(data: &'a T, location: &'static Location<'static>) -> Self {
+        Self {
+            data,
+            location,
+            elapsed: None,
+            index: None,
+            depth: 0,
+        }
+    }
+
+    /// Sets the time elapsed since the context scope guard was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    ///
+    /// use unwind_context::{build_unwind_context_data, FrameInfo};
+    ///
+    /// let data = build_unwind_context_data!(fn func());
+    /// let frame = FrameInfo::new(&data, core::panic::Location::caller())
+    ///     .with_elapsed(Some(Duration::from_millis(1_820)));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_elapsed(mut self, elapsed: Option<Duration>) -> Self {
+        self.elapsed = elapsed;
+        self
+    }
+
+    /// Sets the frame index within a single unwind.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, FrameInfo};
+    ///
+    /// let data = build_unwind_context_data!(fn func());
+    /// let frame = FrameInfo::new(&data, core::panic::Location::caller()).with_index(Some(0));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_index(mut self, index: Option<usize>) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Sets the number of context scope guards enclosing the context scope
+    /// guard of this frame. The frame is indented proportionally to it if an
+    /// indent string is specified in [`FrameOptions`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, FrameInfo};
+    ///
+    /// let data = build_unwind_context_data!(fn func());
+    /// let frame = FrameInfo::new(&data, core::panic::Location::caller()).with_depth(2);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_depth(mut self, depth: usize) -> Self {
+        self.depth = depth;
+        self
+    }
+}
+
+/// An error returned by the [`render_frame_into`] function.
+///
+/// In both cases the buffer contains a valid UTF-8 string of the given length
+/// with the frame output rendered so far.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, FrameInfo, FrameOptions, RenderFrameError};
+///
+/// let data = build_unwind_context_data!(fn func());
+/// let frame = FrameInfo::new(&data, core::panic::Location::caller());
+/// let mut buffer = [0; 4];
+/// let result = unwind_context::render_frame_into(&mut buffer, &frame, &FrameOptions::new());
+/// assert_eq!(result, Err(RenderFrameError::Truncated { written: 4 }));
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum RenderFrameError {
+    /// The frame does not fit into the buffer and was truncated at the last
+    /// character boundary that fits.
+    Truncated {
+        /// The number of bytes written into the buffer.
+        written: usize,
+    },
+    /// The context data formatting failed.
+    Fmt {
+        /// The number of bytes written into the buffer.
+        written: usize,
+    },
+}
+
+impl RenderFrameError {
+    /// Returns the number of bytes written into the buffer before the error
+    /// occurred.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let error = unwind_context::RenderFrameError::Truncated { written: 4 };
+    /// assert_eq!(error.written(), 4);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn written(&self) -> usize {
+        match self {
+            Self::Truncated { written } | Self::Fmt { written } => *written,
+        }
+    }
+}
+
+impl Display for RenderFrameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Truncated { written } => {
+                write!(f, "unwind context frame truncated after {written} bytes")
+            }
+            Self::Fmt { written } => {
+                write!(
+                    f,
+                    "unwind context frame formatting failed after {written} bytes"
+                )
+            }
+        }
+    }
+}
+
+if_core_error! {
+    impl crate::compat::Error for RenderFrameError {}
+}
+
+if_not_core_error! {
+    #[cfg(feature = "std")]
+    impl crate::compat::Error for RenderFrameError {}
+}
+
+/// Renders an unwind context frame into a caller-provided byte buffer.
+///
+/// It uses the same formatting as context scope guards use when a panic
+/// occurs and does not allocate, so it can be used in environments that must
+/// format into a pre-allocated buffer, like embedded panic handlers.
+///
+/// Returns the number of bytes written. The written part of the buffer is
+/// always a valid UTF-8 string.
+///
+/// # Errors
+///
+/// Returns [`RenderFrameError::Truncated`] if the frame does not fit into the
+/// buffer. In this case the buffer contains the longest frame prefix that fits
+/// and ends at a character boundary.
+///
+/// Returns [`RenderFrameError::Fmt`] if the context data formatting fails.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, FrameInfo, FrameOptions};
+///
+/// let foo = 123;
+/// let data = build_unwind_context_data!(fn func(foo));
+/// let frame = FrameInfo::new(&data, core::panic::Location::caller());
+///
+/// let mut buffer = [0; 256];
+/// let len = unwind_context::render_frame_into(&mut buffer, &frame, &FrameOptions::new()).unwrap();
+/// let output = core::str::from_utf8(&buffer[..len]).unwrap();
+/// assert!(output.starts_with("fn func(foo: 123)\n    at "));
+/// ```
+pub fn render_frame_into<T>(
+    buffer: &mut [u8],
+    frame: &FrameInfo<'_, T>,
+    options: &FrameOptions,
+) -> Result<usize, RenderFrameError>
+where
+    T: Debug + DebugAnsiColored,
+{
+    let mut writer = FixedBufWriter::new(buffer);
+    let result = format_frame_into(&mut writer, frame, options);
+    let written = writer.len();
+    if writer.is_truncated() {
+        Err(RenderFrameError::Truncated { written })
+    } else if result.is_err() {
+        Err(RenderFrameError::Fmt { written })
+    } else {
+        Ok(written)
+    }
+}
+
+/// Formats an unwind context frame into a given [`core::fmt::Write`] writer.
+///
+/// It uses the same formatting as context scope guards use when a panic
+/// occurs and does not allocate by itself, so it can be used with bounded
+/// writers like `heapless::String<N>` to render a frame for transmission over
+/// UART or CAN in `no_std` environments. Use [`render_frame_into`] to render a
+/// frame into a byte buffer instead.
+///
+/// # Errors
+///
+/// This function will return an error if the writer fails, for example when
+/// a bounded writer runs out of capacity, or if the context data formatting
+/// fails. In this case the writer contains the frame output written so far.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, FrameInfo, FrameOptions, LocationStyle};
+///
+/// let foo = 123;
+/// let data = build_unwind_context_data!(fn func(foo));
+/// let frame = FrameInfo::new(&data, core::panic::Location::caller());
+/// let options = FrameOptions::new().with_location_style(LocationStyle::Hidden);
+///
+/// let mut output = String::new();
+/// unwind_context::format_frame_into(&mut output, &frame, &options).unwrap();
+/// assert_eq!(output, "fn func(foo: 123)\n");
+/// ```
+pub fn format_frame_into<W, T>(
+    writer: &mut W,
+    frame: &FrameInfo<'_, T>,
+    options: &FrameOptions,
+) -> FmtResult
+where
+    W: FmtWrite + ?Sized,
+    T: Debug + DebugAnsiColored,
+{
+    write!(
+        writer,
+        "{}",
+        FrameFmt {
+            frame: *frame,
+            options,
+        }
+    )
+}
+
+/// An utility wrapper type which is used to format an unwind context frame
+/// using the given options.
+#[derive(Debug)]
+pub(crate) struct FrameFmt<'a, T> {
+    pub frame: FrameInfo<'a, T>,
+    pub options: &'a FrameOptions,
+}
+
+impl<T> Display for FrameFmt<'_, T>
+where
+    T: Debug + DebugAnsiColored,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.options.indent() {
+            Some(indent) if self.frame.depth > 0 => self.write_frame(&mut IndentWriter {
+                writer: f,
+                indent,
+                depth: self.frame.depth,
+                is_line_start: true,
+            }),
+            _ => self.write_frame(f),
+        }
+    }
+}
+
+impl<T> FrameFmt<'_, T>
+where
+    T: Debug + DebugAnsiColored,
+{
+    fn write_frame<W: FmtWrite>(&self, f: &mut W) -> FmtResult {
+        let frame = LayoutFrame::new(ContextFrame::new(&self.frame), self.options);
+        match self.options.layout() {
+            Some(layout) => layout.write_frame(f, &frame)?,
+            None => DefaultLayout.write_frame(f, &frame)?,
+        }
+        #[cfg(feature = "std")]
+        if self.options.should_print_backtrace() {
+            writeln!(
+                f,
+                "stack backtrace:\n{}",
+                std::backtrace::Backtrace::force_capture()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A writer adapter that indents every line by the given number of indent
+/// strings.
+struct IndentWriter<'a, W> {
+    writer: &'a mut W,
+    indent: &'a str,
+    depth: usize,
+    is_line_start: bool,
+}
+
+impl<W: FmtWrite> FmtWrite for IndentWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        for line in s.split_inclusive('\n') {
+            if self.is_line_start {
+                for _ in 0..self.depth {
+                    self.writer.write_str(self.indent)?;
+                }
+            }
+            self.writer.write_str(line)?;
+            self.is_line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+/// An utility wrapper type which is used to format a notice about repeated
+/// unwind context frames that were collapsed.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct RepeatedFramesFmt {
+    pub count: usize,
+}
+
+impl Display for RepeatedFramesFmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.count {
+            1 => f.write_str("... previous frame repeated 1 time\n"),
+            count => writeln!(f, "... previous frame repeated {count} times"),
+        }
+    }
+}
+
+/// An utility wrapper type which is used to format a notice about unwind
+/// context frames suppressed because of the maximum number of frames limit or
+/// the output budget.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct SuppressedFramesFmt {
+    pub count: usize,
+    pub is_budget_exceeded: bool,
+}
+
+impl Display for SuppressedFramesFmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if self.is_budget_exceeded {
+            f.write_str("... output budget exceeded, ")?;
+        } else {
+            f.write_str("... ")?;
+        }
+        match self.count {
+            1 => f.write_str("1 more context frame suppressed\n"),
+            count => writeln!(f, "{count} more context frames suppressed"),
+        }
+    }
+}
+
+/// An utility wrapper type which is used to format a notice about unwind
+/// context frames of the same code location suppressed by a rate limit.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct RateLimitedFramesFmt {
+    pub count: usize,
+}
+
+#[cfg(feature = "std")]
+impl Display for RateLimitedFramesFmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.count {
+            1 => f.write_str("... frame suppressed 1 time by rate limit\n"),
+            count => writeln!(f, "... frame suppressed {count} times by rate limit"),
+        }
+    }
+}
+
+/// An utility wrapper type which is used to format a summary of unwind
+/// context frames printed after the last frame of an unwind.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct UnwindSummaryFmt {
+    pub frames: usize,
+    pub hidden: usize,
+}
+
+#[cfg(feature = "std")]
+impl Display for UnwindSummaryFmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.frames {
+            1 => f.write_str("-- 1 context frame")?,
+            frames => write!(f, "-- {frames} context frames")?,
+        }
+        if self.hidden > 0 {
+            write!(f, " ({} suppressed)", self.hidden)?;
+        }
+        f.write_str(" --\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Error as FmtError;
+    use core::panic::Location;
+    use core::time::Duration;
+
+    use crate::test_common::TEST_COLOR_SCHEME;
+    use crate::test_util::{buf_fmt, FixedBufWriter};
+    use crate::{
+        build_unwind_context_data, format_frame_into, render_frame_into, FrameInfo, FrameOptions,
+        LocationStyle, RenderFrameError,
+    };
+
+    #[test]
+    #[allow(clippy::too_many_lines)]
+    fn test_render_frame_into() {
+        let foo = 123;
+        let data = build_unwind_context_data!(fn func(foo));
+        let location = Location::caller();
+        let frame = FrameInfo::new(&data, location);
+
+        let mut expected_buffer = [0; 256];
+        let expected = buf_fmt(
+            &mut expected_buffer,
+            format_args!(
+                "fn func(foo: 123)\n    at {}:{}:{}\n",
+                location.file(),
+                location.line(),
+                location.column()
+            ),
+        )
+        .unwrap();
+
+        let mut buffer = [0; 256];
+        let len = render_frame_into(&mut buffer, &frame, &FrameOptions::new()).unwrap();
+        assert_eq!(core::str::from_utf8(&buffer[..len]), Ok(expected));
+
+        let options = FrameOptions::new().with_color_scheme(Some(&TEST_COLOR_SCHEME));
+        let len = render_frame_into(&mut buffer, &frame, &options).unwrap();
+        let output = core::str::from_utf8(&buffer[..len]).unwrap();
+        assert!(output.starts_with(concat!(
+            "{FN}fn {FN_NAME}func{FN_BRACE}({DEF}{ARG}foo{DEF}: {NUM}123{DEF}",
+            "{FN_BRACE}){DEF}\n    at {LOC}"
+        )));
+        assert!(output.ends_with("{DEF}\n"));
+
+        let frame = frame.with_elapsed(Some(Duration::from_millis(1_820)));
+        let len = render_frame_into(&mut buffer, &frame, &FrameOptions::new()).unwrap();
+        let output = core::str::from_utf8(&buffer[..len]).unwrap();
+        assert!(output.starts_with("fn func(foo: 123)\n    at "));
+        assert!(output.ends_with(" after 1.82s in scope\n"));
+
+        let frame = frame.with_elapsed(None).with_index(Some(2));
+        let len = render_frame_into(&mut buffer, &frame, &FrameOptions::new()).unwrap();
+        let output = core::str::from_utf8(&buffer[..len]).unwrap();
+        assert!(output.starts_with("#2 fn func(foo: 123)\n    at "));
+
+        let frame = frame.with_index(None).with_depth(2);
+        let len = render_frame_into(&mut buffer, &frame, &FrameOptions::new()).unwrap();
+        assert_eq!(core::str::from_utf8(&buffer[..len]), Ok(expected));
+        let options = FrameOptions::new().with_indent(Some("| "));
+        let len = render_frame_into(&mut buffer, &frame, &options).unwrap();
+        let output = core::str::from_utf8(&buffer[..len]).unwrap();
+        assert!(output.starts_with("| | fn func(foo: 123)\n| |     at "));
+        assert_eq!(output.matches("| | ").count(), 2);
+        assert!(output.ends_with('\n'));
+
+        let frame = frame.with_depth(0);
+        let options = FrameOptions::new().with_location_style(LocationStyle::WithoutColumn);
+        let len = render_frame_into(&mut buffer, &frame, &options).unwrap();
+        let mut without_column_buffer = [0; 256];
+        let without_column = buf_fmt(
+            &mut without_column_buffer,
+            format_args!(
+                "fn func(foo: 123)\n    at {}:{}\n",
+                location.file(),
+                location.line(),
+            ),
+        )
+        .unwrap();
+        assert_eq!(core::str::from_utf8(&buffer[..len]), Ok(without_column));
+
+        let options = FrameOptions::new().with_location_style(LocationStyle::Hidden);
+        let len = render_frame_into(&mut buffer, &frame, &options).unwrap();
+        assert_eq!(
+            core::str::from_utf8(&buffer[..len]),
+            Ok("fn func(foo: 123)\n")
+        );
+
+        let frame = frame.with_elapsed(Some(Duration::from_millis(1_820)));
+        let len = render_frame_into(&mut buffer, &frame, &options).unwrap();
+        assert_eq!(
+            core::str::from_utf8(&buffer[..len]),
+            Ok("fn func(foo: 123) after 1.82s in scope\n")
+        );
+
+        let options = FrameOptions::new()
+            .with_color_scheme(Some(&TEST_COLOR_SCHEME))
+            .with_location_style(LocationStyle::WithoutColumn)
+            .with_location_hyperlink(Some("editor://{file}/{line}/{column}"));
+        let frame = frame.with_elapsed(None);
+        let len = render_frame_into(&mut buffer, &frame, &options).unwrap();
+        let mut hyperlink_buffer = [0; 256];
+        let hyperlink = buf_fmt(
+            &mut hyperlink_buffer,
+            format_args!(
+                "\n    at {{LOC}}\x1b]8;;editor://{}/{}/{}\x1b\\{}:{}\x1b]8;;\x1b\\{{DEF}}\n",
+                location.file(),
+                location.line(),
+                location.column(),
+                location.file(),
+                location.line(),
+            ),
+        )
+        .unwrap();
+        let output = core::str::from_utf8(&buffer[..len]).unwrap();
+        assert!(output.ends_with(hyperlink));
+
+        let options = options.with_color_scheme(None);
+        let len = render_frame_into(&mut buffer, &frame, &options).unwrap();
+        assert_eq!(core::str::from_utf8(&buffer[..len]), Ok(without_column));
+
+        for len in 0..expected.len() {
+            assert_eq!(
+                render_frame_into(&mut buffer[..len], &frame, &FrameOptions::new()),
+                Err(RenderFrameError::Truncated { written: len })
+            );
+            assert_eq!(&buffer[..len], &expected.as_bytes()[..len]);
+        }
+    }
+
+    #[test]
+    fn test_format_frame_into() {
+        let foo = 123;
+        let data = build_unwind_context_data!(fn func(foo));
+        let frame = FrameInfo::new(&data, Location::caller());
+        let options = FrameOptions::new().with_location_style(LocationStyle::Hidden);
+
+        let mut buffer = [0; 32];
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        assert_eq!(format_frame_into(&mut writer, &frame, &options), Ok(()));
+        assert_eq!(writer.into_str(), "fn func(foo: 123)\n");
+
+        // Bounded writers keep the frame output written so far.
+        let mut buffer = [0; 12];
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        assert_eq!(
+            format_frame_into(&mut writer, &frame, &options),
+            Err(FmtError)
+        );
+        assert_eq!(writer.into_str(), "fn func(foo:");
+    }
+}
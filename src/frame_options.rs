@@ -0,0 +1,1302 @@
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::{AtomicU8, Ordering as AtomicOrdering};
+#[cfg(feature = "std")]
+use std::sync::{PoisonError, RwLock};
+
+use crate::context_layout::LayoutRef;
+#[cfg(feature = "std")]
+use crate::get_context_layout;
+use crate::{AnsiColorScheme, ContextLayout};
+
+#[cfg(feature = "std")]
+static SHOULD_PRINT_THREAD_INFO: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "std")]
+static SHOULD_PRINT_PROCESS_ID: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "std")]
+static SHOULD_NUMBER_FRAMES: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "std")]
+static SHOULD_LOCK_STDERR: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "std")]
+static FRAME_INDENT: RwLock<Option<&'static str>> = RwLock::new(None);
+
+#[cfg(feature = "std")]
+static LOCATION_PATH: RwLock<LocationPath> = RwLock::new(LocationPath::Full);
+
+#[cfg(feature = "std")]
+static LOCATION_FORMAT: RwLock<Option<&'static str>> = RwLock::new(None);
+
+#[cfg(feature = "std")]
+static LOCATION_HYPERLINK: RwLock<Option<&'static str>> = RwLock::new(None);
+
+#[cfg(feature = "std")]
+static SHOULD_MEASURE_ELAPSED_TIME: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "std")]
+static SHOULD_PRINT_SCOPE_ENTRY: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "std")]
+static SHOULD_PRINT_ON_DROP: AtomicBool = AtomicBool::new(false);
+
+static LOCATION_STYLE: AtomicU8 = AtomicU8::new(LocationStyle::Full.to_u8());
+
+/// A style of the code location printed after the unwind context frame data.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{FrameOptions, LocationStyle};
+///
+/// let options = FrameOptions::new().with_location_style(LocationStyle::WithoutColumn);
+/// assert_eq!(options.location_style(), LocationStyle::WithoutColumn);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum LocationStyle {
+    /// Print the location as `at file:line:column` on a separate line.
+    #[default]
+    Full,
+    /// Print the location as `at file:line` on a separate line.
+    WithoutColumn,
+    /// Do not print the location. The frame data and the optional information
+    /// usually printed after the location, like the thread name, are printed
+    /// on a single line.
+    Hidden,
+}
+
+impl LocationStyle {
+    const fn to_u8(self) -> u8 {
+        match self {
+            Self::Full => 0,
+            Self::WithoutColumn => 1,
+            Self::Hidden => 2,
+        }
+    }
+
+    const fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::WithoutColumn,
+            2 => Self::Hidden,
+            _ => Self::Full,
+        }
+    }
+}
+
+/// A way to shorten the file path of the code location printed after the
+/// unwind context frame data.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::LocationPath;
+///
+/// assert_eq!(
+///     LocationPath::Full.shorten("/src/crate/src/lib.rs"),
+///     "/src/crate/src/lib.rs"
+/// );
+/// assert_eq!(
+///     LocationPath::FileName.shorten("/src/crate/src/lib.rs"),
+///     "lib.rs"
+/// );
+/// assert_eq!(
+///     LocationPath::StripPrefix("/src/crate").shorten("/src/crate/src/lib.rs"),
+///     "src/lib.rs"
+/// );
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum LocationPath {
+    /// Print the file path as is.
+    #[default]
+    Full,
+    /// Print only the file name without its directories.
+    FileName,
+    /// Strip the given prefix, like the workspace root or
+    /// `env!("CARGO_MANIFEST_DIR")`, from the file path if it starts with it.
+    StripPrefix(&'static str),
+}
+
+impl LocationPath {
+    /// Returns the given file path shortened according to this setting.
+    ///
+    /// Both `/` and `\` are treated as path separators.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::LocationPath;
+    ///
+    /// let path = LocationPath::StripPrefix("/home/user/project");
+    /// assert_eq!(
+    ///     path.shorten("/home/user/project/src/main.rs"),
+    ///     "src/main.rs"
+    /// );
+    /// assert_eq!(
+    ///     path.shorten("/rustc/library/core/src/option.rs"),
+    ///     "/rustc/library/core/src/option.rs"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn shorten(self, file: &str) -> &str {
+        const fn is_separator(ch: char) -> bool {
+            ch == '/' || ch == '\\'
+        }
+
+        match self {
+            Self::Full => file,
+            Self::FileName => file.rsplit(is_separator).next().unwrap_or(file),
+            Self::StripPrefix(prefix) => match file.strip_prefix(prefix) {
+                Some(rest) if rest.is_empty() || rest.starts_with(is_separator) => {
+                    rest.trim_start_matches(is_separator)
+                }
+                Some(_) | None => file,
+            },
+        }
+    }
+}
+
+/// A structure representing the options used to print an unwind context frame.
+///
+/// This type is not intended to be used directly. Consider using macros like
+/// [`unwind_context`], [`unwind_context_with_io`] or
+/// [`unwind_context_with_fmt`] instead.
+///
+/// # Examples
+///
+/// ```rust
+/// let options = unwind_context::FrameOptions::new()
+///     .with_color_scheme(Some(&unwind_context::DEFAULT_DEFAULT_COLOR_SCHEME));
+/// ```
+///
+/// [`unwind_context`]: crate::unwind_context
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+/// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct FrameOptions {
+    color_scheme: Option<&'static AnsiColorScheme>,
+    thread_info: Option<bool>,
+    process_id: Option<bool>,
+    indent: Option<&'static str>,
+    location_style: Option<LocationStyle>,
+    location_path: LocationPath,
+    location_format: Option<&'static str>,
+    location_hyperlink: Option<&'static str>,
+    layout: Option<LayoutRef>,
+    backtrace: bool,
+    verbose: Option<bool>,
+    print_on_drop: Option<bool>,
+}
+
+impl FrameOptions {
+    /// Creates a new `FrameOptions` without colorization and with all other
+    /// options determined by the global settings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert_eq!(options.color_scheme(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            color_scheme: None,
+            thread_info: None,
+            process_id: None,
+            indent: None,
+            location_style: None,
+            location_path: LocationPath::Full,
+            location_format: None,
+            location_hyperlink: None,
+            layout: None,
+            backtrace: false,
+            verbose: None,
+            print_on_drop: None,
+        }
+    }
+
+    /// Sets the color scheme used to print a frame, or disables colorization
+    /// if `None` is given.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new()
+    ///     .with_color_scheme(Some(&unwind_context::DEFAULT_DEFAULT_COLOR_SCHEME));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_color_scheme(
+        mut self,
+        color_scheme: Option<&'static AnsiColorScheme>,
+    ) -> Self {
+        self.color_scheme = color_scheme;
+        self
+    }
+
+    /// Returns the color scheme used to print a frame, if colorization is
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert_eq!(options.color_scheme(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn color_scheme(&self) -> Option<&'static AnsiColorScheme> {
+        self.color_scheme
+    }
+
+    /// Sets the indent string repeated before every frame line once per
+    /// enclosing context scope guard, or disables indentation if `None` is
+    /// given.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new().with_indent(Some("  "));
+    /// assert_eq!(options.indent(), Some("  "));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_indent(mut self, indent: Option<&'static str>) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// Returns the indent string repeated before every frame line once per
+    /// enclosing context scope guard, if indentation is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert_eq!(options.indent(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn indent(&self) -> Option<&'static str> {
+        self.indent
+    }
+
+    /// Sets the style of the frame location regardless of the global
+    /// [`set_location_style`] setting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{FrameOptions, LocationStyle};
+    ///
+    /// let options = FrameOptions::new().with_location_style(LocationStyle::Hidden);
+    /// assert_eq!(options.location_style(), LocationStyle::Hidden);
+    /// ```
+    ///
+    /// [`set_location_style`]: crate::set_location_style
+    #[inline]
+    #[must_use]
+    pub const fn with_location_style(mut self, style: LocationStyle) -> Self {
+        self.location_style = Some(style);
+        self
+    }
+
+    /// Returns the style of the frame location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{FrameOptions, LocationStyle};
+    ///
+    /// let options = FrameOptions::new();
+    /// assert_eq!(options.location_style(), LocationStyle::Full);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn location_style(&self) -> LocationStyle {
+        self.location_style.unwrap_or_else(get_location_style)
+    }
+
+    /// Sets the way to shorten the file path of the frame location.
+    ///
+    /// Context scope guards use the global [`set_location_path`] setting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{FrameOptions, LocationPath};
+    ///
+    /// let options = FrameOptions::new().with_location_path(LocationPath::FileName);
+    /// assert_eq!(options.location_path(), LocationPath::FileName);
+    /// ```
+    ///
+    /// [`set_location_path`]: crate::set_location_path
+    #[inline]
+    #[must_use]
+    pub const fn with_location_path(mut self, path: LocationPath) -> Self {
+        self.location_path = path;
+        self
+    }
+
+    /// Returns the way to shorten the file path of the frame location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{FrameOptions, LocationPath};
+    ///
+    /// let options = FrameOptions::new();
+    /// assert_eq!(options.location_path(), LocationPath::Full);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn location_path(&self) -> LocationPath {
+        self.location_path
+    }
+
+    /// Sets the format of the frame location, or restores the format selected
+    /// by the location style if `None` is given.
+    ///
+    /// The format may contain `{file}`, `{line}`, and `{column}`
+    /// placeholders. It takes precedence over the
+    /// [`LocationStyle::WithoutColumn`] location style.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new().with_location_format(Some("{file}({line})"));
+    /// assert_eq!(options.location_format(), Some("{file}({line})"));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_location_format(mut self, format: Option<&'static str>) -> Self {
+        self.location_format = format;
+        self
+    }
+
+    /// Returns the format of the frame location, if set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert_eq!(options.location_format(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn location_format(&self) -> Option<&'static str> {
+        self.location_format
+    }
+
+    /// Sets the URL template of the OSC 8 terminal hyperlink wrapping the frame
+    /// location, or disables hyperlinks if `None` is given.
+    ///
+    /// The template may contain `{file}`, `{line}`, and `{column}`
+    /// placeholders. Hyperlinks are written only if colorization is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new()
+    ///     .with_location_hyperlink(Some("vscode://file/{file}:{line}:{column}"));
+    /// assert_eq!(
+    ///     options.location_hyperlink(),
+    ///     Some("vscode://file/{file}:{line}:{column}")
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_location_hyperlink(mut self, template: Option<&'static str>) -> Self {
+        self.location_hyperlink = template;
+        self
+    }
+
+    /// Returns the URL template of the OSC 8 terminal hyperlink wrapping the
+    /// frame location, if hyperlinks are enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert_eq!(options.location_hyperlink(), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn location_hyperlink(&self) -> Option<&'static str> {
+        self.location_hyperlink
+    }
+
+    /// Sets the layout used to write a frame, or restores the layout set with
+    /// the global [`set_context_layout`] setting if `None` is given.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{DefaultLayout, FrameOptions};
+    ///
+    /// let options = FrameOptions::new().with_layout(Some(&DefaultLayout));
+    /// assert!(options.layout().is_some());
+    /// ```
+    ///
+    /// [`set_context_layout`]: crate::set_context_layout
+    #[inline]
+    #[must_use]
+    pub const fn with_layout(mut self, layout: Option<&'static dyn ContextLayout>) -> Self {
+        self.layout = match layout {
+            Some(layout) => Some(LayoutRef(layout)),
+            None => None,
+        };
+        self
+    }
+
+    /// Returns the layout used to write a frame, if set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert!(options.layout().is_none());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn layout(&self) -> Option<&'static dyn ContextLayout> {
+        match self.layout {
+            Some(LayoutRef(layout)) => Some(layout),
+            None => None,
+        }
+    }
+
+    /// Enables or disables printing of the current thread name and ID after
+    /// the frame location regardless of the global
+    /// [`set_thread_info_enabled`] setting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new().with_thread_info(true);
+    /// assert!(options.should_print_thread_info());
+    /// ```
+    ///
+    /// [`set_thread_info_enabled`]: crate::set_thread_info_enabled
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub const fn with_thread_info(mut self, enabled: bool) -> Self {
+        self.thread_info = Some(enabled);
+        self
+    }
+
+    /// Returns `true` if the current thread name and ID should be printed
+    /// after the frame location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert!(!options.should_print_thread_info());
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn should_print_thread_info(&self) -> bool {
+        self.thread_info.unwrap_or_else(is_thread_info_enabled)
+    }
+
+    /// Enables or disables printing of the current process ID after the frame
+    /// location regardless of the global [`set_process_id_enabled`] setting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new().with_process_id(true);
+    /// assert!(options.should_print_process_id());
+    /// ```
+    ///
+    /// [`set_process_id_enabled`]: crate::set_process_id_enabled
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub const fn with_process_id(mut self, enabled: bool) -> Self {
+        self.process_id = Some(enabled);
+        self
+    }
+
+    /// Returns `true` if the current process ID should be printed after the
+    /// frame location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert!(!options.should_print_process_id());
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn should_print_process_id(&self) -> bool {
+        self.process_id.unwrap_or_else(is_process_id_enabled)
+    }
+
+    /// Enables or disables printing of a [`std::backtrace::Backtrace`]
+    /// captured when the frame is printed after the frame location.
+    ///
+    /// Since the frame is printed while the stack is being unwound, the
+    /// captured backtrace shows the real call stack leading to the function
+    /// that created the context scope guard, including functions that are not
+    /// instrumented with unwind context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new().with_backtrace(true);
+    /// assert!(options.should_print_backtrace());
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub const fn with_backtrace(mut self, enabled: bool) -> Self {
+        self.backtrace = enabled;
+        self
+    }
+
+    /// Returns `true` if a backtrace should be printed after the frame
+    /// location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert!(!options.should_print_backtrace());
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub const fn should_print_backtrace(&self) -> bool {
+        self.backtrace
+    }
+
+    /// Enables or disables printing of the frame prefixed with `enter` when
+    /// the context scope guard is created regardless of the global
+    /// [`set_verbose_enabled`] setting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new().with_verbose(true);
+    /// assert!(options.is_verbose());
+    /// ```
+    ///
+    /// [`set_verbose_enabled`]: crate::set_verbose_enabled
+    #[inline]
+    #[must_use]
+    pub const fn with_verbose(mut self, enabled: bool) -> Self {
+        self.verbose = Some(enabled);
+        self
+    }
+
+    /// Returns `true` if the frame should be printed when the context scope
+    /// guard is created.
+    ///
+    /// Without the `std` feature the global setting is not available, and the
+    /// frame is printed only if it was enabled with
+    /// [`FrameOptions::with_verbose`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert!(!options.is_verbose());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_verbose(&self) -> bool {
+        #[cfg(feature = "std")]
+        {
+            self.verbose.unwrap_or_else(is_verbose_enabled)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.verbose.unwrap_or(false)
+        }
+    }
+
+    /// Enables or disables printing of the frame on every context scope guard
+    /// drop, tagged with `ok` or `panic`, regardless of the global
+    /// [`set_print_on_drop_enabled`] setting.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new().with_print_on_drop(true);
+    /// assert!(options.should_print_on_drop());
+    /// ```
+    ///
+    /// [`set_print_on_drop_enabled`]: crate::set_print_on_drop_enabled
+    #[inline]
+    #[must_use]
+    pub const fn with_print_on_drop(mut self, enabled: bool) -> Self {
+        self.print_on_drop = Some(enabled);
+        self
+    }
+
+    /// Returns `true` if the frame should be printed on every context scope
+    /// guard drop, even if the thread is not panicking.
+    ///
+    /// Without the `std` feature the global setting is not available, and the
+    /// frame is printed on every drop only if it was enabled with
+    /// [`FrameOptions::with_print_on_drop`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let options = unwind_context::FrameOptions::new();
+    /// assert!(!options.should_print_on_drop());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn should_print_on_drop(&self) -> bool {
+        #[cfg(feature = "std")]
+        {
+            self.print_on_drop.unwrap_or_else(is_print_on_drop_enabled)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            self.print_on_drop.unwrap_or(false)
+        }
+    }
+}
+
+/// Returns the given frame options of a context scope guard with the
+/// settings that are not stored in the guard resolved according to the global
+/// settings.
+///
+/// It is called only when the frame is printed, so that context scope guard
+/// creation does not need to read global settings guarded by locks.
+#[inline]
+pub(crate) fn resolve_frame_options(options: FrameOptions) -> FrameOptions {
+    #[cfg(feature = "std")]
+    let options = options
+        .with_indent(options.indent().or_else(get_frame_indent))
+        .with_location_path(get_location_path())
+        .with_location_format(options.location_format().or_else(get_location_format))
+        .with_location_hyperlink(options.location_hyperlink().or_else(get_location_hyperlink))
+        .with_layout(options.layout().or_else(get_context_layout));
+    options
+}
+
+/// Sets the style of the code location printed after the data of every unwind
+/// context frame.
+///
+/// It allows to drop the column number or the whole location line if it is
+/// too noisy, for example on minimal serial consoles. By default the location
+/// is printed as `at file:line:column`. It can also be set for a specific
+/// context scope guard with the `location_style = ...` macro parameter.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context, LocationStyle};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_location_style(LocationStyle::WithoutColumn);
+/// // On panic, it will print something like:
+/// // fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:4
+/// func(123, "abc");
+/// # unwind_context::set_location_style(LocationStyle::Full);
+/// ```
+#[inline]
+pub fn set_location_style(style: LocationStyle) {
+    LOCATION_STYLE.store(style.to_u8(), AtomicOrdering::Relaxed);
+}
+
+/// Returns the style of the code location printed after the data of every
+/// unwind context frame.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::LocationStyle;
+///
+/// assert_eq!(unwind_context::get_location_style(), LocationStyle::Full);
+/// ```
+#[inline]
+#[must_use]
+pub fn get_location_style() -> LocationStyle {
+    LocationStyle::from_u8(LOCATION_STYLE.load(AtomicOrdering::Relaxed))
+}
+
+/// Sets the way to shorten the file paths of the code locations printed with
+/// unwind context frames and the panic location header.
+///
+/// It allows to print only the file name, or to strip a common prefix like the
+/// workspace root, so that frames of dependencies located by absolute paths
+/// stay short. By default file paths are printed as is.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context, LocationPath};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_location_path(LocationPath::StripPrefix(env!("CARGO_MANIFEST_DIR")));
+/// func(123, "abc");
+/// unwind_context::set_location_path(LocationPath::FileName);
+/// // On panic, it will print something like:
+/// // fn func(foo: 123, bar: "abc")
+/// //     at main.rs:4:16
+/// func(123, "abc");
+/// # unwind_context::set_location_path(LocationPath::Full);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_location_path(path: LocationPath) {
+    *LOCATION_PATH
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = path;
+}
+
+/// Returns the way to shorten the file paths of the code locations printed
+/// with unwind context frames.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::LocationPath;
+///
+/// assert_eq!(unwind_context::get_location_path(), LocationPath::Full);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn get_location_path() -> LocationPath {
+    *LOCATION_PATH.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sets the format of the locations printed after the data of unwind context
+/// frames, or restores the [default format](crate::DEFAULT_LOCATION_FORMAT) if
+/// `None` is given.
+///
+/// The format may contain `{file}`, `{line}`, and `{column}` placeholders. It
+/// allows to make the output clickable or to match the locations with IDE
+/// problem matchers without post-processing, for example with the
+/// `vscode://file/{file}:{line}:{column}` format or the MSVC-like
+/// `{file}({line},{column})` format. The `{file}` placeholder is replaced with
+/// the file path shortened according to the [`set_location_path`] setting.
+///
+/// The format takes precedence over the [`LocationStyle::WithoutColumn`]
+/// location style.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_location_format(Some("{file}({line},{column})"));
+/// // On panic, it will print something like:
+/// // fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs(4,16)
+/// func(123, "abc");
+/// # unwind_context::set_location_format(None);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_location_format(format: Option<&'static str>) {
+    *LOCATION_FORMAT
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = format;
+}
+
+/// Returns the format of the locations printed after the data of unwind
+/// context frames, if set.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(unwind_context::get_location_format(), None);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn get_location_format() -> Option<&'static str> {
+    *LOCATION_FORMAT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sets the URL template of the OSC 8 terminal hyperlinks wrapping the
+/// locations of unwind context frames, or disables hyperlinks if `None` is
+/// given.
+///
+/// The template may contain `{file}`, `{line}`, and `{column}` placeholders,
+/// like [`FILE_HYPERLINK_TEMPLATE`] or `vscode://file/{file}:{line}:{column}`.
+/// The `{file}` placeholder is replaced with the full file path regardless of
+/// the [`set_location_path`] setting. It makes the locations clickable in
+/// terminals supporting OSC 8 hyperlinks, like `iTerm2`, `WezTerm`, or the
+/// VS Code integrated terminal. Other terminals usually ignore them.
+///
+/// Hyperlinks are written only if colorization is enabled. By default
+/// hyperlinks are disabled.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_location_hyperlink(Some("vscode://file/{file}:{line}:{column}"));
+/// func(123, "abc");
+/// # unwind_context::set_location_hyperlink(None);
+/// ```
+///
+/// [`FILE_HYPERLINK_TEMPLATE`]: crate::FILE_HYPERLINK_TEMPLATE
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_location_hyperlink(template: Option<&'static str>) {
+    *LOCATION_HYPERLINK
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = template;
+}
+
+/// Returns the URL template of the OSC 8 terminal hyperlinks wrapping the
+/// locations of unwind context frames, if hyperlinks are enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(unwind_context::get_location_hyperlink(), None);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn get_location_hyperlink() -> Option<&'static str> {
+    *LOCATION_HYPERLINK
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Enables or disables printing of the current thread name and ID after the
+/// location of every unwind context frame.
+///
+/// By default thread information is not printed. It can also be enabled for a
+/// specific context scope guard with the `thread_info = true` macro parameter.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_thread_info_enabled(true);
+/// // On panic, it will print something like:
+/// // fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:4:16 in thread 'main' (ThreadId(1))
+/// func(123, "abc");
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_thread_info_enabled(enabled: bool) {
+    SHOULD_PRINT_THREAD_INFO.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if printing of the current thread name and ID was enabled
+/// globally.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::is_thread_info_enabled());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn is_thread_info_enabled() -> bool {
+    SHOULD_PRINT_THREAD_INFO.load(AtomicOrdering::Relaxed)
+}
+
+/// Enables or disables printing of the current process ID after the location
+/// of every unwind context frame.
+///
+/// It helps to separate interleaved output of multi-process test harnesses or
+/// forking servers. By default the process ID is not printed. It can also be
+/// enabled for a specific context scope guard with the `process_id = true`
+/// macro parameter.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_process_id_enabled(true);
+/// // On panic, it will print something like:
+/// // fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:4:16 in process 12345
+/// func(123, "abc");
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_process_id_enabled(enabled: bool) {
+    SHOULD_PRINT_PROCESS_ID.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if printing of the current process ID was enabled globally.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::is_process_id_enabled());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn is_process_id_enabled() -> bool {
+    SHOULD_PRINT_PROCESS_ID.load(AtomicOrdering::Relaxed)
+}
+
+/// Sets the indent string used to indent unwind context frames proportionally
+/// to the nesting depth of their context scope guards, or disables indentation
+/// if `None` is given.
+///
+/// The indent string is repeated before every frame line once per context
+/// scope guard enclosing the frame guard, so the call structure of the unwind
+/// is visually apparent. The setting applies to all frames printed after this
+/// call.
+///
+/// By default frames are not indented.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn outer(foo: u32) {
+///     let _ctx = unwind_context!(fn(foo));
+///     inner("abc");
+/// }
+///
+/// fn inner(bar: &str) {
+///     let _ctx = unwind_context!(fn(bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_frame_indent(Some("  "));
+/// // On panic, it will print something like:
+/// //   fn inner(bar: "abc")
+/// //       at src/main.rs:9:16
+/// // fn outer(foo: 123)
+/// //     at src/main.rs:4:16
+/// outer(123);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_frame_indent(indent: Option<&'static str>) {
+    *FRAME_INDENT.write().unwrap_or_else(PoisonError::into_inner) = indent;
+}
+
+/// Returns the indent string used to indent unwind context frames
+/// proportionally to the nesting depth of their context scope guards, if
+/// indentation is enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(unwind_context::get_frame_indent(), None);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn get_frame_indent() -> Option<&'static str> {
+    *FRAME_INDENT.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Enables or disables measuring of the time spent in every unwind context
+/// scope created after this call. The measured time is printed after the
+/// context location, like `after 1.82s in scope`.
+///
+/// By default elapsed time is not measured, so the context scope guard
+/// creation does not query the system clock. It can also be enabled for a
+/// specific context scope guard with the `elapsed_time = true` macro parameter.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_elapsed_time_enabled(true);
+/// // On panic, it will print something like:
+/// // fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:4:16 after 1.82s in scope
+/// func(123, "abc");
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_elapsed_time_enabled(enabled: bool) {
+    SHOULD_MEASURE_ELAPSED_TIME.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if measuring of the time spent in unwind context scopes was
+/// enabled globally.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::is_elapsed_time_enabled());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn is_elapsed_time_enabled() -> bool {
+    SHOULD_MEASURE_ELAPSED_TIME.load(AtomicOrdering::Relaxed)
+}
+
+/// Enables or disables the verbose trace mode, printing every unwind context
+/// frame prefixed with `enter` when its context scope guard is created, in
+/// addition to printing it on unwind.
+///
+/// It turns the existing instrumentation into a lightweight execution tracer
+/// during debugging sessions. Entry frames are written to the writer of the
+/// context scope guard, are not numbered, and are not captured or counted
+/// within any unwind. By default the verbose mode is disabled. It can also be
+/// enabled for a specific context scope guard with the `verbose = true` macro
+/// parameter.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_verbose_enabled(true);
+/// // It will print something like:
+/// // enter fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:4:16
+/// func(123, "abc");
+/// # unwind_context::set_verbose_enabled(false);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_verbose_enabled(enabled: bool) {
+    SHOULD_PRINT_SCOPE_ENTRY.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if the verbose trace mode printing unwind context frames on
+/// scope entry was enabled globally.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::is_verbose_enabled());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn is_verbose_enabled() -> bool {
+    SHOULD_PRINT_SCOPE_ENTRY.load(AtomicOrdering::Relaxed)
+}
+
+/// Enables or disables printing of every unwind context frame when its
+/// context scope guard is dropped, regardless of whether the thread is
+/// panicking, like a scope guard.
+///
+/// Frames printed on a normal scope exit are prefixed with `ok`, and frames
+/// printed on unwind are prefixed with `panic`. It allows using the same
+/// instrumentation to trace normal control flow when chasing logic bugs that
+/// do not panic. Frames printed on a normal scope exit are not numbered, and
+/// are not captured or counted within any unwind. By default frames are
+/// printed only on unwind. It can also be enabled for a specific context scope
+/// guard with the `print_on_drop = true` macro parameter.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_print_on_drop_enabled(true);
+/// // It will print something like:
+/// // ok fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:4:16
+/// func(123, "abc");
+/// # unwind_context::set_print_on_drop_enabled(false);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_print_on_drop_enabled(enabled: bool) {
+    SHOULD_PRINT_ON_DROP.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if printing of unwind context frames on every context scope
+/// guard drop was enabled globally.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::is_print_on_drop_enabled());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn is_print_on_drop_enabled() -> bool {
+    SHOULD_PRINT_ON_DROP.load(AtomicOrdering::Relaxed)
+}
+
+/// Enables or disables numbering of unwind context frames printed during a
+/// single unwind.
+///
+/// Frames are numbered like `#0`, `#1`, `#2`, starting from the innermost
+/// frame, similar to backtrace frame numbers. The numbering restarts when a
+/// context scope guard is dropped while the thread is not panicking, for
+/// example after the panic was caught with [`std::panic::catch_unwind`].
+///
+/// By default frames are not numbered.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_frame_numbers_enabled(true);
+/// // On panic, it will print something like:
+/// // #0 fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:4:16
+/// func(123, "abc");
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_frame_numbers_enabled(enabled: bool) {
+    SHOULD_NUMBER_FRAMES.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if numbering of unwind context frames was enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::are_frame_numbers_enabled());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn are_frame_numbers_enabled() -> bool {
+    SHOULD_NUMBER_FRAMES.load(AtomicOrdering::Relaxed)
+}
+
+/// Enables or disables locking of the standard error stream for the duration
+/// of an unwind of a thread.
+///
+/// When enabled, [`std::io::Stderr`] is locked by the first context scope guard
+/// that prints its frame during an unwind, and the lock is tracked by the
+/// current thread and held until the outermost context scope guard prints its
+/// frame and the unwind end. So all frames of one unwind together with the
+/// unwind banners and notes appear contiguously even if several threads panic
+/// concurrently. Since the standard error lock is reentrant, context scope
+/// guards writing to [`std::io::stderr`] on the unwinding thread are not
+/// blocked.
+///
+/// If the panic is caught while outer context scope guards are still alive,
+/// the lock is held until any context scope guard of the thread is created or
+/// dropped, so other threads writing to the standard error stream are blocked
+/// until then.
+///
+/// By default the standard error stream is not locked.
+///
+/// # Examples
+///
+/// ```rust
+/// unwind_context::set_stderr_lock_enabled(true);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_stderr_lock_enabled(enabled: bool) {
+    SHOULD_LOCK_STDERR.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if locking of the standard error stream for the duration of
+/// an unwind of a thread was enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::is_stderr_lock_enabled());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn is_stderr_lock_enabled() -> bool {
+    SHOULD_LOCK_STDERR.load(AtomicOrdering::Relaxed)
+}
@@ -0,0 +1,172 @@
+use core::fmt::Debug;
+use std::sync::{PoisonError, RwLock};
+
+use crate::{ContextFrame, DebugAnsiColored, FrameInfo};
+
+static FRAME_SINK: RwLock<Option<FrameSink>> = RwLock::new(None);
+
+/// A frame sink receiving every unwind context frame printed by context scope
+/// guards of all threads.
+///
+/// It is registered with the [`set_global_frame_sink`] function. See its
+/// documentation for more details.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{ContextFrame, FrameSink};
+///
+/// fn report(frame: &ContextFrame<'_>) {
+///     // Send the frame to a crash reporter.
+/// }
+///
+/// static REPORTER: FrameSink = FrameSink::new(report);
+/// static EXCLUSIVE_REPORTER: FrameSink = FrameSink::new(report).with_output(false);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct FrameSink {
+    callback: fn(&ContextFrame<'_>),
+    should_write_output: bool,
+}
+
+impl FrameSink {
+    /// Creates a new frame sink calling the given function in addition to
+    /// writing frames to the context scope guard writers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::FrameSink;
+    ///
+    /// static NOOP_SINK: FrameSink = FrameSink::new(|_| {});
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(callback: fn(&ContextFrame<'_>)) -> Self {
+        Self {
+            callback,
+            should_write_output: true,
+        }
+    }
+
+    /// Sets whether frames are still written to the context scope guard
+    /// writers. If `false`, the sink is called instead of writing frames.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::FrameSink;
+    ///
+    /// static EXCLUSIVE_SINK: FrameSink = FrameSink::new(|_| {}).with_output(false);
+    /// assert!(!EXCLUSIVE_SINK.should_write_output());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_output(mut self, enabled: bool) -> Self {
+        self.should_write_output = enabled;
+        self
+    }
+
+    /// Returns `true` if frames are still written to the context scope guard
+    /// writers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::FrameSink;
+    ///
+    /// assert!(FrameSink::new(|_| {}).should_write_output());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn should_write_output(&self) -> bool {
+        self.should_write_output
+    }
+
+    /// Calls the sink function with the given frame.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, ContextFrame, FrameInfo, FrameSink};
+    ///
+    /// let data = build_unwind_context_data!(fn func());
+    /// let frame = ContextFrame::new(&FrameInfo::new(&data, core::panic::Location::caller()));
+    /// FrameSink::new(|frame| assert_eq!(frame.func_name(), Some("func"))).send(&frame);
+    /// ```
+    #[inline]
+    pub fn send(&self, frame: &ContextFrame<'_>) {
+        (self.callback)(frame);
+    }
+}
+
+/// Sets the frame sink receiving every unwind context frame printed by
+/// context scope guards of all threads, or removes it if `None` is given.
+///
+/// The sink is called whenever a context scope guard is dropped during a
+/// panic, with the same frames that are written to the guard writer, so crash
+/// reporters, metrics systems, and GUI debuggers can consume frames without
+/// parsing the standard error output. Frames recorded with a
+/// [`FrameCapture`] are also passed to the sink.
+///
+/// Depending on [`FrameSink::with_output`], frames are written to the
+/// context scope guard writers in addition to or instead of being passed to
+/// the sink.
+///
+/// Note that the sink is called while the thread is panicking, so a panic
+/// inside it aborts the process.
+///
+/// By default no frame sink is set.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context, ContextFrame, FrameSink};
+///
+/// fn report(frame: &ContextFrame<'_>) {
+///     // Send the frame to a crash reporter.
+/// }
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_global_frame_sink(Some(FrameSink::new(report)));
+/// func(123, "abc");
+/// unwind_context::set_global_frame_sink(None);
+/// ```
+///
+/// [`FrameCapture`]: crate::FrameCapture
+#[inline]
+pub fn set_global_frame_sink(sink: Option<FrameSink>) {
+    *FRAME_SINK.write().unwrap_or_else(PoisonError::into_inner) = sink;
+}
+
+/// Returns the frame sink receiving every unwind context frame printed by
+/// context scope guards of all threads, if set.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(unwind_context::get_global_frame_sink().is_none());
+/// ```
+#[inline]
+#[must_use]
+pub fn get_global_frame_sink() -> Option<FrameSink> {
+    *FRAME_SINK.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Passes the frame to the global frame sink, if set.
+///
+/// Returns `false` if the frame should not be written to the context scope
+/// guard writer.
+pub(crate) fn send_to_frame_sink<T: Debug + DebugAnsiColored>(frame: &FrameInfo<'_, T>) -> bool {
+    match get_global_frame_sink() {
+        Some(sink) => {
+            sink.send(&ContextFrame::new(frame));
+            sink.should_write_output()
+        }
+        None => true,
+    }
+}
@@ -1,6 +1,6 @@
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 
-use crate::{AnsiColorScheme, AnsiColored, DebugAnsiColored, UnwindContextArgs};
+use crate::{AnsiColorScheme, AnsiColored, ContextVisitor, DebugAnsiColored, UnwindContextArgs};
 
 /// A structure representing function name and its argument names and values.
 ///
@@ -40,8 +40,22 @@ impl<Args> UnwindContextFunc<Args> {
     ///     ),
     /// );
     /// ```
+    ///
+    /// It can also be used to build static context data, like global labels,
+    /// in `const` and `static` items.
+    ///
+    /// ```rust
+    /// use unwind_context::{UnwindContextArg, UnwindContextFunc};
+    ///
+    /// static STARTUP: UnwindContextFunc<(UnwindContextArg<&str>, ())> = UnwindContextFunc::new(
+    ///     "startup",
+    ///     (UnwindContextArg::new(Some("stage"), "init"), ()),
+    /// );
+    ///
+    /// assert_eq!(format!("{STARTUP:?}"), "fn startup(stage: \"init\")");
+    /// ```
     #[inline]
-    pub fn new(name: &'static str, args: Args) -> Self {
+    pub const fn new(name: &'static str, args: Args) -> Self {
         Self { name, args }
     }
 }
@@ -86,6 +100,12 @@ where
         )?;
         Ok(())
     }
+
+    #[inline]
+    fn visit_context(&self, visitor: &mut dyn ContextVisitor) {
+        visitor.visit_func_name(self.name);
+        DebugAnsiColored::visit_context(&UnwindContextArgs::new(&self.args), visitor);
+    }
 }
 
 #[cfg(test)]
@@ -94,7 +114,7 @@ mod tests {
 
     use crate::test_common::{arg, TEST_COLOR_SCHEME};
     use crate::test_util::debug_fmt;
-    use crate::{AnsiColored, UnwindContextFunc};
+    use crate::{AnsiColored, UnwindContextArg, UnwindContextFunc};
 
     #[test]
     fn test_func_fmt() {
@@ -120,6 +140,17 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_func_static_fmt() {
+        static FUNC: AnsiColored<UnwindContextFunc<(UnwindContextArg<i32>, ())>> = AnsiColored::new(
+            UnwindContextFunc::new("foo", (UnwindContextArg::new(Some("bar"), 1), ())),
+            &TEST_COLOR_SCHEME,
+        );
+
+        let mut buffer = [0; 64];
+        assert_eq!(debug_fmt(&mut buffer, &FUNC.value), Ok("fn foo(bar: 1)"));
+    }
+
     #[test]
     fn test_func_colored_fmt() {
         let mut buffer = [0; 128];
@@ -136,9 +167,9 @@ mod tests {
                 "{FN}fn ",
                 "{FN_NAME}foo",
                 "{FN_BRACE}(",
-                "{DEF}bar: ",
+                "{DEF}{ARG}bar{DEF}: ",
                 "{NUM}1",
-                "{DEF}, baz: ",
+                "{DEF}, {ARG}baz{DEF}: ",
                 "{NUM}2",
                 "{DEF}",
                 "{FN_BRACE}",
@@ -0,0 +1,300 @@
+use core::fmt::Debug;
+use core::future::Future;
+use core::panic::Location;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::boxed::Box;
+
+use crate::{DebugAnsiColored, DefaultWriter, StdPanicDetector, UnwindContextWithIo};
+
+/// A future wrapper that prints a task-level unwind context frame if the
+/// wrapped future panics.
+///
+/// Context scope guards created inside a future only identify the functions
+/// that were running when the panic occurred, but not which spawned task they
+/// belong to. This wrapper creates a context scope guard with the given
+/// context data every time the future is polled, so a panic in the future
+/// prints the task context after the frames of the future itself. The frame
+/// location is the place where the wrapper was created, usually the task
+/// spawn site.
+///
+/// It uses [`DefaultWriter`] writer, [`StdPanicDetector`] panic detector, and
+/// a color scheme determined by the [`get_default_color_scheme_if_enabled`]
+/// function, like the [`unwind_context`] macro.
+///
+/// It is usually created with the [`spawn_with_unwind_context_on`] function.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, UnwindContextFuture};
+///
+/// async fn process(request_id: u64) {
+///     // ...
+/// }
+///
+/// let request_id = 123;
+/// let future = UnwindContextFuture::new(
+///     build_unwind_context_data!(fn task(request_id)),
+///     process(request_id),
+/// );
+/// ```
+///
+/// [`unwind_context`]: crate::unwind_context
+/// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
+pub struct UnwindContextFuture<F, T> {
+    future: Pin<Box<F>>,
+    data: T,
+    location: &'static Location<'static>,
+}
+
+// The context data is never pinned, and the future is pinned in the box.
+impl<F, T> Unpin for UnwindContextFuture<F, T> {}
+
+impl<F, T> UnwindContextFuture<F, T> {
+    /// Creates a new `UnwindContextFuture` wrapping the given future with the
+    /// given context data.
+    ///
+    /// The caller location is used as the frame location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, UnwindContextFuture};
+    ///
+    /// let worker_id = 1;
+    /// let future = UnwindContextFuture::new(
+    ///     build_unwind_context_data!(fn worker(worker_id)),
+    ///     async move { worker_id * 2 },
+    /// );
+    /// assert_eq!(format!("{:?}", future.data()), "fn worker(worker_id: 1)");
+    /// ```
+    #[inline]
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    #[track_caller]
+    pub fn new(data: T, future: F) -> Self {
+        Self {
+            future: Box::pin(future),
+            data,
+            location: Location::caller(),
+        }
+    }
+
+    /// Returns the context data of the task.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, UnwindContextFuture};
+    ///
+    /// let future = UnwindContextFuture::new(build_unwind_context_data!(fn task()), async {});
+    /// assert_eq!(format!("{:?}", future.data()), "fn task()");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn data(&self) -> &T {
+        &self.data
+    }
+
+    /// Returns the code location where the wrapper was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, UnwindContextFuture};
+    ///
+    /// let future = UnwindContextFuture::new(build_unwind_context_data!(fn task()), async {});
+    /// assert_eq!(future.location().line(), line!() - 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+
+impl<F, T> Future for UnwindContextFuture<F, T>
+where
+    F: Future,
+    T: Debug + DebugAnsiColored,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let _ctx = UnwindContextWithIo::new_default(&this.data, DefaultWriter, StdPanicDetector)
+            .with_location(this.location);
+        this.future.as_mut().poll(cx)
+    }
+}
+
+impl<F, T: Debug> Debug for UnwindContextFuture<F, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UnwindContextFuture")
+            .field("data", &self.data)
+            .field("location", &self.location)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Spawns a future with a given spawn function, wrapping it into an
+/// [`UnwindContextFuture`] with the given task context data.
+///
+/// It is runtime-agnostic: the spawn function can be any function accepting a
+/// future, like `tokio::spawn`, `async_std::task::spawn`, or
+/// `smol::spawn`. Its result is returned as is. The caller location is used as
+/// the task frame location.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, spawn_with_unwind_context_on};
+///
+/// fn spawn<F: core::future::Future>(future: F) -> F {
+///     // Pass the future to an executor.
+///     future
+/// }
+///
+/// let connection_id = 42;
+/// let task = spawn_with_unwind_context_on(
+///     spawn,
+///     build_unwind_context_data!(fn connection(connection_id)),
+///     async move {
+///         // ...
+///     },
+/// );
+/// ```
+#[inline]
+#[track_caller]
+pub fn spawn_with_unwind_context_on<S, R, F, T>(spawn: S, data: T, future: F) -> R
+where
+    S: FnOnce(UnwindContextFuture<F, T>) -> R,
+    F: Future,
+    T: Debug + DebugAnsiColored,
+{
+    spawn(UnwindContextFuture::new(data, future))
+}
+
+/// Spawns a future on the current Tokio runtime with [`tokio::spawn`],
+/// wrapping it into an [`UnwindContextFuture`] with the given task context
+/// data.
+///
+/// The caller location is used as the task frame location.
+///
+/// # Panics
+///
+/// Panics if called outside of a Tokio runtime, like [`tokio::spawn`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, spawn_with_unwind_context};
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let output = runtime.block_on(async {
+///     let job_id = 7;
+///     let handle = spawn_with_unwind_context(
+///         build_unwind_context_data!(fn job(job_id)),
+///         async move { job_id * 2 },
+///     );
+///     handle.await.unwrap()
+/// });
+/// assert_eq!(output, 14);
+/// ```
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+#[inline]
+#[track_caller]
+pub fn spawn_with_unwind_context<F, T>(data: T, future: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+    T: Debug + DebugAnsiColored + Send + 'static,
+{
+    tokio::spawn(UnwindContextFuture::new(data, future))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::future::Future;
+    use core::panic::AssertUnwindSafe;
+    use core::pin::Pin;
+    use core::task::{Context, Poll, Waker};
+    use std::panic::catch_unwind;
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    use crate::{build_unwind_context_data, spawn_with_unwind_context_on, UnwindContextFuture};
+
+    struct NoopWake;
+
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    fn poll_once<F: Future + Unpin>(future: &mut F) -> Poll<F::Output> {
+        let waker = Waker::from(Arc::new(NoopWake));
+        Pin::new(future).poll(&mut Context::from_waker(&waker))
+    }
+
+    #[test]
+    fn test_unwind_context_future() {
+        let task_id = 1;
+        let data = build_unwind_context_data!(fn task(task_id));
+        let mut future = UnwindContextFuture::new(data, async move { task_id * 2 });
+        assert_eq!(future.location().line(), line!() - 1);
+
+        let capture = crate::capture();
+        assert_eq!(poll_once(&mut future), Poll::Ready(2));
+        assert!(capture.take_frames().is_empty());
+    }
+
+    #[test]
+    fn test_unwind_context_future_panic() {
+        let task_id = 2;
+        let mut future = spawn_with_unwind_context_on(
+            |future| future,
+            build_unwind_context_data!(fn task(task_id)),
+            async move {
+                let _ctx = crate::unwind_context!(fn inner(task_id));
+                assert_ne!(task_id, 2, "task panic");
+            },
+        );
+        let location = future.location();
+
+        let capture = crate::capture();
+        let result = catch_unwind(AssertUnwindSafe(|| poll_once(&mut future)));
+        assert!(result.is_err());
+
+        let frames = capture.take_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].func_name, Some("inner"));
+        assert_eq!(frames[1].func_name, Some("task"));
+        assert_eq!(frames[1].arg("task_id"), Some("2"));
+        assert_eq!(frames[1].location, location);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn test_spawn_with_unwind_context() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+        let capture = crate::capture();
+        let result = runtime.block_on(async {
+            let job_id = 3;
+            crate::spawn_with_unwind_context(
+                build_unwind_context_data!(fn job(job_id)),
+                async move {
+                    assert_ne!(job_id, 3, "job panic");
+                },
+            )
+            .await
+        });
+        assert!(result.unwrap_err().is_panic());
+
+        let frames = capture.take_frames();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].func_name, Some("job"));
+    }
+}
@@ -0,0 +1,241 @@
+#[cfg(feature = "std")]
+use core::num::NonZeroUsize;
+#[cfg(feature = "std")]
+use core::sync::atomic::AtomicBool;
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+#[cfg(feature = "std")]
+use crate::once_flag::OnceFlag;
+use crate::{get_default_color_scheme_if_enabled, AnsiColorScheme, FrameOptions};
+#[cfg(feature = "std")]
+use crate::{is_print_on_drop_enabled, is_verbose_enabled, RateLimit};
+
+/// Options of a context scope guard used when its frame is printed.
+///
+/// It is implemented for [`DefaultGuardOptions`], which stores nothing in the
+/// guard and resolves everything from the global settings, and for
+/// [`CustomGuardOptions`], which stores the options given to the guard.
+pub trait GuardOptions: Copy {
+    /// Returns the options used to print a frame of the guard.
+    fn frame_options(self) -> FrameOptions;
+
+    /// Returns `true` if the frame should be printed when the guard is
+    /// created.
+    #[inline]
+    fn is_verbose(self) -> bool {
+        self.frame_options().is_verbose()
+    }
+
+    /// Returns `true` if the frame should be printed on every guard drop.
+    #[inline]
+    fn should_print_on_drop(self) -> bool {
+        self.frame_options().should_print_on_drop()
+    }
+
+    /// Returns the time from which the time spent in the guard scope is
+    /// measured, given the guard entry time recorded by the current thread
+    /// because of the global [`set_elapsed_time_enabled`] setting.
+    ///
+    /// [`set_elapsed_time_enabled`]: crate::set_elapsed_time_enabled
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn entered_at(self, scope_entered_at: Option<Instant>) -> Option<Instant>;
+
+    /// Returns the rate limit of printed frames of the guard location, if it
+    /// overrides the global setting.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn rate_limit(self) -> Option<&'static RateLimit>;
+
+    /// Returns the sample rate of printed frames of the guard location, if it
+    /// overrides the global setting.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn sample_rate(self) -> Option<NonZeroUsize>;
+
+    /// Returns the flag marking whether a frame of the guard call site has
+    /// already been printed, if the frame is printed at most once.
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    fn printed_once(self) -> Option<&'static AtomicBool>;
+}
+
+/// Context scope guard options that are not stored in the guard and are
+/// resolved from the global settings when its frame is printed.
+///
+/// It is used by macros like [`unwind_context`] and [`unwind_context_with_io`]
+/// when no per-guard options are given, so that the guard stores only the
+/// context data and the code location.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{DefaultGuardOptions, GuardOptions};
+///
+/// let options = DefaultGuardOptions.frame_options();
+/// assert_eq!(options.color_scheme(), None);
+/// ```
+///
+/// [`unwind_context`]: crate::unwind_context
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct DefaultGuardOptions;
+
+impl GuardOptions for DefaultGuardOptions {
+    #[inline]
+    fn frame_options(self) -> FrameOptions {
+        FrameOptions::new().with_color_scheme(get_default_color_scheme_if_enabled())
+    }
+
+    #[inline]
+    fn is_verbose(self) -> bool {
+        #[cfg(feature = "std")]
+        {
+            is_verbose_enabled()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            false
+        }
+    }
+
+    #[inline]
+    fn should_print_on_drop(self) -> bool {
+        #[cfg(feature = "std")]
+        {
+            is_print_on_drop_enabled()
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            false
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn entered_at(self, scope_entered_at: Option<Instant>) -> Option<Instant> {
+        scope_entered_at
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn rate_limit(self) -> Option<&'static RateLimit> {
+        None
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn sample_rate(self) -> Option<NonZeroUsize> {
+        None
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn printed_once(self) -> Option<&'static AtomicBool> {
+        None
+    }
+}
+
+/// Context scope guard options given to a specific guard, like its color
+/// scheme, layout, or rate limit.
+///
+/// It is used by macros like [`unwind_context_with_io`] and
+/// [`unwind_context_with_fmt`] when any per-guard option is given. Options
+/// that are not given are resolved from the global settings when the frame is
+/// printed.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{CustomGuardOptions, GuardOptions};
+///
+/// let options = CustomGuardOptions::new(None).frame_options();
+/// assert_eq!(options.color_scheme(), None);
+/// ```
+///
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+/// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct CustomGuardOptions {
+    pub(crate) frame: FrameOptions,
+    #[cfg(feature = "std")]
+    pub(crate) elapsed_time: Option<bool>,
+    #[cfg(feature = "std")]
+    pub(crate) entered_at: Option<Instant>,
+    #[cfg(feature = "std")]
+    pub(crate) rate_limit: Option<&'static RateLimit>,
+    #[cfg(feature = "std")]
+    pub(crate) sample_rate: Option<NonZeroUsize>,
+    #[cfg(feature = "std")]
+    pub(crate) printed_once: Option<OnceFlag>,
+}
+
+impl CustomGuardOptions {
+    /// Creates a new `CustomGuardOptions` with the given color scheme and all
+    /// other options determined by the global settings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{CustomGuardOptions, GuardOptions};
+    ///
+    /// let options = CustomGuardOptions::new(Some(&unwind_context::DEFAULT_DEFAULT_COLOR_SCHEME));
+    /// assert_eq!(
+    ///     options.frame_options().color_scheme(),
+    ///     Some(&unwind_context::DEFAULT_DEFAULT_COLOR_SCHEME)
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(color_scheme: Option<&'static AnsiColorScheme>) -> Self {
+        Self {
+            frame: FrameOptions::new().with_color_scheme(color_scheme),
+            #[cfg(feature = "std")]
+            elapsed_time: None,
+            #[cfg(feature = "std")]
+            entered_at: None,
+            #[cfg(feature = "std")]
+            rate_limit: None,
+            #[cfg(feature = "std")]
+            sample_rate: None,
+            #[cfg(feature = "std")]
+            printed_once: None,
+        }
+    }
+}
+
+impl GuardOptions for CustomGuardOptions {
+    #[inline]
+    fn frame_options(self) -> FrameOptions {
+        self.frame
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn entered_at(self, scope_entered_at: Option<Instant>) -> Option<Instant> {
+        match self.elapsed_time {
+            Some(true) => self.entered_at,
+            Some(false) => None,
+            None => scope_entered_at,
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn rate_limit(self) -> Option<&'static RateLimit> {
+        self.rate_limit
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn sample_rate(self) -> Option<NonZeroUsize> {
+        self.sample_rate
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn printed_once(self) -> Option<&'static AtomicBool> {
+        self.printed_once.map(|flag| flag.0)
+    }
+}
@@ -0,0 +1,231 @@
+use core::fmt::{Debug, Display, Formatter, Result as FmtResult, Write as FmtWrite};
+
+/// The default maximum number of bytes printed by [`WithHexDump`].
+pub const DEFAULT_HEX_DUMP_MAX_LEN: usize = 64;
+
+/// The number of bytes in a single hex dump group.
+const GROUP_LEN: usize = 8;
+
+/// An utility wrapper type which is used to forward both [`core::fmt::Debug`]
+/// and [`core::fmt::Display`] value implementations to a bounded hex dump of
+/// a byte buffer, like `[20 bytes: 00 01 02 03 04 05 06 07  08 09 ...]`.
+///
+/// Bytes are printed as lowercase hex pairs grouped by eight. At most
+/// [`DEFAULT_HEX_DUMP_MAX_LEN`] bytes are printed by default, the limit can be
+/// changed with [`WithHexDump::with_max_len`]. If the buffer is longer, the
+/// dump is followed by `...`. An ASCII column with non-printable bytes
+/// replaced by `.` can be enabled with [`WithHexDump::with_ascii`].
+///
+/// It is useful for protocol and parser debugging, where the
+/// [`core::fmt::Debug`] representation of a byte slice as a list of decimal
+/// numbers is hard to read.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context, WithHexDump};
+///
+/// fn parse_packet(packet: &[u8]) {
+///     let _ctx = unwind_context!(fn(WithHexDump::new(packet).with_ascii(true)));
+///     // ...
+/// }
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct WithHexDump<T> {
+    value: T,
+    max_len: usize,
+    ascii: bool,
+}
+
+impl<T> WithHexDump<T>
+where
+    T: AsRef<[u8]>,
+{
+    /// Creates a new `WithHexDump` printing at most
+    /// [`DEFAULT_HEX_DUMP_MAX_LEN`] bytes without an ASCII column.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::WithHexDump;
+    ///
+    /// let dump = WithHexDump::new(b"\xde\xad\xbe\xef");
+    /// assert_eq!(format!("{dump:?}"), "[4 bytes: de ad be ef]");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(value: T) -> Self {
+        Self {
+            value,
+            max_len: DEFAULT_HEX_DUMP_MAX_LEN,
+            ascii: false,
+        }
+    }
+
+    /// Sets the maximum number of bytes printed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::WithHexDump;
+    ///
+    /// let dump = WithHexDump::new(b"abcdef").with_max_len(4);
+    /// assert_eq!(format!("{dump:?}"), "[6 bytes: 61 62 63 64 ...]");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Enables or disables the ASCII column printed after the hex bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::WithHexDump;
+    ///
+    /// let dump = WithHexDump::new(b"ok\n").with_ascii(true);
+    /// assert_eq!(format!("{dump:?}"), "[3 bytes: 6f 6b 0a |ok.|]");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_ascii(mut self, enabled: bool) -> Self {
+        self.ascii = enabled;
+        self
+    }
+
+    /// Returns a reference to the wrapped value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::WithHexDump;
+    ///
+    /// let dump = WithHexDump::new([1, 2, 3]);
+    /// assert_eq!(dump.value(), &[1, 2, 3]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn value(&self) -> &T {
+        &self.value
+    }
+
+    fn fmt_dump(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let bytes = self.value.as_ref();
+        let shown = bytes.get(..self.max_len).unwrap_or(bytes);
+
+        write!(f, "[{} bytes:", bytes.len())?;
+        for (index, byte) in shown.iter().enumerate() {
+            if index != 0 && index % GROUP_LEN == 0 {
+                f.write_char(' ')?;
+            }
+            write!(f, " {byte:02x}")?;
+        }
+        if shown.len() < bytes.len() {
+            f.write_str(" ...")?;
+        }
+        if self.ascii {
+            f.write_str(" |")?;
+            for &byte in shown {
+                if byte.is_ascii_graphic() || byte == b' ' {
+                    f.write_char(char::from(byte))?;
+                } else {
+                    f.write_char('.')?;
+                }
+            }
+            f.write_char('|')?;
+        }
+        f.write_char(']')
+    }
+}
+
+impl<T> Display for WithHexDump<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.fmt_dump(f)
+    }
+}
+
+impl<T> Debug for WithHexDump<T>
+where
+    T: AsRef<[u8]>,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.fmt_dump(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::buf_fmt;
+    use crate::{build_unwind_context_data, WithHexDump};
+
+    #[test]
+    fn test_hex_dump() {
+        let mut buffer = [0; 256];
+        let empty: &[u8] = &[];
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{:?}", WithHexDump::new(empty))),
+            Ok("[0 bytes:]")
+        );
+
+        let bytes: [u8; 20] = core::array::from_fn(|index| u8::try_from(index).unwrap());
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{}", WithHexDump::new(&bytes))),
+            Ok(concat!(
+                "[20 bytes: 00 01 02 03 04 05 06 07  08 09 0a 0b 0c 0d 0e 0f  ",
+                "10 11 12 13]"
+            ))
+        );
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{:?}", WithHexDump::new(&bytes).with_max_len(10))
+            ),
+            Ok("[20 bytes: 00 01 02 03 04 05 06 07  08 09 ...]")
+        );
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{:?}", WithHexDump::new(&bytes).with_max_len(0))
+            ),
+            Ok("[20 bytes: ...]")
+        );
+
+        let packet: &[u8] = b"GET /\r\n\xff";
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{:?}", WithHexDump::new(packet).with_ascii(true))
+            ),
+            Ok("[8 bytes: 47 45 54 20 2f 0d 0a ff |GET /...|]")
+        );
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!(
+                    "{:?}",
+                    WithHexDump::new(packet).with_ascii(true).with_max_len(3)
+                )
+            ),
+            Ok("[8 bytes: 47 45 54 ... |GET|]")
+        );
+    }
+
+    #[test]
+    fn test_hex_dump_context() {
+        let mut buffer = [0; 128];
+        let packet: &[u8] = &[1, 2, 255];
+        let context = build_unwind_context_data!(fn parse(WithHexDump::new(packet)));
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{context:?}")),
+            Ok("fn parse(WithHexDump::new(packet): [3 bytes: 01 02 ff])")
+        );
+    }
+}
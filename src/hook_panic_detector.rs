@@ -0,0 +1,369 @@
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+
+use crate::compat::update_panic_hook;
+use crate::PanicDetector;
+
+static IS_HOOK_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+std::thread_local! {
+    static IS_PANICKING: Cell<bool> = const { Cell::new(false) };
+    static PANIC_GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// A [`PanicDetector`] backed by a thread-local flag set inside a panic hook
+/// installed by the [`install_hook`] function.
+///
+/// It is intended for environments where `std::thread::panicking()` is
+/// unavailable or unreliable, like custom runtimes and foreign panic handlers
+/// that do not unwind through the standard library machinery.
+///
+/// The flag is set for the current thread when the panic hook is called and
+/// is cleared when the outermost context scope guard of the thread is dropped
+/// while it is set. A panic caught before it reaches any context scope guard
+/// leaves the flag set, so it should be cleared explicitly with
+/// [`HookPanicDetector::reset`], for example after a runtime catches a panic.
+///
+/// Note that with `panic = "abort"` the process is aborted right after the
+/// panic hook returns and context scope guards are never dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, HookPanicDetector};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!((fn(foo, bar)), panic_detector = HookPanicDetector);
+///     // ...
+/// }
+///
+/// unwind_context::install_hook();
+/// func(123, "abc");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct HookPanicDetector;
+
+impl HookPanicDetector {
+    /// Clears the panic flag of the current thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{HookPanicDetector, PanicDetector};
+    ///
+    /// HookPanicDetector::reset();
+    /// assert!(!HookPanicDetector.is_panicking());
+    /// ```
+    #[inline]
+    pub fn reset() {
+        let _ = IS_PANICKING.try_with(|flag| flag.set(false));
+    }
+}
+
+impl PanicDetector for HookPanicDetector {
+    #[inline]
+    fn is_panicking(&self) -> bool {
+        IS_PANICKING.try_with(Cell::get).unwrap_or(false)
+    }
+}
+
+/// A [`PanicDetector`] that detects only panics that started after it was
+/// created and propagate past the context scope guard.
+///
+/// The [`StdPanicDetector`] reports a panic whenever the current thread is
+/// unwinding. So a context scope guard that is created and dropped normally
+/// while the thread is already unwinding, for example inside a destructor
+/// called during unwinding, prints its context for a panic it has nothing to
+/// do with. This detector records the per-thread panic generation counter
+/// maintained by the panic hook installed with [`install_hook`] when it is
+/// created, and reports a panic only if another panic has started since then.
+///
+/// If the panic hook is not installed, it falls back to the
+/// [`StdPanicDetector`] behavior.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, ScopedPanicDetector};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx =
+///         unwind_context_with_io!((fn(foo, bar)), panic_detector = ScopedPanicDetector::new());
+///     // ...
+/// }
+///
+/// unwind_context::install_hook();
+/// func(123, "abc");
+/// ```
+///
+/// [`StdPanicDetector`]: crate::StdPanicDetector
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct ScopedPanicDetector {
+    generation: Option<u64>,
+}
+
+impl ScopedPanicDetector {
+    /// Creates a new `ScopedPanicDetector` recording the current panic
+    /// generation of the current thread.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{PanicDetector, ScopedPanicDetector};
+    ///
+    /// assert!(!ScopedPanicDetector::new().is_panicking());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            generation: panic_generation(),
+        }
+    }
+}
+
+impl Default for ScopedPanicDetector {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PanicDetector for ScopedPanicDetector {
+    #[inline]
+    fn is_panicking(&self) -> bool {
+        std::thread::panicking()
+            && match self.generation {
+                Some(generation) => panic_generation() != Some(generation),
+                None => true,
+            }
+    }
+}
+
+/// Returns the panic generation of the current thread if the panic hook is
+/// installed.
+pub(crate) fn panic_generation() -> Option<u64> {
+    if IS_HOOK_INSTALLED.load(AtomicOrdering::Relaxed) {
+        PANIC_GENERATION.try_with(Cell::get).ok()
+    } else {
+        None
+    }
+}
+
+/// Installs a panic hook that sets the panic flag of the current thread used
+/// by the [`HookPanicDetector`] and counts the panics of the current thread
+/// used by the [`ScopedPanicDetector`].
+///
+/// The previously set panic hook is preserved and called after the flag is
+/// set. Calling this function more than once has no effect.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{HookPanicDetector, PanicDetector};
+///
+/// unwind_context::install_hook();
+///
+/// let result = std::panic::catch_unwind(|| {
+///     panic!("Error");
+/// });
+/// assert!(result.is_err());
+/// assert!(HookPanicDetector.is_panicking());
+///
+/// HookPanicDetector::reset();
+/// assert!(!HookPanicDetector.is_panicking());
+/// ```
+pub fn install_hook() {
+    if IS_HOOK_INSTALLED.swap(true, AtomicOrdering::Relaxed) {
+        return;
+    }
+    update_panic_hook(|prev, info| {
+        let _ = IS_PANICKING.try_with(|flag| flag.set(true));
+        let _ = PANIC_GENERATION
+            .try_with(|generation| generation.set(generation.get().wrapping_add(1)));
+        prev(info);
+    });
+}
+
+/// Clears the panic flag of the current thread at the end of an unwind.
+#[inline]
+pub(crate) fn end_hook_panic() {
+    if IS_HOOK_INSTALLED.load(AtomicOrdering::Relaxed) {
+        HookPanicDetector::reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::RefCell;
+    use core::fmt::{Result as FmtResult, Write as FmtWrite};
+    use core::panic::AssertUnwindSafe;
+    use std::panic::catch_unwind;
+    use std::string::String;
+
+    use crate::{
+        install_hook, unwind_context_with_fmt, HookPanicDetector, PanicDetector,
+        ScopedPanicDetector, StdPanicDetector,
+    };
+
+    #[derive(Clone, Copy, Debug)]
+    struct SharedWriter<'a>(&'a RefCell<String>);
+
+    impl FmtWrite for SharedWriter<'_> {
+        fn write_str(&mut self, s: &str) -> FmtResult {
+            self.0.borrow_mut().push_str(s);
+            Ok(())
+        }
+    }
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_hook_panic_detector() {
+        let result = std::thread::spawn(|| {
+            install_hook();
+            assert!(!HookPanicDetector.is_panicking());
+
+            let output = RefCell::new(String::new());
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                let value = 123;
+                let _ctx = unwind_context_with_fmt!(
+                    (fn outer(value)),
+                    writer = SharedWriter(&output),
+                    panic_detector = HookPanicDetector,
+                    color_scheme = None,
+                );
+                let _ctx = unwind_context_with_fmt!(
+                    (fn inner(value)),
+                    writer = SharedWriter(&output),
+                    panic_detector = HookPanicDetector,
+                    color_scheme = None,
+                );
+                panic!("Error");
+            }));
+            assert!(result.is_err());
+            let output = output.into_inner();
+            assert!(output.starts_with("fn inner(value: 123)\n    at "));
+            assert!(output.contains("\nfn outer(value: 123)\n    at "));
+
+            // The flag is cleared by the outermost context scope guard.
+            assert!(!HookPanicDetector.is_panicking());
+
+            // A guard dropped normally after a caught and reset panic prints
+            // nothing.
+            let output = RefCell::new(String::new());
+            let value = 123;
+            let ctx = unwind_context_with_fmt!(
+                (fn outer(value)),
+                writer = SharedWriter(&output),
+                panic_detector = HookPanicDetector,
+                color_scheme = None,
+            );
+            let result = catch_unwind(|| panic!("Error"));
+            assert!(result.is_err());
+            assert!(HookPanicDetector.is_panicking());
+            HookPanicDetector::reset();
+            drop(ctx);
+            assert_eq!(output.into_inner(), "");
+            assert!(!HookPanicDetector.is_panicking());
+        })
+        .join();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_hook_panic_detector_without_std_panicking() {
+        let result = std::thread::spawn(|| {
+            install_hook();
+
+            let output = RefCell::new(String::new());
+            let value = 123;
+            let outer = unwind_context_with_fmt!(
+                (fn outer(value)),
+                writer = SharedWriter(&output),
+                panic_detector = HookPanicDetector,
+                color_scheme = None,
+            );
+            let inner = unwind_context_with_fmt!(
+                (fn inner(value)),
+                writer = SharedWriter(&output),
+                panic_detector = HookPanicDetector,
+                color_scheme = None,
+            );
+
+            // Emulates a foreign panic handler that calls the panic hook but
+            // does not unwind through the standard library machinery.
+            super::IS_PANICKING.with(|flag| flag.set(true));
+            assert!(!std::thread::panicking());
+
+            drop(inner);
+            assert!(HookPanicDetector.is_panicking());
+            drop(outer);
+            let output = output.into_inner();
+            assert!(output.starts_with("fn inner(value: 123)\n    at "));
+            assert!(output.contains("\nfn outer(value: 123)\n    at "));
+
+            // The flag is cleared by the outermost context scope guard.
+            assert!(!HookPanicDetector.is_panicking());
+        })
+        .join();
+        assert!(result.is_ok());
+    }
+
+    #[derive(Debug)]
+    struct DropWithContext<'a> {
+        std_output: &'a RefCell<String>,
+        scoped_output: &'a RefCell<String>,
+    }
+
+    impl Drop for DropWithContext<'_> {
+        fn drop(&mut self) {
+            let value = 123;
+            let _ctx = unwind_context_with_fmt!(
+                (fn std_ctx(value)),
+                writer = SharedWriter(self.std_output),
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            let _ctx = unwind_context_with_fmt!(
+                (fn scoped_ctx(value)),
+                writer = SharedWriter(self.scoped_output),
+                panic_detector = ScopedPanicDetector::new(),
+                color_scheme = None,
+            );
+        }
+    }
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_scoped_panic_detector() {
+        let result = std::thread::spawn(|| {
+            install_hook();
+
+            let std_output = RefCell::new(String::new());
+            let scoped_output = RefCell::new(String::new());
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                let _ctx = unwind_context_with_fmt!(
+                    (fn outer()),
+                    writer = SharedWriter(&scoped_output),
+                    panic_detector = ScopedPanicDetector::new(),
+                    color_scheme = None,
+                );
+                let _drop = DropWithContext {
+                    std_output: &std_output,
+                    scoped_output: &scoped_output,
+                };
+                panic!("Error");
+            }));
+            assert!(result.is_err());
+            assert!(std_output
+                .into_inner()
+                .starts_with("fn std_ctx(value: 123)\n    at "));
+            // Guards created during unwinding are not triggered by the panic.
+            let scoped_output = scoped_output.into_inner();
+            assert!(scoped_output.starts_with("fn outer()\n    at "));
+            assert!(!scoped_output.contains("scoped_ctx"));
+        })
+        .join();
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,333 @@
+use core::fmt::{Result as FmtResult, Write as FmtWrite};
+
+use crate::AnsiColorScheme;
+
+/// A color scheme marking colorized fragments for the [`HtmlWriter`].
+///
+/// Instead of ANSI escape sequences every field holds a marker with a CSS
+/// class name, which the [`HtmlWriter`] replaces with a
+/// `<span class="uc-...">` element. It allows any colorized output of this
+/// crate to be rendered as HTML, for example in documentation, web dashboards,
+/// or CI annotations. The [`HTML_STYLESHEET`] defines colors for all the
+/// classes.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::fmt::Write;
+///
+/// use unwind_context::{AnsiColored, HtmlWriter, UnwindContextArg, HTML_COLOR_SCHEME};
+///
+/// let mut writer = HtmlWriter::new(String::new());
+/// write!(
+///     writer,
+///     "{:?}",
+///     AnsiColored::new(UnwindContextArg::new(Some("foo"), 1), &HTML_COLOR_SCHEME)
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     writer.finish().unwrap(),
+///     concat!(
+///         r#"<span class="uc-arg-name">foo</span>: "#,
+///         r#"<span class="uc-number">1</span>"#
+///     )
+/// );
+/// ```
+pub static HTML_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+    default: "\u{1b}\u{7}",
+    location: "\u{1b}location\u{7}",
+    location_file: "\u{1b}location-file\u{7}",
+    location_line: "\u{1b}location-line\u{7}",
+    location_column: "\u{1b}location-column\u{7}",
+    fn_keyword: "\u{1b}fn-keyword\u{7}",
+    func_name: "\u{1b}func-name\u{7}",
+    func_braces: "\u{1b}func-braces\u{7}",
+    arg_name: "\u{1b}arg-name\u{7}",
+    punct: "\u{1b}\u{7}",
+    value_braces: "\u{1b}value-braces\u{7}",
+    ident: "\u{1b}ident\u{7}",
+    item: "\u{1b}item\u{7}",
+    boolean: "\u{1b}boolean\u{7}",
+    number: "\u{1b}number\u{7}",
+    quoted: "\u{1b}quoted\u{7}",
+    escaped: "\u{1b}escaped\u{7}",
+};
+
+/// A default CSS stylesheet for the classes used by the [`HtmlWriter`] with
+/// the [`HTML_COLOR_SCHEME`].
+///
+/// The colors match the [`DEFAULT_DEFAULT_COLOR_SCHEME`] rendered with the
+/// Tango terminal palette.
+///
+/// # Examples
+///
+/// ```rust
+/// let page = format!(
+///     "<style>{}</style><pre>{}</pre>",
+///     unwind_context::HTML_STYLESHEET,
+///     "...",
+/// );
+/// ```
+///
+/// [`DEFAULT_DEFAULT_COLOR_SCHEME`]: crate::DEFAULT_DEFAULT_COLOR_SCHEME
+pub const HTML_STYLESHEET: &str = "\
+.uc-location, .uc-location-file, .uc-location-line, .uc-location-column { color: #729fcf; }
+.uc-fn-keyword { color: #c4a000; }
+.uc-func-name { color: #fce94f; }
+.uc-ident, .uc-item { color: #c4a000; }
+.uc-boolean { color: #fce94f; font-weight: bold; }
+.uc-number { color: #34e2e2; }
+.uc-quoted { color: #4e9a06; }
+.uc-escaped { color: #ad7fa8; }
+";
+
+/// A writer adapter rendering output colorized with the
+/// [`HTML_COLOR_SCHEME`] as HTML.
+///
+/// Every color scheme marker is replaced with a `<span class="uc-...">`
+/// element closed before the next marker, and the `&`, `<`, `>`, `"`, and `'`
+/// characters of the text are escaped. Call [`HtmlWriter::finish`] to close
+/// the last element.
+///
+/// Note that ANSI escape sequences, like OSC 8 location hyperlinks, are not
+/// supported and should be disabled.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::fmt::Write;
+///
+/// use unwind_context::{HtmlWriter, HTML_COLOR_SCHEME};
+///
+/// let mut writer = HtmlWriter::new(String::new());
+/// write!(
+///     writer,
+///     "{}<b>{}",
+///     HTML_COLOR_SCHEME.number, HTML_COLOR_SCHEME.default
+/// )
+/// .unwrap();
+/// assert_eq!(
+///     writer.finish().unwrap(),
+///     r#"<span class="uc-number">&lt;b&gt;</span>"#
+/// );
+/// ```
+#[derive(Clone, Debug)]
+pub struct HtmlWriter<W> {
+    writer: W,
+    marker: HtmlMarkerState,
+    is_span_open: bool,
+}
+
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+enum HtmlMarkerState {
+    Text,
+    MarkerStart,
+    MarkerClass,
+}
+
+impl<W: FmtWrite> HtmlWriter<W> {
+    /// Creates a new `HtmlWriter` writing HTML to the given writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::HtmlWriter::new(String::new());
+    /// ```
+    #[inline]
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            marker: HtmlMarkerState::Text,
+            is_span_open: false,
+        }
+    }
+
+    /// Closes the last opened `<span>` element, if any, and returns the
+    /// underlying writer.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the underlying writer fails.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// use unwind_context::{HtmlWriter, HTML_COLOR_SCHEME};
+    ///
+    /// let mut writer = HtmlWriter::new(String::new());
+    /// write!(writer, "{}true", HTML_COLOR_SCHEME.boolean).unwrap();
+    /// assert_eq!(
+    ///     writer.finish().unwrap(),
+    ///     r#"<span class="uc-boolean">true</span>"#
+    /// );
+    /// ```
+    pub fn finish(mut self) -> Result<W, core::fmt::Error> {
+        self.close_span()?;
+        Ok(self.writer)
+    }
+
+    fn close_span(&mut self) -> FmtResult {
+        if self.is_span_open {
+            self.writer.write_str("</span>")?;
+            self.is_span_open = false;
+        }
+        Ok(())
+    }
+}
+
+impl<W: FmtWrite> FmtWrite for HtmlWriter<W> {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        for ch in s.chars() {
+            match (self.marker, ch) {
+                (HtmlMarkerState::Text, '\u{1b}') => {
+                    self.close_span()?;
+                    self.marker = HtmlMarkerState::MarkerStart;
+                }
+                (HtmlMarkerState::Text, ch) => match ch {
+                    '&' => self.writer.write_str("&amp;")?,
+                    '<' => self.writer.write_str("&lt;")?,
+                    '>' => self.writer.write_str("&gt;")?,
+                    '"' => self.writer.write_str("&quot;")?,
+                    '\'' => self.writer.write_str("&#39;")?,
+                    ch => self.writer.write_char(ch)?,
+                },
+                (HtmlMarkerState::MarkerStart, '\u{7}') => {
+                    self.marker = HtmlMarkerState::Text;
+                }
+                (HtmlMarkerState::MarkerClass, '\u{7}') => {
+                    self.writer.write_str("\">")?;
+                    self.is_span_open = true;
+                    self.marker = HtmlMarkerState::Text;
+                }
+                (HtmlMarkerState::MarkerStart | HtmlMarkerState::MarkerClass, ch) => {
+                    if self.marker == HtmlMarkerState::MarkerStart {
+                        self.writer.write_str("<span class=\"uc-")?;
+                        self.marker = HtmlMarkerState::MarkerClass;
+                    }
+                    if ch.is_ascii_alphanumeric() || ch == '-' {
+                        self.writer.write_char(ch)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders an unwind context frame as an HTML fragment.
+///
+/// It uses the same formatting as context scope guards use when a panic
+/// occurs, but colorizes the frame with `<span class="uc-...">` elements
+/// instead of ANSI escape sequences using the [`HTML_COLOR_SCHEME`] and the
+/// [`HtmlWriter`]. The color scheme and the location hyperlink template of the
+/// given options are ignored. The [`HTML_STYLESHEET`] can be used to style the
+/// fragment.
+///
+/// If the context data formatting fails, the frame output rendered so far is
+/// returned.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, FrameInfo, FrameOptions};
+///
+/// let foo = "<tag>";
+/// let data = build_unwind_context_data!(fn func(foo));
+/// let frame = FrameInfo::new(&data, core::panic::Location::caller());
+///
+/// let html = unwind_context::render_frame_html(&frame, &FrameOptions::new());
+/// assert!(html.starts_with(concat!(
+///     r#"<span class="uc-fn-keyword">fn </span>"#,
+///     r#"<span class="uc-func-name">func</span>"#,
+///     r#"<span class="uc-func-braces">(</span>"#,
+///     r#"<span class="uc-arg-name">foo</span>: "#,
+///     r#"<span class="uc-quoted">&quot;&lt;tag&gt;&quot;</span>"#,
+/// )));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[must_use]
+pub fn render_frame_html<T>(
+    frame: &crate::FrameInfo<'_, T>,
+    options: &crate::FrameOptions,
+) -> alloc::string::String
+where
+    T: core::fmt::Debug + crate::DebugAnsiColored,
+{
+    let options = options
+        .with_color_scheme(Some(&HTML_COLOR_SCHEME))
+        .with_location_hyperlink(None);
+    let mut writer = HtmlWriter::new(alloc::string::String::new());
+    let _ = crate::format_frame_into(&mut writer, frame, &options);
+    let _ = writer.close_span();
+    writer.writer
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::fmt::Write;
+    use core::panic::Location;
+    use std::string::String;
+
+    use crate::html::{render_frame_html, HtmlWriter, HTML_COLOR_SCHEME};
+    use crate::{build_unwind_context_data, FrameInfo, FrameOptions, LocationStyle};
+
+    #[test]
+    fn test_html_writer() {
+        let mut writer = HtmlWriter::new(String::new());
+        write!(
+            writer,
+            "a&b{}'c'{}{}\"d\"{}",
+            HTML_COLOR_SCHEME.quoted,
+            HTML_COLOR_SCHEME.location_line,
+            HTML_COLOR_SCHEME.location,
+            HTML_COLOR_SCHEME.default,
+        )
+        .unwrap();
+        assert_eq!(
+            writer.finish().unwrap(),
+            concat!(
+                "a&amp;b",
+                r#"<span class="uc-quoted">&#39;c&#39;</span>"#,
+                r#"<span class="uc-location-line"></span>"#,
+                r#"<span class="uc-location">&quot;d&quot;</span>"#,
+            )
+        );
+
+        // The last element is closed on finish and markers may be split.
+        let mut writer = HtmlWriter::new(String::new());
+        writer.write_str("\u{1b}num").unwrap();
+        writer.write_str("ber\u{7}1").unwrap();
+        assert_eq!(
+            writer.finish().unwrap(),
+            r#"<span class="uc-number">1</span>"#
+        );
+    }
+
+    #[test]
+    fn test_render_frame_html() {
+        let foo = 1;
+        let bar = "<a>";
+        let data = build_unwind_context_data!(fn func(foo, bar));
+        let frame = FrameInfo::new(&data, Location::caller());
+        let options = FrameOptions::new()
+            .with_location_style(LocationStyle::Hidden)
+            .with_location_hyperlink(Some("file://{file}"));
+        assert_eq!(
+            render_frame_html(&frame, &options),
+            concat!(
+                r#"<span class="uc-fn-keyword">fn </span>"#,
+                r#"<span class="uc-func-name">func</span>"#,
+                r#"<span class="uc-func-braces">(</span>"#,
+                r#"<span class="uc-arg-name">foo</span>: "#,
+                r#"<span class="uc-number">1</span>, "#,
+                r#"<span class="uc-arg-name">bar</span>: "#,
+                r#"<span class="uc-quoted">&quot;&lt;a&gt;&quot;</span>"#,
+                r#"<span class="uc-func-braces">)</span>"#,
+                "\n"
+            )
+        );
+    }
+}
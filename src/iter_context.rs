@@ -0,0 +1,193 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use core::panic::Location;
+
+use crate::{
+    DebugSnapshot, DefaultWriter, StdPanicDetector, UnwindContextArg, UnwindContextFunc,
+    UnwindContextWithIo,
+};
+
+/// The context data of an [`UnwindContextIter`] guard with the index and the
+/// formatted snapshot of the last yielded item.
+type IterContextData = UnwindContextFunc<(
+    UnwindContextArg<usize>,
+    (UnwindContextArg<DebugSnapshot>, ()),
+)>;
+
+/// An extension trait for iterators adding an unwind context with the
+/// current element.
+///
+/// The [`with_unwind_context`] adapter reports which element was being
+/// processed when a panic occurred, without a context macro in the loop body.
+///
+/// [`with_unwind_context`]: UnwindContextIterator::with_unwind_context
+pub trait UnwindContextIterator: Iterator + Sized {
+    /// Creates an iterator that prints the index and the value of the last
+    /// yielded element if a panic occurs while it is alive.
+    ///
+    /// The adapter keeps a single context scope guard, created when the first
+    /// element is yielded and updated on every [`Iterator::next`] call. When
+    /// the loop body panics, the adapter is dropped during unwinding, so its
+    /// frame is printed after the frames created inside the loop body. The
+    /// element is formatted eagerly into a [`DebugSnapshot`] before it is
+    /// yielded, so the adapter is best suited for elements with a short
+    /// [`core::fmt::Debug`] output.
+    ///
+    /// The guard uses [`DefaultWriter`] writer, [`StdPanicDetector`] panic
+    /// detector, and a color scheme determined by the
+    /// [`get_default_color_scheme_if_enabled`] function, like the
+    /// [`unwind_context`] macro. The caller location is used as the frame
+    /// location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::UnwindContextIterator;
+    ///
+    /// let mut total = 0;
+    /// for value in ["1", "2", "3"].into_iter().with_unwind_context() {
+    ///     total += value.parse::<u32>().unwrap();
+    /// }
+    /// assert_eq!(total, 6);
+    /// ```
+    ///
+    /// [`unwind_context`]: crate::unwind_context
+    /// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
+    #[inline]
+    #[must_use = "iterators are lazy and do nothing unless consumed"]
+    #[track_caller]
+    fn with_unwind_context(self) -> UnwindContextIter<Self>
+    where
+        Self::Item: Debug,
+    {
+        UnwindContextIter {
+            iter: self,
+            next_index: 0,
+            location: Location::caller(),
+            guard: None,
+        }
+    }
+}
+
+impl<I: Iterator> UnwindContextIterator for I {}
+
+/// An iterator adapter printing the index and the value of the last yielded
+/// element if a panic occurs while it is alive.
+///
+/// It is created with the [`UnwindContextIterator::with_unwind_context`]
+/// method.
+#[must_use = "iterators are lazy and do nothing unless consumed"]
+pub struct UnwindContextIter<I> {
+    iter: I,
+    next_index: usize,
+    location: &'static Location<'static>,
+    guard: Option<UnwindContextWithIo<DefaultWriter, IterContextData, StdPanicDetector>>,
+}
+
+impl<I> UnwindContextIter<I> {
+    /// Returns the code location where the adapter was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::UnwindContextIterator;
+    ///
+    /// let iter = [1, 2, 3].into_iter().with_unwind_context();
+    /// assert_eq!(iter.location().line(), line!() - 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+
+impl<I: Debug> Debug for UnwindContextIter<I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("UnwindContextIter")
+            .field("iter", &self.iter)
+            .field("next_index", &self.next_index)
+            .field("location", &self.location)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I> Iterator for UnwindContextIter<I>
+where
+    I: Iterator,
+    I::Item: Debug,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let data = UnwindContextFunc::new(
+            "iter",
+            (
+                UnwindContextArg::new(Some("index"), self.next_index),
+                (
+                    UnwindContextArg::new(Some("item"), DebugSnapshot::new(&item)),
+                    (),
+                ),
+            ),
+        );
+        if let Some(guard) = &mut self.guard {
+            *guard.data_mut() = data;
+        } else {
+            self.guard = Some(
+                UnwindContextWithIo::new_default(data, DefaultWriter, StdPanicDetector)
+                    .with_location(self.location),
+            );
+        }
+        self.next_index = self.next_index.saturating_add(1);
+        Some(item)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::panic::AssertUnwindSafe;
+    use std::panic::catch_unwind;
+    use std::vec::Vec;
+
+    use crate::UnwindContextIterator;
+
+    #[test]
+    fn test_with_unwind_context() {
+        let capture = crate::capture();
+        let values: Vec<u32> = [1, 2, 3]
+            .into_iter()
+            .with_unwind_context()
+            .map(|value| value * 2)
+            .collect();
+        assert_eq!(values, [2, 4, 6]);
+        assert!(capture.take_frames().is_empty());
+    }
+
+    #[test]
+    fn test_with_unwind_context_panic() {
+        let iter = ["a", "b", "c", "d"].into_iter().with_unwind_context();
+        let location = iter.location();
+
+        let capture = crate::capture();
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            for value in iter {
+                let _ctx = crate::unwind_context!(fn process(value));
+                assert_ne!(value, "c", "item panic");
+            }
+        }));
+        assert!(result.is_err());
+
+        let frames = capture.take_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].func_name, Some("process"));
+        assert_eq!(frames[1].func_name, Some("iter"));
+        assert_eq!(frames[1].arg("index"), Some("2"));
+        assert_eq!(frames[1].arg("item"), Some("\"c\""));
+        assert_eq!(frames[1].location, location);
+    }
+}
@@ -82,7 +82,9 @@
 //! whereas with `unwind_context!(fn(a, b, c))` it will also print function
 //! names as well. Note that it uses the [`core::fmt::Debug`] representation. If
 //! you want to use the [`core::fmt::Display`] representation, you can use the
-//! [`WithDisplay`] wrapper.
+//! [`WithDisplay`] wrapper. For values that do not implement
+//! [`core::fmt::Debug`] at all, you can use the [`WithTypeName`] wrapper to
+//! print only their type name.
 //!
 //! You can use the [`set_colors_enabled`] function to unconditionally enable
 //! the 16-ANSI-color colorization. If you want to enable colorization only if
@@ -173,16 +175,18 @@
 //!                 )
 //!             },
 //!             (
-//!                 unwind_context::UnwindContextArg::new(Some("a"), a),
+//!                 unwind_context::UnwindContextColoredArg::new(Some("a"), a),
 //!                 (
-//!                     unwind_context::UnwindContextArg::new(Some("&b"), &b),
+//!                     unwind_context::UnwindContextColoredArg::new(
+//!                         Some("&b"), &b
+//!                     ),
 //!                     (
 //!                         unwind_context::UnwindContextArg::new(
 //!                             None,
 //!                             unwind_context::NonExhaustiveMarker,
 //!                         ),
 //!                         (
-//!                             unwind_context::UnwindContextArg::new(
+//!                             unwind_context::UnwindContextColoredArg::new(
 //!                                 Some("d.clone()"), d.clone()
 //!                                ),
 //!                             (),
@@ -191,7 +195,7 @@
 //!                 ),
 //!             ),
 //!         ),
-//!         ::std::io::stderr(),
+//!         unwind_context::DefaultWriter,
 //!         unwind_context::StdPanicDetector,
 //!         unwind_context::get_default_color_scheme_if_enabled(),
 //!     );
@@ -199,10 +203,10 @@
 //!     for i in 0..10 {
 //!         let _ctx = unwind_context::UnwindContextWithIo::new(
 //!             unwind_context::UnwindContextArgs::new((
-//!                 unwind_context::UnwindContextArg::new(Some("i"), i),
+//!                 unwind_context::UnwindContextColoredArg::new(Some("i"), i),
 //!                 (),
 //!             )),
-//!             ::std::io::stderr(),
+//!             unwind_context::DefaultWriter,
 //!             unwind_context::StdPanicDetector,
 //!             unwind_context::get_default_color_scheme_if_enabled(),
 //!         );
@@ -213,13 +217,89 @@
 #![doc = ""]
 //! # Feature Flags
 //!
-//! - `std` (enabled by default): Enables [`UnwindContextWithIo`] structure,
+//! - `std` (enabled by default): Enables [`UnwindContextWithIo`] type alias,
 //!   [`unwind_context`], [`debug_unwind_context`], [`unwind_context_with_io`],
 //!   and [`debug_unwind_context_with_io`] macros.
+//! - `alloc` (enabled by `std`): Enables [`UnwindContextDyn`] type-erased
+//!   context data and the `dyn:` prefix of the context macros.
 //! - `detect-color-support`: Enables [`enable_colors_if_supported`] function
 //!   and [`supports-color`] optional dependency.
+//! - `windows-console`: Enables [`enable_virtual_terminal_processing`]
+//!   function, which is also called by [`enable_colors_if_supported`] to let
+//!   older Windows consoles process ANSI escape sequences. Implies
+//!   `detect-color-support`.
 //! - `custom-default-colors`: Enables [`set_default_color_scheme`] function and
 //!   [`atomic_ref`] optional dependency.
+//! - `timestamps`: Enables [`set_clock_source`] function and [`ClockSource`]
+//!   structure used to print a timestamp before every unwind context frame, and
+//!   [`atomic_ref`] optional dependency.
+//! - `anyhow`: Enables [`WithContextFrames`] implementation for [`anyhow`]
+//!   errors and [`anyhow`] optional dependency.
+//! - `eyre`: Enables [`WithContextFrames`] implementation for [`eyre`] reports
+//!   and [`eyre`] optional dependency.
+//! - `color-eyre`: Enables [`UnwindContextSection`] trait used to add captured
+//!   frames as a dedicated section of [`color-eyre`] reports, and
+//!   [`color-eyre`] optional dependency. Implies `eyre`.
+//! - `serde`: Enables `Serialize` implementations for [`CapturedFrame`] and
+//!   [`CapturedArg`] structures, and [`serde`] optional dependency.
+//! - `wasm`: Enables [`WasmConsoleWriter`] writer, [`WasmPanicDetector`] panic
+//!   detector, and [`unwind_context_wasm`] macro used to print context frames
+//!   to the browser console on the `wasm32-unknown-unknown` target, and
+//!   [`wasm-bindgen`] optional dependency. Implies `std`.
+//! - `android-log`: Enables [`AndroidLogWriter`] writer used to print context
+//!   frames to the Android system log. Implies `std`.
+//! - `syslog`: Enables [`SyslogWriter`] writer used to print context frames to
+//!   the local syslog daemon and [`send_frame_to_journal`] frame sink function
+//!   used to send context frames to the systemd journal with structured code
+//!   location fields. Implies `std`.
+//! - `win-debug-output`: Enables [`WinDebugOutputWriter`] writer used to print
+//!   context frames to the Windows debugger output with `OutputDebugStringW`,
+//!   for example for GUI applications without a console. Implies `std`.
+//! - `semihosting`: Enables [`SemihostingWriter`] writer used to print context
+//!   frames to the host standard output with ARM semihosting on bare-metal
+//!   Cortex-M targets, and [`cortex-m-semihosting`] optional dependency.
+//! - `rtt`: Enables [`RttWriter`] writer used to print context frames to the
+//!   RTT print channel on bare-metal targets, and [`rtt-target`] optional
+//!   dependency.
+//! - `async`: Enables [`UnwindContextFuture`] future wrapper and
+//!   [`spawn_with_unwind_context_on`] function used to print a task-level
+//!   context frame if a spawned task panics, with any async runtime. Implies
+//!   `std`.
+//! - `tokio`: Enables [`spawn_with_unwind_context`] function spawning a task
+//!   with a task-level context frame on the Tokio runtime, and [`tokio`]
+//!   optional dependency. Implies `async`.
+//! - `rayon`: Enables [`UnwindContextParallelIterator`] extension trait adding
+//!   the `unwind_context_by` parallel iterator adapter used to print a per-item
+//!   context frame if processing an item panics, and [`rayon`] optional
+//!   dependency. Implies `std`.
+//! - `off`: Makes all context macros like [`unwind_context`] expand to `()`
+//!   without any code generation, so the instrumentation can be kept in the
+//!   source code of performance-sensitive applications while producing
+//!   completely clean builds. `unwind_context_scope` only runs its block. It is
+//!   intended to be enabled only by the final binary.
+//! - `force-debug-context`: Makes debug context macros like
+//!   [`debug_unwind_context`] create context scope guards even if debug
+//!   assertions are disabled, for example to keep context in `--release` test
+//!   runs. Takes precedence over `disable-debug-context`.
+//! - `disable-debug-context`: Makes debug context macros like
+//!   [`debug_unwind_context`] expand to `()` even if debug assertions are
+//!   enabled, for example in release profiles with `debug-assertions = true`.
+//!
+//! # Minimum supported Rust version
+//!
+//! The minimum supported Rust version (MSRV) of this crate is 1.70.
+//!
+//! The MSRV policy is tiered by feature:
+//! - The core macros and types and the `std`, `detect-color-support`,
+//!   `custom-default-colors`, and `timestamps` features require Rust 1.70.
+//!   Raising this version is considered a breaking change.
+//! - Optional integrations with other crates may require a newer compiler.
+//!   Their requirements are listed next to the corresponding feature flags and
+//!   never affect users who do not enable them.
+//! - Some APIs rely on newer standard library items, like `core::error::Error`
+//!   or `std::panic::PanicHookInfo`. The build script detects the compiler
+//!   version and falls back to older equivalents, so they do not raise the
+//!   minimum compiler version either.
 //!
 //! # Similar crates
 //!
@@ -254,51 +334,367 @@
 //!
 //! [`supports-color`]: https://crates.io/crates/supports-color
 //! [`atomic_ref`]: https://crates.io/crates/atomic_ref
+//! [`anyhow`]: https://crates.io/crates/anyhow
+//! [`eyre`]: https://crates.io/crates/eyre
+//! [`color-eyre`]: https://crates.io/crates/color-eyre
+//! [`serde`]: https://crates.io/crates/serde
+//! [`wasm-bindgen`]: https://crates.io/crates/wasm-bindgen
+//! [`cortex-m-semihosting`]: https://crates.io/crates/cortex-m-semihosting
+//! [`rtt-target`]: https://crates.io/crates/rtt-target
+//! [`tokio`]: https://crates.io/crates/tokio
+//! [`rayon`]: https://crates.io/crates/rayon
 //! [`scopeguard`]: https://crates.io/crates/scopeguard
 //! [`panic-context`]: https://crates.io/crates/panic-context
 //! [`econtext`]: https://crates.io/crates/econtext
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
 #[cfg(test)]
-use version_sync as _; // Used in integration tests.
+use critical_section as _; // Used in `rtt` feature tests.
+#[cfg(test)]
+use serde_json as _;
+#[cfg(test)]
+use version_sync as _; // Used in integration tests. // Used in `serde` feature tests.
 
+#[cfg(feature = "android-log")]
+#[cfg_attr(docsrs, doc(cfg(feature = "android-log")))]
+mod android_log_writer;
 mod arg;
 mod args;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod assert;
+mod buf_writer;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod capture;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+mod capture_serde;
+#[cfg(feature = "color-eyre")]
+#[cfg_attr(docsrs, doc(cfg(feature = "color-eyre")))]
+mod color_eyre_section;
 mod color_scheme;
+mod color_scheme_choice;
 mod colored;
+mod colored_value;
+mod compat;
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 mod context;
 mod context_data;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+mod context_dyn;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod context_error;
+mod context_frame;
+mod context_layout;
+mod context_sink;
+mod context_visitor;
 mod context_with_fmt;
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 mod context_with_io;
+mod context_with_sink;
+mod debug_snapshot;
 mod debug_with;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod default_writer;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod env_config;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod file_writer;
+mod frame;
+mod frame_options;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod frame_sink;
 mod func;
 mod func_name;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+mod future;
+mod guard_options;
+mod hex_dump;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod hook_panic_detector;
+mod html;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod iter_context;
+mod location_template;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod max_frames;
 mod non_exhaustive;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod once_flag;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod output_budget;
 mod panic_detector;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod panic_location;
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+mod par_iter;
+mod prefix_writer;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod rate_limit;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod repeated_frames;
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "anyhow", feature = "eyre"))))]
+mod report;
+mod ring_buffer_writer;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod rotating_file_writer;
+#[cfg(feature = "rtt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rtt")))]
+mod rtt_writer;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod run_with_context;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod sampling;
+#[cfg(feature = "semihosting")]
+#[cfg_attr(docsrs, doc(cfg(feature = "semihosting")))]
+mod semihosting_writer;
 mod set_colors;
+mod snapshot;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod stats;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod stderr_writer;
+#[cfg(feature = "syslog")]
+#[cfg_attr(docsrs, doc(cfg(feature = "syslog")))]
+mod syslog_writer;
+mod tee_writer;
 #[cfg(test)]
 mod test_common;
 #[cfg(test)]
 mod test_util;
+pub mod themes;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub mod thread;
+mod thread_contexts;
+#[cfg(feature = "timestamps")]
+#[cfg_attr(docsrs, doc(cfg(feature = "timestamps")))]
+mod timestamp;
+mod tracked;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+mod unwind_banner;
+mod unwind_state;
+mod unwrap_context;
 mod util_macros;
+#[cfg(feature = "wasm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wasm")))]
+mod wasm_writer;
+#[cfg(feature = "win-debug-output")]
+#[cfg_attr(docsrs, doc(cfg(feature = "win-debug-output")))]
+mod win_debug_output;
+#[cfg(feature = "windows-console")]
+#[cfg_attr(docsrs, doc(cfg(feature = "windows-console")))]
+mod windows_console;
+mod writer_fn;
 
+#[cfg(feature = "android-log")]
+#[cfg_attr(docsrs, doc(cfg(feature = "android-log")))]
+pub use android_log_writer::*;
 pub use arg::*;
 pub use args::*;
+pub use buf_writer::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use capture::*;
+#[cfg(feature = "color-eyre")]
+#[cfg_attr(docsrs, doc(cfg(feature = "color-eyre")))]
+pub use color_eyre_section::*;
 pub use color_scheme::*;
+pub use color_scheme_choice::*;
 pub use colored::*;
+pub use colored_value::*;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub use context_dyn::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use context_error::*;
+pub use context_frame::*;
+pub use context_layout::*;
+pub use context_sink::*;
+pub use context_visitor::*;
 pub use context_with_fmt::*;
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 pub use context_with_io::*;
+pub use context_with_sink::*;
+pub use debug_snapshot::*;
 pub use debug_with::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use default_writer::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use env_config::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use file_writer::*;
+pub use frame::*;
+pub use frame_options::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use frame_sink::*;
 pub use func::*;
 pub use func_name::*;
+#[cfg(feature = "async")]
+#[cfg_attr(docsrs, doc(cfg(feature = "async")))]
+pub use future::*;
+pub use guard_options::*;
+pub use hex_dump::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use hook_panic_detector::*;
+pub use html::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use iter_context::*;
+pub use location_template::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use max_frames::*;
 pub use non_exhaustive::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use output_budget::*;
 pub use panic_detector::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use panic_location::*;
+#[cfg(feature = "rayon")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rayon")))]
+pub use par_iter::*;
+pub use prefix_writer::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use rate_limit::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use repeated_frames::*;
+#[cfg(any(feature = "anyhow", feature = "eyre"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "anyhow", feature = "eyre"))))]
+pub use report::*;
+pub use ring_buffer_writer::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use rotating_file_writer::*;
+#[cfg(feature = "rtt")]
+#[cfg_attr(docsrs, doc(cfg(feature = "rtt")))]
+pub use rtt_writer::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use run_with_context::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use sampling::*;
+#[cfg(feature = "semihosting")]
+#[cfg_attr(docsrs, doc(cfg(feature = "semihosting")))]
+pub use semihosting_writer::*;
 pub use set_colors::*;
+pub use snapshot::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use stats::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use stderr_writer::*;
+#[cfg(feature = "syslog")]
+#[cfg_attr(docsrs, doc(cfg(feature = "syslog")))]
+pub use syslog_writer::*;
+pub use tee_writer::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use thread_contexts::*;
+#[cfg(feature = "timestamps")]
+#[cfg_attr(docsrs, doc(cfg(feature = "timestamps")))]
+pub use timestamp::*;
+pub use tracked::*;
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub use unwind_banner::*;
+#[cfg(feature = "test-macro")]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-macro")))]
+/// Marks a function as a test and adds an unwind context to it.
+///
+/// It is a replacement for the built-in `#[test]` attribute that reduces
+/// per-test boilerplate. Before running the test body, it enables colors if
+/// they are supported by the terminal with [`enable_colors_if_supported`],
+/// enables writing of the context frames through the test harness output
+/// capture with [`set_eprint_output_enabled`], installs the panic location
+/// hook with [`install_panic_location_hook`], so that the panic location is
+/// printed before the first context frame, and creates a top-level context
+/// scope guard with the test function name, so that the test name is printed
+/// as the outermost context frame if the test panics.
+///
+/// The attribute can be combined with other test attributes like
+/// `#[should_panic]` or `#[ignore]` and can be applied to tests returning
+/// `Result`.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// #[unwind_context::test]
+/// fn test_func() {
+///     func(123, "abc");
+/// }
+///
+/// #[unwind_context::test]
+/// #[should_panic]
+/// fn test_func_panics() {
+///     func(234, "bcd");
+///     panic!("Error");
+/// }
+/// ```
+///
+/// [`enable_colors_if_supported`]: crate::enable_colors_if_supported
+/// [`set_eprint_output_enabled`]: crate::set_eprint_output_enabled
+/// [`install_panic_location_hook`]: crate::install_panic_location_hook
+#[doc(inline)]
+pub use unwind_context_macros::test;
+pub use unwrap_context::*;
+#[cfg(feature = "wasm")]
+#[cfg_attr(docsrs, doc(cfg(feature = "wasm")))]
+pub use wasm_writer::*;
+#[cfg(feature = "win-debug-output")]
+#[cfg_attr(docsrs, doc(cfg(feature = "win-debug-output")))]
+pub use win_debug_output::*;
+#[cfg(feature = "windows-console")]
+#[cfg_attr(docsrs, doc(cfg(feature = "windows-console")))]
+pub use windows_console::*;
+pub use writer_fn::*;
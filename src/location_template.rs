@@ -0,0 +1,198 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+use crate::AnsiColorScheme;
+
+/// The default location format printed after the unwind context frame data.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{FrameOptions, DEFAULT_LOCATION_FORMAT};
+///
+/// let options = FrameOptions::new();
+/// assert_eq!(
+///     options.location_format().unwrap_or(DEFAULT_LOCATION_FORMAT),
+///     "{file}:{line}:{column}"
+/// );
+/// ```
+pub const DEFAULT_LOCATION_FORMAT: &str = "{file}:{line}:{column}";
+
+/// A hyperlink URL template opening the location file with the default
+/// `file://` URL handler.
+///
+/// Note that the compiler records paths of the current workspace crates
+/// relative to the workspace root, so such links resolve only if the terminal
+/// working directory is the workspace root.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{FrameOptions, FILE_HYPERLINK_TEMPLATE};
+///
+/// let options = FrameOptions::new().with_location_hyperlink(Some(FILE_HYPERLINK_TEMPLATE));
+/// ```
+pub const FILE_HYPERLINK_TEMPLATE: &str = "file://{file}";
+
+/// An utility wrapper type which is used to format a location template with
+/// `{file}`, `{line}`, and `{column}` placeholders.
+///
+/// Unknown placeholders and unmatched braces are written as is. If a color
+/// scheme is set, the placeholder values are colorized with its
+/// `location_file`, `location_line`, and `location_column` styles, each
+/// followed by the `location` style, unless they are equal to the `location`
+/// one.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct LocationTemplateFmt<'a> {
+    pub template: &'a str,
+    pub file: &'a str,
+    pub line: u32,
+    pub column: u32,
+    pub color_scheme: Option<&'static AnsiColorScheme>,
+}
+
+impl LocationTemplateFmt<'_> {
+    fn write_part(
+        &self,
+        f: &mut Formatter<'_>,
+        style: impl FnOnce(&AnsiColorScheme) -> &'static str,
+        value: &dyn Display,
+    ) -> FmtResult {
+        match self
+            .color_scheme
+            .map(|color_scheme| (style(color_scheme), color_scheme.location))
+        {
+            Some((style, location)) if style != location => {
+                write!(f, "{style}{value}{location}")
+            }
+            _ => write!(f, "{value}"),
+        }
+    }
+}
+
+impl Display for LocationTemplateFmt<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let mut rest = self.template;
+        while let Some(start) = rest.find('{') {
+            f.write_str(&rest[..start])?;
+            let tail = &rest[start..];
+            rest = if let Some(tail) = tail.strip_prefix("{file}") {
+                self.write_part(f, |scheme| scheme.location_file, &self.file)?;
+                tail
+            } else if let Some(tail) = tail.strip_prefix("{line}") {
+                self.write_part(f, |scheme| scheme.location_line, &self.line)?;
+                tail
+            } else if let Some(tail) = tail.strip_prefix("{column}") {
+                self.write_part(f, |scheme| scheme.location_column, &self.column)?;
+                tail
+            } else {
+                f.write_str("{")?;
+                &tail[1..]
+            };
+        }
+        f.write_str(rest)
+    }
+}
+
+/// An utility wrapper type which is used to wrap a value into an OSC 8
+/// terminal hyperlink with the given URL.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct HyperlinkFmt<U, T> {
+    pub url: U,
+    pub text: T,
+}
+
+impl<U: Display, T: Display> Display for HyperlinkFmt<U, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", self.url, self.text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::location_template::{HyperlinkFmt, LocationTemplateFmt};
+    use crate::test_common::TEST_COLOR_SCHEME;
+    use crate::test_util::buf_fmt;
+    use crate::AnsiColorScheme;
+
+    fn template(template: &str) -> LocationTemplateFmt<'_> {
+        LocationTemplateFmt {
+            template,
+            file: "src/lib.rs",
+            line: 12,
+            column: 5,
+            color_scheme: None,
+        }
+    }
+
+    #[test]
+    fn test_location_template_fmt() {
+        let mut buffer = [0; 128];
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{}", template("{file}:{line}:{column}"))
+            ),
+            Ok("src/lib.rs:12:5")
+        );
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{}", template("vscode://file/{file}:{line}"))
+            ),
+            Ok("vscode://file/src/lib.rs:12")
+        );
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{}", template("{{file}} {unknown} {"))
+            ),
+            Ok("{src/lib.rs} {unknown} {")
+        );
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{}", template(""))),
+            Ok("")
+        );
+    }
+
+    #[test]
+    fn test_colored_location_template_fmt() {
+        static LOCATION_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
+            location_file: "{FILE}",
+            location_line: "{LINE}",
+            ..TEST_COLOR_SCHEME
+        };
+
+        let mut buffer = [0; 128];
+        let colored = LocationTemplateFmt {
+            color_scheme: Some(&LOCATION_COLOR_SCHEME),
+            ..template("{file}:{line}:{column}")
+        };
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{colored}")),
+            Ok("{FILE}src/lib.rs{LOC}:{LINE}12{LOC}:5")
+        );
+
+        // Parts with the same style as the whole location are not colorized.
+        let colored = LocationTemplateFmt {
+            color_scheme: Some(&TEST_COLOR_SCHEME),
+            ..template("{file}:{line}:{column}")
+        };
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{colored}")),
+            Ok("src/lib.rs:12:5")
+        );
+    }
+
+    #[test]
+    fn test_hyperlink_fmt() {
+        let mut buffer = [0; 128];
+        let hyperlink = HyperlinkFmt {
+            url: template("file://{file}"),
+            text: "lib.rs",
+        };
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{hyperlink}")),
+            Ok("\x1b]8;;file://src/lib.rs\x1b\\lib.rs\x1b]8;;\x1b\\")
+        );
+    }
+}
@@ -0,0 +1,89 @@
+use core::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+use crate::unwind_state::set_current_unwind_max_frames;
+
+static MAX_FRAMES_PER_UNWIND: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Sets the maximum number of unwind context frames printed during a single
+/// unwind, or removes the limit if `None` is given.
+///
+/// Frames exceeding the limit are not printed. Instead, a line like
+/// `... 42 more context frames suppressed` is printed after the last printed
+/// frame when the outermost context scope guard is dropped. It ensures that
+/// pathological call depths can't produce megabytes of output.
+///
+/// By default the number of frames is not limited.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn recursive(depth: usize) {
+///     let _ctx = unwind_context!(fn(depth));
+///     // ...
+///     if let Some(depth) = depth.checked_sub(1) {
+///         recursive(depth);
+///     }
+/// }
+///
+/// unwind_context::set_max_frames_per_unwind(Some(2));
+/// // On panic, it will print something like:
+/// // fn recursive(depth: 0)
+/// //     at src/main.rs:4:16
+/// // fn recursive(depth: 1)
+/// //     at src/main.rs:4:16
+/// // ... 998 more context frames suppressed
+/// recursive(999);
+/// ```
+#[inline]
+pub fn set_max_frames_per_unwind(max_frames: Option<usize>) {
+    MAX_FRAMES_PER_UNWIND.store(max_frames.unwrap_or(usize::MAX), AtomicOrdering::Relaxed);
+}
+
+/// Returns the maximum number of unwind context frames printed during a single
+/// unwind, if limited.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(unwind_context::get_max_frames_per_unwind(), None);
+/// ```
+#[inline]
+#[must_use]
+pub fn get_max_frames_per_unwind() -> Option<usize> {
+    match MAX_FRAMES_PER_UNWIND.load(AtomicOrdering::Relaxed) {
+        usize::MAX => None,
+        max_frames => Some(max_frames),
+    }
+}
+
+/// Sets the maximum number of unwind context frames printed during the current
+/// unwind of the current thread, overriding the global
+/// [`set_max_frames_per_unwind`] setting.
+///
+/// If the current thread is panicking, the limit applies to the current unwind.
+/// Otherwise, it applies to the next unwind of the current thread. The limit is
+/// reset when the unwind ends.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// let handle = std::thread::spawn(|| {
+///     // Only the innermost context frame will be printed on panic.
+///     unwind_context::set_max_frames_for_current_unwind(Some(1));
+///     func(123, "abc");
+/// });
+/// handle.join().unwrap();
+/// ```
+#[inline]
+pub fn set_max_frames_for_current_unwind(max_frames: Option<usize>) {
+    set_current_unwind_max_frames(max_frames);
+}
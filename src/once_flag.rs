@@ -0,0 +1,56 @@
+use core::cmp::Ordering;
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use core::hash::{Hash, Hasher};
+use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+
+/// A reference to a per-call-site flag marking whether a frame has already
+/// been printed, compared and hashed by address, so that it can be stored in
+/// [`UnwindContextWithSink`].
+///
+/// [`UnwindContextWithSink`]: crate::UnwindContextWithSink
+#[derive(Copy, Clone)]
+pub(crate) struct OnceFlag(pub &'static AtomicBool);
+
+impl OnceFlag {
+    fn addr(self) -> *const AtomicBool {
+        self.0
+    }
+
+    /// Marks the frame as printed and returns `true` if it was already
+    /// printed before.
+    pub fn mark_printed(self) -> bool {
+        self.0.swap(true, AtomicOrdering::Relaxed)
+    }
+}
+
+impl PartialEq for OnceFlag {
+    fn eq(&self, other: &Self) -> bool {
+        self.addr() == other.addr()
+    }
+}
+
+impl Eq for OnceFlag {}
+
+impl PartialOrd for OnceFlag {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OnceFlag {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.addr().cmp(&other.addr())
+    }
+}
+
+impl Hash for OnceFlag {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.addr().hash(state);
+    }
+}
+
+impl Debug for OnceFlag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_tuple("OnceFlag").field(&self.addr()).finish()
+    }
+}
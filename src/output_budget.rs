@@ -0,0 +1,87 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::string::{String, ToString};
+
+use crate::unwind_state::consume_output_budget;
+
+static OUTPUT_BUDGET: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+/// Sets the maximum number of bytes of unwind context frames printed during a
+/// single unwind, or removes the limit if `None` is given.
+///
+/// The budget is shared by all frames printed during an unwind. The first
+/// frame that does not fit into the remaining budget is not printed, and
+/// neither are the frames after it. Instead, a line like
+/// `... output budget exceeded, 42 more context frames suppressed` is printed
+/// when the outermost context scope guard is dropped. It protects CI logs and
+/// serial consoles from runaway output caused by huge context arguments.
+///
+/// By default the output size is not limited.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_output_budget(Some(64 * 1024));
+/// func(123, "abc");
+/// ```
+#[inline]
+pub fn set_output_budget(bytes: Option<usize>) {
+    OUTPUT_BUDGET.store(bytes.unwrap_or(usize::MAX), AtomicOrdering::Relaxed);
+}
+
+/// Returns the maximum number of bytes of unwind context frames printed during
+/// a single unwind, if limited.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(unwind_context::get_output_budget(), None);
+/// ```
+#[inline]
+#[must_use]
+pub fn get_output_budget() -> Option<usize> {
+    match OUTPUT_BUDGET.load(AtomicOrdering::Relaxed) {
+        usize::MAX => None,
+        bytes => Some(bytes),
+    }
+}
+
+/// A value charged to the output budget of the current unwind.
+///
+/// If the output size is limited, the value is formatted once to measure it,
+/// and the measured output is written afterwards, so the written frame is
+/// exactly the one charged to the budget.
+pub(crate) enum BudgetedFmt<D> {
+    /// The output size is not limited, the value is formatted when written.
+    Unlimited(D),
+    /// The formatted value charged to the budget.
+    Formatted(String),
+}
+
+impl<D: Display> Display for BudgetedFmt<D> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::Unlimited(value) => value.fmt(f),
+            Self::Formatted(output) => f.write_str(output),
+        }
+    }
+}
+
+/// Charges the length of the formatted value to the remaining output budget
+/// of the current unwind and returns the value to write, or `None` if it does
+/// not fit into the remaining budget.
+pub(crate) fn charge_output_budget<D: Display>(value: D) -> Option<BudgetedFmt<D>> {
+    let Some(budget) = get_output_budget() else {
+        return Some(BudgetedFmt::Unlimited(value));
+    };
+    let output = value.to_string();
+    consume_output_budget(output.len(), budget).then_some(BudgetedFmt::Formatted(output))
+}
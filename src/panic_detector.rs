@@ -1,3 +1,12 @@
+#[cfg(target_has_atomic = "8")]
+use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+#[cfg(feature = "std")]
+use std::sync::Arc;
+
+/// A global panic flag used by the [`GlobalFlagPanicDetector`].
+#[cfg(target_has_atomic = "8")]
+static GLOBAL_PANIC_FLAG: AtomicBool = AtomicBool::new(false);
+
 /// An utility trait which is used to detect panic.
 ///
 /// # Examples
@@ -22,7 +31,6 @@
 ///     // ...
 /// }
 /// ```
-
 pub trait PanicDetector {
     /// Determines whether the current thread is unwinding because of panic.
     ///
@@ -35,6 +43,63 @@ pub trait PanicDetector {
     fn is_panicking(&self) -> bool;
 }
 
+impl<T: PanicDetector + ?Sized> PanicDetector for &T {
+    #[inline]
+    fn is_panicking(&self) -> bool {
+        (**self).is_panicking()
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<T: PanicDetector + ?Sized> PanicDetector for Arc<T> {
+    #[inline]
+    fn is_panicking(&self) -> bool {
+        (**self).is_panicking()
+    }
+}
+
+impl PanicDetector for fn() -> bool {
+    #[inline]
+    fn is_panicking(&self) -> bool {
+        self()
+    }
+}
+
+/// A [`PanicDetector`] wrapper for closures returning whether the current
+/// thread is unwinding because of panic.
+///
+/// The [`PanicDetector`] trait is implemented for references, [`Arc`]
+/// pointers and `fn() -> bool` function pointers directly, but it cannot be
+/// implemented for all closures without conflicting with these
+/// implementations, so closures should be wrapped with this type.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_fmt, FnPanicDetector};
+///
+/// fn func(foo: u32, bar: &str, writer: &mut String, is_panicking: &dyn Fn() -> bool) {
+///     let _ctx = unwind_context_with_fmt!(
+///         (fn(foo, bar)),
+///         writer = writer,
+///         panic_detector = FnPanicDetector(|| is_panicking()),
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// [`Arc`]: std::sync::Arc
+#[derive(Copy, Clone, Debug, Default)]
+pub struct FnPanicDetector<F>(pub F);
+
+impl<F: Fn() -> bool> PanicDetector for FnPanicDetector<F> {
+    #[inline]
+    fn is_panicking(&self) -> bool {
+        (self.0)()
+    }
+}
+
 /// A default [`PanicDetector`] for a crates compiled with the Rust standard
 /// library.
 ///
@@ -68,3 +133,122 @@ impl PanicDetector for StdPanicDetector {
         std::thread::panicking()
     }
 }
+
+/// A [`PanicDetector`] backed by a given [`AtomicBool`] panic flag.
+///
+/// It is intended for `no_std` environments where the panic flag is set by a
+/// custom panic handler or by a runtime before unwinding starts.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::sync::atomic::{AtomicBool, Ordering};
+///
+/// use unwind_context::{unwind_context_with_fmt, AtomicFlagPanicDetector};
+///
+/// static PANIC_FLAG: AtomicBool = AtomicBool::new(false);
+///
+/// fn func(foo: u32, bar: &str, writer: &mut String) {
+///     let _ctx = unwind_context_with_fmt!(
+///         (fn(foo, bar)),
+///         writer = writer,
+///         panic_detector = AtomicFlagPanicDetector(&PANIC_FLAG),
+///     );
+///     // ...
+/// }
+///
+/// // Somewhere in the panic handler:
+/// PANIC_FLAG.store(true, Ordering::Relaxed);
+/// ```
+#[cfg(target_has_atomic = "8")]
+#[derive(Copy, Clone, Debug)]
+pub struct AtomicFlagPanicDetector<'a>(pub &'a AtomicBool);
+
+#[cfg(target_has_atomic = "8")]
+impl PanicDetector for AtomicFlagPanicDetector<'_> {
+    #[inline]
+    fn is_panicking(&self) -> bool {
+        self.0.load(AtomicOrdering::Relaxed)
+    }
+}
+
+/// A [`PanicDetector`] backed by a global panic flag set with the
+/// [`GlobalFlagPanicDetector::set_panicking`] function.
+///
+/// It is a static-global variant of the [`AtomicFlagPanicDetector`] which does
+/// not require defining a custom panic flag.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_fmt, GlobalFlagPanicDetector, PanicDetector};
+///
+/// fn func(foo: u32, bar: &str, writer: &mut String) {
+///     let _ctx = unwind_context_with_fmt!(
+///         (fn(foo, bar)),
+///         writer = writer,
+///         panic_detector = GlobalFlagPanicDetector,
+///     );
+///     // ...
+/// }
+///
+/// assert!(!GlobalFlagPanicDetector.is_panicking());
+/// // Somewhere in the panic handler:
+/// GlobalFlagPanicDetector::set_panicking(true);
+/// assert!(GlobalFlagPanicDetector.is_panicking());
+/// # GlobalFlagPanicDetector::set_panicking(false);
+/// ```
+#[cfg(target_has_atomic = "8")]
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct GlobalFlagPanicDetector;
+
+#[cfg(target_has_atomic = "8")]
+impl GlobalFlagPanicDetector {
+    /// Sets or clears the global panic flag.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// unwind_context::GlobalFlagPanicDetector::set_panicking(false);
+    /// ```
+    #[inline]
+    pub fn set_panicking(panicking: bool) {
+        GLOBAL_PANIC_FLAG.store(panicking, AtomicOrdering::Relaxed);
+    }
+}
+
+#[cfg(target_has_atomic = "8")]
+impl PanicDetector for GlobalFlagPanicDetector {
+    #[inline]
+    fn is_panicking(&self) -> bool {
+        GLOBAL_PANIC_FLAG.load(AtomicOrdering::Relaxed)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::{FnPanicDetector, PanicDetector, StdPanicDetector};
+
+    fn is_panicking<P: PanicDetector>(panic_detector: P) -> bool {
+        panic_detector.is_panicking()
+    }
+
+    #[test]
+    fn test_panic_detector_impls() {
+        let detector = &StdPanicDetector;
+        let shared = Arc::new(StdPanicDetector);
+        let detector_fn: fn() -> bool = || true;
+        let closure = &FnPanicDetector(|| true);
+        assert!(!is_panicking(detector));
+        assert!(!is_panicking(Arc::clone(&shared)));
+        assert!(!is_panicking(&shared));
+        assert!(is_panicking(detector_fn));
+        assert!(!is_panicking(FnPanicDetector(|| false)));
+        assert!(is_panicking(closure));
+
+        let dyn_detector: &dyn PanicDetector = &StdPanicDetector;
+        assert!(!is_panicking(dyn_detector));
+    }
+}
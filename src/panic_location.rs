@@ -0,0 +1,114 @@
+use core::cell::RefCell;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::string::{String, ToString};
+
+use crate::compat::update_panic_hook;
+use crate::location_template::LocationTemplateFmt;
+use crate::{get_location_path, AnsiColorScheme, DEFAULT_LOCATION_FORMAT};
+
+static IS_PANIC_LOCATION_HOOK_INSTALLED: AtomicBool = AtomicBool::new(false);
+
+std::thread_local! {
+    static PANIC_LOCATION: RefCell<Option<PanicLocation>> = const { RefCell::new(None) };
+}
+
+/// The source location of the last panic of the current thread recorded by
+/// the panic location hook.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub(crate) struct PanicLocation {
+    file: String,
+    line: u32,
+    column: u32,
+}
+
+/// Installs a panic hook that records the source location of every panic, so
+/// that it is printed as `panicked at src/foo.rs:42:13` before the first
+/// unwind context frame of the unwind.
+///
+/// Context scope guards only know the locations where they were created. With
+/// this hook the root cause location is printed right next to the context,
+/// which is useful when the panic message itself is printed elsewhere, for
+/// example by a custom panic hook or a logging framework.
+///
+/// The previously set panic hook is preserved and called after the location
+/// is recorded. Calling this function more than once has no effect.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::install_panic_location_hook();
+/// // On panic, it will print something like:
+/// // panicked at src/main.rs:8:5
+/// // fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:4:14
+/// func(123, "abc");
+/// ```
+pub fn install_panic_location_hook() {
+    if IS_PANIC_LOCATION_HOOK_INSTALLED.swap(true, AtomicOrdering::Relaxed) {
+        return;
+    }
+    update_panic_hook(|prev, info| {
+        if let Some(location) = info.location() {
+            let _ = PANIC_LOCATION.try_with(|cell| {
+                if let Ok(mut cell) = cell.try_borrow_mut() {
+                    *cell = Some(PanicLocation {
+                        file: location.file().to_string(),
+                        line: location.line(),
+                        column: location.column(),
+                    });
+                }
+            });
+        }
+        prev(info);
+    });
+}
+
+/// Takes the source location of the last panic of the current thread, if it
+/// was recorded by the panic location hook.
+pub(crate) fn take_panic_location() -> Option<PanicLocation> {
+    if !IS_PANIC_LOCATION_HOOK_INSTALLED.load(AtomicOrdering::Relaxed) {
+        return None;
+    }
+    PANIC_LOCATION
+        .try_with(|cell| cell.try_borrow_mut().ok()?.take())
+        .ok()
+        .flatten()
+}
+
+/// An utility wrapper type which is used to format the panic location header
+/// printed before the first unwind context frame.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct PanicLocationFmt<'a> {
+    pub location: &'a PanicLocation,
+    pub color_scheme: Option<&'static AnsiColorScheme>,
+}
+
+impl Display for PanicLocationFmt<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let PanicLocation { file, line, column } = self.location;
+        let text = LocationTemplateFmt {
+            template: DEFAULT_LOCATION_FORMAT,
+            file: get_location_path().shorten(file),
+            line: *line,
+            column: *column,
+            color_scheme: self.color_scheme,
+        };
+        if let Some(color_scheme) = self.color_scheme {
+            writeln!(
+                f,
+                "panicked at {}{text}{}",
+                color_scheme.location, color_scheme.default
+            )
+        } else {
+            writeln!(f, "panicked at {text}")
+        }
+    }
+}
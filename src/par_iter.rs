@@ -0,0 +1,291 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+use core::panic::Location;
+
+use rayon::iter::plumbing::{Consumer, Folder, UnindexedConsumer};
+use rayon::iter::ParallelIterator;
+
+use crate::{DebugAnsiColored, DefaultWriter, StdPanicDetector, UnwindContextWithIo};
+
+/// An extension trait for [`rayon`] parallel iterators adding a per-item
+/// unwind context.
+///
+/// Context scope guards are thread-local, and work stealing makes rayon
+/// process items on arbitrary worker threads, so a panic inside
+/// `par_iter().map(...)` does not show which element caused it. The
+/// [`unwind_context_by`] adapter creates a context scope guard for every item
+/// while it is being processed by the following adapters and the consumer, so
+/// a panic prints the context data of the offending element.
+///
+/// [`rayon`]: https://crates.io/crates/rayon
+/// [`unwind_context_by`]: UnwindContextParallelIterator::unwind_context_by
+pub trait UnwindContextParallelIterator: ParallelIterator {
+    /// Creates a parallel iterator that prints the context data built by the
+    /// given function from the item if processing the item panics.
+    ///
+    /// The context data is built for every item before it is passed further,
+    /// so the function should be cheap. It receives a reference to the item
+    /// and returns owned context data, so the item fields can be copied or
+    /// cloned into it. The guard uses [`DefaultWriter`] writer,
+    /// [`StdPanicDetector`] panic detector, and a color scheme determined by
+    /// the [`get_default_color_scheme_if_enabled`] function, like the
+    /// [`unwind_context`] macro. The caller location is used as the frame
+    /// location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rayon::prelude::*;
+    /// use unwind_context::{build_unwind_context_data, UnwindContextParallelIterator};
+    ///
+    /// let sum: u32 = [1, 2, 3]
+    ///     .par_iter()
+    ///     .unwind_context_by(|&&value| build_unwind_context_data!(fn item(value)))
+    ///     .map(|value| value * 2)
+    ///     .sum();
+    /// assert_eq!(sum, 12);
+    /// ```
+    ///
+    /// [`unwind_context`]: crate::unwind_context
+    /// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
+    #[inline]
+    #[must_use = "iterator adapters are lazy and do nothing unless consumed"]
+    #[track_caller]
+    fn unwind_context_by<F, T>(self, context: F) -> UnwindContextBy<Self, F>
+    where
+        F: Fn(&Self::Item) -> T + Sync + Send,
+        T: Debug + DebugAnsiColored,
+    {
+        UnwindContextBy {
+            base: self,
+            context,
+            location: Location::caller(),
+        }
+    }
+}
+
+impl<I: ParallelIterator> UnwindContextParallelIterator for I {}
+
+/// A parallel iterator adapter printing a per-item unwind context frame if
+/// processing an item panics.
+///
+/// It is created with the [`UnwindContextParallelIterator::unwind_context_by`]
+/// method.
+#[must_use = "iterator adapters are lazy and do nothing unless consumed"]
+#[derive(Clone)]
+pub struct UnwindContextBy<I, F> {
+    base: I,
+    context: F,
+    location: &'static Location<'static>,
+}
+
+impl<I, F> UnwindContextBy<I, F> {
+    /// Returns the code location where the adapter was created.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use rayon::prelude::*;
+    /// use unwind_context::{build_unwind_context_data, UnwindContextParallelIterator};
+    ///
+    /// let iter = (0..4_u32)
+    ///     .into_par_iter()
+    ///     .unwind_context_by(|&value| build_unwind_context_data!(fn item(value)));
+    /// assert_eq!(iter.location().line(), line!() - 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+
+impl<I: Debug, F> Debug for UnwindContextBy<I, F> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("UnwindContextBy")
+            .field("base", &self.base)
+            .field("location", &self.location)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<I, F, T> ParallelIterator for UnwindContextBy<I, F>
+where
+    I: ParallelIterator,
+    F: Fn(&I::Item) -> T + Sync + Send,
+    T: Debug + DebugAnsiColored,
+{
+    type Item = I::Item;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.base.drive_unindexed(UnwindContextConsumer {
+            base: consumer,
+            context: &self.context,
+            location: self.location,
+        })
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.base.opt_len()
+    }
+}
+
+struct UnwindContextConsumer<'f, C, F> {
+    base: C,
+    context: &'f F,
+    location: &'static Location<'static>,
+}
+
+impl<'f, C, F, I, T> Consumer<I> for UnwindContextConsumer<'f, C, F>
+where
+    C: Consumer<I>,
+    F: Fn(&I) -> T + Sync,
+    T: Debug + DebugAnsiColored,
+{
+    type Folder = UnwindContextFolder<'f, C::Folder, F>;
+    type Reducer = C::Reducer;
+    type Result = C::Result;
+
+    fn split_at(self, index: usize) -> (Self, Self, Self::Reducer) {
+        let Self {
+            base,
+            context,
+            location,
+        } = self;
+        let (left, right, reducer) = base.split_at(index);
+        (
+            Self {
+                base: left,
+                context,
+                location,
+            },
+            Self {
+                base: right,
+                context,
+                location,
+            },
+            reducer,
+        )
+    }
+
+    fn into_folder(self) -> Self::Folder {
+        UnwindContextFolder {
+            base: self.base.into_folder(),
+            context: self.context,
+            location: self.location,
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.base.full()
+    }
+}
+
+impl<C, F, I, T> UnindexedConsumer<I> for UnwindContextConsumer<'_, C, F>
+where
+    C: UnindexedConsumer<I>,
+    F: Fn(&I) -> T + Sync,
+    T: Debug + DebugAnsiColored,
+{
+    fn split_off_left(&self) -> Self {
+        Self {
+            base: self.base.split_off_left(),
+            context: self.context,
+            location: self.location,
+        }
+    }
+
+    fn to_reducer(&self) -> Self::Reducer {
+        self.base.to_reducer()
+    }
+}
+
+struct UnwindContextFolder<'f, C, F> {
+    base: C,
+    context: &'f F,
+    location: &'static Location<'static>,
+}
+
+impl<C, F, I, T> Folder<I> for UnwindContextFolder<'_, C, F>
+where
+    C: Folder<I>,
+    F: Fn(&I) -> T,
+    T: Debug + DebugAnsiColored,
+{
+    type Result = C::Result;
+
+    fn consume(self, item: I) -> Self {
+        let ctx = UnwindContextWithIo::new_default(
+            (self.context)(&item),
+            DefaultWriter,
+            StdPanicDetector,
+        )
+        .with_location(self.location);
+        let base = self.base.consume(item);
+        drop(ctx);
+        Self {
+            base,
+            context: self.context,
+            location: self.location,
+        }
+    }
+
+    fn complete(self) -> Self::Result {
+        self.base.complete()
+    }
+
+    fn full(&self) -> bool {
+        self.base.full()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::panic::AssertUnwindSafe;
+    use std::panic::catch_unwind;
+    use std::vec::Vec;
+
+    use rayon::prelude::*;
+    use rayon::ThreadPoolBuilder;
+
+    use crate::{build_unwind_context_data, UnwindContextParallelIterator};
+
+    #[test]
+    fn test_unwind_context_by() {
+        let capture = crate::capture();
+        let values: Vec<u32> = (0..8_u32)
+            .into_par_iter()
+            .unwind_context_by(|&value| build_unwind_context_data!(fn item(value)))
+            .map(|value| value * 2)
+            .collect();
+        assert_eq!(values, [0, 2, 4, 6, 8, 10, 12, 14]);
+        assert!(capture.take_frames().is_empty());
+    }
+
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_unwind_context_by_panic() {
+        // A single-threaded pool processes all items in the thread that
+        // installs the operation, so the thread-local capture sees the frames.
+        let pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+        let (location, frames) = pool.install(|| {
+            let capture = crate::capture();
+            let iter = (0..8_u32)
+                .into_par_iter()
+                .unwind_context_by(|&value| build_unwind_context_data!(fn item(value)));
+            let location = iter.location();
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                iter.for_each(|value| assert_ne!(value, 5, "item panic"));
+            }));
+            assert!(result.is_err());
+            (location, capture.take_frames())
+        });
+
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].func_name, Some("item"));
+        assert_eq!(frames[0].arg("value"), Some("5"));
+        assert_eq!(frames[0].location, location);
+    }
+}
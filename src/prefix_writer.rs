@@ -0,0 +1,177 @@
+use core::fmt::{Result as FmtResult, Write as FmtWrite};
+#[cfg(feature = "std")]
+use std::io::{Result as IoResult, Write as IoWrite};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// A writer adapter that inserts a prefix at the start of every output line.
+///
+/// It can be used to distinguish unwind context frames from normal application
+/// logging written to the same stream. The prefix is inserted before the first
+/// character of each line, so no dangling prefix is written after the last
+/// line break.
+///
+/// It implements [`core::fmt::Write`] if the underlying writer implements it,
+/// and [`std::io::Write`] if the underlying writer implements it and the `std`
+/// feature is enabled. In the latter case every write is forwarded to the
+/// underlying writer with a single `write_all` call.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, PrefixWriter};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!(
+///         (fn(foo, bar)),
+///         writer = PrefixWriter::new(std::io::stderr(), "[unwind] "),
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// ```rust
+/// use core::fmt::Write;
+///
+/// let mut writer = unwind_context::PrefixWriter::new(String::new(), "> ");
+/// write!(writer, "abc\ndef").unwrap();
+/// write!(writer, "ghi\n\njkl\n").unwrap();
+/// assert_eq!(writer.into_inner(), "> abc\n> defghi\n> \n> jkl\n");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PrefixWriter<W, P = &'static str> {
+    writer: W,
+    prefix: P,
+    is_line_start: bool,
+}
+
+impl<W, P: AsRef<str>> PrefixWriter<W, P> {
+    /// Creates a new `PrefixWriter` inserting the given prefix at the start of
+    /// every line written to the given writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::PrefixWriter::new(std::io::stderr(), "[unwind] ");
+    /// ```
+    #[inline]
+    pub const fn new(writer: W, prefix: P) -> Self {
+        Self {
+            writer,
+            prefix,
+            is_line_start: true,
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::PrefixWriter::new(String::new(), "> ");
+    /// assert_eq!(writer.get_ref(), "");
+    /// ```
+    #[inline]
+    pub const fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns a mutable reference to the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut writer = unwind_context::PrefixWriter::new(String::new(), "> ");
+    /// writer.get_mut().push_str("abc");
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    /// Consumes the `PrefixWriter`, returning the underlying writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::PrefixWriter::new(String::new(), "> ");
+    /// assert_eq!(writer.into_inner(), "");
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: FmtWrite, P: AsRef<str>> FmtWrite for PrefixWriter<W, P> {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        for line in s.split_inclusive('\n') {
+            if self.is_line_start {
+                self.writer.write_str(self.prefix.as_ref())?;
+            }
+            self.writer.write_str(line)?;
+            self.is_line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<W: IoWrite, P: AsRef<str>> IoWrite for PrefixWriter<W, P> {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let prefix = self.prefix.as_ref().as_bytes();
+        let mut output = Vec::with_capacity(buf.len());
+        let mut is_line_start = self.is_line_start;
+        for line in buf.split_inclusive(|&byte| byte == b'\n') {
+            if is_line_start {
+                output.extend_from_slice(prefix);
+            }
+            output.extend_from_slice(line);
+            is_line_start = line.ends_with(b"\n");
+        }
+        self.writer.write_all(&output)?;
+        self.is_line_start = is_line_start;
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write as FmtWrite;
+
+    use crate::test_util::FixedBufWriter;
+    use crate::PrefixWriter;
+
+    #[test]
+    fn test_prefix_writer_fmt() {
+        let mut buffer = [0; 64];
+        let mut writer = PrefixWriter::new(FixedBufWriter::new(&mut buffer), "[unwind] ");
+        assert_eq!(writer.write_str("abc\n    at def"), Ok(()));
+        assert_eq!(writer.write_str(":1:2\n"), Ok(()));
+        assert_eq!(writer.write_str(""), Ok(()));
+        assert_eq!(writer.write_str("ghi\n"), Ok(()));
+        assert_eq!(
+            writer.into_inner().into_str(),
+            "[unwind] abc\n[unwind]     at def:1:2\n[unwind] ghi\n"
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_prefix_writer_io() {
+        use std::io::Write as IoWrite;
+        use std::vec::Vec;
+
+        let mut writer = PrefixWriter::new(Vec::new(), "> ");
+        assert!(writer.write_all(b"abc\n\ndef").is_ok());
+        assert!(writer.write_all(b"ghi\n").is_ok());
+        assert!(writer.flush().is_ok());
+        assert_eq!(writer.into_inner(), b"> abc\n> \n> defghi\n");
+    }
+}
@@ -0,0 +1,251 @@
+use core::panic::Location;
+use core::time::Duration;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, PoisonError, RwLock};
+use std::time::Instant;
+
+static RATE_LIMIT: RwLock<Option<RateLimit>> = RwLock::new(None);
+
+/// The state of a code location, shared by all context scope guards created
+/// there.
+#[derive(Copy, Clone, Debug)]
+struct LocationState {
+    last_printed_at: Option<Instant>,
+    suppressed: usize,
+}
+
+type LocationStates = Mutex<HashMap<&'static Location<'static>, LocationState>>;
+
+static LOCATION_STATES: OnceLock<LocationStates> = OnceLock::new();
+
+/// A limit of how often unwind context frames of context scope guards created
+/// at the same code location are printed.
+///
+/// The first frame of a code location is always printed. After that a frame is
+/// printed only if both the given interval has passed and the given number of
+/// frames of the same location occurred since the last printed one. The
+/// skipped frames are counted, and the count is printed after the next printed
+/// frame of the location in a line like
+/// `... frame suppressed 42 times by rate limit`.
+///
+/// It is intended for guards inside hot loops or per-request handlers that
+/// catch panics repeatedly. The limit can be set for all guards with the
+/// [`set_rate_limit`] function or for a single guard with the
+/// `rate_limit = ...` parameter of macros like [`unwind_context_with_io`].
+///
+/// Skipped frames are not numbered, not recorded with a [`FrameCapture`], and
+/// not passed to a [`FrameSink`].
+///
+/// # Examples
+///
+/// ```rust
+/// use core::time::Duration;
+///
+/// use unwind_context::RateLimit;
+///
+/// let limit = RateLimit::per_interval(Duration::from_secs(10)).with_occurrences(100);
+/// assert_eq!(limit.interval(), Duration::from_secs(10));
+/// assert_eq!(limit.occurrences(), 100);
+/// ```
+///
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+/// [`FrameCapture`]: crate::FrameCapture
+/// [`FrameSink`]: crate::FrameSink
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct RateLimit {
+    interval: Duration,
+    occurrences: usize,
+}
+
+impl RateLimit {
+    /// Creates a new `RateLimit` printing at most one frame of a code location
+    /// per the given interval.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    ///
+    /// let limit = unwind_context::RateLimit::per_interval(Duration::from_secs(1));
+    /// assert_eq!(limit.occurrences(), 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn per_interval(interval: Duration) -> Self {
+        Self {
+            interval,
+            occurrences: 1,
+        }
+    }
+
+    /// Creates a new `RateLimit` printing at most one frame of a code location
+    /// per the given number of its occurrences.
+    ///
+    /// Zero is treated as one, which means no limit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let limit = unwind_context::RateLimit::per_occurrences(1000);
+    /// assert_eq!(limit.interval(), core::time::Duration::ZERO);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn per_occurrences(occurrences: usize) -> Self {
+        Self::per_interval(Duration::ZERO).with_occurrences(occurrences)
+    }
+
+    /// Sets the number of occurrences of a code location required between
+    /// printed frames in addition to the interval.
+    ///
+    /// Zero is treated as one, which means no limit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::time::Duration;
+    ///
+    /// let limit = unwind_context::RateLimit::per_interval(Duration::from_secs(1)).with_occurrences(0);
+    /// assert_eq!(limit.occurrences(), 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn with_occurrences(mut self, occurrences: usize) -> Self {
+        self.occurrences = if occurrences == 0 { 1 } else { occurrences };
+        self
+    }
+
+    /// Returns the minimum interval between printed frames of a code location.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let limit = unwind_context::RateLimit::per_occurrences(10);
+    /// assert_eq!(limit.interval(), core::time::Duration::ZERO);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    /// Returns the number of occurrences of a code location per printed frame.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let limit = unwind_context::RateLimit::per_occurrences(10);
+    /// assert_eq!(limit.occurrences(), 10);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn occurrences(&self) -> usize {
+        self.occurrences
+    }
+}
+
+/// Sets the rate limit applied to frames of all context scope guards, or
+/// removes it if `None` is given.
+///
+/// The limit is tracked separately for every code location. Context scope
+/// guards with the `rate_limit = ...` macro parameter use their own limit
+/// instead. See [`RateLimit`] for more details.
+///
+/// By default frames are not rate-limited.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::time::Duration;
+///
+/// use unwind_context::{unwind_context, RateLimit};
+///
+/// fn handle_request(id: u32) {
+///     let _ctx = unwind_context!(fn(id));
+///     // ...
+/// }
+///
+/// unwind_context::set_rate_limit(Some(RateLimit::per_interval(Duration::from_secs(10))));
+/// handle_request(123);
+/// unwind_context::set_rate_limit(None);
+/// ```
+#[inline]
+pub fn set_rate_limit(limit: Option<RateLimit>) {
+    *RATE_LIMIT.write().unwrap_or_else(PoisonError::into_inner) = limit;
+}
+
+/// Returns the rate limit applied to frames of all context scope guards, if
+/// set.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(unwind_context::get_rate_limit(), None);
+/// ```
+#[inline]
+#[must_use]
+pub fn get_rate_limit() -> Option<RateLimit> {
+    *RATE_LIMIT.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Registers an occurrence of a frame of the given code location and returns
+/// the number of frames of the location suppressed since the last printed one
+/// if this frame should be printed.
+pub(crate) fn check_rate_limit(
+    limit: Option<&RateLimit>,
+    location: &'static Location<'static>,
+) -> Option<usize> {
+    let Some(limit) = limit.copied().or_else(get_rate_limit) else {
+        return Some(0);
+    };
+    let mut states = LOCATION_STATES
+        .get_or_init(LocationStates::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let now = Instant::now();
+    let state = states.entry(location).or_insert(LocationState {
+        last_printed_at: None,
+        suppressed: 0,
+    });
+    let is_allowed = state.last_printed_at.map_or(true, |last_printed_at| {
+        now.saturating_duration_since(last_printed_at) >= limit.interval
+            && state.suppressed.saturating_add(1) >= limit.occurrences
+    });
+    if is_allowed {
+        state.last_printed_at = Some(now);
+        Some(core::mem::take(&mut state.suppressed))
+    } else {
+        state.suppressed = state.suppressed.saturating_add(1);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::panic::Location;
+    use core::time::Duration;
+
+    use crate::rate_limit::check_rate_limit;
+    use crate::RateLimit;
+
+    #[test]
+    fn test_rate_limit_per_occurrences() {
+        let location = Location::caller();
+        let limit = RateLimit::per_occurrences(3);
+        let results: [_; 7] = core::array::from_fn(|_| check_rate_limit(Some(&limit), location));
+        assert_eq!(results, [Some(0), None, None, Some(2), None, None, Some(2)]);
+    }
+
+    #[test]
+    fn test_rate_limit_per_interval() {
+        let location = Location::caller();
+        let limit = RateLimit::per_interval(Duration::from_secs(3600));
+        assert_eq!(check_rate_limit(Some(&limit), location), Some(0));
+        assert_eq!(check_rate_limit(Some(&limit), location), None);
+        assert_eq!(check_rate_limit(Some(&limit), location), None);
+
+        let limit = RateLimit::per_occurrences(1);
+        assert_eq!(check_rate_limit(Some(&limit), location), Some(2));
+        assert_eq!(check_rate_limit(Some(&limit), location), Some(0));
+    }
+}
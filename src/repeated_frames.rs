@@ -0,0 +1,101 @@
+use core::sync::atomic::{AtomicU8, Ordering as AtomicOrdering};
+
+static COLLAPSE_REPEATED_FRAMES: AtomicU8 = AtomicU8::new(CollapseRepeatedFrames::Never.to_u8());
+
+/// A mode of collapsing consecutive repeated unwind context frames printed
+/// during a single unwind.
+///
+/// A panic deep in a recursive function may print hundreds of nearly identical
+/// frames. When collapsing is enabled, only the first frame of a run of
+/// repeated frames is printed, followed by a line like
+/// `... previous frame repeated 312 times`.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::CollapseRepeatedFrames;
+///
+/// unwind_context::set_collapse_repeated_frames(CollapseRepeatedFrames::SameLocation);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum CollapseRepeatedFrames {
+    /// Repeated frames are not collapsed.
+    #[default]
+    Never,
+    /// Consecutive frames created at the same code location are collapsed
+    /// regardless of their context data.
+    SameLocation,
+    /// Consecutive frames created at the same code location and having the
+    /// same formatted context data are collapsed.
+    ///
+    /// The formatted context data is compared exactly, but only up to 256
+    /// bytes, so frames with longer formatted context data are never
+    /// collapsed.
+    SameLocationAndData,
+}
+
+impl CollapseRepeatedFrames {
+    const fn to_u8(self) -> u8 {
+        match self {
+            Self::Never => 0,
+            Self::SameLocation => 1,
+            Self::SameLocationAndData => 2,
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Self::SameLocation,
+            2 => Self::SameLocationAndData,
+            _ => Self::Never,
+        }
+    }
+}
+
+/// Sets the mode of collapsing consecutive repeated unwind context frames
+/// printed during a single unwind.
+///
+/// By default repeated frames are not collapsed.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context, CollapseRepeatedFrames};
+///
+/// fn recursive(depth: usize) {
+///     let _ctx = unwind_context!(fn(depth));
+///     // ...
+///     if let Some(depth) = depth.checked_sub(1) {
+///         recursive(depth);
+///     }
+/// }
+///
+/// unwind_context::set_collapse_repeated_frames(CollapseRepeatedFrames::SameLocation);
+/// // On panic, it will print something like:
+/// // fn recursive(depth: 0)
+/// //     at src/main.rs:4:16
+/// // ... previous frame repeated 312 times
+/// recursive(312);
+/// ```
+#[inline]
+pub fn set_collapse_repeated_frames(mode: CollapseRepeatedFrames) {
+    COLLAPSE_REPEATED_FRAMES.store(mode.to_u8(), AtomicOrdering::Relaxed);
+}
+
+/// Returns the mode of collapsing consecutive repeated unwind context frames.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::CollapseRepeatedFrames;
+///
+/// assert_eq!(
+///     unwind_context::get_collapse_repeated_frames(),
+///     CollapseRepeatedFrames::Never
+/// );
+/// ```
+#[inline]
+#[must_use]
+pub fn get_collapse_repeated_frames() -> CollapseRepeatedFrames {
+    CollapseRepeatedFrames::from_u8(COLLAPSE_REPEATED_FRAMES.load(AtomicOrdering::Relaxed))
+}
@@ -0,0 +1,145 @@
+use crate::{ContextFrames, FrameCapture};
+
+/// An extension trait for error report types that attaches unwind context
+/// frames recorded by a [`FrameCapture`] to the report.
+///
+/// It is implemented for [`anyhow::Error`] with the `anyhow` feature and for
+/// [`eyre::Report`] with the `eyre` feature, as well as for [`Result`] types
+/// with these errors. The frames are taken from the capture and attached as
+/// a [`ContextFrames`] context message, so that the panic context and the
+/// error chain are reported in one place. If no frames were captured, the
+/// report is returned unchanged.
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// # #[cfg(feature = "anyhow")]
+/// # {
+/// use std::panic::catch_unwind;
+///
+/// use unwind_context::{unwind_context, WithContextFrames};
+///
+/// fn func(foo: u32) -> u32 {
+///     let _ctx = unwind_context!(fn func(foo));
+///     assert!(foo < 100, "foo is too large");
+///     foo
+/// }
+///
+/// fn handle(foo: u32) -> anyhow::Result<u32> {
+///     let capture = unwind_context::capture();
+///     catch_unwind(|| func(foo))
+///         .map_err(|_| anyhow::anyhow!("handler panicked"))
+///         .with_context_frames(&capture)
+/// }
+///
+/// assert_eq!(handle(12).unwrap(), 12);
+///
+/// let error = handle(123).unwrap_err();
+/// let message = format!("{error:#}");
+/// assert!(message.starts_with("unwind context:\n    fn func(foo: 123) at "));
+/// assert!(message.ends_with(": handler panicked"));
+/// # }
+#[doc = "```"]
+#[doc = ""]
+/// [`anyhow::Error`]: https://docs.rs/anyhow/latest/anyhow/struct.Error.html
+/// [`eyre::Report`]: https://docs.rs/eyre/latest/eyre/struct.Report.html
+pub trait WithContextFrames: Sized {
+    /// Takes the frames recorded by the given capture and attaches them to
+    /// the error report.
+    #[must_use]
+    fn with_context_frames(self, capture: &FrameCapture) -> Self;
+}
+
+#[cfg(feature = "anyhow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "anyhow")))]
+impl WithContextFrames for anyhow::Error {
+    #[inline]
+    fn with_context_frames(self, capture: &FrameCapture) -> Self {
+        let frames = capture.take_frames();
+        if frames.is_empty() {
+            self
+        } else {
+            self.context(ContextFrames::from(frames))
+        }
+    }
+}
+
+#[cfg(feature = "anyhow")]
+#[cfg_attr(docsrs, doc(cfg(feature = "anyhow")))]
+impl<T> WithContextFrames for Result<T, anyhow::Error> {
+    #[inline]
+    fn with_context_frames(self, capture: &FrameCapture) -> Self {
+        self.map_err(|err| err.with_context_frames(capture))
+    }
+}
+
+#[cfg(feature = "eyre")]
+#[cfg_attr(docsrs, doc(cfg(feature = "eyre")))]
+impl WithContextFrames for eyre::Report {
+    #[inline]
+    fn with_context_frames(self, capture: &FrameCapture) -> Self {
+        let frames = capture.take_frames();
+        if frames.is_empty() {
+            self
+        } else {
+            self.wrap_err(ContextFrames::from(frames))
+        }
+    }
+}
+
+#[cfg(feature = "eyre")]
+#[cfg_attr(docsrs, doc(cfg(feature = "eyre")))]
+impl<T> WithContextFrames for Result<T, eyre::Report> {
+    #[inline]
+    fn with_context_frames(self, capture: &FrameCapture) -> Self {
+        self.map_err(|err| err.with_context_frames(capture))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "anyhow")]
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_anyhow_with_context_frames() {
+        use std::format;
+        use std::panic::catch_unwind;
+        use std::string::ToString;
+
+        use crate::{capture, unwind_context, WithContextFrames};
+
+        let capture = capture();
+        let error = anyhow::anyhow!("Error").with_context_frames(&capture);
+        assert_eq!(format!("{error:#}"), "Error");
+
+        let _ = catch_unwind(|| {
+            let _ctx = unwind_context!(fn func());
+            panic!("Error");
+        });
+        let error = anyhow::anyhow!("Error").with_context_frames(&capture);
+        assert!(format!("{error:#}").starts_with("unwind context:\n    fn func() at "));
+        assert_eq!(error.root_cause().to_string(), "Error");
+        assert!(capture.is_empty());
+    }
+
+    #[cfg(feature = "eyre")]
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_eyre_with_context_frames() {
+        use std::format;
+        use std::panic::catch_unwind;
+        use std::string::ToString;
+
+        use crate::{capture, unwind_context, WithContextFrames};
+
+        let capture = capture();
+        let _ = catch_unwind(|| {
+            let _ctx = unwind_context!(fn func());
+            panic!("Error");
+        });
+        let result: eyre::Result<()> = Err(eyre::eyre!("Error")).with_context_frames(&capture);
+        let error = result.unwrap_err();
+        assert!(format!("{error}").starts_with("unwind context:\n    fn func() at "));
+        assert_eq!(error.root_cause().to_string(), "Error");
+    }
+}
@@ -0,0 +1,294 @@
+use core::fmt::{Result as FmtResult, Write as FmtWrite};
+#[cfg(feature = "std")]
+use std::io::{Result as IoResult, Write as IoWrite};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// A writer that retains the last bytes written to it in a caller-provided
+/// fixed-size buffer.
+///
+/// It implements [`core::fmt::Write`] and, if the `std` feature is enabled,
+/// [`std::io::Write`]. Writes never fail: when the buffer is full, the oldest
+/// bytes are overwritten. The retained output can later be inspected with
+/// [`RingBufferWriter::as_slices`], for example to attach it to a crash
+/// report. It does not allocate and works in `no_std` environments.
+///
+/// Since the oldest bytes are overwritten byte by byte, the retained output
+/// may start in the middle of a line or a multi-byte UTF-8 character.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_fmt, RingBufferWriter, StdPanicDetector};
+///
+/// fn func(foo: u32, bar: &str, ring: &mut RingBufferWriter<'_>) {
+///     let _ctx = unwind_context_with_fmt!(
+///         (fn(foo, bar)),
+///         writer = ring,
+///         panic_detector = StdPanicDetector,
+///     );
+///     // ...
+/// }
+///
+/// let mut buffer = [0; 1024];
+/// let mut ring = RingBufferWriter::new(&mut buffer);
+/// func(123, "abc", &mut ring);
+/// assert!(ring.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct RingBufferWriter<'a> {
+    buffer: &'a mut [u8],
+    start: usize,
+    len: usize,
+}
+
+impl<'a> RingBufferWriter<'a> {
+    /// Creates a new empty `RingBufferWriter` retaining at most
+    /// `buffer.len()` last written bytes in the given buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut buffer = [0; 16];
+    /// let writer = unwind_context::RingBufferWriter::new(&mut buffer);
+    /// ```
+    #[inline]
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self {
+            buffer,
+            start: 0,
+            len: 0,
+        }
+    }
+
+    /// Returns the maximum number of retained bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut buffer = [0; 16];
+    /// let writer = unwind_context::RingBufferWriter::new(&mut buffer);
+    /// assert_eq!(writer.capacity(), 16);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the number of currently retained bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0; 4];
+    /// let mut writer = unwind_context::RingBufferWriter::new(&mut buffer);
+    /// writer.write_str("abcdef").unwrap();
+    /// assert_eq!(writer.len(), 4);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no bytes are retained.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut buffer = [0; 16];
+    /// let writer = unwind_context::RingBufferWriter::new(&mut buffer);
+    /// assert!(writer.is_empty());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discards all retained bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0; 16];
+    /// let mut writer = unwind_context::RingBufferWriter::new(&mut buffer);
+    /// writer.write_str("abc").unwrap();
+    /// writer.clear();
+    /// assert!(writer.is_empty());
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.len = 0;
+    }
+
+    /// Returns the retained bytes as two slices, from the oldest to the
+    /// newest. The second slice is empty unless the retained bytes wrap
+    /// around the end of the buffer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0; 4];
+    /// let mut writer = unwind_context::RingBufferWriter::new(&mut buffer);
+    /// writer.write_str("abc").unwrap();
+    /// writer.write_str("def").unwrap();
+    /// assert_eq!(writer.as_slices(), (&b"cd"[..], &b"ef"[..]));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
+        let end = self.start.saturating_add(self.len);
+        if end <= self.buffer.len() {
+            (&self.buffer[self.start..end], &[])
+        } else {
+            (
+                &self.buffer[self.start..],
+                &self.buffer[..end.saturating_sub(self.buffer.len())],
+            )
+        }
+    }
+
+    /// Returns the retained bytes copied into a new vector, from the oldest to
+    /// the newest.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut buffer = [0; 4];
+    /// let mut writer = unwind_context::RingBufferWriter::new(&mut buffer);
+    /// writer.write_str("abcdef").unwrap();
+    /// assert_eq!(writer.to_vec(), b"cdef");
+    /// ```
+    #[cfg(feature = "std")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+    #[inline]
+    #[must_use]
+    pub fn to_vec(&self) -> Vec<u8> {
+        let (first, second) = self.as_slices();
+        let mut result = Vec::with_capacity(self.len);
+        result.extend_from_slice(first);
+        result.extend_from_slice(second);
+        result
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        let capacity = self.buffer.len();
+        if capacity == 0 {
+            return;
+        }
+        let bytes = &bytes[bytes.len().saturating_sub(capacity)..];
+
+        let end = self.start.saturating_add(self.len);
+        let end = if end >= capacity {
+            end.saturating_sub(capacity)
+        } else {
+            end
+        };
+        let first_len = bytes.len().min(capacity.saturating_sub(end));
+        let (first, second) = bytes.split_at(first_len);
+        self.buffer[end..end.saturating_add(first_len)].copy_from_slice(first);
+        self.buffer[..second.len()].copy_from_slice(second);
+
+        let len = self.len.saturating_add(bytes.len());
+        if len > capacity {
+            let start = self.start.saturating_add(len.saturating_sub(capacity));
+            self.start = if start >= capacity {
+                start.saturating_sub(capacity)
+            } else {
+                start
+            };
+            self.len = capacity;
+        } else {
+            self.len = len;
+        }
+    }
+}
+
+impl FmtWrite for RingBufferWriter<'_> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        self.push(s.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl IoWrite for RingBufferWriter<'_> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.push(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write as FmtWrite;
+
+    use crate::RingBufferWriter;
+
+    #[test]
+    fn test_ring_buffer_writer_fmt() {
+        let mut buffer = [0; 8];
+        let mut writer = RingBufferWriter::new(&mut buffer);
+        assert_eq!(writer.as_slices(), (&b""[..], &b""[..]));
+
+        assert_eq!(writer.write_str("abc"), Ok(()));
+        assert_eq!(writer.as_slices(), (&b"abc"[..], &b""[..]));
+
+        assert_eq!(writer.write_str("defgh"), Ok(()));
+        assert_eq!(writer.as_slices(), (&b"abcdefgh"[..], &b""[..]));
+
+        assert_eq!(writer.write_str("ij"), Ok(()));
+        assert_eq!(writer.as_slices(), (&b"cdefgh"[..], &b"ij"[..]));
+
+        assert_eq!(writer.write_str("klmnop"), Ok(()));
+        assert_eq!(writer.as_slices(), (&b"ijklmnop"[..], &b""[..]));
+
+        assert_eq!(writer.write_str("0123456789"), Ok(()));
+        assert_eq!(writer.as_slices(), (&b"23456789"[..], &b""[..]));
+
+        assert_eq!(writer.write_str("ab"), Ok(()));
+        assert_eq!(writer.write_str("0123456789"), Ok(()));
+        assert_eq!(writer.as_slices(), (&b"234567"[..], &b"89"[..]));
+        assert_eq!(writer.len(), 8);
+
+        writer.clear();
+        assert!(writer.is_empty());
+        assert_eq!(writer.as_slices(), (&b""[..], &b""[..]));
+
+        let mut writer = RingBufferWriter::new(&mut []);
+        assert_eq!(writer.write_str("abc"), Ok(()));
+        assert!(writer.is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_ring_buffer_writer_io() {
+        use std::io::Write as IoWrite;
+
+        let mut buffer = [0; 8];
+        let mut writer = RingBufferWriter::new(&mut buffer);
+        assert!(IoWrite::write_all(&mut writer, b"abc").is_ok());
+        assert!(IoWrite::write_all(&mut writer, b"123456").is_ok());
+        assert!(IoWrite::flush(&mut writer).is_ok());
+        assert_eq!(writer.to_vec(), b"bc123456");
+    }
+}
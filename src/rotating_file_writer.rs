@@ -0,0 +1,257 @@
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, LineWriter, Result as IoResult, Write as IoWrite};
+use std::path::{Path, PathBuf};
+use std::string::ToString;
+
+/// A [`std::io::Write`] writer that appends to a file and rotates it when it
+/// exceeds the configured size.
+///
+/// It is created with the [`rotating_file_writer`] function. See its
+/// documentation for more details.
+#[derive(Debug)]
+pub struct RotatingFileWriter<P: AsRef<Path>> {
+    path: P,
+    max_size: u64,
+    max_files: usize,
+    file: Option<LineWriter<File>>,
+    size: u64,
+}
+
+/// Creates a [`std::io::Write`] writer suitable for the `writer =` parameter of
+/// macros like [`unwind_context_with_io`] that writes to a file at the given
+/// path and rotates it when it exceeds `max_size` bytes.
+///
+/// The file is opened lazily the same way as with [`file_writer`]. Before a
+/// write that would make a non-empty file exceed `max_size` bytes, the file
+/// is rotated: `path` is renamed to `path.1`, `path.1` to `path.2`, and so on,
+/// keeping at most `max_files` old files and removing the oldest one. If
+/// `max_files` is zero, the file is simply truncated. A single write is never
+/// split between files, so a file can exceed `max_size` only when a single
+/// write is larger than `max_size`.
+///
+/// Context scope guards write every frame with a single write call only if
+/// it fits into the 1024-byte formatting buffer of [`IoSink`], so such frames
+/// are kept whole in one file. Longer frames are written with several write
+/// calls and may be split between two files. Either way, repeated panics in a
+/// loop cannot use more than about `max_size * (max_files + 1)` bytes of disk
+/// space.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context_with_io;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!(
+///         (fn(foo, bar)),
+///         writer = unwind_context::rotating_file_writer("target/crash.log", 1 << 20, 3),
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+/// [`file_writer`]: crate::file_writer
+/// [`IoSink`]: crate::IoSink
+#[inline]
+pub fn rotating_file_writer<P: AsRef<Path>>(
+    path: P,
+    max_size: u64,
+    max_files: usize,
+) -> RotatingFileWriter<P> {
+    RotatingFileWriter {
+        path,
+        max_size,
+        max_files,
+        file: None,
+        size: 0,
+    }
+}
+
+impl<P: AsRef<Path>> RotatingFileWriter<P> {
+    /// Returns the path of the current file.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::rotating_file_writer("crash.log", 1024, 3);
+    /// assert_eq!(writer.path(), &"crash.log");
+    /// ```
+    #[inline]
+    pub fn path(&self) -> &P {
+        &self.path
+    }
+
+    /// Returns the maximum size of a file in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::rotating_file_writer("crash.log", 1024, 3);
+    /// assert_eq!(writer.max_size(), 1024);
+    /// ```
+    #[inline]
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// Returns the maximum number of rotated old files kept.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::rotating_file_writer("crash.log", 1024, 3);
+    /// assert_eq!(writer.max_files(), 3);
+    /// ```
+    #[inline]
+    pub fn max_files(&self) -> usize {
+        self.max_files
+    }
+
+    fn file(&mut self, len: usize) -> IoResult<&mut LineWriter<File>> {
+        let path = self.path.as_ref();
+        if self.file.is_none() {
+            if let Some(parent) = path.parent() {
+                if !parent.as_os_str().is_empty() {
+                    fs::create_dir_all(parent)?;
+                }
+            }
+            self.size = match fs::metadata(path) {
+                Ok(metadata) => metadata.len(),
+                Err(err) if err.kind() == ErrorKind::NotFound => 0,
+                Err(err) => return Err(err),
+            };
+        }
+
+        let len = u64::try_from(len).unwrap_or(u64::MAX);
+        if self.size > 0 && self.size.saturating_add(len) > self.max_size {
+            if let Some(mut file) = self.file.take() {
+                file.flush()?;
+            }
+            rotate(path, self.max_files)?;
+            self.size = 0;
+        }
+
+        if let Some(ref mut file) = self.file {
+            return Ok(file);
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(self.file.insert(LineWriter::new(file)))
+    }
+}
+
+impl<P: AsRef<Path>> IoWrite for RotatingFileWriter<P> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        let written = self.file(buf.len())?.write(buf)?;
+        self.size = self
+            .size
+            .saturating_add(u64::try_from(written).unwrap_or(u64::MAX));
+        Ok(written)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        self.file(buf.len())?.write_all(buf)?;
+        self.size = self
+            .size
+            .saturating_add(u64::try_from(buf.len()).unwrap_or(u64::MAX));
+        Ok(())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        match self.file {
+            Some(ref mut file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut rotated = OsString::from(path.as_os_str());
+    rotated.push(".");
+    rotated.push(index.to_string());
+    PathBuf::from(rotated)
+}
+
+fn remove_if_exists(path: &Path) -> IoResult<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+fn rename_if_exists(from: &Path, to: &Path) -> IoResult<()> {
+    match fs::rename(from, to) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+fn rotate(path: &Path, max_files: usize) -> IoResult<()> {
+    if max_files == 0 {
+        return remove_if_exists(path);
+    }
+    remove_if_exists(&rotated_path(path, max_files))?;
+    for index in (1..max_files).rev() {
+        rename_if_exists(
+            &rotated_path(path, index),
+            &rotated_path(path, index.saturating_add(1)),
+        )?;
+    }
+    rename_if_exists(path, &rotated_path(path, 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as IoWrite;
+    use std::{format, fs};
+
+    use crate::rotating_file_writer;
+
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_rotating_file_writer() {
+        let dir = std::env::temp_dir().join(format!(
+            "unwind-context-test-rotating-file-writer-{}",
+            std::process::id()
+        ));
+        let path = dir.join("crash.log");
+        let path_1 = dir.join("crash.log.1");
+        let path_2 = dir.join("crash.log.2");
+        let path_3 = dir.join("crash.log.3");
+        let _ = fs::remove_dir_all(&dir);
+
+        let mut writer = rotating_file_writer(&path, 8, 2);
+        assert!(writer.flush().is_ok());
+        assert!(!dir.exists());
+
+        assert!(writer.write_all(b"abc\n").is_ok());
+        assert!(writer.write_all(b"def\n").is_ok());
+        assert!(writer.write_all(b"ghi\n").is_ok());
+        drop(writer);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "ghi\n");
+        assert_eq!(fs::read_to_string(&path_1).unwrap(), "abc\ndef\n");
+        assert!(!path_2.exists());
+
+        let mut writer = rotating_file_writer(&path, 8, 2);
+        assert!(writer.write_all(b"jkl\n").is_ok());
+        assert!(writer.write_all(b"too long line\n").is_ok());
+        assert!(writer.write_all(b"mno\n").is_ok());
+        drop(writer);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "mno\n");
+        assert_eq!(fs::read_to_string(&path_1).unwrap(), "too long line\n");
+        assert_eq!(fs::read_to_string(&path_2).unwrap(), "ghi\njkl\n");
+        assert!(!path_3.exists());
+
+        let mut writer = rotating_file_writer(&path, 4, 0);
+        assert!(writer.write_all(b"pqr\n").is_ok());
+        drop(writer);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "pqr\n");
+        assert_eq!(fs::read_to_string(&path_1).unwrap(), "too long line\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
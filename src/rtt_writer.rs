@@ -0,0 +1,101 @@
+use core::fmt::{Result as FmtResult, Write as FmtWrite};
+
+/// A [`core::fmt::Write`] writer that writes to the RTT print channel using
+/// [`rtt-target`].
+///
+/// It allows using the [`unwind_context_with_fmt`] macro on bare-metal
+/// targets with a debug probe supporting SEGGER Real-Time Transfer. It writes
+/// to the given virtual terminal of the channel used by the `rprint` macro,
+/// which must be set up with `rtt_init_print` or `set_print_channel`
+/// beforehand, otherwise the output is silently discarded.
+///
+/// An [`rtt_target::UpChannel`] can also be used as a writer directly.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_fmt, PanicDetector, RttWriter};
+///
+/// #[derive(Clone, Copy, Debug)]
+/// struct MyPanicDetector;
+///
+/// impl PanicDetector for MyPanicDetector {
+///     fn is_panicking(&self) -> bool {
+///         // Check a flag set by the panic handler.
+///         false
+///     }
+/// }
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_fmt!(
+///         (fn(foo, bar)),
+///         writer = RttWriter::default(),
+///         panic_detector = MyPanicDetector,
+///         color_scheme = None,
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// [`rtt-target`]: https://crates.io/crates/rtt-target
+/// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct RttWriter {
+    terminal: u8,
+}
+
+impl RttWriter {
+    /// Creates a new `RttWriter` writing to the given virtual terminal of the
+    /// RTT print channel.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::RttWriter::new(1);
+    /// assert_eq!(writer.terminal(), 1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(terminal: u8) -> Self {
+        Self { terminal }
+    }
+
+    /// Returns the virtual terminal number the writer writes to.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// assert_eq!(unwind_context::RttWriter::default().terminal(), 0);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn terminal(&self) -> u8 {
+        self.terminal
+    }
+}
+
+impl FmtWrite for RttWriter {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        rtt_target::with_terminal_channel(|channel| {
+            let _ = channel.write(self.terminal).write_str(s);
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write as FmtWrite;
+
+    use crate::RttWriter;
+
+    #[test]
+    fn test_rtt_writer() {
+        assert_eq!(RttWriter::default().terminal(), 0);
+        assert_eq!(RttWriter::new(2).terminal(), 2);
+
+        // The output is discarded if the print channel is not set up.
+        let mut writer = RttWriter::new(1);
+        assert!(write!(writer, "fn func(foo: {})", 1).is_ok());
+    }
+}
@@ -0,0 +1,89 @@
+use core::fmt::Debug;
+use core::panic::Location;
+
+use crate::{DebugAnsiColored, DefaultWriter, StdPanicDetector, UnwindContextWithIo};
+
+/// Runs the given closure inside a context scope guard with the given context
+/// data and returns its result.
+///
+/// It is a function form of the [`unwind_context`] macro for situations where
+/// inserting a `let _ctx = ...` binding is awkward, like combinator chains or
+/// generated code. The guard is created before the closure is called and
+/// dropped after it returns, so it can not be dropped early by mistake.
+///
+/// It uses [`DefaultWriter`] writer, [`StdPanicDetector`] panic detector, and
+/// a color scheme determined by the [`get_default_color_scheme_if_enabled`]
+/// function, like the [`unwind_context`] macro. The caller location is used
+/// as the frame location.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, run_with_unwind_context};
+///
+/// let values = ["1", "2", "3"];
+/// let sum: u32 = values
+///     .iter()
+///     .map(|value| {
+///         run_with_unwind_context(build_unwind_context_data!(fn parse(value)), || {
+///             value.parse::<u32>().unwrap()
+///         })
+///     })
+///     .sum();
+/// assert_eq!(sum, 6);
+/// ```
+///
+/// [`unwind_context`]: crate::unwind_context
+/// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
+#[inline]
+#[track_caller]
+pub fn run_with_unwind_context<T, F, R>(data: T, f: F) -> R
+where
+    T: Debug + DebugAnsiColored,
+    F: FnOnce() -> R,
+{
+    let _ctx = UnwindContextWithIo::new_default(data, DefaultWriter, StdPanicDetector)
+        .with_location(Location::caller())
+        .trace_entry();
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use core::panic::AssertUnwindSafe;
+    use std::panic::catch_unwind;
+
+    use crate::{build_unwind_context_data, run_with_unwind_context};
+
+    #[test]
+    fn test_run_with_unwind_context() {
+        let capture = crate::capture();
+        let value = 2;
+        let result =
+            run_with_unwind_context(build_unwind_context_data!(fn func(value)), || value * 3);
+        assert_eq!(result, 6);
+        assert!(capture.take_frames().is_empty());
+    }
+
+    #[test]
+    fn test_run_with_unwind_context_panic() {
+        let capture = crate::capture();
+        let value = 3;
+        let line = line!() + 2;
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            run_with_unwind_context(build_unwind_context_data!(fn func(value)), || {
+                let _ctx = crate::unwind_context!(fn inner());
+                assert_ne!(value, 3, "closure panic");
+            });
+        }));
+        assert!(result.is_err());
+
+        let frames = capture.take_frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].func_name, Some("inner"));
+        assert_eq!(frames[1].func_name, Some("func"));
+        assert_eq!(frames[1].arg("value"), Some("3"));
+        assert_eq!(frames[1].location.line(), line);
+        assert_eq!(frames[1].location.file(), file!());
+    }
+}
@@ -0,0 +1,113 @@
+use core::num::NonZeroUsize;
+use core::panic::Location;
+use core::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+static SAMPLE_RATE: AtomicUsize = AtomicUsize::new(1);
+
+/// The numbers of frames to skip before the next sampled one of every code
+/// location.
+type LocationSkips = Mutex<HashMap<&'static Location<'static>, usize>>;
+
+static LOCATION_SKIPS: OnceLock<LocationSkips> = OnceLock::new();
+
+/// Sets the sample rate of frames of all context scope guards, or removes it
+/// if `None` is given.
+///
+/// With a sample rate `n`, only the first and then every `n`-th frame of every
+/// code location is printed, and the others are silently skipped. It keeps
+/// logs of high-frequency panic sites, like ones in fuzzing or chaos testing,
+/// manageable while retaining representative context. Unlike [`RateLimit`],
+/// the number of skipped frames is not printed. The occurrences are counted
+/// separately for every code location. Context scope guards with the
+/// `sample_rate = ...` macro parameter use their own sample rate instead.
+///
+/// Zero is treated as one, which means every frame is printed. Skipped frames
+/// are not numbered, not recorded with a [`FrameCapture`], and not passed to a
+/// [`FrameSink`].
+///
+/// By default every frame is printed.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn fuzz_target(input: &[u8]) {
+///     let _ctx = unwind_context!(fn(input));
+///     // ...
+/// }
+///
+/// unwind_context::set_sample_rate(Some(100));
+/// fuzz_target(b"abc");
+/// unwind_context::set_sample_rate(None);
+/// ```
+///
+/// [`RateLimit`]: crate::RateLimit
+/// [`FrameCapture`]: crate::FrameCapture
+/// [`FrameSink`]: crate::FrameSink
+#[inline]
+pub fn set_sample_rate(rate: Option<usize>) {
+    SAMPLE_RATE.store(rate.unwrap_or(1).max(1), AtomicOrdering::Relaxed);
+}
+
+/// Returns the sample rate of frames of all context scope guards, if set.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(unwind_context::get_sample_rate(), None);
+/// ```
+#[inline]
+#[must_use]
+pub fn get_sample_rate() -> Option<usize> {
+    match SAMPLE_RATE.load(AtomicOrdering::Relaxed) {
+        1 => None,
+        rate => Some(rate),
+    }
+}
+
+/// Registers an occurrence of a frame of the given code location and returns
+/// `true` if it is sampled and should be printed.
+pub(crate) fn is_sampled(rate: Option<NonZeroUsize>, location: &'static Location<'static>) -> bool {
+    let rate = rate.map_or_else(
+        || SAMPLE_RATE.load(AtomicOrdering::Relaxed),
+        NonZeroUsize::get,
+    );
+    if rate <= 1 {
+        return true;
+    }
+    let mut skips = LOCATION_SKIPS
+        .get_or_init(LocationSkips::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    let remaining = skips.entry(location).or_insert(0);
+    if *remaining == 0 {
+        *remaining = rate.saturating_sub(1);
+        true
+    } else {
+        *remaining = remaining.saturating_sub(1);
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::num::NonZeroUsize;
+    use core::panic::Location;
+
+    use crate::sampling::is_sampled;
+
+    #[test]
+    fn test_is_sampled() {
+        let location = Location::caller();
+        let rate = NonZeroUsize::new(3);
+        let results: [_; 7] = core::array::from_fn(|_| is_sampled(rate, location));
+        assert_eq!(results, [true, false, false, true, false, false, true]);
+
+        let rate = NonZeroUsize::new(1);
+        assert!(is_sampled(rate, location));
+        assert!(is_sampled(rate, location));
+    }
+}
@@ -0,0 +1,112 @@
+use core::fmt::{Debug, Error as FmtError, Formatter, Result as FmtResult, Write as FmtWrite};
+
+use cortex_m_semihosting::hio::{self, HostStream};
+
+/// A [`core::fmt::Write`] writer that writes to the host standard output with
+/// ARM semihosting using [`cortex-m-semihosting`].
+///
+/// It allows using the [`unwind_context_with_fmt`] macro on bare-metal
+/// Cortex-M targets running under a debugger or an emulator supporting
+/// semihosting, like `OpenOCD`, `probe-rs`, or QEMU. The host standard output
+/// handle is opened on the first write and reused afterwards.
+///
+/// Note that semihosting calls halt the processor if no debugger is attached,
+/// and they are not supported on other targets.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_fmt, PanicDetector, SemihostingWriter};
+///
+/// #[derive(Clone, Copy, Debug)]
+/// struct MyPanicDetector;
+///
+/// impl PanicDetector for MyPanicDetector {
+///     fn is_panicking(&self) -> bool {
+///         // Check a flag set by the panic handler.
+///         false
+///     }
+/// }
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_fmt!(
+///         (fn(foo, bar)),
+///         writer = SemihostingWriter::new(),
+///         panic_detector = MyPanicDetector,
+///         color_scheme = None,
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// [`cortex-m-semihosting`]: https://crates.io/crates/cortex-m-semihosting
+/// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+#[derive(Copy, Clone, Default)]
+pub struct SemihostingWriter {
+    stream: Option<HostStream>,
+}
+
+impl SemihostingWriter {
+    /// Creates a new `SemihostingWriter` writing to the host standard output.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::SemihostingWriter::new();
+    /// assert!(!writer.is_open());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { stream: None }
+    }
+
+    /// Returns `true` if the host standard output handle is already opened.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::SemihostingWriter::default();
+    /// assert!(!writer.is_open());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn is_open(&self) -> bool {
+        self.stream.is_some()
+    }
+}
+
+impl Debug for SemihostingWriter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.debug_struct("SemihostingWriter")
+            .field("is_open", &self.is_open())
+            .finish()
+    }
+}
+
+impl FmtWrite for SemihostingWriter {
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            stream @ None => stream.insert(hio::hstdout().map_err(|()| FmtError)?),
+        };
+        stream.write_all(s.as_bytes()).map_err(|()| FmtError)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::format;
+
+    use crate::SemihostingWriter;
+
+    #[test]
+    fn test_semihosting_writer() {
+        assert!(!SemihostingWriter::new().is_open());
+        assert!(!SemihostingWriter::default().is_open());
+        assert_eq!(
+            format!("{:?}", SemihostingWriter::new()),
+            "SemihostingWriter { is_open: false }"
+        );
+    }
+}
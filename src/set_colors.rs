@@ -1,16 +1,30 @@
-use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+#[cfg(feature = "std")]
+use core::cell::Cell;
+#[cfg(feature = "std")]
+use core::marker::PhantomData;
+use core::sync::atomic::{AtomicBool, AtomicU8, Ordering as AtomicOrdering};
 
 #[cfg(feature = "custom-default-colors")]
 use atomic_ref::AtomicRef;
 
-use crate::{AnsiColorScheme, DEFAULT_DEFAULT_COLOR_SCHEME};
+use crate::{AnsiColorScheme, ColorDepth, TerminalBackground, LIGHT_BACKGROUND_COLOR_SCHEME};
 
 static SHOULD_COLORIZE: AtomicBool = AtomicBool::new(false);
 
+static COLOR_DEPTH: AtomicU8 = AtomicU8::new(ColorDepth::Basic.to_u8());
+
+static IS_BACKGROUND_LIGHT: AtomicBool = AtomicBool::new(false);
+
 #[cfg(feature = "custom-default-colors")]
 #[cfg_attr(docsrs, doc(cfg(feature = "custom-default-colors")))]
 static DEFAULT_COLOR_SCHEME: AtomicRef<'_, AnsiColorScheme> = AtomicRef::new(None);
 
+#[cfg(feature = "std")]
+std::thread_local! {
+    static THREAD_SHOULD_COLORIZE: Cell<Option<bool>> = const { Cell::new(None) };
+    static THREAD_COLOR_SCHEME: Cell<Option<&'static AnsiColorScheme>> = const { Cell::new(None) };
+}
+
 /// Enables or disables ANSI colorization.
 ///
 /// Note that this function does not check whether the terminal supports
@@ -62,6 +76,9 @@ pub use set_colors_enabled as set_ansi_colors_enabled;
 
 /// Returns `true` if ANSI colors were enabled before.
 ///
+/// If the `std` feature is enabled, the current thread override set with
+/// [`set_thread_colors_enabled`] takes precedence over the global setting.
+///
 /// By default colorization is disabled.
 ///
 /// # Examples
@@ -73,11 +90,178 @@ pub use set_colors_enabled as set_ansi_colors_enabled;
 ///     eprintln!("colorization is disabled");
 /// }
 /// ```
+///
+/// [`set_thread_colors_enabled`]: crate::set_thread_colors_enabled
 #[inline]
 pub fn are_colors_enabled() -> bool {
+    #[cfg(feature = "std")]
+    if let Some(enabled) = get_thread_colors_enabled() {
+        return enabled;
+    }
     SHOULD_COLORIZE.load(AtomicOrdering::Relaxed)
 }
 
+/// Enables or disables ANSI colorization for the current thread only, or
+/// removes the current thread override if `None` is given.
+///
+/// The thread override takes precedence over the global setting changed with
+/// [`set_colors_enabled`] or [`enable_colors_if_supported`]. It allows tests
+/// running in parallel to configure colorization independently without
+/// serializing access to the global state.
+///
+/// By default no thread override is set.
+///
+/// # Examples
+///
+/// ```rust
+/// unwind_context::set_thread_colors_enabled(Some(true));
+/// assert!(unwind_context::are_colors_enabled());
+///
+/// let handle = std::thread::spawn(|| unwind_context::are_colors_enabled());
+/// assert!(!handle.join().unwrap());
+///
+/// unwind_context::set_thread_colors_enabled(None);
+/// assert!(!unwind_context::are_colors_enabled());
+/// ```
+///
+/// [`enable_colors_if_supported`]: crate::enable_colors_if_supported
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_thread_colors_enabled(enabled: Option<bool>) {
+    let _ = THREAD_SHOULD_COLORIZE.try_with(|cell| cell.set(enabled));
+}
+
+/// Returns the current thread colorization override set with
+/// [`set_thread_colors_enabled`], if any.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(unwind_context::get_thread_colors_enabled(), None);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn get_thread_colors_enabled() -> Option<bool> {
+    THREAD_SHOULD_COLORIZE
+        .try_with(Cell::get)
+        .unwrap_or_default()
+}
+
+/// Sets the ANSI color scheme used by default for the current thread only, or
+/// removes the current thread override if `None` is given.
+///
+/// The thread override takes precedence over the global default color scheme
+/// returned by [`get_default_color_scheme`]. Like the global one, it is used
+/// only if colorization is enabled and no color scheme is specified with the
+/// `color_scheme = ...` macro parameter.
+///
+/// By default no thread override is set.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::themes::MONOKAI;
+///
+/// unwind_context::set_thread_color_scheme(Some(&MONOKAI));
+/// assert_eq!(unwind_context::get_default_color_scheme(), &MONOKAI);
+/// unwind_context::set_thread_color_scheme(None);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn set_thread_color_scheme(color_scheme: Option<&'static AnsiColorScheme>) {
+    let _ = THREAD_COLOR_SCHEME.try_with(|cell| cell.set(color_scheme));
+}
+
+/// Returns the current thread ANSI color scheme override set with
+/// [`set_thread_color_scheme`], if any.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(unwind_context::get_thread_color_scheme().is_none());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+#[must_use]
+pub fn get_thread_color_scheme() -> Option<&'static AnsiColorScheme> {
+    THREAD_COLOR_SCHEME.try_with(Cell::get).unwrap_or_default()
+}
+
+/// A guard that overrides the ANSI color scheme of the current thread while it
+/// is alive.
+///
+/// It is created with the [`with_color_scheme`] function. See its
+/// documentation for more details.
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[derive(Debug)]
+#[must_use = "\
+    if unused, the `ColorSchemeGuard` will immediately drop,
+    consider binding the `ColorSchemeGuard` like `let _guard = ...`.
+"]
+pub struct ColorSchemeGuard {
+    previous: Option<&'static AnsiColorScheme>,
+    _not_send: PhantomData<*const ()>,
+}
+
+/// Overrides the ANSI color scheme of the current thread until the returned
+/// [`ColorSchemeGuard`] is dropped.
+///
+/// It allows a library section to temporarily render frames with its own
+/// palette without permanently changing the global or thread state. The
+/// previous thread override set with [`set_thread_color_scheme`], if any, is
+/// restored when the guard is dropped, so guards can be nested. Nested guards
+/// are expected to be dropped in the reverse order of their creation.
+///
+/// Like [`set_thread_color_scheme`], it does not enable colorization itself.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::themes::{MONOKAI, SOLARIZED_DARK};
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// let guard = unwind_context::with_color_scheme(&MONOKAI);
+/// assert_eq!(unwind_context::get_default_color_scheme(), &MONOKAI);
+/// {
+///     let _guard = unwind_context::with_color_scheme(&SOLARIZED_DARK);
+///     assert_eq!(unwind_context::get_default_color_scheme(), &SOLARIZED_DARK);
+///     func(123, "abc");
+/// }
+/// assert_eq!(unwind_context::get_default_color_scheme(), &MONOKAI);
+/// drop(guard);
+/// assert!(unwind_context::get_thread_color_scheme().is_none());
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn with_color_scheme(color_scheme: &'static AnsiColorScheme) -> ColorSchemeGuard {
+    let previous = get_thread_color_scheme();
+    set_thread_color_scheme(Some(color_scheme));
+    ColorSchemeGuard {
+        previous,
+        _not_send: PhantomData,
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for ColorSchemeGuard {
+    #[inline]
+    fn drop(&mut self) {
+        set_thread_color_scheme(self.previous);
+    }
+}
+
 #[doc(hidden)]
 #[deprecated(since = "0.2.0", note = "renamed to `are_colors_enabled`.")]
 pub use are_colors_enabled as are_ansi_colors_enabled;
@@ -87,8 +271,19 @@ pub use are_colors_enabled as are_ansi_colors_enabled;
 /// Enables ANSI colors if supported by the terminal for stderr stream for all
 /// threads.
 ///
-/// It checks for a basic colors support. By default, it enables 16-ANSI-color
-/// colorization if the colors have not changed.
+/// Use [`enable_colors_if_supported_for`] to check other streams.
+///
+/// It also sets the color depth with [`set_color_depth`] to the richest
+/// palette supported by the terminal, so that the 256-color or truecolor
+/// default color scheme is used if no custom default color scheme is set. If
+/// the `std` feature is enabled, it also sets the terminal background with
+/// [`set_terminal_background`] if it is detected by
+/// [`detect_terminal_background`].
+///
+/// If the `windows-console` feature is enabled, it also enables processing of
+/// ANSI escape sequences by older Windows consoles with
+/// [`enable_virtual_terminal_processing`] and does not enable colors if it
+/// fails.
 ///
 /// This function uses [`supports-color`] crate to detect color support.
 /// [`supports-color`] crate takes the `NO_COLOR` and `FORCE_COLOR` environment
@@ -130,19 +325,295 @@ pub use are_colors_enabled as are_ansi_colors_enabled;
 /// ```
 ///
 /// [`supports-color`]: https://crates.io/crates/supports-color
+/// [`enable_virtual_terminal_processing`]: crate::enable_virtual_terminal_processing
 #[inline]
 pub fn enable_colors_if_supported() {
-    use supports_color::Stream;
-    if supports_color::on(Stream::Stderr).is_some() {
+    enable_colors_if_supported_for(TerminalStream::Stderr);
+}
+
+#[cfg(feature = "detect-color-support")]
+#[cfg_attr(docsrs, doc(cfg(feature = "detect-color-support")))]
+/// Enables ANSI colors if supported by the terminal for the given stream for
+/// all threads.
+///
+/// It is similar to [`enable_colors_if_supported`], but checks the given
+/// stream instead of stderr. It is useful if the unwind context is written to
+/// stdout, for example with the `writer = std::io::stdout()` macro parameter.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, TerminalStream};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!((fn(foo, bar)), writer = std::io::stdout());
+///     // ...
+/// }
+///
+/// unwind_context::enable_colors_if_supported_for(TerminalStream::Stdout);
+/// func(123, "abc");
+/// # unwind_context::set_colors_enabled(false);
+/// ```
+#[inline]
+pub fn enable_colors_if_supported_for(stream: TerminalStream) {
+    if let Some(level) = supports_color::on(stream.to_supports_color_stream()) {
+        set_color_depth(if level.has_16m {
+            ColorDepth::TrueColor
+        } else if level.has_256 {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Basic
+        });
+        #[cfg(feature = "windows-console")]
+        if !crate::windows_console::enable_virtual_terminal_processing_for(stream) {
+            return;
+        }
+        #[cfg(feature = "std")]
+        if let Some(background) = detect_terminal_background() {
+            set_terminal_background(background);
+        }
         set_colors_enabled(true);
     }
 }
 
+#[cfg(feature = "detect-color-support")]
+#[cfg_attr(docsrs, doc(cfg(feature = "detect-color-support")))]
+/// Returns the default ANSI color scheme if the terminal of the given stream
+/// supports colors, `None` otherwise.
+///
+/// Unlike [`get_default_color_scheme_if_enabled`], it does not depend on the
+/// global colorization setting, so it can be used to choose colorization for a
+/// specific context scope guard with the `color_scheme = ...` macro parameter.
+/// The color support detection result is cached for every stream.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, TerminalStream};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!(
+///         (fn(foo, bar)),
+///         writer = std::io::stdout(),
+///         color_scheme = unwind_context::color_scheme_if_supported(TerminalStream::Stdout),
+///     );
+///     // ...
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn color_scheme_if_supported(stream: TerminalStream) -> Option<&'static AnsiColorScheme> {
+    supports_color::on_cached(stream.to_supports_color_stream()).map(|_| get_default_color_scheme())
+}
+
+#[cfg(feature = "detect-color-support")]
+#[cfg_attr(docsrs, doc(cfg(feature = "detect-color-support")))]
+/// A standard output stream whose terminal color support is detected.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::TerminalStream;
+///
+/// unwind_context::enable_colors_if_supported_for(TerminalStream::Stdout);
+/// # unwind_context::set_colors_enabled(false);
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub enum TerminalStream {
+    /// The standard output stream.
+    Stdout,
+    /// The standard error stream.
+    #[default]
+    Stderr,
+}
+
+#[cfg(feature = "detect-color-support")]
+impl TerminalStream {
+    fn to_supports_color_stream(self) -> supports_color::Stream {
+        match self {
+            Self::Stdout => supports_color::Stream::Stdout,
+            Self::Stderr => supports_color::Stream::Stderr,
+        }
+    }
+}
+
 #[cfg(feature = "detect-color-support")]
 #[doc(hidden)]
 #[deprecated(since = "0.2.0", note = "renamed to `enable_colors_if_supported`.")]
 pub use enable_colors_if_supported as enable_ansi_colors_if_supported;
 
+/// Enables ANSI colors for all threads according to the `NO_COLOR`,
+/// `FORCE_COLOR`, and `TERM` environment variables.
+///
+/// It is a lightweight alternative to [`enable_colors_if_supported`] that does
+/// not require any additional dependencies. Colors are enabled if
+/// `FORCE_COLOR` is set to a non-empty value other than `0` or `false`.
+/// Otherwise, they are enabled only if `NO_COLOR` is not set to a non-empty
+/// value, `TERM` is not `dumb`, and stderr is a terminal.
+///
+/// Like [`enable_colors_if_supported`], it never disables colors enabled
+/// before and does not detect the color depth.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::enable_colors_from_env();
+/// func(123, "abc");
+/// ```
+///
+/// [`enable_colors_if_supported`]: crate::enable_colors_if_supported
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[inline]
+pub fn enable_colors_from_env() {
+    use std::env::var_os;
+    use std::io::IsTerminal;
+
+    let should_force = var_os("FORCE_COLOR")
+        .is_some_and(|value| !value.is_empty() && value != "0" && value != "false");
+    let should_colorize = should_force
+        || (var_os("NO_COLOR").map_or(true, |value| value.is_empty())
+            && var_os("TERM").map_or(true, |value| value != "dumb")
+            && std::io::stderr().is_terminal());
+    if should_colorize {
+        set_colors_enabled(true);
+    }
+}
+
+/// Sets the number of colors supported by the terminal for all threads.
+///
+/// The color depth chooses the default ANSI color scheme used if colorization
+/// is enabled and no custom default color scheme is set: the
+/// [`DEFAULT_DEFAULT_COLOR_SCHEME`], the [`ANSI_256_COLOR_SCHEME`], or the
+/// [`TRUECOLOR_COLOR_SCHEME`]. It is set automatically by
+/// [`enable_colors_if_supported`].
+///
+/// By default the basic 16-color palette is used.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{ColorDepth, TRUECOLOR_COLOR_SCHEME};
+///
+/// unwind_context::set_color_depth(ColorDepth::TrueColor);
+/// # #[cfg(not(feature = "custom-default-colors"))]
+/// assert_eq!(
+///     unwind_context::get_default_color_scheme(),
+///     &TRUECOLOR_COLOR_SCHEME
+/// );
+/// # unwind_context::set_color_depth(ColorDepth::Basic);
+/// ```
+///
+/// [`DEFAULT_DEFAULT_COLOR_SCHEME`]: crate::DEFAULT_DEFAULT_COLOR_SCHEME
+/// [`ANSI_256_COLOR_SCHEME`]: crate::ANSI_256_COLOR_SCHEME
+/// [`TRUECOLOR_COLOR_SCHEME`]: crate::TRUECOLOR_COLOR_SCHEME
+/// [`enable_colors_if_supported`]: crate::enable_colors_if_supported
+#[inline]
+pub fn set_color_depth(depth: ColorDepth) {
+    COLOR_DEPTH.store(depth.to_u8(), AtomicOrdering::Relaxed);
+}
+
+/// Returns the number of colors supported by the terminal set with
+/// [`set_color_depth`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::ColorDepth;
+///
+/// assert_eq!(unwind_context::get_color_depth(), ColorDepth::Basic);
+/// ```
+#[inline]
+#[must_use]
+pub fn get_color_depth() -> ColorDepth {
+    ColorDepth::from_u8(COLOR_DEPTH.load(AtomicOrdering::Relaxed))
+}
+
+/// Sets the terminal background brightness for all threads.
+///
+/// If the background is set to [`TerminalBackground::Light`], the
+/// [`LIGHT_BACKGROUND_COLOR_SCHEME`] is used as the default ANSI color scheme
+/// if colorization is enabled and no custom default color scheme is set. It is
+/// set automatically by [`enable_colors_if_supported`] if the background is
+/// detected.
+///
+/// By default a dark background is assumed.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::TerminalBackground;
+///
+/// if let Some(background) = unwind_context::detect_terminal_background() {
+///     unwind_context::set_terminal_background(background);
+/// }
+/// # unwind_context::set_terminal_background(TerminalBackground::Dark);
+/// ```
+///
+/// [`LIGHT_BACKGROUND_COLOR_SCHEME`]: crate::LIGHT_BACKGROUND_COLOR_SCHEME
+/// [`enable_colors_if_supported`]: crate::enable_colors_if_supported
+#[inline]
+pub fn set_terminal_background(background: TerminalBackground) {
+    IS_BACKGROUND_LIGHT.store(
+        background == TerminalBackground::Light,
+        AtomicOrdering::Relaxed,
+    );
+}
+
+/// Returns the terminal background brightness set with
+/// [`set_terminal_background`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::TerminalBackground;
+///
+/// assert_eq!(
+///     unwind_context::get_terminal_background(),
+///     TerminalBackground::Dark
+/// );
+/// ```
+#[inline]
+#[must_use]
+pub fn get_terminal_background() -> TerminalBackground {
+    if IS_BACKGROUND_LIGHT.load(AtomicOrdering::Relaxed) {
+        TerminalBackground::Light
+    } else {
+        TerminalBackground::Dark
+    }
+}
+
+/// Detects the terminal background brightness on a best-effort basis.
+///
+/// It checks the `COLORFGBG` environment variable set by some terminals, like
+/// `rxvt` or `Konsole`. Returns `None` if the background can not be detected.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::TerminalBackground;
+///
+/// std::env::set_var("COLORFGBG", "0;15");
+/// assert_eq!(
+///     unwind_context::detect_terminal_background(),
+///     Some(TerminalBackground::Light)
+/// );
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[must_use]
+pub fn detect_terminal_background() -> Option<TerminalBackground> {
+    let value = std::env::var("COLORFGBG").ok()?;
+    TerminalBackground::from_colorfgbg(&value)
+}
+
 #[cfg(feature = "custom-default-colors")]
 #[cfg_attr(docsrs, doc(cfg(feature = "custom-default-colors")))]
 /// Sets default ANSI color scheme for all threads.
@@ -156,9 +627,14 @@ pub use enable_colors_if_supported as enable_ansi_colors_if_supported;
 /// unwind_context::set_default_color_scheme(&unwind_context::AnsiColorScheme {
 ///     default: "\u{1b}[0m",
 ///     location: "\u{1b}[31m",
+///     location_file: "\u{1b}[31m",
+///     location_line: "\u{1b}[1;31m",
+///     location_column: "\u{1b}[31m",
 ///     fn_keyword: "\u{1b}[32m",
 ///     func_name: "\u{1b}[33m",
 ///     func_braces: "\u{1b}[34m",
+///     arg_name: "\u{1b}[95m",
+///     punct: "\u{1b}[90m",
 ///     value_braces: "\u{1b}[35m",
 ///     ident: "\u{1b}[36m",
 ///     item: "\u{1b}[37m",
@@ -182,6 +658,9 @@ pub use set_default_color_scheme as set_ansi_color_scheme;
 
 /// Returns the currently set default ANSI color scheme.
 ///
+/// If the `std` feature is enabled, the current thread override set with
+/// [`set_thread_color_scheme`] takes precedence over the global one.
+///
 /// # Examples
 ///
 /// ```rust
@@ -194,9 +673,15 @@ pub use set_default_color_scheme as set_ansi_color_scheme;
 /// let color_scheme = unwind_context::get_default_color_scheme();
 /// eprintln!("color scheme: {:?}", color_scheme);
 /// ```
+///
+/// [`set_thread_color_scheme`]: crate::set_thread_color_scheme
 #[inline]
 #[must_use]
 pub fn get_default_color_scheme() -> &'static AnsiColorScheme {
+    #[cfg(feature = "std")]
+    if let Some(color_scheme) = get_thread_color_scheme() {
+        return color_scheme;
+    }
     get_default_ansi_color_scheme_impl()
 }
 
@@ -209,13 +694,21 @@ pub use get_default_color_scheme as get_ansi_color_scheme;
 fn get_default_ansi_color_scheme_impl() -> &'static AnsiColorScheme {
     DEFAULT_COLOR_SCHEME
         .load(AtomicOrdering::Acquire)
-        .unwrap_or(&DEFAULT_DEFAULT_COLOR_SCHEME)
+        .unwrap_or_else(get_default_color_scheme_for_terminal)
 }
 
 #[cfg(not(feature = "custom-default-colors"))]
 #[inline]
 fn get_default_ansi_color_scheme_impl() -> &'static AnsiColorScheme {
-    &DEFAULT_DEFAULT_COLOR_SCHEME
+    get_default_color_scheme_for_terminal()
+}
+
+#[inline]
+fn get_default_color_scheme_for_terminal() -> &'static AnsiColorScheme {
+    match get_terminal_background() {
+        TerminalBackground::Dark => get_color_depth().color_scheme(),
+        TerminalBackground::Light => &LIGHT_BACKGROUND_COLOR_SCHEME,
+    }
 }
 
 /// Returns current ANSI color scheme if ANSI colors were enabled, `None`
@@ -248,15 +741,22 @@ pub use get_default_color_scheme_if_enabled as get_ansi_color_scheme_if_colors_e
 
 #[cfg(all(test, feature = "std"))]
 mod tests {
-    #[cfg(all(feature = "std", feature = "detect-color-support"))]
-    use crate::enable_colors_if_supported;
+    #[cfg(feature = "custom-default-colors")]
+    use crate::set_default_color_scheme;
     use crate::test_common::{SERIAL_TEST, TEST_COLOR_SCHEME};
     use crate::test_util::FixedBufWriter;
     use crate::{
-        are_colors_enabled, set_colors_enabled, unwind_context_with_fmt, StdPanicDetector,
+        are_colors_enabled, enable_colors_from_env, get_color_depth, get_terminal_background,
+        get_thread_color_scheme, get_thread_colors_enabled, set_color_depth, set_colors_enabled,
+        set_terminal_background, set_thread_color_scheme, set_thread_colors_enabled,
+        unwind_context_with_fmt, with_color_scheme, ColorDepth, StdPanicDetector,
+        TerminalBackground, DEFAULT_DEFAULT_COLOR_SCHEME,
+    };
+    #[cfg(all(feature = "std", feature = "detect-color-support"))]
+    use crate::{
+        color_scheme_if_supported, enable_colors_if_supported, enable_colors_if_supported_for,
+        TerminalStream,
     };
-    #[cfg(feature = "custom-default-colors")]
-    use crate::{set_default_color_scheme, DEFAULT_DEFAULT_COLOR_SCHEME};
 
     #[test]
     fn test_set_ansi_colors_enabled() {
@@ -291,9 +791,9 @@ mod tests {
         );
         ctx.print();
         drop(ctx);
-        assert!(writer
-            .into_str()
-            .starts_with("foo: {NUM}123{DEF}, bar: {QUOT}\"BAR\"{DEF}\n    at {LOC}"));
+        assert!(writer.into_str().starts_with(
+            "{ARG}foo{DEF}: {NUM}123{DEF}, {ARG}bar{DEF}: {QUOT}\"BAR\"{DEF}\n    at {LOC}"
+        ));
 
         set_colors_enabled(true);
         assert!(are_colors_enabled());
@@ -322,9 +822,9 @@ mod tests {
         );
         ctx.print();
         drop(ctx);
-        assert!(writer
-            .into_str()
-            .starts_with("foo: {NUM}123{DEF}, bar: {QUOT}\"BAR\"{DEF}\n    at {LOC}"));
+        assert!(writer.into_str().starts_with(
+            "{ARG}foo{DEF}: {NUM}123{DEF}, {ARG}bar{DEF}: {QUOT}\"BAR\"{DEF}\n    at {LOC}"
+        ));
 
         set_colors_enabled(false);
         assert!(!are_colors_enabled());
@@ -343,6 +843,181 @@ mod tests {
             .starts_with("foo: 123, bar: \"BAR\"\n    at "));
     }
 
+    #[test]
+    fn test_set_thread_colors_enabled_and_color_scheme() {
+        // No `SERIAL_TEST` lock is needed as thread overrides take precedence
+        // over the global settings.
+        let mut buffer = [0; 128];
+        let foo = 123;
+
+        assert_eq!(get_thread_colors_enabled(), None);
+        assert!(get_thread_color_scheme().is_none());
+
+        set_thread_colors_enabled(Some(true));
+        set_thread_color_scheme(Some(&TEST_COLOR_SCHEME));
+        assert!(are_colors_enabled());
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        let mut ctx = unwind_context_with_fmt!(
+            (foo),
+            writer = &mut writer,
+            panic_detector = StdPanicDetector,
+        );
+        ctx.print();
+        drop(ctx);
+        assert!(writer
+            .into_str()
+            .starts_with("{ARG}foo{DEF}: {NUM}123{DEF}\n    at {LOC}"));
+
+        // Other threads are not affected.
+        let handle = std::thread::spawn(|| {
+            (
+                get_thread_colors_enabled(),
+                get_thread_color_scheme().is_some(),
+            )
+        });
+        assert_eq!(handle.join().unwrap(), (None, false));
+
+        set_thread_colors_enabled(Some(false));
+        assert!(!are_colors_enabled());
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        let mut ctx = unwind_context_with_fmt!(
+            (foo),
+            writer = &mut writer,
+            panic_detector = StdPanicDetector,
+        );
+        ctx.print();
+        drop(ctx);
+        assert!(writer.into_str().starts_with("foo: 123\n    at "));
+
+        set_thread_colors_enabled(None);
+        set_thread_color_scheme(None);
+        assert_eq!(get_thread_colors_enabled(), None);
+        assert!(get_thread_color_scheme().is_none());
+    }
+
+    #[test]
+    fn test_with_color_scheme() {
+        let mut buffer = [0; 128];
+        let foo = 123;
+
+        set_thread_colors_enabled(Some(true));
+        let guard = with_color_scheme(&TEST_COLOR_SCHEME);
+        assert_eq!(get_thread_color_scheme(), Some(&TEST_COLOR_SCHEME));
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        let mut ctx = unwind_context_with_fmt!(
+            (foo),
+            writer = &mut writer,
+            panic_detector = StdPanicDetector,
+        );
+        ctx.print();
+        drop(ctx);
+        assert!(writer
+            .into_str()
+            .starts_with("{ARG}foo{DEF}: {NUM}123{DEF}\n    at {LOC}"));
+
+        // Nested guards restore the previous override.
+        let nested = with_color_scheme(&DEFAULT_DEFAULT_COLOR_SCHEME);
+        assert_eq!(
+            get_thread_color_scheme(),
+            Some(&DEFAULT_DEFAULT_COLOR_SCHEME)
+        );
+        drop(nested);
+        assert_eq!(get_thread_color_scheme(), Some(&TEST_COLOR_SCHEME));
+
+        drop(guard);
+        assert!(get_thread_color_scheme().is_none());
+        set_thread_colors_enabled(None);
+    }
+
+    #[cfg(not(feature = "custom-default-colors"))]
+    #[test]
+    fn test_set_color_depth() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        let mut buffer = [0; 128];
+        let foo = 123;
+
+        assert_eq!(get_color_depth(), ColorDepth::Basic);
+        set_colors_enabled(true);
+        set_color_depth(ColorDepth::Ansi256);
+        assert_eq!(get_color_depth(), ColorDepth::Ansi256);
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        let mut ctx = unwind_context_with_fmt!(
+            (foo),
+            writer = &mut writer,
+            panic_detector = StdPanicDetector,
+        );
+        ctx.print();
+        drop(ctx);
+        assert!(writer
+            .into_str()
+            .starts_with("foo: \u{1b}[0;38;5;80m123\u{1b}[0m\n    at \u{1b}[0;38;5;75m"));
+
+        set_color_depth(ColorDepth::TrueColor);
+        assert_eq!(get_color_depth(), ColorDepth::TrueColor);
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        let mut ctx = unwind_context_with_fmt!(
+            (foo),
+            writer = &mut writer,
+            panic_detector = StdPanicDetector,
+        );
+        ctx.print();
+        drop(ctx);
+        assert!(writer
+            .into_str()
+            .starts_with("foo: \u{1b}[0;38;2;86;182;194m123\u{1b}[0m\n    at "));
+
+        // The light background color scheme is used regardless of the depth.
+        set_terminal_background(TerminalBackground::Light);
+        assert_eq!(get_terminal_background(), TerminalBackground::Light);
+        let mut writer = FixedBufWriter::new(&mut buffer);
+        let mut ctx = unwind_context_with_fmt!(
+            (foo),
+            writer = &mut writer,
+            panic_detector = StdPanicDetector,
+        );
+        ctx.print();
+        drop(ctx);
+        assert!(writer
+            .into_str()
+            .starts_with("foo: \u{1b}[0;36m123\u{1b}[0m\n    at \u{1b}[0;34m"));
+        set_terminal_background(TerminalBackground::Dark);
+
+        set_color_depth(ColorDepth::Basic);
+        set_colors_enabled(false);
+        assert_eq!(get_color_depth(), ColorDepth::Basic);
+    }
+
+    #[test]
+    fn test_enable_colors_from_env() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!are_colors_enabled());
+
+        std::env::remove_var("FORCE_COLOR");
+        std::env::set_var("NO_COLOR", "1");
+        enable_colors_from_env();
+        assert!(!are_colors_enabled());
+
+        std::env::set_var("FORCE_COLOR", "0");
+        enable_colors_from_env();
+        assert!(!are_colors_enabled());
+
+        std::env::set_var("FORCE_COLOR", "1");
+        enable_colors_from_env();
+        assert!(are_colors_enabled());
+        set_colors_enabled(false);
+
+        std::env::remove_var("FORCE_COLOR");
+        std::env::remove_var("NO_COLOR");
+        std::env::set_var("TERM", "dumb");
+        enable_colors_from_env();
+        assert!(!are_colors_enabled());
+
+        set_colors_enabled(false);
+        assert!(!are_colors_enabled());
+    }
+
     #[cfg(all(feature = "std", feature = "detect-color-support"))]
     #[test]
     fn test_enable_ansi_colors_if_supported() {
@@ -352,16 +1027,38 @@ mod tests {
 
         std::env::remove_var("FORCE_COLOR");
         std::env::remove_var("NO_COLOR");
+        std::env::remove_var("COLORFGBG");
         std::env::set_var("IGNORE_IS_TERMINAL", "true");
         std::env::set_var("TERM", "dumb");
         enable_colors_if_supported();
         assert!(!are_colors_enabled());
 
         std::env::set_var("TERM", "xterm-256color");
+        std::env::remove_var("COLORTERM");
+        enable_colors_if_supported();
+        assert!(are_colors_enabled());
+        assert_eq!(get_color_depth(), ColorDepth::Ansi256);
+        set_colors_enabled(false);
+
+        enable_colors_if_supported_for(TerminalStream::Stdout);
+        assert!(are_colors_enabled());
+        set_colors_enabled(false);
+
         std::env::set_var("COLORTERM", "truecolor");
         enable_colors_if_supported();
         assert!(are_colors_enabled());
+        assert_eq!(get_color_depth(), ColorDepth::TrueColor);
+        assert_eq!(get_terminal_background(), TerminalBackground::Dark);
+        set_colors_enabled(false);
+
+        std::env::set_var("COLORFGBG", "0;15");
+        enable_colors_if_supported();
+        assert!(are_colors_enabled());
+        assert_eq!(get_terminal_background(), TerminalBackground::Light);
+        std::env::remove_var("COLORFGBG");
+        set_terminal_background(TerminalBackground::Dark);
         set_colors_enabled(false);
+        set_color_depth(ColorDepth::Basic);
 
         std::env::set_var("NO_COLOR", "true");
         enable_colors_if_supported();
@@ -372,6 +1069,8 @@ mod tests {
         enable_colors_if_supported();
         assert!(are_colors_enabled());
         set_colors_enabled(false);
+        set_color_depth(ColorDepth::Basic);
+        assert!(color_scheme_if_supported(TerminalStream::Stderr).is_some());
 
         set_colors_enabled(false);
         assert!(!are_colors_enabled());
@@ -416,9 +1115,9 @@ mod tests {
         );
         ctx.print();
         drop(ctx);
-        assert!(writer
-            .into_str()
-            .starts_with("foo: {NUM}123{DEF}, bar: {QUOT}\"BAR\"{DEF}\n    at {LOC}"));
+        assert!(writer.into_str().starts_with(
+            "{ARG}foo{DEF}: {NUM}123{DEF}, {ARG}bar{DEF}: {QUOT}\"BAR\"{DEF}\n    at {LOC}"
+        ));
 
         set_default_color_scheme(&DEFAULT_DEFAULT_COLOR_SCHEME);
 
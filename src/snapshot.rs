@@ -0,0 +1,91 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+/// An utility wrapper type which is used to store a clone of a value made when
+/// the context is created and to forward its [`core::fmt::Debug`]
+/// implementation to the clone.
+///
+/// Context arguments are formatted lazily, when the context is printed. So if
+/// a value is mutated after the context scope guard is created, for example
+/// through interior mutability, its final state is printed. A snapshot
+/// records the state of the value at the scope entry instead. Unlike
+/// [`DebugSnapshot`] it does not format the value eagerly, it only clones it,
+/// which is usually cheaper for small values.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::cell::Cell;
+///
+/// use unwind_context::{unwind_context, Snapshot};
+///
+/// fn func(counter: &Cell<u32>) {
+///     let _ctx = unwind_context!(fn(Snapshot::new(counter)));
+///     counter.set(counter.get() + 1);
+///     // ...
+/// }
+/// ```
+///
+/// [`DebugSnapshot`]: crate::DebugSnapshot
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default)]
+pub struct Snapshot<T>(
+    /// The clone of the value made when the snapshot was created.
+    pub T,
+);
+
+impl<T: Clone> Snapshot<T> {
+    /// Creates a new `Snapshot` with a clone of the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::Snapshot;
+    ///
+    /// let mut value = vec![1, 2, 3];
+    /// let snapshot = Snapshot::new(&value);
+    /// value.push(4);
+    /// assert_eq!(snapshot.0, [1, 2, 3]);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(value: &T) -> Self {
+        Self(value.clone())
+    }
+}
+
+impl<T> Debug for Snapshot<T>
+where
+    T: Debug,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use crate::test_util::buf_fmt;
+    use crate::{build_unwind_context_data, Snapshot};
+
+    #[test]
+    fn test_snapshot() {
+        let mut buffer = [0; 128];
+        let counter = Cell::new(1);
+
+        let context = build_unwind_context_data!(fn func(&counter, Snapshot::new(&counter)));
+        counter.set(2);
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{context:?}")),
+            Ok(concat!(
+                "fn func(&counter: Cell { value: 2 }, ",
+                "Snapshot::new(&counter): Cell { value: 1 })"
+            ))
+        );
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{:#?}", Snapshot::new(&(1, 2)))),
+            Ok("(\n    1,\n    2,\n)")
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use core::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+
+static UNWINDS: AtomicUsize = AtomicUsize::new(0);
+static FRAMES_PRINTED: AtomicUsize = AtomicUsize::new(0);
+static FRAMES_SUPPRESSED: AtomicUsize = AtomicUsize::new(0);
+
+/// Process-wide statistics of unwind context output since the program start.
+///
+/// It is returned by the [`stats`] function. The counters are updated by all
+/// threads with relaxed atomic operations, so they are intended for
+/// monitoring, like exporting metrics of a service that intentionally catches
+/// panics, rather than for synchronization.
+///
+/// # Examples
+///
+/// ```rust
+/// let stats = unwind_context::stats();
+/// println!(
+///     "{} unwinds, {} frames printed, {} frames suppressed",
+///     stats.unwinds, stats.frames_printed, stats.frames_suppressed
+/// );
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+#[non_exhaustive]
+pub struct UnwindStats {
+    /// The number of detected unwinds, that is unwinds that dropped at least
+    /// one context scope guard.
+    pub unwinds: usize,
+    /// The number of unwind context frames written to their writers.
+    pub frames_printed: usize,
+    /// The number of unwind context frames that were not printed because they
+    /// were collapsed as repeated, exceeded the maximum number of frames per
+    /// unwind, were not sampled, already printed once, or rate-limited, or did
+    /// not fit into the output budget.
+    pub frames_suppressed: usize,
+}
+
+/// Returns the statistics of unwind context output since the program start.
+///
+/// Frames captured with [`capture`] and frames printed on scope entry or on
+/// a normal scope exit are not counted.
+///
+/// # Examples
+#[cfg_attr(not(feature = "off"), doc = "```rust")]
+#[cfg_attr(feature = "off", doc = "```rust,ignore")]
+/// use std::panic::catch_unwind;
+///
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32) {
+///     let _ctx = unwind_context!(fn(foo));
+///     assert_ne!(foo, 0);
+/// }
+///
+/// let before = unwind_context::stats();
+/// assert!(catch_unwind(|| func(0)).is_err());
+/// let after = unwind_context::stats();
+/// assert!(after.unwinds > before.unwinds);
+/// assert!(after.frames_printed > before.frames_printed);
+#[doc = "```"]
+#[doc = ""]
+/// [`capture`]: crate::capture
+#[inline]
+#[must_use]
+pub fn stats() -> UnwindStats {
+    UnwindStats {
+        unwinds: UNWINDS.load(AtomicOrdering::Relaxed),
+        frames_printed: FRAMES_PRINTED.load(AtomicOrdering::Relaxed),
+        frames_suppressed: FRAMES_SUPPRESSED.load(AtomicOrdering::Relaxed),
+    }
+}
+
+/// Counts a newly detected unwind.
+#[inline]
+pub(crate) fn record_unwind() {
+    let _ = UNWINDS.fetch_add(1, AtomicOrdering::Relaxed);
+}
+
+/// Counts a frame written to its writer.
+#[inline]
+pub(crate) fn record_frame_printed() {
+    let _ = FRAMES_PRINTED.fetch_add(1, AtomicOrdering::Relaxed);
+}
+
+/// Counts a frame that was not printed because of the output limits.
+#[inline]
+pub(crate) fn record_frame_suppressed() {
+    let _ = FRAMES_SUPPRESSED.fetch_add(1, AtomicOrdering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use core::panic::AssertUnwindSafe;
+    use std::panic::catch_unwind;
+    use std::string::String;
+
+    use crate::{stats, unwind_context_with_fmt, StdPanicDetector};
+
+    #[test]
+    fn test_stats() {
+        fn func(foo: u32, output: &mut String) {
+            let _ctx = unwind_context_with_fmt!(
+                (fn func(foo)),
+                writer = output,
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            assert_ne!(foo, 0, "stats panic");
+        }
+
+        let mut output = String::new();
+        let before = stats();
+        let result = catch_unwind(AssertUnwindSafe(|| func(0, &mut output)));
+        assert!(result.is_err());
+        let after = stats();
+
+        // Other tests may update the counters concurrently.
+        assert!(after.unwinds > before.unwinds);
+        assert!(after.frames_printed > before.frames_printed);
+        assert!(after.frames_suppressed >= before.frames_suppressed);
+        assert!(output.starts_with("fn func(foo: 0)"));
+    }
+}
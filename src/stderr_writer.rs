@@ -0,0 +1,119 @@
+use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::io::{self, Result as IoResult, Write as IoWrite};
+use std::string::String;
+
+/// Whether the frames written with [`StderrWriter`] are printed with the
+/// [`std::eprint`] macro.
+static SHOULD_USE_EPRINT: AtomicBool = AtomicBool::new(false);
+
+/// A [`std::io::Write`] writer that writes to the standard error stream.
+///
+/// It is used by the [`DefaultWriter`] default writer of the
+/// [`unwind_context`] and [`unwind_context_with_io`] macros if no other writer
+/// is set with [`set_default_writer`]. By default it writes directly to
+/// [`std::io::stderr`]. If enabled with [`set_eprint_output_enabled`], it
+/// writes with the [`std::eprint`] macro instead, so that the output is
+/// captured by the test harness like any other output of the test.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, StderrWriter};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!((fn(foo, bar)), writer = StderrWriter);
+///     // ...
+/// }
+/// ```
+///
+/// [`DefaultWriter`]: crate::DefaultWriter
+/// [`set_default_writer`]: crate::set_default_writer
+/// [`unwind_context`]: crate::unwind_context
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct StderrWriter;
+
+impl IoWrite for StderrWriter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        if is_eprint_output_enabled() {
+            #[allow(clippy::print_stderr)] // Used to let the test harness capture the output.
+            match core::str::from_utf8(buf) {
+                Ok(buf) => std::eprint!("{buf}"),
+                Err(_) => std::eprint!("{}", String::from_utf8_lossy(buf)),
+            }
+            Ok(())
+        } else {
+            io::stderr().write_all(buf)
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        io::stderr().flush()
+    }
+}
+
+/// Enables or disables writing of the unwind context frames with the
+/// [`std::eprint`] macro instead of writing directly to [`std::io::stderr`]
+/// by the [`StderrWriter`] writer.
+///
+/// Under `cargo test`, direct writes to [`std::io::stderr`] bypass the output
+/// capture of the test harness, so context frames printed by panics in
+/// passing tests, for example the ones caught with
+/// [`std::panic::catch_unwind`] or raised in spawned threads, leak into the
+/// terminal. When this mode is enabled, frames are captured and shown only for
+/// failing tests. It is enabled automatically by the `unwind_context::test`
+/// attribute of the `test-macro` feature.
+///
+/// Note that [`std::eprint`] panics if writing to the standard error stream
+/// fails, which aborts the process if it happens during unwinding. Therefore
+/// this mode is intended to be used in tests only.
+///
+/// By default this mode is disabled.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// # /*
+/// #[test]
+/// # */
+/// fn test() {
+///     unwind_context::set_eprint_output_enabled(true);
+///     // ...
+///     func(234, "bcd");
+///     // ...
+/// }
+/// # test();
+/// ```
+#[inline]
+pub fn set_eprint_output_enabled(enabled: bool) {
+    SHOULD_USE_EPRINT.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if writing of the unwind context frames with the
+/// [`std::eprint`] macro was enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::is_eprint_output_enabled());
+/// ```
+#[inline]
+#[must_use]
+pub fn is_eprint_output_enabled() -> bool {
+    SHOULD_USE_EPRINT.load(AtomicOrdering::Relaxed)
+}
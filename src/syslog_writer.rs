@@ -0,0 +1,324 @@
+use std::io::{Result as IoResult, Write as IoWrite};
+#[cfg(any(unix, test))]
+use std::vec::Vec;
+
+use crate::ContextFrame;
+
+/// The default identifier used by [`SyslogWriter`] and
+/// [`send_frame_to_journal`].
+pub const DEFAULT_SYSLOG_IDENTIFIER: &str = "unwind-context";
+
+/// The path of the local syslog socket.
+#[cfg(unix)]
+const SYSLOG_SOCKET_PATH: &str = "/dev/log";
+
+/// The path of the systemd journal native protocol socket.
+#[cfg(unix)]
+const JOURNAL_SOCKET_PATH: &str = "/run/systemd/journal/socket";
+
+/// The syslog priority value for the `user` facility and the `err` severity.
+#[cfg(any(unix, test))]
+const SYSLOG_USER_ERR_PRIORITY: u8 = 11;
+
+/// The syslog `err` severity used as the journal `PRIORITY` field.
+#[cfg(any(unix, test))]
+const JOURNAL_ERR_PRIORITY: &[u8] = b"3";
+
+/// A [`std::io::Write`] writer that writes to the local syslog daemon.
+///
+/// Daemons usually have their standard error stream discarded or redirected,
+/// so the context frames written with the default writer may be lost. This
+/// writer sends a syslog message with the `user` facility, the `err`
+/// severity, and the given identifier to the `/dev/log` socket once per write
+/// with the trailing newline removed, and context scope guards write every
+/// frame with a single write. If the socket is not available or on non-Unix
+/// targets it writes to the standard error stream with [`StderrWriter`]
+/// instead.
+///
+/// Use the [`send_frame_to_journal`] frame sink to send frames to the systemd
+/// journal with structured code location fields instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, SyslogWriter};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!((fn(foo, bar)), writer = SyslogWriter::new("my-daemon"));
+///     // ...
+/// }
+/// ```
+///
+/// [`StderrWriter`]: crate::StderrWriter
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct SyslogWriter {
+    identifier: &'static str,
+}
+
+impl SyslogWriter {
+    /// Creates a new `SyslogWriter` writing syslog messages with the given
+    /// identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::SyslogWriter::new("my-daemon");
+    /// assert_eq!(writer.identifier(), "my-daemon");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(identifier: &'static str) -> Self {
+        Self { identifier }
+    }
+
+    /// Returns the identifier of syslog messages written by the writer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{SyslogWriter, DEFAULT_SYSLOG_IDENTIFIER};
+    ///
+    /// assert_eq!(
+    ///     SyslogWriter::default().identifier(),
+    ///     DEFAULT_SYSLOG_IDENTIFIER
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn identifier(&self) -> &'static str {
+        self.identifier
+    }
+}
+
+impl Default for SyslogWriter {
+    #[inline]
+    fn default() -> Self {
+        Self::new(DEFAULT_SYSLOG_IDENTIFIER)
+    }
+}
+
+impl IoWrite for SyslogWriter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    #[cfg(unix)]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        if buf.strip_suffix(b"\n").unwrap_or(buf).is_empty() {
+            return Ok(());
+        }
+        let message = syslog_message(self.identifier, buf);
+        if send_datagram(&message, SYSLOG_SOCKET_PATH).is_err() {
+            crate::StderrWriter.write_all(buf)?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(unix))]
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        crate::StderrWriter.write_all(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+/// Sends an unwind context frame to the systemd journal.
+///
+/// It is intended to be used as a [`FrameSink`] callback. The journal entry
+/// is sent with the journal native protocol and contains the `err` priority,
+/// the [`DEFAULT_SYSLOG_IDENTIFIER`] identifier, the context data with its
+/// location as the message, and the `CODE_FILE`, `CODE_LINE`, and
+/// `CODE_FUNC` structured fields, so the frames can be found next to the
+/// panic message and filtered with `journalctl`. If the journal is not
+/// available or on non-Unix targets the frame is written to the standard
+/// error stream with [`StderrWriter`] instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context, FrameSink};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_global_frame_sink(Some(
+///     FrameSink::new(unwind_context::send_frame_to_journal).with_output(false),
+/// ));
+/// func(123, "abc");
+/// unwind_context::set_global_frame_sink(None);
+/// ```
+///
+/// [`FrameSink`]: crate::FrameSink
+/// [`StderrWriter`]: crate::StderrWriter
+pub fn send_frame_to_journal(frame: &ContextFrame<'_>) {
+    #[cfg(unix)]
+    if send_datagram(&journal_entry(frame), JOURNAL_SOCKET_PATH).is_ok() {
+        return;
+    }
+
+    let location = frame.location();
+    let _ = writeln!(
+        crate::StderrWriter,
+        "{:?}\n    at {}:{}:{}",
+        frame.data(),
+        location.file(),
+        location.line(),
+        location.column()
+    );
+}
+
+/// Sends a single datagram to the Unix socket at the given path.
+#[cfg(unix)]
+fn send_datagram(message: &[u8], path: &str) -> IoResult<()> {
+    let socket = std::os::unix::net::UnixDatagram::unbound()?;
+    let _ = socket.send_to(message, path)?;
+    Ok(())
+}
+
+/// Builds a syslog message with the `user` facility and the `err` severity
+/// without the trailing newline of the written bytes.
+#[cfg(any(unix, test))]
+fn syslog_message(identifier: &str, buf: &[u8]) -> Vec<u8> {
+    let buf = buf.strip_suffix(b"\n").unwrap_or(buf);
+    let mut message =
+        Vec::with_capacity(buf.len().saturating_add(identifier.len()).saturating_add(8));
+    let _ = write!(message, "<{SYSLOG_USER_ERR_PRIORITY}>{identifier}: ");
+    message.extend_from_slice(buf);
+    message
+}
+
+/// Builds a systemd journal native protocol entry for the given frame.
+#[cfg(any(unix, test))]
+fn journal_entry(frame: &ContextFrame<'_>) -> Vec<u8> {
+    let location = frame.location();
+    let mut entry = Vec::new();
+    append_journal_field(&mut entry, "PRIORITY", JOURNAL_ERR_PRIORITY);
+    append_journal_field(
+        &mut entry,
+        "SYSLOG_IDENTIFIER",
+        DEFAULT_SYSLOG_IDENTIFIER.as_bytes(),
+    );
+
+    let mut message = Vec::new();
+    let _ = write!(
+        message,
+        "{:?}\n    at {}:{}:{}",
+        frame.data(),
+        location.file(),
+        location.line(),
+        location.column()
+    );
+    append_journal_field(&mut entry, "MESSAGE", &message);
+
+    append_journal_field(&mut entry, "CODE_FILE", location.file().as_bytes());
+    let mut line = Vec::new();
+    let _ = write!(line, "{}", location.line());
+    append_journal_field(&mut entry, "CODE_LINE", &line);
+    if let Some(func_name) = frame.func_name() {
+        append_journal_field(&mut entry, "CODE_FUNC", func_name.as_bytes());
+    }
+    entry
+}
+
+/// Appends a journal native protocol field, using the binary encoding for
+/// values containing newlines.
+#[cfg(any(unix, test))]
+fn append_journal_field(entry: &mut Vec<u8>, key: &str, value: &[u8]) {
+    entry.extend_from_slice(key.as_bytes());
+    if value.contains(&b'\n') {
+        entry.push(b'\n');
+        let len = u64::try_from(value.len()).unwrap_or(u64::MAX);
+        entry.extend_from_slice(&len.to_le_bytes());
+    } else {
+        entry.push(b'=');
+    }
+    entry.extend_from_slice(value);
+    entry.push(b'\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use core::panic::Location;
+    use std::io::Write as IoWrite;
+    use std::vec::Vec;
+
+    use crate::syslog_writer::{append_journal_field, journal_entry, syslog_message};
+    use crate::{
+        build_unwind_context_data, ContextFrame, FrameInfo, SyslogWriter, DEFAULT_SYSLOG_IDENTIFIER,
+    };
+
+    #[test]
+    fn test_syslog_message() {
+        assert_eq!(
+            syslog_message("app", b"fn func(foo: 1)\n"),
+            b"<11>app: fn func(foo: 1)"
+        );
+        assert_eq!(syslog_message("app", b"a\nb\n\n"), b"<11>app: a\nb\n");
+        assert_eq!(syslog_message("app", b""), b"<11>app: ");
+    }
+
+    #[test]
+    fn test_append_journal_field() {
+        let mut entry = Vec::new();
+        append_journal_field(&mut entry, "KEY", b"value");
+        append_journal_field(&mut entry, "MULTI", b"a\nb");
+        assert_eq!(entry, b"KEY=value\nMULTI\n\x03\0\0\0\0\0\0\0a\nb\n");
+    }
+
+    #[test]
+    fn test_journal_entry() {
+        let foo = 1;
+        let data = build_unwind_context_data!(fn func(foo));
+        let location = Location::caller();
+        let frame = ContextFrame::new(&FrameInfo::new(&data, location));
+        let entry = journal_entry(&frame);
+
+        let mut expected = Vec::new();
+        append_journal_field(&mut expected, "PRIORITY", b"3");
+        append_journal_field(&mut expected, "SYSLOG_IDENTIFIER", b"unwind-context");
+        append_journal_field(
+            &mut expected,
+            "MESSAGE",
+            std::format!(
+                "fn func(foo: 1)\n    at {}:{}:{}",
+                location.file(),
+                location.line(),
+                location.column()
+            )
+            .as_bytes(),
+        );
+        append_journal_field(&mut expected, "CODE_FILE", location.file().as_bytes());
+        append_journal_field(
+            &mut expected,
+            "CODE_LINE",
+            std::format!("{}", location.line()).as_bytes(),
+        );
+        append_journal_field(&mut expected, "CODE_FUNC", b"func");
+        assert_eq!(entry, expected);
+
+        let data = build_unwind_context_data!(foo);
+        let frame = ContextFrame::new(&FrameInfo::new(&data, location));
+        assert!(!journal_entry(&frame)
+            .windows(b"CODE_FUNC".len())
+            .any(|window| window == b"CODE_FUNC"));
+    }
+
+    #[test]
+    fn test_syslog_writer() {
+        assert_eq!(
+            SyslogWriter::default().identifier(),
+            DEFAULT_SYSLOG_IDENTIFIER
+        );
+        assert_eq!(SyslogWriter::new("app").identifier(), "app");
+        assert_eq!(SyslogWriter::new("app").write(b"").unwrap(), 0);
+        assert!(SyslogWriter::new("app").flush().is_ok());
+    }
+}
@@ -0,0 +1,195 @@
+use core::fmt::{Result as FmtResult, Write as FmtWrite};
+#[cfg(feature = "std")]
+use std::io::{Result as IoResult, Write as IoWrite};
+
+/// A writer combinator that duplicates all output to two writers.
+///
+/// It implements [`core::fmt::Write`] if both writers implement it, and
+/// [`std::io::Write`] if both writers implement it and the `std` feature is
+/// enabled. It can be used directly as the `writer =` parameter of macros like
+/// [`unwind_context_with_io`] or [`unwind_context_with_fmt`]. To duplicate the
+/// output to more than two writers, nest `TeeWriter`s.
+///
+/// Output is written to both writers even if writing to the first one fails.
+/// In this case the first error is returned.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, TeeWriter};
+///
+/// fn func(foo: u32, bar: &str, crash_log: &mut Vec<u8>) {
+///     let _ctx = unwind_context_with_io!(
+///         (fn(foo, bar)),
+///         writer = TeeWriter::new(std::io::stderr(), crash_log),
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_fmt, StdPanicDetector, TeeWriter};
+///
+/// fn func(foo: u32, bar: &str, first: &mut String, second: &mut String, third: &mut String) {
+///     let _ctx = unwind_context_with_fmt!(
+///         (fn(foo, bar)),
+///         writer = TeeWriter::new(first, TeeWriter::new(second, third)),
+///         panic_detector = StdPanicDetector,
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+/// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct TeeWriter<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> TeeWriter<A, B> {
+    /// Creates a new `TeeWriter` duplicating output to the given writers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let writer = unwind_context::TeeWriter::new(String::new(), String::new());
+    /// ```
+    #[inline]
+    pub const fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+
+    /// Returns references to the underlying writers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut writer = unwind_context::TeeWriter::new(String::new(), String::new());
+    /// writer.write_str("abc").unwrap();
+    /// assert_eq!(writer.get_ref(), (&"abc".to_owned(), &"abc".to_owned()));
+    /// ```
+    #[inline]
+    pub const fn get_ref(&self) -> (&A, &B) {
+        (&self.first, &self.second)
+    }
+
+    /// Returns mutable references to the underlying writers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut writer = unwind_context::TeeWriter::new(String::new(), String::new());
+    /// writer.get_mut().0.push_str("abc");
+    /// ```
+    #[inline]
+    pub fn get_mut(&mut self) -> (&mut A, &mut B) {
+        (&mut self.first, &mut self.second)
+    }
+
+    /// Consumes the `TeeWriter`, returning the underlying writers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::fmt::Write;
+    ///
+    /// let mut writer = unwind_context::TeeWriter::new(String::new(), String::new());
+    /// writer.write_str("abc").unwrap();
+    /// let (first, second) = writer.into_inner();
+    /// assert_eq!(first, "abc");
+    /// assert_eq!(second, "abc");
+    /// ```
+    #[inline]
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: FmtWrite, B: FmtWrite> FmtWrite for TeeWriter<A, B> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        let first = self.first.write_str(s);
+        let second = self.second.write_str(s);
+        first.and(second)
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl<A: IoWrite, B: IoWrite> IoWrite for TeeWriter<A, B> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        let first = self.first.write_all(buf);
+        let second = self.second.write_all(buf);
+        first.and(second)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        let first = self.first.flush();
+        let second = self.second.flush();
+        first.and(second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::{Error as FmtError, Result as FmtResult, Write as FmtWrite};
+
+    use crate::test_util::FixedBufWriter;
+    use crate::TeeWriter;
+
+    #[derive(Debug)]
+    struct FailingWriter;
+
+    impl FmtWrite for FailingWriter {
+        fn write_str(&mut self, _: &str) -> FmtResult {
+            Err(FmtError)
+        }
+    }
+
+    #[test]
+    fn test_tee_writer_fmt() {
+        let mut first = [0; 16];
+        let mut second = [0; 16];
+        let mut writer = TeeWriter::new(
+            FixedBufWriter::new(&mut first),
+            FixedBufWriter::new(&mut second),
+        );
+        assert_eq!(write!(writer, "abc{}", 123), Ok(()));
+        let (first, second) = writer.into_inner();
+        assert_eq!(first.into_str(), "abc123");
+        assert_eq!(second.into_str(), "abc123");
+
+        let mut buffer = [0; 16];
+        let mut writer = TeeWriter::new(FailingWriter, FixedBufWriter::new(&mut buffer));
+        assert_eq!(writer.write_str("abc"), Err(FmtError));
+        assert_eq!(writer.into_inner().1.into_str(), "abc");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_tee_writer_io() {
+        use std::io::Write as IoWrite;
+        use std::vec::Vec;
+
+        let mut writer = TeeWriter::new(Vec::new(), TeeWriter::new(Vec::new(), Vec::new()));
+        assert!(write!(writer, "abc{}", 123).is_ok());
+        assert!(writer.flush().is_ok());
+        let (first, rest) = writer.into_inner();
+        let (second, third) = rest.into_inner();
+        assert_eq!(first, b"abc123");
+        assert_eq!(second, b"abc123");
+        assert_eq!(third, b"abc123");
+    }
+}
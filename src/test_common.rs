@@ -12,9 +12,14 @@ pub static SERIAL_TEST: Mutex<()> = Mutex::new(());
 pub static TEST_COLOR_SCHEME: AnsiColorScheme = AnsiColorScheme {
     default: "{DEF}",
     location: "{LOC}",
+    location_file: "{LOC}",
+    location_line: "{LOC}",
+    location_column: "{LOC}",
     fn_keyword: "{FN}",
     func_name: "{FN_NAME}",
     func_braces: "{FN_BRACE}",
+    arg_name: "{ARG}",
+    punct: "{DEF}",
     value_braces: "{BRACE}",
     ident: "{IDENT}",
     item: "{ITEM}",
@@ -1,40 +1,12 @@
 use core::fmt::{
     Arguments as FmtArguments, Debug, Error as FmtError, Formatter, Result as FmtResult,
-    Write as FmtWrite,
 };
 #[cfg(feature = "std")]
 use std::string::String;
 #[cfg(feature = "std")]
 use std::sync::mpsc;
 
-#[derive(Debug)]
-pub struct FixedBufWriter<'a> {
-    buffer: &'a mut [u8],
-    used: usize,
-}
-
-impl<'a> FixedBufWriter<'a> {
-    pub fn new(buffer: &'a mut [u8]) -> Self {
-        Self { buffer, used: 0 }
-    }
-
-    pub fn into_str(self) -> &'a str {
-        core::str::from_utf8(&self.buffer[0..self.used]).expect("unexpected UTF8 error")
-    }
-}
-
-impl FmtWrite for FixedBufWriter<'_> {
-    fn write_str(&mut self, s: &str) -> FmtResult {
-        let from = self.used;
-        let until = from.checked_add(s.len()).ok_or(FmtError)?;
-        self.buffer
-            .get_mut(from..until)
-            .ok_or(FmtError)?
-            .copy_from_slice(s.as_bytes());
-        self.used = until;
-        Ok(())
-    }
-}
+pub(crate) use crate::FixedBufWriter;
 
 pub fn buf_fmt<'a>(buffer: &'a mut [u8], args: FmtArguments<'_>) -> Result<&'a str, FmtError> {
     let mut writer = FixedBufWriter::new(buffer);
@@ -116,21 +88,3 @@ fn test_pattern_matcher() {
     assert_eq!(value_ref.read_until("a"), Ok(" b"));
     assert_eq!(value_ref.read_until("z"), Ok(""));
 }
-
-#[test]
-fn test_no_panic_on_buffer_overflow() {
-    let mut buffer = [0; 10];
-    let mut writer = FixedBufWriter::new(&mut buffer);
-    assert_eq!(writer.write_str("0123456789"), Ok(()));
-
-    let mut buffer = [0; 9];
-    let mut writer = FixedBufWriter::new(&mut buffer);
-    assert_eq!(writer.write_str("0123456789"), Err(FmtError));
-
-    let mut writer = FixedBufWriter::new(&mut []);
-
-    // Emulate an inconsistent state with a very high value of buffer used.
-    writer.used = usize::MAX - 9;
-
-    assert_eq!(writer.write_str("0123456789"), Err(FmtError));
-}
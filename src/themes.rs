@@ -0,0 +1,133 @@
+//! Ready-made ANSI color schemes.
+//!
+//! The color schemes can be used as a default color scheme with
+//! `set_default_color_scheme` if the `custom-default-colors` feature is
+//! enabled, or for specific context scope guards with the
+//! `color_scheme = Some(&...)` macro parameter.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use unwind_context::{themes, unwind_context_with_io};
+//!
+//! fn func(foo: u32, bar: &str) {
+//!     let _ctx = unwind_context_with_io!((fn(foo, bar)), color_scheme = Some(&themes::MONOKAI));
+//!     // ...
+//! }
+//! ```
+
+use crate::AnsiColorScheme;
+
+/// A truecolor color scheme based on the Solarized Dark palette.
+///
+/// # Examples
+#[cfg_attr(feature = "custom-default-colors", doc = "```rust")]
+#[cfg_attr(not(feature = "custom-default-colors"), doc = "```rust,compile_fail")]
+/// use unwind_context::themes::SOLARIZED_DARK;
+///
+/// unwind_context::set_default_color_scheme(&SOLARIZED_DARK);
+#[doc = "```"]
+pub static SOLARIZED_DARK: AnsiColorScheme = AnsiColorScheme {
+    default: "\u{1b}[0m",
+    location: crate::ansi_style!(fg = (88, 110, 117)),
+    location_file: crate::ansi_style!(fg = (88, 110, 117)),
+    location_line: crate::ansi_style!(bold, fg = (147, 161, 161)),
+    location_column: crate::ansi_style!(fg = (88, 110, 117)),
+    fn_keyword: crate::ansi_style!(fg = (133, 153, 0)),
+    func_name: crate::ansi_style!(bold, fg = (38, 139, 210)),
+    func_braces: crate::ansi_style!(fg = (147, 161, 161)),
+    arg_name: crate::ansi_style!(fg = (131, 148, 150)),
+    punct: crate::ansi_style!(fg = (88, 110, 117)),
+    value_braces: crate::ansi_style!(fg = (147, 161, 161)),
+    ident: crate::ansi_style!(fg = (181, 137, 0)),
+    item: crate::ansi_style!(fg = (203, 75, 22)),
+    boolean: crate::ansi_style!(fg = (108, 113, 196)),
+    number: crate::ansi_style!(fg = (211, 54, 130)),
+    quoted: crate::ansi_style!(fg = (42, 161, 152)),
+    escaped: crate::ansi_style!(fg = (220, 50, 47)),
+};
+
+/// A truecolor color scheme based on the Monokai palette.
+///
+/// # Examples
+#[cfg_attr(feature = "custom-default-colors", doc = "```rust")]
+#[cfg_attr(not(feature = "custom-default-colors"), doc = "```rust,compile_fail")]
+/// unwind_context::set_default_color_scheme(&unwind_context::themes::MONOKAI);
+#[doc = "```"]
+pub static MONOKAI: AnsiColorScheme = AnsiColorScheme {
+    default: "\u{1b}[0m",
+    location: crate::ansi_style!(fg = (117, 113, 94)),
+    location_file: crate::ansi_style!(fg = (117, 113, 94)),
+    location_line: crate::ansi_style!(fg = (230, 219, 116)),
+    location_column: crate::ansi_style!(fg = (117, 113, 94)),
+    fn_keyword: crate::ansi_style!(fg = (102, 217, 239)),
+    func_name: crate::ansi_style!(fg = (166, 226, 46)),
+    func_braces: crate::ansi_style!(fg = (248, 248, 242)),
+    arg_name: crate::ansi_style!(fg = (253, 151, 31)),
+    punct: crate::ansi_style!(fg = (117, 113, 94)),
+    value_braces: crate::ansi_style!(fg = (248, 248, 242)),
+    ident: crate::ansi_style!(fg = (248, 248, 242)),
+    item: crate::ansi_style!(italic, fg = (102, 217, 239)),
+    boolean: crate::ansi_style!(fg = (174, 129, 255)),
+    number: crate::ansi_style!(fg = (174, 129, 255)),
+    quoted: crate::ansi_style!(fg = (230, 219, 116)),
+    escaped: crate::ansi_style!(fg = (249, 38, 114)),
+};
+
+/// A color scheme using bold bright colors of the basic 16-color palette for
+/// maximum readability.
+///
+/// # Examples
+#[cfg_attr(feature = "custom-default-colors", doc = "```rust")]
+#[cfg_attr(not(feature = "custom-default-colors"), doc = "```rust,compile_fail")]
+/// use unwind_context::themes::HIGH_CONTRAST;
+///
+/// unwind_context::set_default_color_scheme(&HIGH_CONTRAST);
+#[doc = "```"]
+pub static HIGH_CONTRAST: AnsiColorScheme = AnsiColorScheme {
+    default: "\u{1b}[0m",
+    location: "\u{1b}[0;1;96m",
+    location_file: "\u{1b}[0;1;96m",
+    location_line: "\u{1b}[0;1;93m",
+    location_column: "\u{1b}[0;1;96m",
+    fn_keyword: "\u{1b}[0;1;97m",
+    func_name: "\u{1b}[0;1;93m",
+    func_braces: "\u{1b}[0;1;97m",
+    arg_name: "\u{1b}[0;1;96m",
+    punct: "\u{1b}[0;1;97m",
+    value_braces: "\u{1b}[0;1;97m",
+    ident: "\u{1b}[0;1;97m",
+    item: "\u{1b}[0;1;95m",
+    boolean: "\u{1b}[0;1;91m",
+    number: "\u{1b}[0;1;92m",
+    quoted: "\u{1b}[0;1;93m",
+    escaped: "\u{1b}[0;1;91m",
+};
+
+/// A color scheme that only highlights function names and dims locations,
+/// leaving the rest of the context uncolored.
+///
+/// # Examples
+#[cfg_attr(feature = "custom-default-colors", doc = "```rust")]
+#[cfg_attr(not(feature = "custom-default-colors"), doc = "```rust,compile_fail")]
+/// unwind_context::set_default_color_scheme(&unwind_context::themes::MINIMAL);
+#[doc = "```"]
+pub static MINIMAL: AnsiColorScheme = AnsiColorScheme {
+    default: "\u{1b}[0m",
+    location: crate::ansi_style!(dim),
+    location_file: crate::ansi_style!(dim),
+    location_line: crate::ansi_style!(dim),
+    location_column: crate::ansi_style!(dim),
+    fn_keyword: crate::ansi_style!(),
+    func_name: crate::ansi_style!(bold),
+    func_braces: crate::ansi_style!(),
+    arg_name: crate::ansi_style!(),
+    punct: crate::ansi_style!(),
+    value_braces: crate::ansi_style!(),
+    ident: crate::ansi_style!(),
+    item: crate::ansi_style!(),
+    boolean: crate::ansi_style!(),
+    number: crate::ansi_style!(),
+    quoted: crate::ansi_style!(),
+    escaped: crate::ansi_style!(),
+};
@@ -0,0 +1,231 @@
+//! Thread spawn helpers propagating unwind context to child threads.
+//!
+//! Context scope guards are thread-local, so a panic in a worker thread
+//! prints only the frames created inside this thread and does not show where
+//! and why the thread was started. The helpers of this module spawn a thread
+//! with a top-level context scope guard created inside the new thread. It
+//! prints the given context data with the spawn site location and the child
+//! thread name and ID, so the frame is named after the thread that panicked.
+//! The thread color settings of the parent thread set with
+//! [`set_thread_colors_enabled`] and [`set_thread_color_scheme`] are copied to
+//! the child thread.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use unwind_context::build_unwind_context_data;
+//!
+//! let worker_id = 3;
+//! let handle = unwind_context::thread::spawn_with_context(
+//!     build_unwind_context_data!(fn worker(worker_id)),
+//!     move || worker_id * 2,
+//! );
+//! assert_eq!(handle.join().unwrap(), 6);
+//! ```
+//!
+//! [`set_thread_colors_enabled`]: crate::set_thread_colors_enabled
+//! [`set_thread_color_scheme`]: crate::set_thread_color_scheme
+
+use core::fmt::Debug;
+use core::panic::Location;
+use std::io::{Result as IoResult, Write as IoWrite};
+use std::thread::{Builder, JoinHandle};
+
+use crate::{
+    get_default_color_scheme_if_enabled, get_thread_color_scheme, get_thread_colors_enabled,
+    set_thread_color_scheme, set_thread_colors_enabled, DebugAnsiColored, DefaultWriter,
+    StdPanicDetector, UnwindContextWithIo,
+};
+
+/// Spawns a new thread with a top-level context scope guard, returning a
+/// [`JoinHandle`] for it.
+///
+/// If the thread panics, the given context data is printed with the caller
+/// location and the child thread name and ID after all the frames of the
+/// thread. The guard uses [`DefaultWriter`] writer, [`StdPanicDetector`] panic
+/// detector, and the default color scheme if colors are enabled for the child
+/// thread. See the [module-level documentation](self) for more details.
+///
+/// # Panics
+///
+/// Panics if the OS fails to create a thread, like [`std::thread::spawn`].
+/// Use [`spawn_with_context_in`] to handle such errors.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::build_unwind_context_data;
+///
+/// let (shard, retries) = (2, 5);
+/// let handle = unwind_context::thread::spawn_with_context(
+///     build_unwind_context_data!(fn shard_worker(shard, retries)),
+///     move || {
+///         // ...
+///     },
+/// );
+/// handle.join().unwrap();
+/// ```
+#[track_caller]
+pub fn spawn_with_context<D, F, T>(data: D, f: F) -> JoinHandle<T>
+where
+    D: Debug + DebugAnsiColored + Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    spawn_impl(Builder::new(), DefaultWriter, Location::caller(), data, f)
+        .expect("failed to spawn thread")
+}
+
+/// Spawns a new thread configured with the given [`Builder`] with a top-level
+/// context scope guard, returning a [`JoinHandle`] for it.
+///
+/// It allows setting the child thread name, which is printed with the frame,
+/// and its stack size. See [`spawn_with_context`] for more details.
+///
+/// # Errors
+///
+/// This function will return an error if the OS fails to create a thread,
+/// like [`Builder::spawn`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::build_unwind_context_data;
+///
+/// let path = "data.csv";
+/// let handle = unwind_context::thread::spawn_with_context_in(
+///     std::thread::Builder::new().name("loader".to_owned()),
+///     build_unwind_context_data!(fn load(path)),
+///     move || path.len(),
+/// )
+/// .unwrap();
+/// assert_eq!(handle.join().unwrap(), 8);
+/// ```
+#[track_caller]
+pub fn spawn_with_context_in<D, F, T>(builder: Builder, data: D, f: F) -> IoResult<JoinHandle<T>>
+where
+    D: Debug + DebugAnsiColored + Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    spawn_impl(builder, DefaultWriter, Location::caller(), data, f)
+}
+
+fn spawn_impl<W, D, F, T>(
+    builder: Builder,
+    writer: W,
+    location: &'static Location<'static>,
+    data: D,
+    f: F,
+) -> IoResult<JoinHandle<T>>
+where
+    W: IoWrite + Send + 'static,
+    D: Debug + DebugAnsiColored + Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let colors_enabled = get_thread_colors_enabled();
+    let color_scheme = get_thread_color_scheme();
+    builder.spawn(move || {
+        set_thread_colors_enabled(colors_enabled);
+        set_thread_color_scheme(color_scheme);
+        let _ctx = UnwindContextWithIo::new(
+            data,
+            writer,
+            StdPanicDetector,
+            get_default_color_scheme_if_enabled(),
+        )
+        .with_location(location)
+        .with_thread_info(true)
+        .trace_entry();
+        f()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use core::panic::Location;
+    use std::borrow::ToOwned;
+    use std::io::{Result as IoResult, Write as IoWrite};
+    use std::string::String;
+    use std::sync::mpsc;
+    use std::thread::Builder;
+
+    use crate::test_common::TEST_COLOR_SCHEME;
+    use crate::test_util::{collect_string_from_recv, PatternMatcher};
+    use crate::thread::{spawn_impl, spawn_with_context};
+    use crate::{
+        build_unwind_context_data, get_thread_color_scheme, get_thread_colors_enabled,
+        set_thread_color_scheme, set_thread_colors_enabled,
+    };
+
+    struct Writer(mpsc::Sender<String>);
+
+    impl IoWrite for Writer {
+        #[allow(clippy::unwrap_used)]
+        fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+            self.0
+                .send(String::from_utf8(buf.to_owned()).unwrap())
+                .unwrap();
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> IoResult<()> {
+            Ok(())
+        }
+    }
+
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_spawn_with_context() {
+        let worker_id = 1;
+        let handle = spawn_with_context(
+            build_unwind_context_data!(fn worker(worker_id)),
+            move || {
+                (
+                    worker_id * 2,
+                    get_thread_colors_enabled(),
+                    get_thread_color_scheme().is_some(),
+                )
+            },
+        );
+        assert_eq!(handle.join().unwrap(), (2, None, false));
+    }
+
+    #[allow(clippy::unwrap_used)]
+    #[test]
+    fn test_spawn_with_context_panic() {
+        let (sender, recv) = mpsc::channel();
+        let worker_id = 2;
+        let location = Location::caller();
+
+        set_thread_colors_enabled(Some(true));
+        set_thread_color_scheme(Some(&TEST_COLOR_SCHEME));
+        let handle = spawn_impl(
+            Builder::new().name("worker-2".to_owned()),
+            Writer(sender),
+            location,
+            build_unwind_context_data!(fn worker(worker_id)),
+            move || {
+                assert_ne!(worker_id, 2, "worker panic");
+            },
+        )
+        .unwrap();
+        set_thread_colors_enabled(None);
+        set_thread_color_scheme(None);
+        assert!(handle.join().is_err());
+
+        let output = collect_string_from_recv(&recv);
+        let output = &mut output.as_str();
+        output
+            .expect_str(concat!(
+                "{FN}fn {FN_NAME}worker{FN_BRACE}({DEF}{ARG}worker_id{DEF}: ",
+                "{NUM}2{DEF}{FN_BRACE}){DEF}\n    at {LOC}",
+            ))
+            .unwrap();
+        let _location = output.read_until(" in thread ").unwrap();
+        output.expect_str("'worker-2' (").unwrap();
+        let _thread_id = output.read_until(")").unwrap();
+        assert_eq!(*output, ")\n");
+    }
+}
@@ -0,0 +1,283 @@
+#[cfg(feature = "std")]
+use core::cell::Cell;
+#[cfg(feature = "std")]
+use core::marker::PhantomData;
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+
+#[cfg(feature = "std")]
+static CONTEXTS_ENABLED_GLOBALLY: AtomicBool = AtomicBool::new(true);
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static CONTEXTS_ENABLED: Cell<bool> = const { Cell::new(true) };
+    static SUPPRESSION_DEPTH: Cell<usize> = const { Cell::new(0) };
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+/// Enables or disables unwind context printing for all threads.
+///
+/// By default context printing is enabled. Disabling it silences all context
+/// scope guards regardless of the per-thread
+/// [`set_thread_contexts_enabled`] setting. It is also used by
+/// [`configure_from_env`] for the `UNWIND_CONTEXT=off` setting.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_contexts_enabled(false);
+/// func(123, "abc");
+/// unwind_context::set_contexts_enabled(true);
+/// ```
+///
+/// [`configure_from_env`]: crate::configure_from_env
+#[inline]
+pub fn set_contexts_enabled(enabled: bool) {
+    CONTEXTS_ENABLED_GLOBALLY.store(enabled, AtomicOrdering::Relaxed);
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+/// Returns `true` if unwind context printing is enabled for all threads.
+///
+/// By default context printing is enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(unwind_context::are_contexts_enabled());
+/// ```
+#[inline]
+#[must_use]
+pub fn are_contexts_enabled() -> bool {
+    CONTEXTS_ENABLED_GLOBALLY.load(AtomicOrdering::Relaxed)
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+/// Enables or disables unwind context printing for the current thread.
+///
+/// By default context printing is enabled for all threads. Disabling it is
+/// useful for worker threads that are expected to panic, for example in fuzz
+/// harnesses or speculative computations, so that their unwind context does
+/// not flood the output. The setting only affects context scope guards dropped
+/// on the current thread.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// let handle = std::thread::spawn(|| {
+///     unwind_context::set_thread_contexts_enabled(false);
+///     // Panics expected in this thread will not print the unwind context.
+///     func(123, "abc");
+/// });
+/// handle.join().unwrap();
+/// ```
+#[inline]
+pub fn set_thread_contexts_enabled(enabled: bool) {
+    CONTEXTS_ENABLED.with(|cell| cell.set(enabled));
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+/// Returns `true` if unwind context printing is enabled for the current
+/// thread.
+///
+/// By default context printing is enabled for all threads.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(unwind_context::are_thread_contexts_enabled());
+/// unwind_context::set_thread_contexts_enabled(false);
+/// assert!(!unwind_context::are_thread_contexts_enabled());
+/// ```
+#[inline]
+#[must_use]
+pub fn are_thread_contexts_enabled() -> bool {
+    // The thread local may already be destroyed if a context scope guard is
+    // dropped during thread shutdown.
+    CONTEXTS_ENABLED.try_with(Cell::get).unwrap_or(true)
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+/// A scope guard that suppresses unwind context printing on the current thread
+/// while it is alive.
+///
+/// It is created by the [`suppress_scope`] function.
+///
+/// # Examples
+///
+/// ```rust
+/// let _suppressed: unwind_context::SuppressScopeGuard = unwind_context::suppress_scope();
+/// ```
+#[derive(Debug)]
+pub struct SuppressScopeGuard {
+    // The guard modifies a thread local state, so it should not be sent to
+    // other threads.
+    _not_send: PhantomData<*const ()>,
+}
+
+#[cfg(feature = "std")]
+impl Drop for SuppressScopeGuard {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = SUPPRESSION_DEPTH.try_with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+/// Suppresses unwind context printing on the current thread until the returned
+/// guard is dropped.
+///
+/// All context scope guards dropped during unwinding on the current thread
+/// while the returned guard is alive will not print their context. It is
+/// useful around helpers that intentionally trigger and catch panics, like
+/// `assert_panics!`-style test helpers. Suppression scopes can be nested.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) -> u32 {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     foo.checked_sub(bar.len() as u32).unwrap()
+/// }
+///
+/// {
+///     let _suppressed = unwind_context::suppress_scope();
+///     // The expected panic will not print the unwind context.
+///     let result = std::panic::catch_unwind(|| func(1, "abc"));
+///     assert!(result.is_err());
+/// }
+/// ```
+#[inline]
+#[must_use = "\
+    if unused, the `SuppressScopeGuard` will immediately drop,
+    consider binding the `SuppressScopeGuard` like `let _suppressed = ...`.
+"]
+pub fn suppress_scope() -> SuppressScopeGuard {
+    SUPPRESSION_DEPTH.with(|depth| depth.set(depth.get().saturating_add(1)));
+    SuppressScopeGuard {
+        _not_send: PhantomData,
+    }
+}
+
+/// Returns `true` if a context scope guard dropped during unwinding on the
+/// current thread should print its context.
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn should_print_context() -> bool {
+    are_contexts_enabled()
+        && are_thread_contexts_enabled()
+        && SUPPRESSION_DEPTH.try_with(Cell::get).unwrap_or(0) == 0
+}
+
+/// Returns `true` if a context scope guard dropped during unwinding on the
+/// current thread should print its context.
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn should_print_context() -> bool {
+    true
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::test_util::FixedBufWriter;
+    use crate::{
+        are_thread_contexts_enabled, set_thread_contexts_enabled, suppress_scope,
+        unwind_context_with_fmt, StdPanicDetector,
+    };
+
+    #[allow(clippy::panic)]
+    fn panic_with_context(writer: &mut FixedBufWriter<'_>) {
+        let value = 123;
+        let result = std::panic::catch_unwind(core::panic::AssertUnwindSafe(|| {
+            let _ctx = unwind_context_with_fmt!(
+                (value),
+                writer = &mut *writer,
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            panic!("expected panic");
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_set_thread_contexts_enabled() {
+        let mut buffer = [0; 128];
+
+        let result = std::thread::spawn(move || {
+            assert!(are_thread_contexts_enabled());
+            set_thread_contexts_enabled(false);
+            assert!(!are_thread_contexts_enabled());
+
+            let mut writer = FixedBufWriter::new(&mut buffer);
+            panic_with_context(&mut writer);
+            assert_eq!(writer.into_str(), "");
+
+            set_thread_contexts_enabled(true);
+            assert!(are_thread_contexts_enabled());
+
+            let mut writer = FixedBufWriter::new(&mut buffer);
+            panic_with_context(&mut writer);
+            assert!(writer.into_str().starts_with("value: 123\n    at "));
+        })
+        .join();
+        assert!(result.is_ok());
+
+        // Other threads are not affected.
+        assert!(are_thread_contexts_enabled());
+    }
+
+    #[test]
+    fn test_suppress_scope() {
+        let mut buffer = [0; 128];
+
+        let result = std::thread::spawn(move || {
+            {
+                let _suppressed = suppress_scope();
+                let mut writer = FixedBufWriter::new(&mut buffer);
+                panic_with_context(&mut writer);
+                assert_eq!(writer.into_str(), "");
+
+                {
+                    let _suppressed = suppress_scope();
+                    let mut writer = FixedBufWriter::new(&mut buffer);
+                    panic_with_context(&mut writer);
+                    assert_eq!(writer.into_str(), "");
+                }
+
+                // Still suppressed by the outer scope.
+                let mut writer = FixedBufWriter::new(&mut buffer);
+                panic_with_context(&mut writer);
+                assert_eq!(writer.into_str(), "");
+            }
+
+            let mut writer = FixedBufWriter::new(&mut buffer);
+            panic_with_context(&mut writer);
+            assert!(writer.into_str().starts_with("value: 123\n    at "));
+        })
+        .join();
+        assert!(result.is_ok());
+    }
+}
@@ -0,0 +1,280 @@
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::sync::atomic::Ordering as AtomicOrdering;
+use core::time::Duration;
+
+use atomic_ref::AtomicRef;
+
+static CLOCK_SOURCE: AtomicRef<'_, ClockSource> = AtomicRef::new(None);
+
+/// A timestamp printed before unwind context frames.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::time::Duration;
+///
+/// use unwind_context::Timestamp;
+///
+/// let timestamp = Timestamp::WallClock(Duration::from_secs(1_700_000_000));
+/// assert_eq!(timestamp.to_string(), "2023-11-14T22:13:20.000000Z");
+///
+/// let timestamp = Timestamp::Monotonic(Duration::from_millis(1_820));
+/// assert_eq!(timestamp.to_string(), "1.820000s");
+///
+/// let timestamp = Timestamp::Ticks(12345);
+/// assert_eq!(timestamp.to_string(), "12345");
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub enum Timestamp {
+    /// A wall-clock time represented as a duration since the Unix epoch. It is
+    /// printed as an RFC 3339 UTC date and time.
+    WallClock(Duration),
+    /// A monotonic time represented as a duration since some unspecified
+    /// moment, like a program start. It is printed as a number of seconds.
+    Monotonic(Duration),
+    /// A custom tick counter value.
+    Ticks(u64),
+}
+
+/// A clock source used to get timestamps printed before unwind context
+/// frames.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::sync::atomic::{AtomicU64, Ordering};
+///
+/// use unwind_context::{ClockSource, Timestamp};
+///
+/// static TICKS: AtomicU64 = AtomicU64::new(0);
+///
+/// static TICK_CLOCK: ClockSource =
+///     ClockSource::new(|| Timestamp::Ticks(TICKS.load(Ordering::Relaxed)));
+///
+/// unwind_context::set_clock_source(Some(&TICK_CLOCK));
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct ClockSource {
+    now: fn() -> Timestamp,
+}
+
+impl ClockSource {
+    /// Creates a new clock source using the given function to get the current
+    /// timestamp.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{ClockSource, Timestamp};
+    ///
+    /// static ZERO_CLOCK: ClockSource = ClockSource::new(|| Timestamp::Ticks(0));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new(now: fn() -> Timestamp) -> Self {
+        Self { now }
+    }
+
+    /// Returns the current timestamp.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{ClockSource, Timestamp};
+    ///
+    /// let clock = ClockSource::new(|| Timestamp::Ticks(0));
+    /// assert_eq!(clock.now(), Timestamp::Ticks(0));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn now(&self) -> Timestamp {
+        (self.now)()
+    }
+}
+
+/// A clock source providing the current system wall-clock time.
+///
+/// # Examples
+///
+/// ```rust
+/// unwind_context::set_clock_source(Some(&unwind_context::WALL_CLOCK));
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub static WALL_CLOCK: ClockSource = ClockSource::new(|| {
+    Timestamp::WallClock(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default(),
+    )
+});
+
+/// A clock source providing the monotonic time elapsed since this clock was
+/// set with [`set_clock_source`], which is usually done at the program start.
+///
+/// # Examples
+///
+/// ```rust
+/// unwind_context::set_clock_source(Some(&unwind_context::MONOTONIC_CLOCK));
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub static MONOTONIC_CLOCK: ClockSource = ClockSource::new(|| {
+    Timestamp::Monotonic(
+        MONOTONIC_CLOCK_START
+            .get_or_init(std::time::Instant::now)
+            .elapsed(),
+    )
+});
+
+/// The moment the [`MONOTONIC_CLOCK`] time is measured from.
+#[cfg(feature = "std")]
+static MONOTONIC_CLOCK_START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+
+/// Sets the clock source used to get timestamps printed before unwind context
+/// frames for all threads, or disables timestamps if `None` is given.
+///
+/// By default timestamps are disabled. If the `std` feature is enabled,
+/// [`WALL_CLOCK`] and [`MONOTONIC_CLOCK`] clock sources can be used.
+/// Otherwise, a custom clock source can be created with [`ClockSource::new`],
+/// for example, to print a hardware tick counter.
+///
+/// This function uses [`atomic_ref`] crate to modify a static `AtomicRef` with
+/// a clock source.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_clock_source(Some(&unwind_context::WALL_CLOCK));
+/// // On panic, it will print something like:
+/// // [2024-02-29T12:34:56.123456Z] fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:4:16
+/// func(123, "abc");
+/// ```
+///
+/// [`atomic_ref`]: https://crates.io/crates/atomic_ref
+#[inline]
+pub fn set_clock_source(clock_source: Option<&'static ClockSource>) {
+    #[cfg(feature = "std")]
+    if clock_source.is_some_and(|clock_source| core::ptr::eq(clock_source, &MONOTONIC_CLOCK)) {
+        let _ = MONOTONIC_CLOCK_START.get_or_init(std::time::Instant::now);
+    }
+    CLOCK_SOURCE.store(clock_source, AtomicOrdering::Release);
+}
+
+/// Returns the currently set clock source, if timestamps are enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(unwind_context::get_clock_source().is_none());
+///
+/// if let Some(clock_source) = unwind_context::get_clock_source() {
+///     eprintln!("current timestamp: {}", clock_source.now());
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn get_clock_source() -> Option<&'static ClockSource> {
+    CLOCK_SOURCE.load(AtomicOrdering::Acquire)
+}
+
+impl Display for Timestamp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Self::WallClock(duration) => {
+                let (year, month, day, seconds) = civil_from_unix_seconds(duration.as_secs());
+                write!(
+                    f,
+                    "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:06}Z",
+                    seconds / 3600,
+                    seconds / 60 % 60,
+                    seconds % 60,
+                    duration.subsec_micros(),
+                )
+            }
+            Self::Monotonic(duration) => {
+                write!(f, "{}.{:06}s", duration.as_secs(), duration.subsec_micros())
+            }
+            Self::Ticks(ticks) => write!(f, "{ticks}"),
+        }
+    }
+}
+
+/// Converts a number of seconds since the Unix epoch to a proleptic Gregorian
+/// calendar year, month, day, and seconds since midnight.
+///
+/// It uses the `civil_from_days` algorithm by Howard Hinnant.
+#[allow(clippy::arithmetic_side_effects)] // All values are bounded.
+fn civil_from_unix_seconds(unix_seconds: u64) -> (u64, u64, u64, u64) {
+    let days = unix_seconds / 86_400 + 719_468;
+    let seconds = unix_seconds % 86_400;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    (year, month, day, seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::test_util::buf_fmt;
+    use crate::timestamp::civil_from_unix_seconds;
+    use crate::Timestamp;
+
+    #[test]
+    fn test_civil_from_unix_seconds() {
+        assert_eq!(civil_from_unix_seconds(0), (1970, 1, 1, 0));
+        assert_eq!(civil_from_unix_seconds(86_399), (1970, 1, 1, 86_399));
+        assert_eq!(civil_from_unix_seconds(951_782_400), (2000, 2, 29, 0));
+        assert_eq!(civil_from_unix_seconds(1_709_164_800), (2024, 2, 29, 0));
+        assert_eq!(
+            civil_from_unix_seconds(1_735_689_599),
+            (2024, 12, 31, 86_399)
+        );
+    }
+
+    #[test]
+    fn test_timestamp_fmt() {
+        let mut buffer = [0; 64];
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!(
+                    "{}",
+                    Timestamp::WallClock(Duration::new(1_709_210_096, 123_456_789))
+                )
+            ),
+            Ok("2024-02-29T12:34:56.123456Z")
+        );
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!("{}", Timestamp::Monotonic(Duration::new(12, 5_000)))
+            ),
+            Ok("12.000005s")
+        );
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{}", Timestamp::Ticks(42))),
+            Ok("42")
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use core::fmt::{Debug, Formatter, Result as FmtResult};
+
+/// An utility wrapper type which is used to print both the value recorded when
+/// the context is created and the current value, like `3 -> 17`, if they
+/// differ.
+///
+/// It stores a clone of the value made on creation and a reference to the
+/// value itself, so the value can only be changed through interior
+/// mutability, like [`core::cell::Cell`] or [`core::cell::RefCell`], while the
+/// context scope guard is alive. It is useful for loop counters and state
+/// machines stored in cells. If the value was not changed, it is printed once.
+///
+/// It is usually created with the [`track`] function.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::cell::Cell;
+///
+/// use unwind_context::{track, unwind_context};
+///
+/// fn func(state: &Cell<u32>) {
+///     let _ctx = unwind_context!(fn(track(state)));
+///     state.set(17);
+///     // On panic, it will print something like:
+///     // fn func(track(state): Cell { value: 3 } -> Cell { value: 17 })
+///     //     at src/main.rs:7:16
+///     // ...
+/// }
+/// ```
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Tracked<'a, T> {
+    /// The clone of the value made when the wrapper was created.
+    pub initial: T,
+    /// The reference to the tracked value.
+    pub current: &'a T,
+}
+
+impl<'a, T: Clone> Tracked<'a, T> {
+    /// Creates a new `Tracked` wrapper with a clone of the given value and a
+    /// reference to it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::cell::Cell;
+    ///
+    /// let value = Cell::new(1);
+    /// let tracked = unwind_context::Tracked::new(&value);
+    /// value.set(2);
+    /// assert_eq!(
+    ///     format!("{tracked:?}"),
+    ///     "Cell { value: 1 } -> Cell { value: 2 }"
+    /// );
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new(value: &'a T) -> Self {
+        Self {
+            initial: value.clone(),
+            current: value,
+        }
+    }
+}
+
+/// Creates a new [`Tracked`] wrapper printing both the value recorded when
+/// the context is created and the current value if they differ.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::cell::Cell;
+///
+/// use unwind_context::{track, unwind_context};
+///
+/// fn func(counter: &Cell<usize>, items: &[u32]) {
+///     let _ctx = unwind_context!(fn(track(counter), items));
+///     for _ in items {
+///         counter.set(counter.get() + 1);
+///         // ...
+///     }
+/// }
+/// ```
+#[inline]
+#[must_use]
+pub fn track<T: Clone>(value: &T) -> Tracked<'_, T> {
+    Tracked::new(value)
+}
+
+impl<T> Debug for Tracked<'_, T>
+where
+    T: Debug + PartialEq,
+{
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if self.initial == *self.current {
+            Debug::fmt(self.current, f)
+        } else {
+            Debug::fmt(&self.initial, f)?;
+            f.write_str(" -> ")?;
+            Debug::fmt(self.current, f)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use crate::test_common::TEST_COLOR_SCHEME;
+    use crate::test_util::buf_fmt;
+    use crate::{build_unwind_context_data, track, AnsiColored};
+
+    #[test]
+    fn test_tracked() {
+        let mut buffer = [0; 256];
+        let counter = Cell::new(3);
+
+        let context = build_unwind_context_data!(fn func(track(&counter)));
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{context:?}")),
+            Ok("fn func(track(&counter): Cell { value: 3 })")
+        );
+
+        counter.set(17);
+        assert_eq!(
+            buf_fmt(&mut buffer, format_args!("{context:?}")),
+            Ok("fn func(track(&counter): Cell { value: 3 } -> Cell { value: 17 })")
+        );
+
+        let tracked = track(&counter);
+        counter.set(18);
+        assert_eq!(
+            buf_fmt(
+                &mut buffer,
+                format_args!(
+                    "{:?}",
+                    AnsiColored::new(build_unwind_context_data!(tracked), &TEST_COLOR_SCHEME)
+                )
+            ),
+            Ok(concat!(
+                "{ARG}tracked{DEF}: {ITEM}Cell{DEF} {BRACE}{{DEF} {IDENT}value{DEF}: {NUM}17{DEF} ",
+                "{BRACE}}{DEF} {NUM}-{DEF}> {ITEM}Cell{DEF} {BRACE}{{DEF} {IDENT}value{DEF}: ",
+                "{NUM}18{DEF} {BRACE}}{DEF}"
+            ))
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use core::fmt::{Display, Formatter, Result as FmtResult, Write as FmtWrite};
+use core::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
+use std::sync::{PoisonError, RwLock};
+
+/// A function writing a header printed before the first unwind context frame
+/// of an unwind, or a footer printed after the last one.
+pub type UnwindBannerFn = fn(&mut dyn FmtWrite) -> FmtResult;
+
+static UNWIND_HEADER: RwLock<Option<UnwindBannerFn>> = RwLock::new(None);
+static UNWIND_FOOTER: RwLock<Option<UnwindBannerFn>> = RwLock::new(None);
+static SHOULD_PRINT_THREAD_BANNER: AtomicBool = AtomicBool::new(false);
+static SHOULD_PRINT_UNWIND_SUMMARY: AtomicBool = AtomicBool::new(false);
+
+/// Sets the function writing a header printed once before the first unwind
+/// context frame of every unwind, or removes the header if `None` is given.
+///
+/// Together with [`set_unwind_footer`] it makes context blocks easy to spot
+/// and to grep for in long logs. The header is written by the innermost
+/// context scope guard to its own writer, before the panic location recorded
+/// by [`install_panic_location_hook`], if any.
+///
+/// By default no header is printed.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::fmt::{Result, Write};
+///
+/// use unwind_context::unwind_context;
+///
+/// fn header(f: &mut dyn Write) -> Result {
+///     let thread = std::thread::current();
+///     writeln!(
+///         f,
+///         "===== unwind context (thread '{}') =====",
+///         thread.name().unwrap_or("<unnamed>")
+///     )
+/// }
+///
+/// fn footer(f: &mut dyn Write) -> Result {
+///     writeln!(f, "===== end of unwind context =====")
+/// }
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_unwind_header(Some(header));
+/// unwind_context::set_unwind_footer(Some(footer));
+/// // On panic, it will print something like:
+/// // ===== unwind context (thread 'main') =====
+/// // fn func(foo: 123, bar: "abc")
+/// //     at src/main.rs:18:16
+/// // ===== end of unwind context =====
+/// func(123, "abc");
+/// # unwind_context::set_unwind_header(None);
+/// # unwind_context::set_unwind_footer(None);
+/// ```
+///
+/// [`install_panic_location_hook`]: crate::install_panic_location_hook
+#[inline]
+pub fn set_unwind_header(header: Option<UnwindBannerFn>) {
+    *UNWIND_HEADER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = header;
+}
+
+/// Returns the function writing a header printed before the first unwind
+/// context frame of every unwind, if set.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(unwind_context::get_unwind_header().is_none());
+/// ```
+#[inline]
+#[must_use]
+pub fn get_unwind_header() -> Option<UnwindBannerFn> {
+    *UNWIND_HEADER.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Sets the function writing a footer printed once after the last unwind
+/// context frame of every unwind, or removes the footer if `None` is given.
+///
+/// The footer is written by the outermost context scope guard to its own
+/// writer, after the collapsed and suppressed frames reports, if any. See
+/// [`set_unwind_header`] for more details.
+///
+/// By default no footer is printed.
+///
+/// # Examples
+///
+/// ```rust
+/// use core::fmt::{Result, Write};
+///
+/// fn footer(f: &mut dyn Write) -> Result {
+///     writeln!(f, "===== end of unwind context =====")
+/// }
+///
+/// unwind_context::set_unwind_footer(Some(footer));
+/// # unwind_context::set_unwind_footer(None);
+/// ```
+#[inline]
+pub fn set_unwind_footer(footer: Option<UnwindBannerFn>) {
+    *UNWIND_FOOTER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = footer;
+}
+
+/// Returns the function writing a footer printed after the last unwind
+/// context frame of every unwind, if set.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(unwind_context::get_unwind_footer().is_none());
+/// ```
+#[inline]
+#[must_use]
+pub fn get_unwind_footer() -> Option<UnwindBannerFn> {
+    *UNWIND_FOOTER.read().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Enables or disables printing of a `thread 'name' panicking, unwind
+/// context:` banner once before the first unwind context frame of every
+/// unwind.
+///
+/// Unlike the `thread_info = true` macro parameter, which annotates every
+/// frame, the banner makes interleaved output of multiple threads attributable
+/// with a single line per unwind. It is printed after the header set with
+/// [`set_unwind_header`], if any.
+///
+/// By default the thread banner is not printed.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context!(fn(foo, bar));
+///     // ...
+/// }
+///
+/// unwind_context::set_thread_banner_enabled(true);
+/// let handle = std::thread::Builder::new()
+///     .name("worker-7".to_owned())
+///     .spawn(|| {
+///         // On panic, it will print something like:
+///         // thread 'worker-7' panicking, unwind context:
+///         // fn func(foo: 123, bar: "abc")
+///         //     at src/main.rs:4:16
+///         func(123, "abc");
+///     })
+///     .unwrap();
+/// handle.join().unwrap();
+/// # unwind_context::set_thread_banner_enabled(false);
+/// ```
+#[inline]
+pub fn set_thread_banner_enabled(enabled: bool) {
+    SHOULD_PRINT_THREAD_BANNER.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if printing of the thread banner before the first unwind
+/// context frame of every unwind was enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::is_thread_banner_enabled());
+/// ```
+#[inline]
+#[must_use]
+pub fn is_thread_banner_enabled() -> bool {
+    SHOULD_PRINT_THREAD_BANNER.load(AtomicOrdering::Relaxed)
+}
+
+/// Enables or disables printing of a summary like
+/// `-- 14 context frames (3 suppressed) --` once after the last unwind context
+/// frame of every unwind.
+///
+/// The summary counts all context frames of the unwind, including the ones
+/// that were not printed because they were collapsed as repeated, exceeded the
+/// [`set_max_frames_per_unwind`] limit, or exceeded the [`set_output_budget`]
+/// budget. It is printed before the footer set with [`set_unwind_footer`], if
+/// any.
+///
+/// By default the summary is not printed.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context;
+///
+/// fn recursive(depth: usize) {
+///     let _ctx = unwind_context!(fn(depth));
+///     // ...
+///     if let Some(depth) = depth.checked_sub(1) {
+///         recursive(depth);
+///     }
+/// }
+///
+/// unwind_context::set_unwind_summary_enabled(true);
+/// unwind_context::set_max_frames_per_unwind(Some(2));
+/// // On panic, it will print something like:
+/// // fn recursive(depth: 0)
+/// //     at src/main.rs:4:16
+/// // fn recursive(depth: 1)
+/// //     at src/main.rs:4:16
+/// // ... 3 more context frames suppressed
+/// // -- 5 context frames (3 suppressed) --
+/// recursive(4);
+/// # unwind_context::set_max_frames_per_unwind(None);
+/// # unwind_context::set_unwind_summary_enabled(false);
+/// ```
+///
+/// [`set_max_frames_per_unwind`]: crate::set_max_frames_per_unwind
+/// [`set_output_budget`]: crate::set_output_budget
+#[inline]
+pub fn set_unwind_summary_enabled(enabled: bool) {
+    SHOULD_PRINT_UNWIND_SUMMARY.store(enabled, AtomicOrdering::Relaxed);
+}
+
+/// Returns `true` if printing of the summary after the last unwind context
+/// frame of every unwind was enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(!unwind_context::is_unwind_summary_enabled());
+/// ```
+#[inline]
+#[must_use]
+pub fn is_unwind_summary_enabled() -> bool {
+    SHOULD_PRINT_UNWIND_SUMMARY.load(AtomicOrdering::Relaxed)
+}
+
+/// An utility wrapper type which is used to format an unwind header or footer
+/// with a given function.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct UnwindBannerFmt(pub UnwindBannerFn);
+
+impl Display for UnwindBannerFmt {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        (self.0)(f)
+    }
+}
+
+/// An utility wrapper type which is used to format the current thread banner
+/// printed before the first unwind context frame.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct ThreadBannerFmt;
+
+impl Display for ThreadBannerFmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let thread = std::thread::current();
+        writeln!(
+            f,
+            "thread '{}' panicking, unwind context:",
+            thread.name().unwrap_or("<unnamed>")
+        )
+    }
+}
@@ -0,0 +1,523 @@
+#[cfg(feature = "std")]
+use core::cell::{Cell, RefCell};
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use core::fmt::Write as FmtWrite;
+use core::panic::Location;
+#[cfg(feature = "std")]
+use std::io::StderrLock;
+#[cfg(feature = "std")]
+use std::time::Instant;
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+#[cfg(feature = "std")]
+use crate::hook_panic_detector::{end_hook_panic, panic_generation};
+#[cfg(feature = "std")]
+use crate::{
+    are_frame_numbers_enabled, get_collapse_repeated_frames, get_max_frames_per_unwind,
+    is_elapsed_time_enabled, CollapseRepeatedFrames, FixedBufWriter,
+};
+
+/// The size of the buffer used to compare the formatted context data of
+/// consecutive frames. Frames with longer formatted context data are never
+/// collapsed as repeated ones.
+#[cfg(feature = "std")]
+const FRAME_DATA_BUFFER_SIZE: usize = 256;
+
+/// The state of the current unwind shared by all context scope guards of the
+/// current thread.
+#[cfg(feature = "std")]
+#[derive(Copy, Clone, Debug)]
+struct UnwindState {
+    /// The number of live context scope guards.
+    depth: usize,
+    /// Whether the entry time of any live context scope guard is recorded.
+    has_entry_times: bool,
+    /// The index of the next frame printed during the current unwind.
+    next_frame_index: usize,
+    /// The location of the last frame of the current unwind.
+    last_frame: Option<&'static Location<'static>>,
+    /// The number of frames collapsed after the last printed frame.
+    repeated: usize,
+    /// The number of frames printed during the current unwind.
+    printed: usize,
+    /// The number of frames hidden during the current unwind because they were
+    /// collapsed or suppressed.
+    hidden: usize,
+    /// The number of frames suppressed during the current unwind because of
+    /// the maximum number of frames limit.
+    suppressed: usize,
+    /// The maximum number of frames printed during the current unwind
+    /// overriding the global setting, if set. The `usize::MAX` value means
+    /// that the number of frames is not limited.
+    max_frames: Option<usize>,
+    /// The number of bytes of frames printed during the current unwind.
+    written: usize,
+    /// Whether the output budget was exceeded during the current unwind.
+    is_budget_exceeded: bool,
+    /// The panic generation of the current thread when the first frame of the
+    /// current unwind was registered, if the panic hook is installed.
+    generation: Option<u64>,
+}
+
+#[cfg(feature = "std")]
+impl UnwindState {
+    const fn new() -> Self {
+        Self {
+            depth: 0,
+            has_entry_times: false,
+            next_frame_index: 0,
+            last_frame: None,
+            repeated: 0,
+            printed: 0,
+            hidden: 0,
+            suppressed: 0,
+            max_frames: None,
+            written: 0,
+            is_budget_exceeded: false,
+            generation: None,
+        }
+    }
+
+    /// Returns `true` if any frame was registered during the current unwind.
+    const fn is_started(&self) -> bool {
+        self.next_frame_index > 0
+    }
+
+    /// Resets the state of the current unwind keeping the state of live
+    /// context scope guards.
+    fn reset(&mut self) {
+        drop(take_stderr_lock());
+        *self = Self {
+            depth: self.depth,
+            has_entry_times: self.has_entry_times,
+            ..Self::new()
+        };
+    }
+
+    /// Ends the current unwind if the thread is no longer panicking, which
+    /// means that the unwind has been caught, while some context scope guards
+    /// may still be alive.
+    ///
+    /// The panic flag of the [`HookPanicDetector`] is not cleared here, since
+    /// it is used where `std::thread::panicking()` is unreliable. It is
+    /// cleared in [`end_unwind`] instead.
+    ///
+    /// [`HookPanicDetector`]: crate::HookPanicDetector
+    fn end_if_caught(&mut self) {
+        if !std::thread::panicking() && self.is_started() {
+            self.reset();
+        }
+    }
+
+    /// Ends the current unwind if a new panic has been counted by the panic
+    /// hook since its first frame was registered.
+    fn end_if_new_panic(&mut self, generation: Option<u64>) {
+        if self.is_started() && self.generation != generation {
+            self.reset();
+        }
+        self.generation = generation;
+    }
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static UNWIND_STATE: Cell<UnwindState> = const { Cell::new(UnwindState::new()) };
+    static LAST_FRAME_DATA: RefCell<([u8; FRAME_DATA_BUFFER_SIZE], Option<usize>)> =
+        const { RefCell::new(([0; FRAME_DATA_BUFFER_SIZE], None)) };
+    static ENTRY_TIMES: RefCell<Vec<(usize, Instant)>> = const { RefCell::new(Vec::new()) };
+    static STDERR_LOCK: RefCell<Option<StderrLock<'static>>> = const { RefCell::new(None) };
+}
+
+/// Locks the standard error stream until the end of the current unwind of the
+/// current thread, if it is not locked yet.
+#[cfg(feature = "std")]
+pub(crate) fn lock_stderr_for_unwind() {
+    let _ = STDERR_LOCK.try_with(|lock| {
+        if let Ok(mut lock) = lock.try_borrow_mut() {
+            if lock.is_none() {
+                *lock = Some(std::io::stderr().lock());
+            }
+        }
+    });
+}
+
+/// Takes the standard error stream lock held during the current unwind of the
+/// current thread, if any.
+#[cfg(feature = "std")]
+pub(crate) fn take_stderr_lock() -> Option<StderrLock<'static>> {
+    STDERR_LOCK
+        .try_with(|lock| lock.try_borrow_mut().ok()?.take())
+        .ok()
+        .flatten()
+}
+
+/// A decision on how to print a frame during the current unwind.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub(crate) struct FrameSlot {
+    /// Frame index within the current unwind, if frames are numbered.
+    pub index: Option<usize>,
+    /// The number of repeated frames collapsed before this frame that were not
+    /// reported yet.
+    pub repeated_before: usize,
+    /// Whether this frame repeats the previous one or exceeds the maximum
+    /// number of frames and should not be printed.
+    pub is_hidden: bool,
+    /// The number of live context scope guards enclosing this frame guard.
+    pub depth: usize,
+    /// Whether this frame is the first frame of the current unwind.
+    pub is_first: bool,
+}
+
+/// The scope of a context scope guard which frame is printed.
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub(crate) struct GuardScope {
+    /// Whether the guard is the outermost guard of the current thread that
+    /// is dropped.
+    pub is_outermost: bool,
+    /// The time when the guard was created, if its entry time was recorded.
+    #[cfg(feature = "std")]
+    pub entered_at: Option<Instant>,
+}
+
+/// The frames hidden at the end of an unwind that were not reported yet.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+pub(crate) struct UnwindEnd {
+    /// The number of repeated frames collapsed after the last printed frame.
+    pub repeated: usize,
+    /// The number of frames suppressed because of the maximum number of
+    /// frames limit or the output budget.
+    pub suppressed: usize,
+    /// Whether the frames were suppressed because of the output budget.
+    pub is_budget_exceeded: bool,
+    /// The number of frames registered during the current unwind.
+    pub frames: usize,
+    /// The number of frames hidden during the current unwind because they were
+    /// collapsed or suppressed.
+    pub hidden: usize,
+}
+
+#[cfg(feature = "std")]
+#[inline]
+fn update_state<R>(f: impl FnOnce(&mut UnwindState) -> R) -> Option<R> {
+    UNWIND_STATE
+        .try_with(|cell| {
+            let mut state = cell.get();
+            let result = f(&mut state);
+            cell.set(state);
+            result
+        })
+        .ok()
+}
+
+/// Registers a newly created context scope guard and records its entry time
+/// if measuring of the elapsed time is enabled globally.
+///
+/// The entry time is stored by the current thread rather than by the guard,
+/// so that guards without per-guard options store only the context data and
+/// the code location.
+///
+/// It also ends a previously caught unwind, if any.
+#[inline]
+pub(crate) fn enter_scope() {
+    #[cfg(feature = "std")]
+    let is_timed = is_elapsed_time_enabled();
+    #[cfg(feature = "std")]
+    if let Some(depth) = update_state(|state| {
+        state.end_if_caught();
+        let depth = state.depth;
+        state.depth = depth.saturating_add(1);
+        state.has_entry_times |= is_timed;
+        depth
+    }) {
+        if is_timed {
+            record_entry_time(depth);
+        }
+    }
+}
+
+/// Unregisters a dropped context scope guard and returns its scope.
+///
+/// It also ends a previously caught unwind, if any.
+#[inline]
+pub(crate) fn exit_scope() -> GuardScope {
+    #[cfg(feature = "std")]
+    {
+        let (depth, has_entry_times) = update_state(|state| {
+            state.end_if_caught();
+            state.depth = state.depth.saturating_sub(1);
+            (state.depth, state.has_entry_times)
+        })
+        .unwrap_or_default();
+        GuardScope {
+            is_outermost: depth == 0,
+            entered_at: if has_entry_times {
+                take_entry_time(depth)
+            } else {
+                None
+            },
+        }
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        GuardScope { is_outermost: true }
+    }
+}
+
+/// Returns the scope of a live context scope guard, assuming that it is the
+/// innermost live guard of the current thread.
+#[inline]
+pub(crate) fn current_scope() -> GuardScope {
+    GuardScope {
+        is_outermost: false,
+        #[cfg(feature = "std")]
+        entered_at: innermost_entry_time(),
+    }
+}
+
+/// Returns the entry time of the innermost live context scope guard of the
+/// current thread, if it was recorded.
+#[cfg(feature = "std")]
+fn innermost_entry_time() -> Option<Instant> {
+    let depth = scope_depth().checked_sub(1)?;
+    ENTRY_TIMES
+        .try_with(|times| {
+            let times = times.try_borrow().ok()?;
+            times
+                .last()
+                .and_then(|&(entry_depth, time)| (entry_depth == depth).then_some(time))
+        })
+        .ok()
+        .flatten()
+}
+
+#[cfg(feature = "std")]
+#[cold]
+#[inline(never)]
+fn record_entry_time(depth: usize) {
+    let _ = ENTRY_TIMES.try_with(|times| {
+        if let Ok(mut times) = times.try_borrow_mut() {
+            times.push((depth, Instant::now()));
+        }
+    });
+}
+
+/// Removes the entry times of context scope guards at the given depth or
+/// deeper and returns the entry time of the guard at the given depth.
+///
+/// Guards are usually dropped in the reverse order of their creation, so the
+/// removed entry times belong to the dropped guard.
+#[cfg(feature = "std")]
+#[cold]
+#[inline(never)]
+fn take_entry_time(depth: usize) -> Option<Instant> {
+    let (entered_at, is_empty) = ENTRY_TIMES
+        .try_with(|times| {
+            let Ok(mut times) = times.try_borrow_mut() else {
+                return (None, false);
+            };
+            let mut entered_at = None;
+            while let Some(&(entry_depth, time)) = times.last() {
+                if entry_depth < depth {
+                    break;
+                }
+                entered_at = (entry_depth == depth).then_some(time);
+                let _ = times.pop();
+            }
+            (entered_at, times.is_empty())
+        })
+        .unwrap_or((None, false));
+    if is_empty {
+        let _ = update_state(|state| state.has_entry_times = false);
+    }
+    entered_at
+}
+
+/// Returns the number of live context scope guards of the current thread.
+#[inline]
+pub(crate) fn scope_depth() -> usize {
+    #[cfg(feature = "std")]
+    {
+        UNWIND_STATE
+            .try_with(|cell| cell.get().depth)
+            .unwrap_or_default()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        0
+    }
+}
+
+/// Registers a frame printed during the current unwind and returns how it
+/// should be printed.
+///
+/// A new unwind is started if the previous one has been caught, which is
+/// detected either by a context scope guard created or dropped while the thread
+/// was not panicking, or by a new panic counted by the panic hook installed
+/// with [`install_hook`].
+///
+/// Frame numbering and repeated frames collapsing require thread-local storage
+/// and are not supported in `no_std` environments.
+///
+/// [`install_hook`]: crate::install_hook
+#[cfg_attr(not(feature = "std"), allow(unused_variables))]
+pub(crate) fn enter_frame<T: Debug>(data: &T, location: &'static Location<'static>) -> FrameSlot {
+    #[cfg(feature = "std")]
+    if std::thread::panicking() {
+        let mode = get_collapse_repeated_frames();
+        let is_same_data = match mode {
+            CollapseRepeatedFrames::Never => false,
+            CollapseRepeatedFrames::SameLocation => true,
+            CollapseRepeatedFrames::SameLocationAndData => replace_last_frame_data(data),
+        };
+        let should_number = are_frame_numbers_enabled();
+        let generation = panic_generation();
+        return update_state(|state| {
+            state.end_if_new_panic(generation);
+            let index = state.next_frame_index;
+            let is_first = index == 0;
+            state.next_frame_index = index.saturating_add(1);
+            let index = should_number.then_some(index);
+            if state.suppressed > 0 {
+                state.suppressed = state.suppressed.saturating_add(1);
+                state.hidden = state.hidden.saturating_add(1);
+                return FrameSlot {
+                    index,
+                    repeated_before: 0,
+                    is_hidden: true,
+                    depth: state.depth,
+                    is_first,
+                };
+            }
+            let is_collapsed = is_same_data && state.last_frame == Some(location);
+            state.last_frame = Some(location);
+            if is_collapsed {
+                state.repeated = state.repeated.saturating_add(1);
+                state.hidden = state.hidden.saturating_add(1);
+                return FrameSlot {
+                    index,
+                    repeated_before: 0,
+                    is_hidden: true,
+                    depth: state.depth,
+                    is_first,
+                };
+            }
+            let repeated_before = core::mem::take(&mut state.repeated);
+            let max_frames = state.max_frames.or_else(get_max_frames_per_unwind);
+            let is_suppressed = max_frames.is_some_and(|max_frames| state.printed >= max_frames);
+            if is_suppressed {
+                state.suppressed = 1;
+                state.hidden = state.hidden.saturating_add(1);
+            } else {
+                state.printed = state.printed.saturating_add(1);
+            }
+            FrameSlot {
+                index,
+                repeated_before,
+                is_hidden: is_suppressed,
+                depth: state.depth,
+                is_first,
+            }
+        })
+        .unwrap_or_default();
+    }
+    FrameSlot::default()
+}
+
+/// Marks the end of the current unwind and returns the frames hidden at its end
+/// that were not reported yet.
+///
+/// It is called when the outermost context scope guard is dropped during
+/// unwinding, or when any context scope guard is dropped while it does not
+/// detect a panic.
+#[inline]
+pub(crate) fn end_unwind() -> UnwindEnd {
+    #[cfg(feature = "std")]
+    {
+        update_state(|state| {
+            let end = UnwindEnd {
+                repeated: state.repeated,
+                suppressed: state.suppressed,
+                is_budget_exceeded: state.is_budget_exceeded,
+                frames: state.next_frame_index,
+                hidden: state.hidden,
+            };
+            // The maximum number of frames set before a panic applies to the
+            // next unwind.
+            let max_frames = if std::thread::panicking() {
+                None
+            } else {
+                state.max_frames
+            };
+            end_hook_panic();
+            state.reset();
+            state.max_frames = max_frames;
+            end
+        })
+        .unwrap_or_default()
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        UnwindEnd::default()
+    }
+}
+
+/// Sets the maximum number of frames printed during the current or the next
+/// unwind of the current thread overriding the global setting.
+#[cfg(feature = "std")]
+pub(crate) fn set_current_unwind_max_frames(max_frames: Option<usize>) {
+    let generation = panic_generation();
+    let _ = update_state(|state| {
+        state.end_if_caught();
+        state.end_if_new_panic(generation);
+        state.max_frames = Some(max_frames.unwrap_or(usize::MAX));
+    });
+}
+
+/// Charges a frame of the given length printed during the current unwind to
+/// the output budget and returns `false` if it does not fit into the remaining
+/// budget. In this case the frame and all subsequent frames of the current
+/// unwind are suppressed.
+#[cfg(feature = "std")]
+pub(crate) fn consume_output_budget(len: usize, budget: usize) -> bool {
+    if !std::thread::panicking() {
+        return true;
+    }
+    update_state(|state| {
+        let written = state.written.saturating_add(len);
+        if written > budget {
+            state.suppressed = state.suppressed.saturating_add(1);
+            state.hidden = state.hidden.saturating_add(1);
+            state.is_budget_exceeded = true;
+            false
+        } else {
+            state.written = written;
+            true
+        }
+    })
+    .unwrap_or(true)
+}
+
+/// Stores the formatted context data of the current frame and returns `true`
+/// if it is equal to the formatted context data of the previous frame.
+///
+/// The data is compared exactly, and data that does not fit into the buffer is
+/// never considered equal.
+#[cfg(feature = "std")]
+fn replace_last_frame_data<T: Debug>(data: &T) -> bool {
+    let mut buffer = [0; FRAME_DATA_BUFFER_SIZE];
+    let mut writer = FixedBufWriter::new(&mut buffer);
+    let len = write!(writer, "{data:?}").is_ok().then(|| writer.len());
+    LAST_FRAME_DATA
+        .try_with(|last| {
+            let Ok(mut last) = last.try_borrow_mut() else {
+                return false;
+            };
+            let is_same = len
+                .is_some_and(|len| last.1 == Some(len) && buffer.get(..len) == last.0.get(..len));
+            *last = (buffer, len);
+            is_same
+        })
+        .unwrap_or(false)
+}
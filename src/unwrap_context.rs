@@ -0,0 +1,209 @@
+use core::fmt::Debug;
+
+/// An extension trait for [`Option`] and [`Result`] providing unwrap methods
+/// that include a given context in the panic message.
+///
+/// The context can be any [`core::fmt::Debug`] value, for example the one
+/// built with the [`build_unwind_context_data`] macro using the same syntax
+/// as the [`unwind_context`] macro. This gives a one-expression context for
+/// the common case of an unwrap failing somewhere in a loop.
+///
+/// The context value is created before the unwrap, so consider using
+/// references to avoid moving or cloning the captured values.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{build_unwind_context_data, UnwrapContext};
+///
+/// let items = ["1", "2", "3"];
+/// let mut sum = 0;
+/// for (index, item) in items.iter().enumerate() {
+///     let value: u32 = item
+///         .parse()
+///         .unwrap_ctx(build_unwind_context_data!(index, item));
+///     sum += value;
+/// }
+/// assert_eq!(sum, 6);
+/// ```
+///
+/// [`build_unwind_context_data`]: crate::build_unwind_context_data
+/// [`unwind_context`]: crate::unwind_context
+pub trait UnwrapContext<T> {
+    /// Returns the contained value, or panics with a message including the
+    /// given context if there is no value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is [`None`] or [`Err`], with a panic message
+    /// including the given context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, UnwrapContext};
+    ///
+    /// let index = 2;
+    /// let value = Some(123).unwrap_ctx(build_unwind_context_data!(index));
+    /// assert_eq!(value, 123);
+    /// ```
+    ///
+    /// ```rust,should_panic
+    /// use unwind_context::{build_unwind_context_data, UnwrapContext};
+    ///
+    /// let index = 2;
+    /// // Panics with:
+    /// // called `Option::unwrap()` on a `None` value
+    /// // context: index: 2
+    /// let value: u32 = None.unwrap_ctx(build_unwind_context_data!(index));
+    /// ```
+    #[track_caller]
+    fn unwrap_ctx<C: Debug>(self, context: C) -> T;
+
+    /// Returns the contained value, or panics with a given message and a given
+    /// context if there is no value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is [`None`] or [`Err`], with a panic message
+    /// including the given message and context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use unwind_context::{build_unwind_context_data, UnwrapContext};
+    ///
+    /// let (index, item) = (2, "123");
+    /// let value: u32 = item
+    ///     .parse()
+    ///     .expect_ctx("invalid number", build_unwind_context_data!(index, item));
+    /// assert_eq!(value, 123);
+    /// ```
+    ///
+    /// ```rust,should_panic
+    /// use unwind_context::{build_unwind_context_data, UnwrapContext};
+    ///
+    /// let (index, item) = (2, "abc");
+    /// // Panics with:
+    /// // invalid number: ParseIntError { kind: InvalidDigit }
+    /// // context: index: 2, item: "abc"
+    /// let value: u32 = item
+    ///     .parse()
+    ///     .expect_ctx("invalid number", build_unwind_context_data!(index, item));
+    /// ```
+    #[track_caller]
+    fn expect_ctx<C: Debug>(self, msg: &str, context: C) -> T;
+}
+
+impl<T> UnwrapContext<T> for Option<T> {
+    #[inline]
+    #[track_caller]
+    fn unwrap_ctx<C: Debug>(self, context: C) -> T {
+        match self {
+            Some(value) => value,
+            None => unwrap_failed(
+                format_args!("called `Option::unwrap()` on a `None` value"),
+                &context,
+            ),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    fn expect_ctx<C: Debug>(self, msg: &str, context: C) -> T {
+        match self {
+            Some(value) => value,
+            None => unwrap_failed(format_args!("{msg}"), &context),
+        }
+    }
+}
+
+impl<T, E: Debug> UnwrapContext<T> for Result<T, E> {
+    #[inline]
+    #[track_caller]
+    fn unwrap_ctx<C: Debug>(self, context: C) -> T {
+        match self {
+            Ok(value) => value,
+            Err(err) => unwrap_failed(
+                format_args!("called `Result::unwrap()` on an `Err` value: {err:?}"),
+                &context,
+            ),
+        }
+    }
+
+    #[inline]
+    #[track_caller]
+    fn expect_ctx<C: Debug>(self, msg: &str, context: C) -> T {
+        match self {
+            Ok(value) => value,
+            Err(err) => unwrap_failed(format_args!("{msg}: {err:?}"), &context),
+        }
+    }
+}
+
+#[allow(clippy::panic)] // Used to implement unwrap methods.
+#[cold]
+#[inline(never)]
+#[track_caller]
+fn unwrap_failed(message: core::fmt::Arguments<'_>, context: &dyn Debug) -> ! {
+    panic!("{message}\ncontext: {context:?}")
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use core::panic::AssertUnwindSafe;
+    use std::panic::catch_unwind;
+    use std::string::String;
+
+    use crate::{build_unwind_context_data, UnwrapContext};
+
+    fn panic_message<T>(f: impl FnOnce() -> T) -> String {
+        let payload = catch_unwind(AssertUnwindSafe(f))
+            .err()
+            .expect("expected a panic");
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_unwrap_ctx() {
+        let (index, item) = (2, "abc");
+
+        assert_eq!(Some(1).unwrap_ctx(build_unwind_context_data!(index)), 1);
+        assert_eq!(
+            Ok::<_, ()>(2).unwrap_ctx(build_unwind_context_data!(index)),
+            2
+        );
+        assert_eq!(
+            Some(3).expect_ctx("msg", build_unwind_context_data!(index)),
+            3
+        );
+        assert_eq!(
+            Ok::<_, ()>(4).expect_ctx("msg", build_unwind_context_data!(index)),
+            4
+        );
+
+        assert_eq!(
+            panic_message(|| None::<u32>.unwrap_ctx(build_unwind_context_data!(index, item))),
+            "called `Option::unwrap()` on a `None` value\ncontext: index: 2, item: \"abc\""
+        );
+        assert_eq!(
+            panic_message(|| Err::<u32, _>("err").unwrap_ctx(build_unwind_context_data!(index))),
+            "called `Result::unwrap()` on an `Err` value: \"err\"\ncontext: index: 2"
+        );
+        assert_eq!(
+            panic_message(
+                || None::<u32>.expect_ctx("no value", build_unwind_context_data!(fn func(index)))
+            ),
+            "no value\ncontext: fn func(index: 2)"
+        );
+        assert_eq!(
+            panic_message(
+                || Err::<u32, _>(5).expect_ctx("bad value", build_unwind_context_data!(item))
+            ),
+            "bad value: 5\ncontext: item: \"abc\""
+        );
+    }
+}
@@ -8,3 +8,30 @@ macro_rules! expr_or_default_expr {
         $expr
     };
 }
+
+// Expands to the first group of tokens if any of the given tokens are
+// present, and to the second group of tokens otherwise.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! if_any_tokens {
+    ( [], { $( $then:tt )* }, { $( $else:tt )* } ) => { $($else)* };
+    ( [ $( $tokens:tt )+ ], { $( $then:tt )* }, { $( $else:tt )* } ) => { $($then)* };
+}
+
+// The crate own unit tests use the real macros to remain meaningful when all
+// features are enabled.
+#[doc(hidden)]
+#[cfg(not(all(feature = "off", not(test))))]
+#[macro_export]
+macro_rules! unless_off {
+    ( $( $tokens:tt )* ) => { $($tokens)* };
+}
+
+#[doc(hidden)]
+#[cfg(all(feature = "off", not(test)))]
+#[macro_export]
+macro_rules! unless_off {
+    ($($tokens:tt)*) => {
+        ()
+    };
+}
@@ -0,0 +1,193 @@
+#[cfg(any(target_arch = "wasm32", test))]
+use std::borrow::Cow;
+use std::io::{Result as IoResult, Write as IoWrite};
+#[cfg(any(target_arch = "wasm32", test))]
+use std::string::String;
+
+use crate::PanicDetector;
+
+#[cfg(target_arch = "wasm32")]
+mod console {
+    use wasm_bindgen::prelude::wasm_bindgen;
+
+    #[wasm_bindgen]
+    extern "C" {
+        #[wasm_bindgen(js_namespace = console, js_name = error)]
+        pub(super) fn console_error(message: &str);
+    }
+}
+
+/// A [`std::io::Write`] writer that writes to the browser or Node.js console
+/// with `console.error` using [`wasm-bindgen`].
+///
+/// On `wasm32-unknown-unknown` the standard error stream goes nowhere, so the
+/// context frames written with the default writer are lost. This writer calls
+/// `console.error` once per write with the trailing newline removed, and
+/// context scope guards write every frame with a single write. On other
+/// targets it writes to the standard error stream with [`StderrWriter`]
+/// instead.
+///
+/// It is used by the [`unwind_context_wasm`] macro.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, WasmConsoleWriter, WasmPanicDetector};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!(
+///         (fn(foo, bar)),
+///         writer = WasmConsoleWriter,
+///         panic_detector = WasmPanicDetector,
+///     );
+///     // ...
+/// }
+/// ```
+///
+/// [`wasm-bindgen`]: https://crates.io/crates/wasm-bindgen
+/// [`StderrWriter`]: crate::StderrWriter
+/// [`unwind_context_wasm`]: crate::unwind_context_wasm
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct WasmConsoleWriter;
+
+impl IoWrite for WasmConsoleWriter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        let message = console_message(buf);
+        console::console_error(&message);
+        Ok(())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        crate::StderrWriter.write_all(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+/// Converts the written bytes to a console message without the trailing
+/// newline.
+#[cfg(any(target_arch = "wasm32", test))]
+fn console_message(buf: &[u8]) -> Cow<'_, str> {
+    let buf = buf.strip_suffix(b"\n").unwrap_or(buf);
+    String::from_utf8_lossy(buf)
+}
+
+/// A [`PanicDetector`] intended for the `wasm32-unknown-unknown` target.
+///
+/// It uses [`std::thread::panicking`], which is available on this target even
+/// though there are no threads and the standard error stream goes nowhere.
+/// It is used by the [`unwind_context_wasm`] macro.
+///
+/// Note that the `wasm32-unknown-unknown` target aborts on panic by default,
+/// so context scope guards are only dropped during unwinding if the code is
+/// built with `-C panic=unwind`.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{PanicDetector, WasmPanicDetector};
+///
+/// assert!(!WasmPanicDetector.is_panicking());
+/// ```
+///
+/// [`unwind_context_wasm`]: crate::unwind_context_wasm
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct WasmPanicDetector;
+
+impl PanicDetector for WasmPanicDetector {
+    #[inline]
+    fn is_panicking(&self) -> bool {
+        std::thread::panicking()
+    }
+}
+
+/// Creates [`UnwindContextWithIo`] writing to the browser or Node.js console
+/// with a given function or scope context.
+///
+/// It uses [`WasmConsoleWriter`] writer, [`WasmPanicDetector`] panic
+/// detector, and a color scheme determined by the
+/// [`get_default_color_scheme_if_enabled`] function. It accepts the same
+/// context forms as the [`unwind_context`] macro.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::unwind_context_wasm;
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_wasm!(fn(foo, bar));
+///     // ...
+/// }
+/// ```
+///
+/// [`UnwindContextWithIo`]: crate::UnwindContextWithIo
+/// [`get_default_color_scheme_if_enabled`]: crate::get_default_color_scheme_if_enabled
+/// [`unwind_context`]: crate::unwind_context
+#[macro_export]
+macro_rules! unwind_context_wasm {
+    ( $( $context:tt )* ) => {
+        $crate::unwind_context_with_io!(
+            ( $($context)* ),
+            writer = $crate::WasmConsoleWriter,
+            panic_detector = $crate::WasmPanicDetector,
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use core::panic::AssertUnwindSafe;
+    use std::io::Write as IoWrite;
+    use std::panic::catch_unwind;
+
+    use crate::wasm_writer::console_message;
+    use crate::{PanicDetector, WasmConsoleWriter, WasmPanicDetector};
+
+    #[test]
+    fn test_console_message() {
+        assert_eq!(console_message(b"fn func(foo: 1)\n"), "fn func(foo: 1)");
+        assert_eq!(console_message(b"line 1\nline 2\n\n"), "line 1\nline 2\n");
+        assert_eq!(console_message(b"bad \xff"), "bad \u{fffd}");
+        assert_eq!(console_message(b""), "");
+    }
+
+    #[test]
+    fn test_wasm_console_writer() {
+        assert_eq!(WasmConsoleWriter.write(b"").unwrap(), 0);
+        assert!(WasmConsoleWriter.flush().is_ok());
+    }
+
+    #[allow(clippy::panic)]
+    #[test]
+    fn test_wasm_panic_detector() {
+        struct Check<'a>(&'a Cell<bool>);
+
+        impl Drop for Check<'_> {
+            fn drop(&mut self) {
+                self.0.set(WasmPanicDetector.is_panicking());
+            }
+        }
+
+        assert!(!WasmPanicDetector.is_panicking());
+        let is_panicking = Cell::new(false);
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            let _check = Check(&is_panicking);
+            panic!("test panic");
+        }));
+        assert!(result.is_err());
+        assert!(is_panicking.get());
+    }
+}
@@ -0,0 +1,99 @@
+use std::io::{Result as IoResult, Write as IoWrite};
+#[cfg(any(windows, test))]
+use std::string::String;
+#[cfg(any(windows, test))]
+use std::vec::Vec;
+
+/// A [`std::io::Write`] writer that writes to the Windows debugger output with
+/// `OutputDebugStringW`.
+///
+/// Windows GUI applications have no console attached, so the context frames
+/// written with the default writer are lost. This writer sends the written
+/// text to the attached debugger or a system debug output viewer once per
+/// write, and context scope guards write every frame with a single write.
+/// Debuggers do not process ANSI escape sequences, so colors should not be
+/// enabled for this writer. On other targets it writes to the
+/// standard error stream with [`StderrWriter`] instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, WinDebugOutputWriter};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!((fn(foo, bar)), writer = WinDebugOutputWriter);
+///     // ...
+/// }
+/// ```
+///
+/// [`StderrWriter`]: crate::StderrWriter
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct WinDebugOutputWriter;
+
+impl IoWrite for WinDebugOutputWriter {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.write_all(buf)?;
+        Ok(buf.len())
+    }
+
+    #[cfg(windows)]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        #[link(name = "kernel32")]
+        extern "system" {
+            fn OutputDebugStringW(output_string: *const u16);
+        }
+
+        let text = to_wide_string(buf);
+        // SAFETY: The pointer points to a valid nul-terminated UTF-16 string
+        // that outlives the call.
+        unsafe { OutputDebugStringW(text.as_ptr()) };
+        Ok(())
+    }
+
+    #[cfg(not(windows))]
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        crate::StderrWriter.write_all(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+/// Converts the written bytes to a nul-terminated UTF-16 string without the
+/// interior nul characters.
+#[cfg(any(windows, test))]
+fn to_wide_string(buf: &[u8]) -> Vec<u16> {
+    String::from_utf8_lossy(buf)
+        .encode_utf16()
+        .filter(|&unit| unit != 0)
+        .chain([0])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as IoWrite;
+    use std::vec::Vec;
+
+    use crate::win_debug_output::to_wide_string;
+    use crate::WinDebugOutputWriter;
+
+    #[test]
+    fn test_to_wide_string() {
+        let expected: Vec<u16> = "fn func(foo: 1)\n\0".encode_utf16().collect();
+        assert_eq!(to_wide_string(b"fn func(foo: 1)\n"), expected);
+        let expected: Vec<u16> = "ab\u{fffd}\u{1f600}\0".encode_utf16().collect();
+        assert_eq!(to_wide_string(b"a\0b\xff\xf0\x9f\x98\x80"), expected);
+        assert_eq!(to_wide_string(b""), [0]);
+    }
+
+    #[test]
+    fn test_win_debug_output_writer() {
+        assert_eq!(WinDebugOutputWriter.write(b"").unwrap(), 0);
+        assert!(WinDebugOutputWriter.flush().is_ok());
+    }
+}
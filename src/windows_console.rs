@@ -0,0 +1,87 @@
+use crate::TerminalStream;
+
+/// Enables processing of ANSI escape sequences by the Windows console attached
+/// to the stderr stream.
+///
+/// Older Windows consoles print ANSI escape sequences as is unless the
+/// `ENABLE_VIRTUAL_TERMINAL_PROCESSING` console mode is set. This function
+/// sets it for the stderr handle and returns `true` if the console supports
+/// it. On other platforms it does nothing and returns `true`.
+///
+/// It is called automatically by [`enable_colors_if_supported`], which does
+/// not enable colors if virtual terminal processing can not be enabled.
+///
+/// # Examples
+///
+/// ```rust
+/// if unwind_context::enable_virtual_terminal_processing() {
+///     unwind_context::set_colors_enabled(true);
+/// }
+/// # unwind_context::set_colors_enabled(false);
+/// ```
+///
+/// [`enable_colors_if_supported`]: crate::enable_colors_if_supported
+#[inline]
+#[must_use]
+pub fn enable_virtual_terminal_processing() -> bool {
+    enable_virtual_terminal_processing_for(TerminalStream::Stderr)
+}
+
+/// Enables processing of ANSI escape sequences by the Windows console attached
+/// to the given stream.
+#[cfg(windows)]
+pub(crate) fn enable_virtual_terminal_processing_for(stream: TerminalStream) -> bool {
+    type Handle = isize;
+
+    const STD_OUTPUT_HANDLE: u32 = 0xFFFF_FFF5;
+    const STD_ERROR_HANDLE: u32 = 0xFFFF_FFF4;
+    const INVALID_HANDLE_VALUE: Handle = -1;
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetStdHandle(std_handle: u32) -> Handle;
+        fn GetConsoleMode(console_handle: Handle, mode: *mut u32) -> i32;
+        fn SetConsoleMode(console_handle: Handle, mode: u32) -> i32;
+    }
+
+    let std_handle = match stream {
+        TerminalStream::Stdout => STD_OUTPUT_HANDLE,
+        TerminalStream::Stderr => STD_ERROR_HANDLE,
+    };
+    // SAFETY: `GetStdHandle` has no preconditions.
+    let handle = unsafe { GetStdHandle(std_handle) };
+    if handle == 0 || handle == INVALID_HANDLE_VALUE {
+        return false;
+    }
+    let mut mode = 0;
+    // SAFETY: The handle is a valid standard handle and `mode` is a valid
+    // pointer to a writable `u32`.
+    if unsafe { GetConsoleMode(handle, &mut mode) } == 0 {
+        return false;
+    }
+    if mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0 {
+        return true;
+    }
+    // SAFETY: The handle is a valid console handle.
+    unsafe { SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0 }
+}
+
+/// Enables processing of ANSI escape sequences by the Windows console attached
+/// to the given stream.
+#[cfg(not(windows))]
+#[inline]
+pub(crate) fn enable_virtual_terminal_processing_for(_stream: TerminalStream) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::enable_virtual_terminal_processing;
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_enable_virtual_terminal_processing() {
+        assert!(enable_virtual_terminal_processing());
+    }
+}
@@ -0,0 +1,97 @@
+use core::fmt::{Result as FmtResult, Write as FmtWrite};
+#[cfg(feature = "std")]
+use std::io::{Result as IoResult, Write as IoWrite};
+
+/// A writer that creates the underlying writer with a given function only
+/// when something is written.
+///
+/// It implements both [`core::fmt::Write`] and [`std::io::Write`] if the
+/// created writer does. Context scope guards write only when a panic is
+/// detected, so a lazily created writer costs nothing on the hot path: the
+/// guard stores only the function, which is a zero-sized type for non-capturing
+/// closures.
+///
+/// The function is called for every write, and the created writer is dropped
+/// right after it, so it should be cheap, like [`std::io::stderr`].
+///
+/// It is usually created with the `writer_fn = || ...` parameter of macros like
+/// [`unwind_context_with_io`] or [`unwind_context_with_fmt`].
+///
+/// # Examples
+///
+/// ```rust
+/// use unwind_context::{unwind_context_with_io, WriterFn};
+///
+/// fn func(foo: u32, bar: &str) {
+///     let _ctx = unwind_context_with_io!((fn(foo, bar)), writer = WriterFn(std::io::stdout));
+///     let _ctx = unwind_context_with_io!((fn(foo, bar)), writer_fn = std::io::stdout);
+///     // ...
+/// }
+/// ```
+///
+/// [`unwind_context_with_io`]: crate::unwind_context_with_io
+/// [`unwind_context_with_fmt`]: crate::unwind_context_with_fmt
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Default)]
+pub struct WriterFn<F>(
+    /// The function creating the underlying writer.
+    pub F,
+);
+
+impl<F, W> FmtWrite for WriterFn<F>
+where
+    F: FnMut() -> W,
+    W: FmtWrite,
+{
+    #[inline]
+    fn write_str(&mut self, s: &str) -> FmtResult {
+        (self.0)().write_str(s)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<F, W> IoWrite for WriterFn<F>
+where
+    F: FnMut() -> W,
+    W: IoWrite,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        (self.0)().write(buf)
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> IoResult<()> {
+        (self.0)().write_all(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> IoResult<()> {
+        (self.0)().flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use core::fmt::{Result as FmtResult, Write as FmtWrite};
+
+    use crate::WriterFn;
+
+    struct LenWriter<'a>(&'a Cell<usize>);
+
+    impl FmtWrite for LenWriter<'_> {
+        fn write_str(&mut self, s: &str) -> FmtResult {
+            self.0.set(self.0.get().saturating_add(s.len()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_writer_fn() {
+        let len = Cell::new(0);
+        let mut writer = WriterFn(|| LenWriter(&len));
+        assert_eq!(len.get(), 0);
+        assert_eq!(write!(writer, "foo {}", 123), Ok(()));
+        assert_eq!(len.get(), 7);
+    }
+}
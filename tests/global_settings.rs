@@ -0,0 +1,1056 @@
+#![allow(missing_docs, clippy::panic, clippy::unwrap_used)]
+
+#[cfg(feature = "anyhow")]
+use anyhow as _;
+#[cfg(any(feature = "custom-default-colors", feature = "timestamps"))]
+use atomic_ref as _;
+#[cfg(feature = "color-eyre")]
+use color_eyre as _;
+#[cfg(feature = "semihosting")]
+use cortex_m_semihosting as _;
+use critical_section as _;
+#[cfg(feature = "eyre")]
+use eyre as _;
+#[cfg(feature = "rayon")]
+use rayon as _;
+#[cfg(feature = "rtt")]
+use rtt_target as _;
+#[cfg(feature = "serde")]
+use serde as _;
+use serde_json as _;
+#[cfg(feature = "detect-color-support")]
+use supports_color as _;
+#[cfg(feature = "tokio")]
+use tokio as _;
+use unwind_context as _;
+#[cfg(feature = "test-macro")]
+use unwind_context_macros as _;
+use version_sync as _;
+
+#[cfg(all(feature = "std", not(feature = "off")))]
+mod tests {
+    use core::cell::{Cell, RefCell};
+    use core::fmt::{Debug, Formatter, Result as FmtResult, Write as FmtWrite};
+    use core::panic::AssertUnwindSafe;
+    use core::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
+    use core::time::Duration;
+    use std::sync::{mpsc, Mutex};
+    use std::{io, panic, thread};
+
+    use unwind_context::{unwind_context_with_fmt, CollapseRepeatedFrames, StdPanicDetector};
+
+    // Modifying and checking the values of global variables requires a global
+    // lock to prohibit parallel execution in different tests.
+    static SERIAL_TEST: Mutex<()> = Mutex::new(());
+
+    fn print_context<W: FmtWrite>(writer: &mut W) {
+        let value = 123;
+        let mut ctx = unwind_context_with_fmt!(
+            (value),
+            writer = writer,
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+        );
+        ctx.print();
+    }
+
+    #[test]
+    fn test_set_thread_info_enabled() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::is_thread_info_enabled());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+        assert!(!output.contains(" in thread "));
+
+        unwind_context::set_thread_info_enabled(true);
+        assert!(unwind_context::is_thread_info_enabled());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+        assert!(output.contains(" in thread '"));
+        assert!(output.ends_with(")\n"));
+
+        unwind_context::set_thread_info_enabled(false);
+        assert!(!unwind_context::is_thread_info_enabled());
+    }
+
+    #[test]
+    fn test_set_process_id_enabled() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::is_process_id_enabled());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(!output.contains(" in process "));
+
+        unwind_context::set_process_id_enabled(true);
+        assert!(unwind_context::is_process_id_enabled());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+        assert!(output.ends_with(&format!(" in process {}\n", std::process::id())));
+
+        unwind_context::set_process_id_enabled(false);
+        assert!(!unwind_context::is_process_id_enabled());
+    }
+
+    struct SharedWriter<'a>(&'a RefCell<String>);
+
+    impl FmtWrite for SharedWriter<'_> {
+        fn write_str(&mut self, s: &str) -> FmtResult {
+            self.0.borrow_mut().write_str(s)
+        }
+    }
+
+    fn recurse_and_panic(output: &RefCell<String>, depth: usize) {
+        let _ctx = unwind_context_with_fmt!(
+            (depth),
+            writer = SharedWriter(output),
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+        );
+        match depth.checked_sub(1) {
+            Some(depth) => recurse_and_panic(output, depth),
+            None => panic!("expected panic"),
+        }
+    }
+
+    #[test]
+    fn test_set_frame_numbers_enabled() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::are_frame_numbers_enabled());
+        unwind_context::set_frame_numbers_enabled(true);
+        assert!(unwind_context::are_frame_numbers_enabled());
+
+        for _ in 0..2 {
+            let output = RefCell::new(String::new());
+            let result = panic::catch_unwind(AssertUnwindSafe(|| recurse_and_panic(&output, 2)));
+            assert!(result.is_err());
+
+            // Drop of a guard while not panicking resets the frame numbering.
+            drop(unwind_context_with_fmt!(
+                (),
+                writer = SharedWriter(&output),
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            ));
+
+            let output = output.into_inner();
+            let lines: Vec<_> = output.lines().step_by(2).collect();
+            assert_eq!(lines, ["#0 depth: 0", "#1 depth: 1", "#2 depth: 2"]);
+        }
+
+        // Caught panics start a new unwind even if an outer guard is alive. The
+        // panic hook lets the last panic start a new unwind even though no guard
+        // is created or dropped after the previous panic is caught.
+        unwind_context::install_hook();
+        let lines = collect_panic_output(|output| {
+            let _ctx = unwind_context_with_fmt!(
+                ("outer"),
+                writer = SharedWriter(output),
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            for _ in 0..2 {
+                let result = panic::catch_unwind(AssertUnwindSafe(|| recurse_and_panic(output, 1)));
+                assert!(result.is_err());
+            }
+            panic!("expected panic");
+        });
+        assert_eq!(
+            lines,
+            [
+                "#0 depth: 0",
+                "#1 depth: 1",
+                "#0 depth: 0",
+                "#1 depth: 1",
+                "#0 \"outer\"",
+            ]
+        );
+
+        unwind_context::set_frame_numbers_enabled(false);
+        assert!(!unwind_context::are_frame_numbers_enabled());
+    }
+
+    fn recurse_and_panic_within_outer_scope(output: &RefCell<String>, depth: usize) {
+        let _ctx = unwind_context_with_fmt!(
+            ("outer"),
+            writer = SharedWriter(output),
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+        );
+        recurse_and_panic(output, depth);
+    }
+
+    fn recurse_with_data_and_panic(output: &RefCell<String>, data: &str, depth: usize) {
+        let _ctx = unwind_context_with_fmt!(
+            (data),
+            writer = SharedWriter(output),
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+        );
+        match depth.checked_sub(1) {
+            Some(depth) => recurse_with_data_and_panic(output, data, depth),
+            None => panic!("expected panic"),
+        }
+    }
+
+    fn collect_panic_output(f: impl FnOnce(&RefCell<String>)) -> Vec<String> {
+        let output = RefCell::new(String::new());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&output)));
+        assert!(result.is_err());
+        output
+            .into_inner()
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("at "))
+            .map(ToOwned::to_owned)
+            .collect()
+    }
+
+    #[test]
+    fn test_set_collapse_repeated_frames() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert_eq!(
+            unwind_context::get_collapse_repeated_frames(),
+            CollapseRepeatedFrames::Never
+        );
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 3));
+        assert_eq!(lines, ["depth: 0", "depth: 1", "depth: 2", "depth: 3"]);
+
+        unwind_context::set_collapse_repeated_frames(CollapseRepeatedFrames::SameLocation);
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 3));
+        assert_eq!(lines, ["depth: 0", "... previous frame repeated 3 times"]);
+        let lines = collect_panic_output(|output| recurse_and_panic_within_outer_scope(output, 1));
+        assert_eq!(
+            lines,
+            [
+                "depth: 0",
+                "... previous frame repeated 1 time",
+                "\"outer\"",
+            ]
+        );
+
+        unwind_context::set_collapse_repeated_frames(CollapseRepeatedFrames::SameLocationAndData);
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 3));
+        assert_eq!(lines, ["depth: 0", "depth: 1", "depth: 2", "depth: 3"]);
+        let lines = collect_panic_output(|output| recurse_with_data_and_panic(output, "abc", 2));
+        assert_eq!(
+            lines,
+            ["data: \"abc\"", "... previous frame repeated 2 times"]
+        );
+        // Too long context data is never considered repeated.
+        let data = "a".repeat(300);
+        let lines = collect_panic_output(|output| recurse_with_data_and_panic(output, &data, 2));
+        assert_eq!(lines.len(), 3);
+
+        unwind_context::set_collapse_repeated_frames(CollapseRepeatedFrames::Never);
+    }
+
+    #[test]
+    fn test_set_max_frames_per_unwind() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert_eq!(unwind_context::get_max_frames_per_unwind(), None);
+        unwind_context::set_max_frames_per_unwind(Some(2));
+        assert_eq!(unwind_context::get_max_frames_per_unwind(), Some(2));
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 4));
+        assert_eq!(
+            lines,
+            [
+                "depth: 0",
+                "depth: 1",
+                "... 3 more context frames suppressed"
+            ]
+        );
+
+        unwind_context::set_collapse_repeated_frames(CollapseRepeatedFrames::SameLocation);
+        let lines = collect_panic_output(|output| recurse_and_panic_within_outer_scope(output, 2));
+        assert_eq!(
+            lines,
+            [
+                "depth: 0",
+                "... previous frame repeated 2 times",
+                "\"outer\"",
+            ]
+        );
+        unwind_context::set_max_frames_per_unwind(Some(1));
+        let lines = collect_panic_output(|output| recurse_and_panic_within_outer_scope(output, 2));
+        assert_eq!(
+            lines,
+            [
+                "depth: 0",
+                "... previous frame repeated 2 times",
+                "... 1 more context frame suppressed",
+            ]
+        );
+        unwind_context::set_collapse_repeated_frames(CollapseRepeatedFrames::Never);
+
+        unwind_context::set_max_frames_for_current_unwind(Some(3));
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 4));
+        assert_eq!(
+            lines,
+            [
+                "depth: 0",
+                "depth: 1",
+                "depth: 2",
+                "... 2 more context frames suppressed"
+            ]
+        );
+        // The limit for the current unwind is reset after the unwind ends.
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 4));
+        assert_eq!(lines, ["depth: 0", "... 4 more context frames suppressed"]);
+
+        // The limit applies to each caught unwind separately.
+        unwind_context::install_hook();
+        unwind_context::set_max_frames_per_unwind(Some(1));
+        let lines = collect_panic_output(|output| {
+            let _ctx = unwind_context_with_fmt!(
+                ("outer"),
+                writer = SharedWriter(output),
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            for _ in 0..2 {
+                let result = panic::catch_unwind(AssertUnwindSafe(|| recurse_and_panic(output, 1)));
+                assert!(result.is_err());
+            }
+            panic!("expected panic");
+        });
+        assert_eq!(lines, ["depth: 0", "depth: 0", "\"outer\""]);
+
+        unwind_context::set_max_frames_per_unwind(None);
+        assert_eq!(unwind_context::get_max_frames_per_unwind(), None);
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 2));
+        assert_eq!(lines, ["depth: 0", "depth: 1", "depth: 2"]);
+    }
+
+    struct CountedDebug<'a>(&'a Cell<usize>);
+
+    impl Debug for CountedDebug<'_> {
+        fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+            self.0.set(self.0.get().saturating_add(1));
+            write!(f, "{}", self.0.get())
+        }
+    }
+
+    #[test]
+    fn test_set_output_budget() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert_eq!(unwind_context::get_output_budget(), None);
+        let output = RefCell::new(String::new());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| recurse_and_panic(&output, 0)));
+        assert!(result.is_err());
+        let frame_len = output.into_inner().len();
+
+        unwind_context::set_output_budget(Some(2 * frame_len + 1));
+        assert_eq!(unwind_context::get_output_budget(), Some(2 * frame_len + 1));
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 4));
+        assert_eq!(
+            lines,
+            [
+                "depth: 0",
+                "depth: 1",
+                "... output budget exceeded, 3 more context frames suppressed"
+            ]
+        );
+        // The budget is shared only by the frames of a single unwind.
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 1));
+        assert_eq!(lines, ["depth: 0", "depth: 1"]);
+
+        // The frame charged to the budget is formatted only once.
+        let count = Cell::new(0);
+        let lines = collect_panic_output(|output| {
+            let counted = CountedDebug(&count);
+            let _ctx = unwind_context_with_fmt!(
+                (counted),
+                writer = SharedWriter(output),
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            panic!("expected panic");
+        });
+        assert_eq!(lines, ["counted: 1"]);
+        assert_eq!(count.get(), 1);
+
+        unwind_context::set_output_budget(None);
+        assert_eq!(unwind_context::get_output_budget(), None);
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 2));
+        assert_eq!(lines, ["depth: 0", "depth: 1", "depth: 2"]);
+    }
+
+    struct StderrProbeWriter(RefCell<Option<mpsc::Receiver<()>>>);
+
+    impl FmtWrite for &StderrProbeWriter {
+        fn write_str(&mut self, _: &str) -> FmtResult {
+            let mut receiver = self.0.borrow_mut();
+            if let Some(receiver) = &*receiver {
+                // The lock is still held by the unwinding thread.
+                assert!(receiver.try_recv().is_err());
+            } else {
+                let (sender, new_receiver) = mpsc::channel();
+                let _handle = thread::spawn(move || {
+                    let _lock = io::stderr().lock();
+                    sender.send(()).unwrap();
+                });
+                // Another thread can't lock the standard error stream during the unwind.
+                assert!(new_receiver
+                    .recv_timeout(Duration::from_millis(100))
+                    .is_err());
+                *receiver = Some(new_receiver);
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_set_stderr_lock_enabled() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::is_stderr_lock_enabled());
+        unwind_context::set_stderr_lock_enabled(true);
+        assert!(unwind_context::is_stderr_lock_enabled());
+
+        let writer = StderrProbeWriter(RefCell::new(None));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _ctx = unwind_context_with_fmt!(
+                ("outer"),
+                writer = &writer,
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            let _ctx = unwind_context_with_fmt!(
+                ("inner"),
+                writer = &writer,
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            panic!("expected panic");
+        }));
+        assert!(result.is_err());
+
+        // The standard error stream is unlocked after the unwind ends.
+        let receiver = writer.0.into_inner().unwrap();
+        assert!(receiver.recv_timeout(Duration::from_secs(10)).is_ok());
+
+        // The standard error stream is unlocked after a caught unwind when an
+        // outer guard that is still alive is dropped.
+        let writer = StderrProbeWriter(RefCell::new(None));
+        let ctx = unwind_context_with_fmt!(
+            ("outer"),
+            writer = &writer,
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+        );
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let _ctx = unwind_context_with_fmt!(
+                ("inner"),
+                writer = &writer,
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            panic!("expected panic");
+        }));
+        assert!(result.is_err());
+        let receiver = writer.0.borrow_mut().take().unwrap();
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+        drop(ctx);
+        assert!(receiver.recv_timeout(Duration::from_secs(10)).is_ok());
+
+        unwind_context::set_stderr_lock_enabled(false);
+        assert!(!unwind_context::is_stderr_lock_enabled());
+    }
+
+    #[test]
+    fn test_set_eprint_output_enabled() {
+        use std::io::Write as IoWrite;
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::is_eprint_output_enabled());
+        unwind_context::set_eprint_output_enabled(true);
+        assert!(unwind_context::is_eprint_output_enabled());
+
+        // The output is captured by the test harness.
+        let mut writer = unwind_context::StderrWriter;
+        assert!(writer.write_all(b"captured frame\n").is_ok());
+        assert!(writer.write_all(b"\xFF\n").is_ok());
+        assert!(writer.flush().is_ok());
+
+        unwind_context::set_eprint_output_enabled(false);
+        assert!(!unwind_context::is_eprint_output_enabled());
+    }
+
+    #[test]
+    fn test_set_default_writer() {
+        static OUTPUT: Mutex<Vec<u8>> = Mutex::new(Vec::new());
+
+        struct SharedWriter;
+
+        impl io::Write for SharedWriter {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                OUTPUT.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+        fn shared_writer() -> Box<dyn io::Write + Send> {
+            let _ = CREATED.fetch_add(1, AtomicOrdering::Relaxed);
+            Box::new(SharedWriter)
+        }
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(unwind_context::get_default_writer().is_none());
+        unwind_context::set_default_writer(Some(shared_writer));
+        assert!(unwind_context::get_default_writer().is_some());
+
+        let value = 123;
+        let mut ctx = unwind_context::unwind_context_with_io!((value), color_scheme = None);
+        ctx.print();
+        let output = String::from_utf8(OUTPUT.lock().unwrap().split_off(0)).unwrap();
+        assert!(output.starts_with("value: 123\n    at "));
+        assert_eq!(CREATED.swap(0, AtomicOrdering::Relaxed), 1);
+
+        // A frame longer than the formatting buffer uses a single writer.
+        let long = "a".repeat(4000);
+        let mut ctx = unwind_context::unwind_context_with_io!((long), color_scheme = None);
+        ctx.print();
+        let output = String::from_utf8(OUTPUT.lock().unwrap().split_off(0)).unwrap();
+        assert!(output.starts_with("long: \"aaaa"));
+        assert!(output.len() > 4000);
+        assert_eq!(CREATED.swap(0, AtomicOrdering::Relaxed), 1);
+
+        unwind_context::set_default_writer(None);
+        assert!(unwind_context::get_default_writer().is_none());
+    }
+
+    #[test]
+    fn test_set_frame_indent() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert_eq!(unwind_context::get_frame_indent(), None);
+        unwind_context::set_frame_indent(Some("  "));
+        assert_eq!(unwind_context::get_frame_indent(), Some("  "));
+
+        let output = RefCell::new(String::new());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| recurse_and_panic(&output, 2)));
+        assert!(result.is_err());
+        let output = output.into_inner();
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], "    depth: 0");
+        assert!(lines[1].starts_with("        at "));
+        assert_eq!(lines[2], "  depth: 1");
+        assert!(lines[3].starts_with("      at "));
+        assert_eq!(lines[4], "depth: 2");
+        assert!(lines[5].starts_with("    at "));
+
+        unwind_context::set_frame_indent(None);
+        assert_eq!(unwind_context::get_frame_indent(), None);
+    }
+
+    #[test]
+    fn test_set_location_style() {
+        use unwind_context::LocationStyle;
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert_eq!(unwind_context::get_location_style(), LocationStyle::Full);
+        let mut output = String::new();
+        print_context(&mut output);
+        assert_eq!(output.matches(':').count(), 3);
+
+        unwind_context::set_location_style(LocationStyle::WithoutColumn);
+        assert_eq!(
+            unwind_context::get_location_style(),
+            LocationStyle::WithoutColumn
+        );
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+        assert_eq!(output.matches(':').count(), 2);
+
+        unwind_context::set_location_style(LocationStyle::Hidden);
+        let mut output = String::new();
+        print_context(&mut output);
+        assert_eq!(output, "value: 123\n");
+
+        let mut output = String::new();
+        let value = 123;
+        let mut ctx = unwind_context_with_fmt!(
+            (value),
+            writer = &mut output,
+            panic_detector = StdPanicDetector,
+            color_scheme = None,
+            location_style = LocationStyle::Full,
+        );
+        ctx.print();
+        drop(ctx);
+        assert_eq!(output.matches(':').count(), 3);
+
+        unwind_context::set_location_style(LocationStyle::Full);
+        assert_eq!(unwind_context::get_location_style(), LocationStyle::Full);
+    }
+
+    #[test]
+    fn test_set_location_path() {
+        use unwind_context::LocationPath;
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert_eq!(unwind_context::get_location_path(), LocationPath::Full);
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with(&format!("value: 123\n    at {}:", file!())));
+
+        unwind_context::set_location_path(LocationPath::FileName);
+        assert_eq!(unwind_context::get_location_path(), LocationPath::FileName);
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at global_settings.rs:"));
+
+        unwind_context::set_location_path(LocationPath::StripPrefix("tests"));
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at global_settings.rs:"));
+
+        unwind_context::set_location_path(LocationPath::Full);
+        assert_eq!(unwind_context::get_location_path(), LocationPath::Full);
+    }
+
+    #[test]
+    fn test_set_location_format() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert_eq!(unwind_context::get_location_format(), None);
+        unwind_context::set_location_format(Some("{file}({line}) [{column}]"));
+        assert_eq!(
+            unwind_context::get_location_format(),
+            Some("{file}({line}) [{column}]")
+        );
+        let mut output = String::new();
+        print_context(&mut output);
+        let location = output
+            .strip_prefix(&format!("value: 123\n    at {}(", file!()))
+            .unwrap();
+        let (line, column) = location.split_once(") [").unwrap();
+        assert!(line.parse::<u32>().is_ok());
+        assert!(column.strip_suffix("]\n").unwrap().parse::<u32>().is_ok());
+
+        unwind_context::set_location_format(None);
+        assert_eq!(unwind_context::get_location_format(), None);
+    }
+
+    #[test]
+    fn test_set_context_layout() {
+        use unwind_context::{ContextLayout, LayoutFrame};
+
+        struct SingleLineLayout;
+
+        impl ContextLayout for SingleLineLayout {
+            fn write_frame(&self, f: &mut dyn FmtWrite, frame: &LayoutFrame<'_>) -> FmtResult {
+                f.write_str("[")?;
+                frame.write_data(f)?;
+                f.write_str("] ")?;
+                frame.write_location(f)?;
+                f.write_str("\n")
+            }
+        }
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(unwind_context::get_context_layout().is_none());
+        unwind_context::set_context_layout(Some(&SingleLineLayout));
+        assert!(unwind_context::get_context_layout().is_some());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with(&format!("[value: 123] {}:", file!())));
+        assert_eq!(output.lines().count(), 1);
+
+        unwind_context::set_context_layout(None);
+        assert!(unwind_context::get_context_layout().is_none());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+    }
+
+    #[test]
+    fn test_set_unwind_header_and_footer() {
+        fn header(f: &mut dyn FmtWrite) -> FmtResult {
+            writeln!(f, "===== unwind context =====")
+        }
+
+        fn footer(f: &mut dyn FmtWrite) -> FmtResult {
+            writeln!(f, "===== end =====")
+        }
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(unwind_context::get_unwind_header().is_none());
+        assert!(unwind_context::get_unwind_footer().is_none());
+        unwind_context::set_unwind_header(Some(header));
+        unwind_context::set_unwind_footer(Some(footer));
+        assert!(unwind_context::get_unwind_header().is_some());
+        assert!(unwind_context::get_unwind_footer().is_some());
+
+        for _ in 0..2 {
+            let lines = collect_panic_output(|output| recurse_and_panic(output, 1));
+            assert_eq!(
+                lines,
+                [
+                    "===== unwind context =====",
+                    "depth: 0",
+                    "depth: 1",
+                    "===== end =====",
+                ]
+            );
+        }
+
+        // Context printed while not panicking has no header and footer.
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+
+        unwind_context::set_unwind_header(None);
+        unwind_context::set_unwind_footer(None);
+        assert!(unwind_context::get_unwind_header().is_none());
+        assert!(unwind_context::get_unwind_footer().is_none());
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 1));
+        assert_eq!(lines, ["depth: 0", "depth: 1"]);
+    }
+
+    #[test]
+    fn test_set_thread_banner_enabled() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::is_thread_banner_enabled());
+        unwind_context::set_thread_banner_enabled(true);
+        assert!(unwind_context::is_thread_banner_enabled());
+        let lines = thread::Builder::new()
+            .name("worker-7".to_owned())
+            .spawn(|| collect_panic_output(|output| recurse_and_panic(output, 1)))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(
+            lines,
+            [
+                "thread 'worker-7' panicking, unwind context:",
+                "depth: 0",
+                "depth: 1",
+            ]
+        );
+
+        unwind_context::set_thread_banner_enabled(false);
+        assert!(!unwind_context::is_thread_banner_enabled());
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 1));
+        assert_eq!(lines, ["depth: 0", "depth: 1"]);
+    }
+
+    #[test]
+    fn test_set_unwind_summary_enabled() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::is_unwind_summary_enabled());
+        unwind_context::set_unwind_summary_enabled(true);
+        assert!(unwind_context::is_unwind_summary_enabled());
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 0));
+        assert_eq!(lines, ["depth: 0", "-- 1 context frame --"]);
+
+        unwind_context::set_max_frames_per_unwind(Some(2));
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 4));
+        assert_eq!(
+            lines,
+            [
+                "depth: 0",
+                "depth: 1",
+                "... 3 more context frames suppressed",
+                "-- 5 context frames (3 suppressed) --",
+            ]
+        );
+        unwind_context::set_max_frames_per_unwind(None);
+
+        unwind_context::set_collapse_repeated_frames(CollapseRepeatedFrames::SameLocation);
+        let lines = collect_panic_output(|output| recurse_and_panic_within_outer_scope(output, 2));
+        assert_eq!(
+            lines,
+            [
+                "depth: 0",
+                "... previous frame repeated 2 times",
+                "\"outer\"",
+                "-- 4 context frames (2 suppressed) --",
+            ]
+        );
+        unwind_context::set_collapse_repeated_frames(CollapseRepeatedFrames::Never);
+
+        unwind_context::set_unwind_summary_enabled(false);
+        assert!(!unwind_context::is_unwind_summary_enabled());
+        let lines = collect_panic_output(|output| recurse_and_panic(output, 1));
+        assert_eq!(lines, ["depth: 0", "depth: 1"]);
+    }
+
+    #[test]
+    fn test_set_elapsed_time_enabled() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::is_elapsed_time_enabled());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(!output.contains(" in scope"));
+
+        unwind_context::set_elapsed_time_enabled(true);
+        assert!(unwind_context::is_elapsed_time_enabled());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+        assert!(output.contains(" after "));
+        assert!(output.ends_with("s in scope\n"));
+
+        unwind_context::set_elapsed_time_enabled(false);
+        assert!(!unwind_context::is_elapsed_time_enabled());
+    }
+
+    #[test]
+    fn test_set_verbose_enabled() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::is_verbose_enabled());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(!output.contains("enter "));
+
+        unwind_context::set_verbose_enabled(true);
+        assert!(unwind_context::is_verbose_enabled());
+        let mut output = String::new();
+        print_context(&mut output);
+        let lines: Vec<_> = output.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "enter value: 123");
+        assert_eq!(lines[2], "value: 123");
+        assert_eq!(lines[1], lines[3]);
+
+        unwind_context::set_verbose_enabled(false);
+        assert!(!unwind_context::is_verbose_enabled());
+    }
+
+    #[test]
+    fn test_set_print_on_drop_enabled() {
+        fn drop_context<W: FmtWrite>(writer: &mut W) {
+            let value = 123;
+            let _ctx = unwind_context_with_fmt!(
+                (value),
+                writer = writer,
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+        }
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(!unwind_context::is_print_on_drop_enabled());
+        let mut output = String::new();
+        drop_context(&mut output);
+        assert_eq!(output, "");
+
+        unwind_context::set_print_on_drop_enabled(true);
+        assert!(unwind_context::is_print_on_drop_enabled());
+        let mut output = String::new();
+        drop_context(&mut output);
+        assert!(output.starts_with("ok value: 123\n    at "));
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("panic value: 123\n    at "));
+
+        unwind_context::set_print_on_drop_enabled(false);
+        assert!(!unwind_context::is_print_on_drop_enabled());
+    }
+
+    #[test]
+    fn test_set_rate_limit() {
+        use unwind_context::RateLimit;
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert_eq!(unwind_context::get_rate_limit(), None);
+        unwind_context::set_rate_limit(Some(RateLimit::per_occurrences(2)));
+        assert_eq!(
+            unwind_context::get_rate_limit(),
+            Some(RateLimit::per_occurrences(2))
+        );
+        let outputs: Vec<_> = (0..3)
+            .map(|_| {
+                let mut output = String::new();
+                print_context(&mut output);
+                output
+            })
+            .collect();
+        assert!(outputs[0].starts_with("value: 123\n    at "));
+        assert_eq!(outputs[1], "");
+        assert!(outputs[2].starts_with("value: 123\n    at "));
+        assert!(outputs[2].ends_with("\n... frame suppressed 1 time by rate limit\n"));
+
+        unwind_context::set_rate_limit(None);
+        assert_eq!(unwind_context::get_rate_limit(), None);
+    }
+
+    #[test]
+    fn test_set_sample_rate() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert_eq!(unwind_context::get_sample_rate(), None);
+        unwind_context::set_sample_rate(Some(2));
+        assert_eq!(unwind_context::get_sample_rate(), Some(2));
+        let outputs: Vec<_> = (0..3)
+            .map(|_| {
+                let mut output = String::new();
+                print_context(&mut output);
+                output
+            })
+            .collect();
+        assert!(outputs[0].starts_with("value: 123\n    at "));
+        assert_eq!(outputs[1], "");
+        assert_eq!(outputs[0], outputs[2]);
+
+        unwind_context::set_sample_rate(Some(0));
+        assert_eq!(unwind_context::get_sample_rate(), None);
+    }
+
+    #[test]
+    fn test_configure_from_env() {
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(unwind_context::are_contexts_enabled());
+        std::env::set_var(
+            unwind_context::UNWIND_CONTEXT_ENV,
+            "off, color=never, max_frames=16, verbose, unknown, thread_info=maybe",
+        );
+        unwind_context::configure_from_env();
+        assert!(!unwind_context::are_contexts_enabled());
+        assert!(!unwind_context::are_colors_enabled());
+        assert_eq!(unwind_context::get_max_frames_per_unwind(), Some(16));
+        assert!(unwind_context::is_verbose_enabled());
+        assert!(!unwind_context::is_thread_info_enabled());
+
+        let mut output = String::new();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let value = 123;
+            let _ctx = unwind_context_with_fmt!(
+                (value),
+                writer = &mut output,
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            panic!("Error");
+        }));
+        assert!(result.is_err());
+        assert_eq!(output, "");
+
+        std::env::set_var(
+            unwind_context::UNWIND_CONTEXT_ENV,
+            "on,max_frames=0,output_budget=0,verbose=off",
+        );
+        unwind_context::configure_from_env();
+        assert!(unwind_context::are_contexts_enabled());
+        assert_eq!(unwind_context::get_max_frames_per_unwind(), Some(0));
+        assert_eq!(unwind_context::get_output_budget(), Some(0));
+        assert!(!unwind_context::is_verbose_enabled());
+
+        std::env::set_var(
+            unwind_context::UNWIND_CONTEXT_ENV,
+            "max_frames=off,output_budget=off",
+        );
+        unwind_context::configure_from_env();
+        std::env::remove_var(unwind_context::UNWIND_CONTEXT_ENV);
+        assert_eq!(unwind_context::get_max_frames_per_unwind(), None);
+        assert_eq!(unwind_context::get_output_budget(), None);
+    }
+
+    #[test]
+    fn test_set_global_frame_sink() {
+        use unwind_context::{ContextFrame, FrameSink};
+
+        static FRAMES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+        fn record(frame: &ContextFrame<'_>) {
+            FRAMES.lock().unwrap().push(format!(
+                "{:?} {:?} {}",
+                frame.func_name(),
+                frame.data(),
+                frame.location().file()
+            ));
+        }
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(unwind_context::get_global_frame_sink().is_none());
+        unwind_context::set_global_frame_sink(Some(FrameSink::new(record)));
+        assert!(unwind_context::get_global_frame_sink().is_some());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+        assert_eq!(
+            FRAMES.lock().unwrap().split_off(0),
+            ["None value: 123 tests/global_settings.rs"]
+        );
+
+        unwind_context::set_global_frame_sink(Some(FrameSink::new(record).with_output(false)));
+        let mut output = String::new();
+        print_context(&mut output);
+        assert_eq!(output, "");
+        assert_eq!(
+            FRAMES.lock().unwrap().split_off(0),
+            ["None value: 123 tests/global_settings.rs"]
+        );
+
+        unwind_context::set_global_frame_sink(None);
+        assert!(unwind_context::get_global_frame_sink().is_none());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+        assert!(FRAMES.lock().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "timestamps")]
+    #[test]
+    fn test_set_clock_source() {
+        use unwind_context::{ClockSource, Timestamp};
+
+        static TICK_CLOCK: ClockSource = ClockSource::new(|| Timestamp::Ticks(42));
+
+        let _guard = SERIAL_TEST.lock().unwrap();
+
+        assert!(unwind_context::get_clock_source().is_none());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("value: 123\n    at "));
+
+        unwind_context::set_clock_source(Some(&TICK_CLOCK));
+        assert!(unwind_context::get_clock_source().is_some());
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with("[42] value: 123\n    at "));
+
+        unwind_context::set_clock_source(Some(&unwind_context::MONOTONIC_CLOCK));
+        // The monotonic time is measured since the clock source is set.
+        thread::sleep(Duration::from_millis(5));
+        let mut output = String::new();
+        print_context(&mut output);
+        assert!(output.starts_with('['));
+        assert!(!output.starts_with("[0.000000s]"));
+        assert!(output.contains("s] value: 123\n    at "));
+
+        unwind_context::set_clock_source(None);
+        assert!(unwind_context::get_clock_source().is_none());
+    }
+}
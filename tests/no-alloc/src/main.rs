@@ -9,11 +9,15 @@
 
 use core::fmt::{Result as FmtResult, Write as FmtWrite};
 use core::panic::PanicInfo;
+use core::sync::atomic::{AtomicBool, Ordering};
 
-use unwind_context::unwind_context_with_fmt;
+use unwind_context::{unwind_context_with_fmt, AtomicFlagPanicDetector};
+
+static PANIC_FLAG: AtomicBool = AtomicBool::new(false);
 
 #[panic_handler]
 fn panic(_: &PanicInfo<'_>) -> ! {
+    PANIC_FLAG.store(true, Ordering::Relaxed);
     loop {}
 }
 
@@ -28,7 +32,7 @@ pub extern "C" fn func(a: u32, b: *const u8, c: bool) {
     let _ctx = unwind_context_with_fmt!(
         (fn(a, b, c)),
         writer = Writer,
-        panic_detector = CustomPanicDetector,
+        panic_detector = AtomicFlagPanicDetector(&PANIC_FLAG),
         color_scheme = None,
     );
     panic!();
@@ -42,12 +46,3 @@ impl FmtWrite for Writer {
         unimplemented!()
     }
 }
-
-#[derive(Copy, Clone, Debug)]
-pub struct CustomPanicDetector;
-
-impl unwind_context::PanicDetector for CustomPanicDetector {
-    fn is_panicking(&self) -> bool {
-        unimplemented!()
-    }
-}
@@ -13,7 +13,7 @@ use alloc::string::String;
 use core::alloc::{GlobalAlloc, Layout};
 use core::panic::PanicInfo;
 
-use unwind_context::unwind_context_with_fmt;
+use unwind_context::{unwind_context_with_fmt, GlobalFlagPanicDetector};
 
 #[global_allocator]
 static GLOBAL_ALLOCATOR: DummyAllocator = DummyAllocator;
@@ -23,6 +23,7 @@ struct DummyAllocator;
 
 #[panic_handler]
 fn panic(_: &PanicInfo<'_>) -> ! {
+    GlobalFlagPanicDetector::set_panicking(true);
     loop {}
 }
 
@@ -38,21 +39,12 @@ pub extern "C" fn func(a: u32, b: *const u8, c: bool) {
     let _ctx = unwind_context_with_fmt!(
         (fn(a, b, c)),
         writer = &mut writer,
-        panic_detector = CustomPanicDetector,
+        panic_detector = GlobalFlagPanicDetector,
         color_scheme = None,
     );
     panic!();
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct CustomPanicDetector;
-
-impl unwind_context::PanicDetector for CustomPanicDetector {
-    fn is_panicking(&self) -> bool {
-        unimplemented!()
-    }
-}
-
 unsafe impl GlobalAlloc for DummyAllocator {
     unsafe fn alloc(&self, _layout: Layout) -> *mut u8 {
         unimplemented!()
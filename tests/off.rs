@@ -0,0 +1,56 @@
+#![allow(missing_docs)]
+
+#[cfg(feature = "anyhow")]
+use anyhow as _;
+#[cfg(any(feature = "custom-default-colors", feature = "timestamps"))]
+use atomic_ref as _;
+#[cfg(feature = "color-eyre")]
+use color_eyre as _;
+#[cfg(feature = "semihosting")]
+use cortex_m_semihosting as _;
+use critical_section as _;
+#[cfg(feature = "eyre")]
+use eyre as _;
+#[cfg(feature = "rayon")]
+use rayon as _;
+#[cfg(feature = "rtt")]
+use rtt_target as _;
+#[cfg(feature = "serde")]
+use serde as _;
+use serde_json as _;
+#[cfg(feature = "detect-color-support")]
+use supports_color as _;
+#[cfg(feature = "tokio")]
+use tokio as _;
+use unwind_context::{
+    unwind_context, unwind_context_scope, unwind_context_with_fmt, unwind_context_with_io,
+};
+#[cfg(feature = "test-macro")]
+use unwind_context_macros as _;
+use version_sync as _;
+
+#[test]
+fn test_macros_expand_to_unit() {
+    let foo = 123;
+    let capture = unwind_context::capture();
+    let result = std::panic::catch_unwind(|| {
+        let () = unwind_context!(fn func(foo));
+        let () = unwind_context_with_io!((fn func(foo)), verbose = true);
+        let () = unwind_context_with_fmt!(
+            (fn func(foo)),
+            writer = &mut String::new(),
+            panic_detector = unwind_context::StdPanicDetector,
+            print_on_drop = true,
+        );
+        assert_ne!(foo, 123, "Error");
+    });
+    assert!(result.is_err());
+    assert!(capture.take_frames().is_empty());
+}
+
+#[test]
+fn test_unwind_context_scope_runs_block() {
+    let foo = 123;
+    let value = unwind_context_scope!((fn func(foo)), { foo * 2 });
+    assert_eq!(value, 246);
+}
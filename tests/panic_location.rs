@@ -0,0 +1,95 @@
+#![allow(missing_docs, clippy::panic, clippy::unwrap_used)]
+
+#[cfg(feature = "anyhow")]
+use anyhow as _;
+#[cfg(any(feature = "custom-default-colors", feature = "timestamps"))]
+use atomic_ref as _;
+#[cfg(feature = "color-eyre")]
+use color_eyre as _;
+#[cfg(feature = "semihosting")]
+use cortex_m_semihosting as _;
+use critical_section as _;
+#[cfg(feature = "eyre")]
+use eyre as _;
+#[cfg(feature = "rayon")]
+use rayon as _;
+#[cfg(feature = "rtt")]
+use rtt_target as _;
+#[cfg(feature = "serde")]
+use serde as _;
+use serde_json as _;
+#[cfg(feature = "detect-color-support")]
+use supports_color as _;
+#[cfg(feature = "tokio")]
+use tokio as _;
+use unwind_context as _;
+#[cfg(feature = "test-macro")]
+use unwind_context_macros as _;
+use version_sync as _;
+
+#[cfg(all(feature = "std", not(feature = "off")))]
+mod tests {
+    use core::cell::RefCell;
+    use core::fmt::{Result as FmtResult, Write as FmtWrite};
+    use core::panic::AssertUnwindSafe;
+    use std::panic;
+
+    use unwind_context::{unwind_context_with_fmt, StdPanicDetector};
+
+    #[derive(Clone, Copy, Debug)]
+    struct SharedWriter<'a>(&'a RefCell<String>);
+
+    impl FmtWrite for SharedWriter<'_> {
+        fn write_str(&mut self, s: &str) -> FmtResult {
+            self.0.borrow_mut().push_str(s);
+            Ok(())
+        }
+    }
+
+    fn panic_with_context() -> String {
+        let output = RefCell::new(String::new());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let value = 123;
+            let _ctx = unwind_context_with_fmt!(
+                (fn outer(value)),
+                writer = SharedWriter(&output),
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            let _ctx = unwind_context_with_fmt!(
+                (fn inner(value)),
+                writer = SharedWriter(&output),
+                panic_detector = StdPanicDetector,
+                color_scheme = None,
+            );
+            panic!("Error");
+        }));
+        assert!(result.is_err());
+        output.into_inner()
+    }
+
+    #[test]
+    fn test_install_panic_location_hook() {
+        let output = panic_with_context();
+        assert!(output.starts_with("fn inner(value: 123)\n    at "));
+        assert!(!output.contains("panicked at "));
+
+        unwind_context::install_panic_location_hook();
+        unwind_context::install_panic_location_hook();
+
+        let output = panic_with_context();
+        let mut lines = output.lines();
+        let header = lines.next().unwrap();
+        assert!(header.starts_with("panicked at tests/panic_location.rs:"));
+        assert_eq!(lines.next(), Some("fn inner(value: 123)"));
+        let inner_location = lines.next().unwrap();
+        assert!(inner_location.starts_with("    at tests/panic_location.rs:"));
+        assert_ne!(&header["panicked ".len()..], inner_location.trim_start());
+        assert_eq!(lines.next(), Some("fn outer(value: 123)"));
+        assert!(lines
+            .next()
+            .unwrap()
+            .starts_with("    at tests/panic_location.rs:"));
+        assert_eq!(lines.next(), None);
+    }
+}
@@ -0,0 +1,110 @@
+#![allow(missing_docs, clippy::panic, clippy::unwrap_used)]
+#![cfg_attr(feature = "off", allow(clippy::no_effect_underscore_binding))]
+
+#[cfg(feature = "anyhow")]
+use anyhow as _;
+#[cfg(any(feature = "custom-default-colors", feature = "timestamps"))]
+use atomic_ref as _;
+#[cfg(feature = "color-eyre")]
+use color_eyre as _;
+#[cfg(feature = "semihosting")]
+use cortex_m_semihosting as _;
+use critical_section as _;
+#[cfg(feature = "eyre")]
+use eyre as _;
+#[cfg(feature = "rayon")]
+use rayon as _;
+#[cfg(feature = "rtt")]
+use rtt_target as _;
+#[cfg(feature = "serde")]
+use serde as _;
+use serde_json as _;
+use supports_color as _;
+#[cfg(feature = "tokio")]
+use tokio as _;
+use unwind_context::unwind_context;
+use unwind_context_macros as _;
+use version_sync as _;
+
+fn func(value: u32, name: &str) -> u32 {
+    let _ctx = unwind_context!(fn(value, name));
+    assert!(!name.is_empty(), "empty name");
+    value
+}
+
+#[unwind_context::test]
+fn test_attribute() {
+    assert_eq!(func(123, "abc"), 123);
+}
+
+#[unwind_context::test]
+fn test_attribute_with_result() -> Result<(), core::num::ParseIntError> {
+    assert_eq!(func("234".parse()?, "bcd"), 234);
+    Ok(())
+}
+
+#[unwind_context::test]
+#[should_panic(expected = "empty name")]
+fn test_attribute_should_panic() {
+    let _ = func(345, "");
+}
+
+#[unwind_context::test]
+#[ignore = "called by `test_attribute_context_frame`"]
+fn test_attribute_panicking() {
+    let _ = func(456, "");
+}
+
+#[cfg(not(feature = "off"))]
+#[test]
+fn test_attribute_context_frame() {
+    let capture = unwind_context::capture();
+    let result = std::panic::catch_unwind(test_attribute_panicking);
+    assert!(result.is_err());
+    let frames = capture.take_frames();
+    assert_eq!(frames.len(), 2);
+    assert_eq!(frames[0].arg("value"), Some("456"));
+    assert_eq!(frames[1].func_name, Some("test_attribute_panicking"));
+    assert!(frames[1].args.is_empty());
+}
+
+#[cfg(not(feature = "off"))]
+#[test]
+fn test_attribute_panic_location() {
+    use core::cell::RefCell;
+    use std::io::{Result as IoResult, Write as IoWrite};
+
+    std::thread_local! {
+        static OUTPUT: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct ThreadOutput;
+
+    impl IoWrite for ThreadOutput {
+        fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+            OUTPUT.with(|output| output.borrow_mut().extend_from_slice(buf));
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> IoResult<()> {
+            Ok(())
+        }
+    }
+
+    fn thread_output() -> Box<dyn IoWrite + Send> {
+        Box::new(ThreadOutput)
+    }
+
+    unwind_context::set_default_writer(Some(thread_output));
+    let result = std::panic::catch_unwind(test_attribute_panicking);
+    unwind_context::set_default_writer(None);
+    assert!(result.is_err());
+
+    let output = String::from_utf8(OUTPUT.with(RefCell::take)).unwrap();
+    let mut lines = output.lines();
+    let header = lines.next().unwrap();
+    assert!(header.starts_with("panicked at "));
+    assert!(header.contains("test_macro.rs"));
+    assert!(lines.next().unwrap().contains("func"));
+    assert!(output.contains("test_attribute_panicking"));
+}
@@ -1,8 +1,30 @@
-#[cfg(feature = "custom-default-colors")]
+#![allow(missing_docs)]
+
+#[cfg(feature = "anyhow")]
+use anyhow as _;
+#[cfg(any(feature = "custom-default-colors", feature = "timestamps"))]
 use atomic_ref as _;
+#[cfg(feature = "color-eyre")]
+use color_eyre as _;
+#[cfg(feature = "semihosting")]
+use cortex_m_semihosting as _;
+use critical_section as _;
+#[cfg(feature = "eyre")]
+use eyre as _;
+#[cfg(feature = "rayon")]
+use rayon as _;
+#[cfg(feature = "rtt")]
+use rtt_target as _;
+#[cfg(feature = "serde")]
+use serde as _;
+use serde_json as _;
 #[cfg(feature = "detect-color-support")]
 use supports_color as _;
+#[cfg(feature = "tokio")]
+use tokio as _;
 use unwind_context as _;
+#[cfg(feature = "test-macro")]
+use unwind_context_macros as _;
 
 #[test]
 fn test_readme_deps() {